@@ -0,0 +1,35 @@
+// Attaches to the running game and records the camera into a telemetry CSV, like pressing T in the
+// free cam, until Ctrl+C. Run it with `cargo run --example attach_telemetry` while the game is in a level.
+
+use std::io::Write;
+use std::time::Duration;
+
+use thps3_free_cam::process::{ProcessAccess, ProcessHandle};
+use thps3_free_cam::shutdown;
+use thps3_free_cam::telemetry::TelemetryRecorder;
+
+fn main() -> Result<(), String> {
+    let process = ProcessHandle::new("Skate3.exe", ProcessAccess::Read)?;
+    let base_addr = process.get_base_address()?;
+    
+    let mut recorder = TelemetryRecorder::start("example_telemetry")?;
+    let log_path = recorder.log_path().to_string();
+    println!("Recording to {} - Ctrl+C to stop", log_path);
+    
+    shutdown::install();
+    let session = shutdown::session();
+    while !shutdown::requested() {
+        let matrix = process.get_camera_matrix(base_addr)?;
+        let position = matrix.get_position();
+        recorder.record(&position, Some(&matrix), None)?;
+        
+        print!("\rX:{:.1} Y:{:.1} Z:{:.1}   ", position.x, position.y, position.z);
+        std::io::stdout().flush().map_err(|e| e.to_string())?;
+        std::thread::sleep(Duration::from_millis(16));
+    }
+    
+    recorder.finish()?;
+    println!("\nSaved {}", log_path);
+    drop(session);
+    Ok(())
+}
@@ -0,0 +1,51 @@
+// A plugin that turns the camera into a dolly: Numpad5 starts and stops a slow slide to the right, Numpad4 and
+// Numpad6 change the speed. Flying still works while it slides.
+// Build it with `cargo build --release --example dolly_plugin --target i686-pc-windows-msvc` and copy
+// dolly_plugin.dll from target/i686-pc-windows-msvc/release/examples into plugin_dir.
+
+use thps3_free_cam::export_plugin;
+use thps3_free_cam::input::Key;
+use thps3_free_cam::math::Vec3;
+use thps3_free_cam::plugin::{CameraContext, Plugin};
+
+const TOGGLE: Key = Key(0x65);  // Numpad5
+const SLOWER: Key = Key(0x64);  // Numpad4
+const FASTER: Key = Key(0x66);  // Numpad6
+
+struct Dolly {
+    sliding: bool,
+    speed: f32, // Units per second
+}
+
+impl Default for Dolly {
+    fn default() -> Self {
+        Self { sliding: false, speed: 100.0 }
+    }
+}
+
+impl Plugin for Dolly {
+    fn on_frame(&mut self, context: &mut CameraContext) {
+        if !self.sliding || context.patch == 0 {
+            return;
+        }
+        let mut matrix = context.matrix();
+        let right = matrix.rotation_matrix().x_axis;
+        let position = Vec3::from(&matrix.get_position()) + right * self.speed * context.delta;
+        matrix.set_position(&position.into());
+        context.set_matrix(&matrix);
+    }
+    
+    fn on_hotkey(&mut self, key: Key) {
+        match key {
+            TOGGLE => {
+                self.sliding = !self.sliding;
+                println!("\n🛤️ Dolly {}", if self.sliding { "sliding" } else { "stopped" });
+            }
+            SLOWER => self.speed = (self.speed / 1.5).max(5.0),
+            FASTER => self.speed = (self.speed * 1.5).min(2000.0),
+            _ => {}
+        }
+    }
+}
+
+export_plugin!(Dolly, "dolly");
@@ -0,0 +1,45 @@
+// Plays a saved camera path in the running game, the way F6 does in the free cam.
+// Run it with `cargo run --example play_path -- camera_path.toml` while the game is in a level.
+
+use std::time::{Duration, Instant};
+
+use thps3_free_cam::interpolation::InterpolationMode;
+use thps3_free_cam::path::CameraPath;
+use thps3_free_cam::process::{ProcessAccess, ProcessHandle};
+use thps3_free_cam::shutdown;
+
+fn main() -> Result<(), String> {
+    let file = std::env::args().nth(1).unwrap_or_else(|| "camera_path.toml".to_string());
+    let path = CameraPath::load(&file)?;
+    if path.len() < 2 {
+        return Err(format!("'{}' needs at least two keyframes", file));
+    }
+    
+    let process = ProcessHandle::new("Skate3.exe", ProcessAccess::Write)?;
+    let base_addr = process.get_base_address()?;
+    
+    // Keep the game off the camera while the path plays, and hand it back afterwards even when playback fails
+    let mut patch = process.patch_with_nops(process.get_camera_write_patch_address(base_addr)?, &process.game().camera_write().original_bytes)?;
+    shutdown::install();
+    let session = shutdown::session();
+    println!("Playing {} keyframes over {:.1}s - Ctrl+C to stop", path.len(), path.duration());
+    let result = play(&process, base_addr, &path);
+    process.restore_patch(&mut patch)?;
+    drop(session);
+    result
+}
+
+fn play(process: &ProcessHandle, base_addr: usize, path: &CameraPath) -> Result<(), String> {
+    let mut matrix = process.get_camera_matrix(base_addr)?;
+    let started = Instant::now();
+    while !shutdown::requested() {
+        let elapsed = started.elapsed().as_secs_f32().min(path.duration());
+        path.apply(elapsed, InterpolationMode::default(), &mut matrix);
+        process.set_camera_matrix(base_addr, &matrix)?;
+        if elapsed >= path.duration() {
+            break;
+        }
+        std::thread::sleep(Duration::from_millis(16));
+    }
+    Ok(())
+}
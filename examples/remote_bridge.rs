@@ -0,0 +1,69 @@
+// A bare-bones remote control: the same HTTP/WebSocket API as `remote_port` in the free cam, but
+// writing the camera straight into the game without any keyboard control on top.
+// Run it with `cargo run --example remote_bridge -- 8080 <token>` and send it the requests from the README
+// with `Authorization: Bearer <token>`.
+
+use std::time::Duration;
+
+use thps3_free_cam::camera::{CameraMatrix, CameraPose, CameraPosition, Quaternion};
+use thps3_free_cam::process::{ProcessAccess, ProcessHandle};
+use thps3_free_cam::remote::{CameraState, RemoteAccess, RemoteCommand, RemoteServer};
+use thps3_free_cam::shutdown;
+
+fn main() -> Result<(), String> {
+    let port = match std::env::args().nth(1) {
+        Some(port) => port.parse().map_err(|e| format!("Invalid port '{}': {}", port, e))?,
+        None => 8080,
+    };
+    let token = std::env::args().nth(2).ok_or("Pass the token requests have to carry after the port")?;
+    
+    let process = ProcessHandle::new("Skate3.exe", ProcessAccess::Write)?;
+    let base_addr = process.get_base_address()?;
+    let remote = RemoteServer::start("127.0.0.1", port, RemoteAccess { token, origins: Vec::new() })?;
+    
+    let mut patch = process.patch_with_nops(process.get_camera_write_patch_address(base_addr)?, &process.game().camera_write().original_bytes)?;
+    shutdown::install();
+    let session = shutdown::session();
+    println!("Bridging http://127.0.0.1:{} to the game camera - Ctrl+C to stop", port);
+    let result = bridge(&process, base_addr, &remote);
+    process.restore_patch(&mut patch)?;
+    drop(remote);
+    drop(session);
+    result
+}
+
+fn bridge(process: &ProcessHandle, base_addr: usize, remote: &RemoteServer) -> Result<(), String> {
+    while !shutdown::requested() {
+        for command in remote.poll() {
+            let mut matrix = process.get_camera_matrix(base_addr)?;
+            match command {
+                RemoteCommand::SetPosition { x, y, z } => matrix.set_position(&CameraPosition::new(x, y, z)),
+                RemoteCommand::SetMatrix { matrix: data } => matrix = CameraMatrix { data },
+                RemoteCommand::SetRotation { rotation: [x, y, z, w] } => matrix.set_rotation(&Quaternion::new(x, y, z, w)),
+                RemoteCommand::SetPose { position: [x, y, z], rotation: [qx, qy, qz, qw] } => {
+                    matrix.set_pose(&CameraPose::new(CameraPosition::new(x, y, z), Quaternion::new(qx, qy, qz, qw)));
+                }
+                // FOV, speed and actions need the free cam's controller and offsets file
+                other => {
+                    println!("Ignoring {:?}", other);
+                    continue;
+                }
+            }
+            process.set_camera_matrix(base_addr, &matrix)?;
+        }
+        
+        let matrix = process.get_camera_matrix(base_addr)?;
+        let CameraPose { position, rotation } = matrix.pose();
+        remote.publish(CameraState {
+            position: [position.x, position.y, position.z],
+            rotation: [rotation.x, rotation.y, rotation.z, rotation.w],
+            matrix: matrix.data.to_vec(),
+            speed: 0.0,
+            patch: true,
+            fov: None,
+            stats: None,
+        });
+        std::thread::sleep(Duration::from_millis(16));
+    }
+    Ok(())
+}
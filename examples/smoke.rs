@@ -0,0 +1,53 @@
+// Runs the attach pipeline against every memory image in a directory, the way attaching to the game would,
+// and fails when a step that worked when the image was recorded doesn't any more. Every image is replayed with
+// the built-in camera chains, plus the chains of an offsets file when one is passed, so a change to either that
+// no longer finds the recorded memory fails. Images come from `record-image`.
+// Run it with `cargo run --example smoke -- images [offsets.toml]`; CI runs it on `images` on every push.
+
+use thps3_free_cam::image::{print_pipeline, run_pipeline, MemoryImage};
+use thps3_free_cam::offsets::{load_offsets, GameOffsets};
+use thps3_free_cam::process::ProcessHandle;
+
+fn main() -> Result<(), String> {
+    let directory = std::env::args().nth(1).unwrap_or_else(|| "images".to_string());
+    // A file that was asked for has to be there, load_offsets would quietly fall back to the built-in chains
+    let offsets = match std::env::args().nth(2) {
+        Some(path) if !std::path::Path::new(&path).is_file() => return Err(format!("No offsets file at '{}'", path)),
+        Some(path) => load_offsets(&path)?,
+        None => GameOffsets::default(),
+    };
+    let mut images: Vec<String> = std::fs::read_dir(&directory)
+        .map(|listing| {
+            listing.filter_map(Result::ok)
+                .map(|item| item.path())
+                .filter(|path| path.extension().is_some_and(|extension| extension == "img"))
+                .map(|path| path.to_string_lossy().into_owned())
+                .collect()
+        })
+        .unwrap_or_default();
+    images.sort();
+    // Passing without replaying anything would hide a missing or mistyped directory
+    if images.is_empty() {
+        return Err(format!("No memory images in '{}' - record one with `thps3_free_cam record-image`", directory));
+    }
+    
+    let mut failed = 0;
+    for file in &images {
+        let image = MemoryImage::load(file)?;
+        
+        println!("\n{} ({}, {} page(s))", file, image.executable, image.len());
+        let process = ProcessHandle::from_image(image);
+        let steps = run_pipeline(&process, &offsets);
+        print_pipeline(&steps);
+        if steps.iter().any(|step| step.result.is_err()) {
+            failed += 1;
+        }
+    }
+    
+    // A non-zero exit code, so CI notices
+    if failed > 0 {
+        return Err(format!("{} of {} image(s) failed the pipeline", failed, images.len()));
+    }
+    println!("\n✅ All {} image(s) passed", images.len());
+    Ok(())
+}
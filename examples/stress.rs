@@ -0,0 +1,188 @@
+// Worst-case contention on the camera matrix: a hold writer, a telemetry reader and path playback each
+// hammer the same matrix from their own thread, well above the game's frame rate, and the report shows
+// how long every read and write took and whether a read ever caught a half-written matrix.
+// The camera is simulated in this process's own memory and reached through ProcessHandle like the game's,
+// so it runs without the game. Run it with `cargo run --release --example stress -- 10 2000` for
+// 10 seconds at 2000 operations per second per thread.
+
+use std::time::{Duration, Instant};
+
+use thps3_free_cam::camera::{CameraMatrix, CameraPosition, Quaternion};
+use thps3_free_cam::interpolation::InterpolationMode;
+use thps3_free_cam::path::{CameraPath, Keyframe};
+use thps3_free_cam::process::{ProcessAccess, ProcessHandle};
+
+// Writers stamp every matrix in the three unused bottom-row floats, a read with mismatched stamps is torn
+const STAMPS: [usize; 3] = [3, 7, 11];
+
+struct Report {
+    name: &'static str,
+    latencies: Vec<Duration>, // One per read or write
+    late: u64,                // Ticks that started more than a whole period behind schedule
+    torn: u64,                // Reads that mixed two writes
+}
+
+impl Report {
+    fn new(name: &'static str) -> Self {
+        Self { name, latencies: Vec::new(), late: 0, torn: 0 }
+    }
+    
+    fn percentile(&self, sorted: &[Duration], fraction: f64) -> f64 {
+        let index = ((sorted.len() - 1) as f64 * fraction).round() as usize;
+        sorted[index].as_secs_f64() * 1_000_000.0
+    }
+    
+    fn print(&self, seconds: f64) {
+        let mut sorted = self.latencies.clone();
+        sorted.sort();
+        if sorted.is_empty() {
+            println!("{:<16} no operations", self.name);
+            return;
+        }
+        println!("{:<16} {:>9} {:>9.0} {:>9.1} {:>9.1} {:>9.1} {:>7} {:>7}",
+                 self.name, sorted.len(), sorted.len() as f64 / seconds, self.percentile(&sorted, 0.5),
+                 self.percentile(&sorted, 0.99), self.percentile(&sorted, 1.0), self.late, self.torn);
+    }
+}
+
+fn stamped(matrix: &CameraMatrix, stamp: u32) -> [f32; 16] {
+    let mut data = matrix.data;
+    for index in STAMPS {
+        data[index] = stamp as f32;
+    }
+    data
+}
+
+// Calls `tick` on a fixed schedule until `duration` is up, counting ticks that start a whole period late
+fn run_at(rate: u32, duration: Duration, report: &mut Report, mut tick: impl FnMut(&mut Report) -> Result<(), String>) -> Result<(), String> {
+    let period = Duration::from_secs_f64(1.0 / rate as f64);
+    let started = Instant::now();
+    let mut next = started;
+    while started.elapsed() < duration {
+        tick(report)?;
+        next += period;
+        let now = Instant::now();
+        if now > next + period {
+            report.late += 1;
+            next = now;
+        } else if now < next {
+            std::thread::sleep(next - now);
+        }
+    }
+    Ok(())
+}
+
+// The freeze/hold writer: keeps putting the same pose back, like a held camera or tunable
+fn hold_writer(pid: u32, address: usize, rate: u32, duration: Duration) -> Result<Report, String> {
+    let process = ProcessHandle::from_pid(pid, ProcessAccess::Write)?;
+    let mut report = Report::new("hold writer");
+    let mut pose = CameraMatrix::new();
+    pose.set_position(&CameraPosition::new(0.0, 100.0, 0.0));
+    let mut stamp = 0u32;
+    run_at(rate, duration, &mut report, |report| {
+        stamp += 2; // Even stamps, playback uses odd ones
+        let data = stamped(&pose, stamp);
+        let started = Instant::now();
+        process.write_memory(address, &data)?;
+        report.latencies.push(started.elapsed());
+        Ok(())
+    })?;
+    Ok(report)
+}
+
+// Path playback: a looping path written with a new pose every tick
+fn path_player(pid: u32, address: usize, rate: u32, duration: Duration) -> Result<Report, String> {
+    let process = ProcessHandle::from_pid(pid, ProcessAccess::Write)?;
+    let mut report = Report::new("path playback");
+    let turn = |angle: f32| {
+        let (sin, cos) = (angle / 2.0).sin_cos();
+        Quaternion::new(0.0, sin, 0.0, cos)
+    };
+    let path = CameraPath::from_keyframes((0..4).map(|i| Keyframe {
+        time: i as f32,
+        position: [i as f32 * 100.0, 50.0, 0.0],
+        rotation: { let q = turn(i as f32 * 0.8); [q.x, q.y, q.z, q.w] },
+        ease_in: 0.0,
+        ease_out: 0.0,
+    }).collect());
+    
+    let mut matrix = CameraMatrix::new();
+    let started = Instant::now();
+    let mut stamp = 1u32;
+    run_at(rate, duration, &mut report, |report| {
+        stamp += 2;
+        path.apply(started.elapsed().as_secs_f32() % path.duration(), InterpolationMode::Spline, &mut matrix);
+        let data = stamped(&matrix, stamp);
+        let write_started = Instant::now();
+        process.write_memory(address, &data)?;
+        report.latencies.push(write_started.elapsed());
+        Ok(())
+    })?;
+    Ok(report)
+}
+
+// The telemetry reader: reads the whole matrix like a recording tick and checks it came from a single write
+fn telemetry_reader(pid: u32, address: usize, rate: u32, duration: Duration) -> Result<Report, String> {
+    let process = ProcessHandle::from_pid(pid, ProcessAccess::Write)?;
+    let mut report = Report::new("telemetry read");
+    run_at(rate, duration, &mut report, |report| {
+        let started = Instant::now();
+        let data: [f32; 16] = process.read_memory(address)?;
+        report.latencies.push(started.elapsed());
+        if STAMPS.iter().any(|&index| data[index] != data[STAMPS[0]]) {
+            report.torn += 1;
+        }
+        Ok(())
+    })?;
+    Ok(report)
+}
+
+fn main() -> Result<(), String> {
+    let mut args = std::env::args().skip(1);
+    let seconds: u64 = match args.next() {
+        Some(seconds) => seconds.parse().map_err(|e| format!("Invalid duration '{}': {}", seconds, e))?,
+        None => 10,
+    };
+    let rate: u32 = match args.next() {
+        Some(rate) => rate.parse().map_err(|e| format!("Invalid rate '{}': {}", rate, e))?,
+        None => 1000,
+    };
+    if rate == 0 {
+        return Err("The rate has to be at least 1 operation per second".to_string());
+    }
+    
+    // The simulated camera lives for the whole run, the threads only get its address. It stays a raw pointer:
+    // WriteProcessMemory changes it behind the compiler's back, which a Rust reference to it wouldn't allow.
+    let camera: *mut [f32; 16] = Box::into_raw(Box::new(CameraMatrix::new().data));
+    let address = camera as usize;
+    let pid = std::process::id();
+    let duration = Duration::from_secs(seconds);
+    
+    println!("Stressing a simulated camera for {}s at {} operations per second per thread...", seconds, rate);
+    let reports = std::thread::scope(|scope| {
+        let workers = [
+            scope.spawn(move || hold_writer(pid, address, rate, duration)),
+            scope.spawn(move || path_player(pid, address, rate, duration)),
+            scope.spawn(move || telemetry_reader(pid, address, rate, duration)),
+        ];
+        workers.map(|worker| worker.join().unwrap_or_else(|_| Err("worker thread panicked".to_string())))
+    });
+    // Every thread with the address has been joined, nothing reads or writes the camera any more
+    drop(unsafe { Box::from_raw(camera) });
+    
+    println!();
+    println!("{:<16} {:>9} {:>9} {:>9} {:>9} {:>9} {:>7} {:>7}", "thread", "ops", "ops/s", "p50 µs", "p99 µs", "max µs", "late", "torn");
+    let mut torn = 0;
+    for report in reports {
+        let report = report?;
+        report.print(seconds as f64);
+        torn += report.torn;
+    }
+    
+    // A non-zero exit code, so a script running the stress test notices
+    if torn > 0 {
+        return Err(format!("{} read(s) mixed two writes - the camera needs one writer at a time", torn));
+    }
+    println!("\n✅ Every read saw a whole matrix");
+    Ok(())
+}
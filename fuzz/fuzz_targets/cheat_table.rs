@@ -0,0 +1,11 @@
+// Cheat Engine tables are downloaded from forums and imported with `import-table`, the XML is read by hand
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+use thps3_free_cam::cheat_table::parse_cheat_table;
+
+fuzz_target!(|contents: &str| {
+    let _ = parse_cheat_table(contents, "fuzz");
+});
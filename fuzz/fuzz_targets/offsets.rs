@@ -0,0 +1,11 @@
+// offsets.toml is shared between players for other game builds and is read before anything is patched
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+use thps3_free_cam::offsets::parse_offsets;
+
+fuzz_target!(|contents: &str| {
+    let _ = parse_offsets(contents, "fuzz");
+});
@@ -0,0 +1,35 @@
+// Every path format the free cam reads from `path_dir` or `path convert`, followed by what playback does
+// with a path that loaded. The first byte picks the format, the rest is the file.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+use thps3_free_cam::camera::CameraMatrix;
+use thps3_free_cam::convert::{parse_path, PathFormat};
+use thps3_free_cam::interpolation::InterpolationMode;
+
+const FORMATS: [PathFormat; 4] = [PathFormat::Path, PathFormat::Csv, PathFormat::Blender, PathFormat::Chan];
+const MODES: [InterpolationMode; 3] = [InterpolationMode::Linear, InterpolationMode::Spline, InterpolationMode::Squad];
+
+fuzz_target!(|data: &[u8]| {
+    let Some((&selector, file)) = data.split_first() else {
+        return;
+    };
+    let Ok(contents) = std::str::from_utf8(file) else {
+        return;
+    };
+    let Ok(path) = parse_path(contents, "fuzz", FORMATS[selector as usize % FORMATS.len()], 60.0) else {
+        return;
+    };
+    
+    // Keyframe times come straight from the file, so this covers NaN, infinite and out of order times too
+    let duration = path.duration();
+    let mut matrix = CameraMatrix::new();
+    for mode in MODES {
+        for time in [-1.0, 0.0, duration * 0.5, duration, duration + 1.0, f32::NAN] {
+            path.apply(time, mode, &mut matrix);
+        }
+    }
+    path.keyframe_index_at(duration * 0.5);
+});
@@ -0,0 +1,157 @@
+use std::ffi::CString;
+use std::mem;
+use std::ops::Range;
+use std::ptr;
+use std::sync::atomic::{AtomicPtr, AtomicUsize, Ordering};
+
+use winapi::shared::minwindef::DWORD;
+use winapi::um::errhandlingapi::AddVectoredExceptionHandler;
+use winapi::um::handleapi::INVALID_HANDLE_VALUE;
+use winapi::um::libloaderapi::GetModuleHandleA;
+use winapi::um::memoryapi::{MapViewOfFile, ReadProcessMemory, VirtualProtect, FILE_MAP_ALL_ACCESS};
+use winapi::um::minwinbase::EXCEPTION_BREAKPOINT;
+use winapi::um::processthreadsapi::{FlushInstructionCache, GetCurrentProcess, GetCurrentProcessId};
+use winapi::um::winbase::CreateFileMappingA;
+use winapi::um::winnt::{CONTEXT, EXCEPTION_POINTERS, LONG, PAGE_EXECUTE_READWRITE, PAGE_READWRITE};
+use winapi::vc::excpt::{EXCEPTION_CONTINUE_EXECUTION, EXCEPTION_CONTINUE_SEARCH};
+
+use crate::{mapping_name, SharedCamera, CAMERA_COPY_BYTES, CAMERA_COPY_OFFSET, SHARED_MAGIC, SHARED_VERSION};
+
+static SHARED: AtomicPtr<SharedCamera> = AtomicPtr::new(ptr::null_mut());
+static HOOK_ADDRESS: AtomicUsize = AtomicUsize::new(0);
+
+const INT3: u8 = 0xCC;
+const DIRECTION_FLAG: DWORD = 0x400;
+
+// The camera copy is only two bytes, too short for a jump, so it becomes an INT3 and the
+// breakpoint is handled here instead of in a debugger
+pub fn install() {
+    let Some(shared) = create_shared() else { return };
+    SHARED.store(shared, Ordering::SeqCst);
+    
+    // Without a match the hook stays off and the free cam reports hook_address 0
+    let base = unsafe { GetModuleHandleA(ptr::null()) } as usize;
+    let Some(address) = find_camera_copy(base) else { return };
+    HOOK_ADDRESS.store(address, Ordering::SeqCst);
+    
+    unsafe {
+        AddVectoredExceptionHandler(1, Some(on_exception));
+        
+        let mut old_protect = 0;
+        if VirtualProtect(address as *mut _, 1, PAGE_EXECUTE_READWRITE, &mut old_protect) == 0 {
+            return;
+        }
+        ptr::write_volatile(address as *mut u8, INT3);
+        VirtualProtect(address as *mut _, 1, old_protect, &mut old_protect);
+        FlushInstructionCache(GetCurrentProcess(), address as *const _, 1);
+        
+        ptr::write_volatile(&mut (*shared).hook_address, address as u32);
+    }
+}
+
+// Shared block created by `install`, null when the mapping couldn't be made
+pub fn shared() -> *mut SharedCamera {
+    SHARED.load(Ordering::SeqCst)
+}
+
+fn create_shared() -> Option<*mut SharedCamera> {
+    let name = CString::new(mapping_name(unsafe { GetCurrentProcessId() })).ok()?;
+    unsafe {
+        let mapping = CreateFileMappingA(
+            INVALID_HANDLE_VALUE,
+            ptr::null_mut(),
+            PAGE_READWRITE,
+            0,
+            mem::size_of::<SharedCamera>() as DWORD,
+            name.as_ptr(),
+        );
+        if mapping.is_null() {
+            return None;
+        }
+        
+        // The mapping stays open for as long as the game runs, the view starts zeroed
+        let view = MapViewOfFile(mapping, FILE_MAP_ALL_ACCESS, 0, 0, 0) as *mut SharedCamera;
+        if view.is_null() {
+            return None;
+        }
+        ptr::write_volatile(&mut (*view).version, SHARED_VERSION);
+        ptr::write_volatile(&mut (*view).magic, SHARED_MAGIC);
+        Some(view)
+    }
+}
+
+// Same base/.text variants the free cam tries, but only an exact byte match is accepted
+fn find_camera_copy(base: usize) -> Option<usize> {
+    let text_section_offset = 0x1000;
+    let addresses_to_try = [
+        base + CAMERA_COPY_OFFSET,
+        base + text_section_offset + CAMERA_COPY_OFFSET,
+        base + CAMERA_COPY_OFFSET - text_section_offset,
+    ];
+    
+    addresses_to_try.into_iter().find(|&addr| {
+        // ReadProcessMemory on ourselves fails cleanly on unmapped memory instead of crashing the game
+        let mut bytes = [0u8; CAMERA_COPY_BYTES.len()];
+        let mut bytes_read = 0;
+        let result = unsafe {
+            ReadProcessMemory(GetCurrentProcess(), addr as *const _, bytes.as_mut_ptr() as *mut _, bytes.len(), &mut bytes_read)
+        };
+        result != 0 && bytes_read == bytes.len() && bytes == CAMERA_COPY_BYTES
+    })
+}
+
+// Does what the replaced "repe movsd" would have done and returns the bytes it wrote
+unsafe fn emulate_copy(context: &mut CONTEXT) -> Range<usize> {
+    let start = context.Edi;
+    let step: DWORD = if context.EFlags & DIRECTION_FLAG != 0 { 4u32.wrapping_neg() } else { 4 };
+    
+    while context.Ecx != 0 {
+        unsafe {
+            let value = ptr::read_unaligned(context.Esi as *const u32);
+            ptr::write_unaligned(context.Edi as *mut u32, value);
+        }
+        context.Esi = context.Esi.wrapping_add(step);
+        context.Edi = context.Edi.wrapping_add(step);
+        context.Ecx -= 1;
+    }
+    
+    if step == 4 {
+        start as usize..context.Edi as usize
+    } else {
+        context.Edi.wrapping_add(4) as usize..start.wrapping_add(4) as usize
+    }
+}
+
+unsafe extern "system" fn on_exception(info: *mut EXCEPTION_POINTERS) -> LONG {
+    let address = HOOK_ADDRESS.load(Ordering::Relaxed);
+    let record = unsafe { &*(*info).ExceptionRecord };
+    if record.ExceptionCode != EXCEPTION_BREAKPOINT || record.ExceptionAddress as usize != address {
+        return EXCEPTION_CONTINUE_SEARCH;
+    }
+    
+    let context = unsafe { &mut *(*info).ContextRecord };
+    let written = unsafe { emulate_copy(context) };
+    context.Eip = (address + CAMERA_COPY_BYTES.len()) as DWORD;
+    
+    let shared = SHARED.load(Ordering::Relaxed);
+    unsafe {
+        let frames = ptr::read_volatile(&(*shared).frames);
+        ptr::write_volatile(&mut (*shared).frames, frames.wrapping_add(1));
+        
+        // Put the free cam matrix back right after the game copied its own over it
+        let target = ptr::read_volatile(&(*shared).matrix_address) as usize;
+        if ptr::read_volatile(&(*shared).active) != 0 && target != 0 && written.contains(&target) {
+            let front = (ptr::read_volatile(&(*shared).front) & 1) as usize;
+            let matrix = ptr::read_volatile(&(*shared).matrices[front]);
+            let channels = ptr::read_volatile(&(*shared).channels);
+            // Floats left out of the mask keep what the game just copied, e.g. its own rotation
+            for (i, value) in matrix.iter().enumerate() {
+                if channels == 0 || channels & (1 << i) != 0 {
+                    ptr::write_unaligned((target as *mut f32).add(i), *value);
+                }
+            }
+        }
+    }
+    
+    EXCEPTION_CONTINUE_EXECUTION
+}
@@ -0,0 +1,68 @@
+// Internal camera hook, injected into the game by `thps3_free_cam --internal`.
+// The free cam writes the matrix into shared memory and the hook copies it over the
+// game camera right after the game's own camera update, inside the game loop.
+
+#[cfg(all(windows, target_arch = "x86"))]
+mod detour;
+#[cfg(all(windows, target_arch = "x86"))]
+mod osd;
+
+// Marks an initialised shared block ("THP3")
+pub const SHARED_MAGIC: u32 = 0x3350_4854;
+
+// Bumped whenever SharedCamera changes. Hooks from before the version field have `active` there, 0 or 1.
+pub const SHARED_VERSION: u32 = 2;
+
+// Longest in-game status line, including the terminating NUL
+pub const OSD_TEXT_LEN: usize = 128;
+
+// `osd_state` values
+pub const OSD_PENDING: u32 = 0;
+pub const OSD_HOOKED: u32 = 1;
+pub const OSD_FAILED: u32 = 2;
+
+// Offset of the "repe movsd" camera copy from the module base (Skate3.exe.text+16B2E4)
+pub const CAMERA_COPY_OFFSET: usize = 0x16B2E4;
+pub const CAMERA_COPY_BYTES: [u8; 2] = [0xF3, 0xA5];
+
+// Named file mapping the hook creates, one per game process
+pub fn mapping_name(pid: u32) -> String {
+    format!("Local\\thps3_free_cam_{}", pid)
+}
+
+// Layout of the shared memory block, both sides must agree on it
+#[repr(C)]
+pub struct SharedCamera {
+    pub magic: u32,
+    pub version: u32,
+    pub active: u32,              // Non-zero while the free cam overrides the game camera
+    pub matrix_address: u32,      // Camera matrix in the game, resolved by the free cam
+    pub front: u32,               // Which of `matrices` holds the latest complete write
+    pub matrices: [[f32; 16]; 2], // Double buffered so the hook never reads a half-written matrix
+    pub hook_address: u32,        // Patched instruction, 0 when the hook could not be installed
+    pub frames: u32,              // Bumped by the hook every time the game updates the camera
+    pub channels: u32,            // Bit per matrix float the hook copies over the game's, 0 copies all of them
+    pub osd_sequence: u32,        // Odd while the free cam is writing `osd_text`
+    pub osd_text: [u8; OSD_TEXT_LEN], // Status line the hook draws in the game, NUL-terminated, empty hides it
+    pub osd_state: u32,           // Whether the hook got into the game's Direct3D frames, see OSD_HOOKED
+}
+
+#[cfg(windows)]
+#[unsafe(no_mangle)]
+pub extern "system" fn DllMain(
+    module: winapi::shared::minwindef::HINSTANCE,
+    reason: winapi::shared::minwindef::DWORD,
+    _reserved: winapi::shared::minwindef::LPVOID,
+) -> winapi::shared::minwindef::BOOL {
+    if reason == winapi::um::winnt::DLL_PROCESS_ATTACH {
+        unsafe { winapi::um::libloaderapi::DisableThreadLibraryCalls(module) };
+        
+        // The game is 32-bit, other builds only exist so the workspace compiles everywhere
+        #[cfg(target_arch = "x86")]
+        {
+            detour::install();
+            osd::install();
+        }
+    }
+    winapi::shared::minwindef::TRUE
+}
@@ -0,0 +1,376 @@
+// In-game status line, for players in exclusive fullscreen where no window can sit on top of the game.
+// IDirect3DDevice8::Present is hooked through the device vtable, which d3d8.dll shares between all its
+// devices, so a throwaway device is enough to find it. Every frame the line is copied into the back buffer
+// right before the game shows it. D3D8 can't draw text without D3DX, so GDI draws it into a bitmap first.
+
+use std::cell::RefCell;
+use std::ffi::c_void;
+use std::mem;
+use std::ptr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use winapi::shared::minwindef::{BOOL, FARPROC, TRUE};
+use winapi::shared::windef::{HWND, POINT, RECT, SIZE};
+use winapi::um::libloaderapi::{GetProcAddress, LoadLibraryA};
+use winapi::um::memoryapi::VirtualProtect;
+use winapi::um::wingdi::{
+    BitBlt, CreateCompatibleDC, CreateDIBSection, CreateFontW, DeleteDC, DeleteObject, GdiFlush,
+    GetTextExtentPoint32W, SelectObject, SetBkMode, SetTextColor, TextOutW, BITMAPINFO, BI_RGB, BLACKNESS,
+    DIB_RGB_COLORS, FW_BOLD, NONANTIALIASED_QUALITY, RGB, TRANSPARENT,
+};
+use winapi::um::winnt::{HRESULT, PAGE_READWRITE};
+use winapi::um::winuser::{CreateWindowExA, DestroyWindow, WS_POPUP};
+
+use crate::{detour, SharedCamera, OSD_FAILED, OSD_HOOKED};
+
+const D3D_SDK_VERSION: u32 = 220;
+const D3DDEVTYPE_HAL: u32 = 1;
+const D3DSWAPEFFECT_DISCARD: u32 = 1;
+const D3DCREATE_SOFTWARE_VERTEXPROCESSING: u32 = 0x20;
+const D3DBACKBUFFER_TYPE_MONO: u32 = 0;
+const D3DFMT_A8R8G8B8: u32 = 21;
+const D3DFMT_X8R8G8B8: u32 = 22;
+const D3DFMT_R5G6B5: u32 = 23;
+const D3DFMT_X1R5G5B5: u32 = 24;
+const D3DFMT_A1R5G5B5: u32 = 25;
+
+// Vtable slots of the COM methods used here
+const RELEASE: usize = 2;
+const DIRECT3D_GET_ADAPTER_DISPLAY_MODE: usize = 8;
+const DIRECT3D_CREATE_DEVICE: usize = 15;
+const DEVICE_PRESENT: usize = 15;
+const DEVICE_GET_BACK_BUFFER: usize = 16;
+const DEVICE_CREATE_IMAGE_SURFACE: usize = 27;
+const DEVICE_COPY_RECTS: usize = 28;
+const SURFACE_GET_DESC: usize = 8;
+const SURFACE_LOCK_RECT: usize = 9;
+const SURFACE_UNLOCK_RECT: usize = 10;
+
+// Gap between the line and the top left corner of the picture, and around the text inside the box
+const MARGIN: i32 = 8;
+const PADDING: i32 = 4;
+const FONT_HEIGHT: i32 = 16;
+
+type Present = unsafe extern "system" fn(*mut c_void, *const RECT, *const RECT, HWND, *const c_void) -> HRESULT;
+type GetBackBuffer = unsafe extern "system" fn(*mut c_void, u32, u32, *mut *mut c_void) -> HRESULT;
+type CreateImageSurface = unsafe extern "system" fn(*mut c_void, u32, u32, u32, *mut *mut c_void) -> HRESULT;
+type CopyRects = unsafe extern "system" fn(*mut c_void, *mut c_void, *const RECT, u32, *mut c_void, *const POINT) -> HRESULT;
+type GetDesc = unsafe extern "system" fn(*mut c_void, *mut SurfaceDesc) -> HRESULT;
+type LockRect = unsafe extern "system" fn(*mut c_void, *mut LockedRect, *const RECT, u32) -> HRESULT;
+type UnlockRect = unsafe extern "system" fn(*mut c_void) -> HRESULT;
+type Release = unsafe extern "system" fn(*mut c_void) -> u32;
+type Direct3DCreate8 = unsafe extern "system" fn(u32) -> *mut c_void;
+type GetAdapterDisplayMode = unsafe extern "system" fn(*mut c_void, u32, *mut DisplayMode) -> HRESULT;
+type CreateDevice = unsafe extern "system" fn(*mut c_void, u32, u32, HWND, u32, *mut PresentParameters, *mut *mut c_void) -> HRESULT;
+
+// D3D8 structures, laid out as in d3d8types.h
+#[repr(C)]
+#[derive(Default)]
+struct DisplayMode {
+    width: u32,
+    height: u32,
+    refresh_rate: u32,
+    format: u32,
+}
+
+#[repr(C)]
+struct PresentParameters {
+    back_buffer_width: u32,
+    back_buffer_height: u32,
+    back_buffer_format: u32,
+    back_buffer_count: u32,
+    multi_sample_type: u32,
+    swap_effect: u32,
+    device_window: HWND,
+    windowed: BOOL,
+    enable_auto_depth_stencil: BOOL,
+    auto_depth_stencil_format: u32,
+    flags: u32,
+    full_screen_refresh_rate: u32,
+    full_screen_presentation_interval: u32,
+}
+
+#[repr(C)]
+#[derive(Default)]
+struct SurfaceDesc {
+    format: u32,
+    resource_type: u32,
+    usage: u32,
+    pool: u32,
+    size: u32,
+    multi_sample_type: u32,
+    width: u32,
+    height: u32,
+}
+
+#[repr(C)]
+struct LockedRect {
+    pitch: i32,
+    bits: *mut u8,
+}
+
+// The game's Present, called after the line is drawn
+static ORIGINAL_PRESENT: AtomicUsize = AtomicUsize::new(0);
+
+// Status line as a system memory surface in the back buffer's format, rebuilt when the text or format changes
+struct Line {
+    surface: *mut c_void,
+    format: u32,
+    sequence: u32,
+    width: i32,
+    height: i32,
+}
+
+thread_local! {
+    // Present is only ever called on the game's render thread
+    static LINE: RefCell<Option<Line>> = const { RefCell::new(None) };
+}
+
+// COM method `index` of `object`, all of them are stdcall with `this` first
+unsafe fn method<F>(object: *mut c_void, index: usize) -> F {
+    unsafe { mem::transmute_copy(&*(*(object as *const *const usize)).add(index)) }
+}
+
+unsafe fn release(object: *mut c_void) {
+    unsafe { method::<Release>(object, RELEASE)(object) };
+}
+
+// Creating a device means waiting for the loader lock, so the hook is put in on its own thread
+pub fn install() {
+    let shared = detour::shared();
+    if shared.is_null() {
+        return;
+    }
+    // Pointers don't cross threads, the number does
+    let shared = shared as usize;
+    std::thread::spawn(move || {
+        let state = if unsafe { hook_present() } { OSD_HOOKED } else { OSD_FAILED };
+        unsafe { ptr::write_volatile(&mut (*(shared as *mut SharedCamera)).osd_state, state) };
+    });
+}
+
+unsafe fn hook_present() -> bool {
+    let Some(vtable) = (unsafe { device_vtable() }) else { return false };
+    let slot = unsafe { vtable.add(DEVICE_PRESENT) };
+    
+    unsafe {
+        let mut old_protect = 0;
+        if VirtualProtect(slot as *mut _, mem::size_of::<usize>(), PAGE_READWRITE, &mut old_protect) == 0 {
+            return false;
+        }
+        ORIGINAL_PRESENT.store(ptr::read_volatile(slot), Ordering::SeqCst);
+        ptr::write_volatile(slot, present as Present as usize);
+        VirtualProtect(slot as *mut _, mem::size_of::<usize>(), old_protect, &mut old_protect);
+    }
+    true
+}
+
+// Vtable of d3d8.dll's devices, read from a small windowed device made just for that
+unsafe fn device_vtable() -> Option<*mut usize> {
+    unsafe {
+        let d3d8 = LoadLibraryA(c"d3d8.dll".as_ptr());
+        if d3d8.is_null() {
+            return None;
+        }
+        let create = GetProcAddress(d3d8, c"Direct3DCreate8".as_ptr());
+        if create.is_null() {
+            return None;
+        }
+        let d3d = mem::transmute::<FARPROC, Direct3DCreate8>(create)(D3D_SDK_VERSION);
+        if d3d.is_null() {
+            return None;
+        }
+        
+        let mut mode = DisplayMode::default();
+        let window = CreateWindowExA(0, c"STATIC".as_ptr(), c"".as_ptr(), WS_POPUP, 0, 0, 64, 64,
+                                     ptr::null_mut(), ptr::null_mut(), ptr::null_mut(), ptr::null_mut());
+        let mut device = ptr::null_mut();
+        if !window.is_null() && method::<GetAdapterDisplayMode>(d3d, DIRECT3D_GET_ADAPTER_DISPLAY_MODE)(d3d, 0, &mut mode) >= 0 {
+            let mut parameters = PresentParameters {
+                back_buffer_width: 64,
+                back_buffer_height: 64,
+                back_buffer_format: mode.format,
+                back_buffer_count: 1,
+                multi_sample_type: 0,
+                swap_effect: D3DSWAPEFFECT_DISCARD,
+                device_window: window,
+                windowed: TRUE,
+                enable_auto_depth_stencil: 0,
+                auto_depth_stencil_format: 0,
+                flags: 0,
+                full_screen_refresh_rate: 0,
+                full_screen_presentation_interval: 0,
+            };
+            let result = method::<CreateDevice>(d3d, DIRECT3D_CREATE_DEVICE)(
+                d3d, 0, D3DDEVTYPE_HAL, window, D3DCREATE_SOFTWARE_VERTEXPROCESSING, &mut parameters, &mut device,
+            );
+            if result < 0 {
+                device = ptr::null_mut();
+            }
+        }
+        
+        let vtable = (!device.is_null()).then(|| *(device as *const *mut usize));
+        if !device.is_null() {
+            release(device);
+        }
+        release(d3d);
+        if !window.is_null() {
+            DestroyWindow(window);
+        }
+        vtable
+    }
+}
+
+unsafe extern "system" fn present(device: *mut c_void, source: *const RECT, destination: *const RECT,
+                                  window: HWND, dirty: *const c_void) -> HRESULT {
+    unsafe {
+        draw(device);
+        let original: Present = mem::transmute(ORIGINAL_PRESENT.load(Ordering::Relaxed));
+        original(device, source, destination, window, dirty)
+    }
+}
+
+// The latest complete status line and its sequence number, None while the free cam is writing it
+fn read_text(shared: *mut SharedCamera) -> Option<(u32, Vec<u16>)> {
+    unsafe {
+        let before = ptr::read_volatile(&(*shared).osd_sequence);
+        if before & 1 != 0 {
+            return None;
+        }
+        let bytes = ptr::read_volatile(&(*shared).osd_text);
+        if ptr::read_volatile(&(*shared).osd_sequence) != before {
+            return None;
+        }
+        let length = bytes.iter().position(|&byte| byte == 0).unwrap_or(bytes.len());
+        Some((before, String::from_utf8_lossy(&bytes[..length]).encode_utf16().collect()))
+    }
+}
+
+// Copies the status line into the top left corner of the frame about to be shown
+unsafe fn draw(device: *mut c_void) {
+    let shared = detour::shared();
+    
+    unsafe {
+        let mut back_buffer = ptr::null_mut();
+        if method::<GetBackBuffer>(device, DEVICE_GET_BACK_BUFFER)(device, 0, D3DBACKBUFFER_TYPE_MONO, &mut back_buffer) < 0 {
+            return;
+        }
+        let mut desc = SurfaceDesc::default();
+        method::<GetDesc>(back_buffer, SURFACE_GET_DESC)(back_buffer, &mut desc);
+        
+        LINE.with_borrow_mut(|line| {
+            // A half-written line keeps showing the last one
+            if let Some((sequence, text)) = read_text(shared)
+                && line.as_ref().is_none_or(|line| line.sequence != sequence || line.format != desc.format)
+            {
+                if let Some(old) = line.take() {
+                    release(old.surface);
+                }
+                *line = build_line(device, desc.format, sequence, &text);
+            }
+            
+            // Cut to fit on very small back buffers, CopyRects fails on anything sticking out
+            if let Some(line) = line {
+                let width = line.width.min(desc.width as i32 - MARGIN);
+                let height = line.height.min(desc.height as i32 - MARGIN);
+                if width > 0 && height > 0 {
+                    let source = RECT { left: 0, top: 0, right: width, bottom: height };
+                    let point = POINT { x: MARGIN, y: MARGIN };
+                    method::<CopyRects>(device, DEVICE_COPY_RECTS)(device, line.surface, &source, 1, back_buffer, &point);
+                }
+            }
+        });
+        release(back_buffer);
+    }
+}
+
+// White text on a black box, None for an empty line or a back buffer format it can't write
+unsafe fn build_line(device: *mut c_void, format: u32, sequence: u32, text: &[u16]) -> Option<Line> {
+    let formats = [D3DFMT_A8R8G8B8, D3DFMT_X8R8G8B8, D3DFMT_R5G6B5, D3DFMT_X1R5G5B5, D3DFMT_A1R5G5B5];
+    if text.is_empty() || !formats.contains(&format) {
+        return None;
+    }
+    let (width, height, pixels) = unsafe { render_text(text)? };
+    
+    unsafe {
+        let mut surface = ptr::null_mut();
+        if method::<CreateImageSurface>(device, DEVICE_CREATE_IMAGE_SURFACE)(device, width as u32, height as u32, format, &mut surface) < 0 {
+            return None;
+        }
+        let mut locked = LockedRect { pitch: 0, bits: ptr::null_mut() };
+        if method::<LockRect>(surface, SURFACE_LOCK_RECT)(surface, &mut locked, ptr::null(), 0) < 0 {
+            release(surface);
+            return None;
+        }
+        
+        for (y, row) in pixels.chunks(width as usize).enumerate() {
+            let start = locked.bits.add(y * locked.pitch as usize);
+            for (x, &pixel) in row.iter().enumerate() {
+                let (r, g, b) = ((pixel >> 16) & 0xFF, (pixel >> 8) & 0xFF, pixel & 0xFF);
+                match format {
+                    D3DFMT_A8R8G8B8 | D3DFMT_X8R8G8B8 => {
+                        ptr::write_unaligned((start as *mut u32).add(x), 0xFF00_0000 | pixel);
+                    }
+                    D3DFMT_R5G6B5 => {
+                        let value = ((r >> 3) << 11) | ((g >> 2) << 5) | (b >> 3);
+                        ptr::write_unaligned((start as *mut u16).add(x), value as u16);
+                    }
+                    D3DFMT_X1R5G5B5 | D3DFMT_A1R5G5B5 => {
+                        let value = 0x8000 | ((r >> 3) << 10) | ((g >> 3) << 5) | (b >> 3);
+                        ptr::write_unaligned((start as *mut u16).add(x), value as u16);
+                    }
+                    _ => {}
+                }
+            }
+        }
+        method::<UnlockRect>(surface, SURFACE_UNLOCK_RECT)(surface);
+        Some(Line { surface, format, sequence, width, height })
+    }
+}
+
+// Draws `text` with GDI into a 32-bit bitmap, returned as its size and 0x00RRGGBB pixels, top row first
+unsafe fn render_text(text: &[u16]) -> Option<(i32, i32, Vec<u32>)> {
+    unsafe {
+        let dc = CreateCompatibleDC(ptr::null_mut());
+        if dc.is_null() {
+            return None;
+        }
+        // Sharp pixels on the black box, smoothing would only blur it against the black
+        let face: Vec<u16> = "Consolas\0".encode_utf16().collect();
+        let font = CreateFontW(FONT_HEIGHT, 0, 0, 0, FW_BOLD, 0, 0, 0, 0, 0, 0, NONANTIALIASED_QUALITY, 0, face.as_ptr());
+        let old_font = SelectObject(dc, font as *mut _);
+        
+        let mut size = SIZE { cx: 0, cy: 0 };
+        GetTextExtentPoint32W(dc, text.as_ptr(), text.len() as i32, &mut size);
+        let (width, height) = (size.cx + 2 * PADDING, size.cy + 2 * PADDING);
+        
+        let mut info: BITMAPINFO = mem::zeroed();
+        info.bmiHeader.biSize = mem::size_of_val(&info.bmiHeader) as u32;
+        info.bmiHeader.biWidth = width;
+        info.bmiHeader.biHeight = -height; // Top-down
+        info.bmiHeader.biPlanes = 1;
+        info.bmiHeader.biBitCount = 32;
+        info.bmiHeader.biCompression = BI_RGB;
+        let mut bits = ptr::null_mut();
+        let bitmap = CreateDIBSection(dc, &info, DIB_RGB_COLORS, &mut bits, ptr::null_mut(), 0);
+        
+        let mut pixels = None;
+        if !bitmap.is_null() && !bits.is_null() {
+            let old_bitmap = SelectObject(dc, bitmap as *mut _);
+            BitBlt(dc, 0, 0, width, height, ptr::null_mut(), 0, 0, BLACKNESS);
+            SetBkMode(dc, TRANSPARENT as i32);
+            SetTextColor(dc, RGB(255, 255, 255));
+            TextOutW(dc, PADDING, PADDING, text.as_ptr(), text.len() as i32);
+            GdiFlush();
+            
+            pixels = Some(std::slice::from_raw_parts(bits as *const u32, (width * height) as usize).to_vec());
+            SelectObject(dc, old_bitmap);
+            DeleteObject(bitmap as *mut _);
+        }
+        
+        SelectObject(dc, old_font);
+        DeleteObject(font as *mut _);
+        DeleteDC(dc);
+        pixels.map(|pixels| (width, height, pixels))
+    }
+}
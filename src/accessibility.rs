@@ -0,0 +1,131 @@
+// Single-switch control: one key steps through the modes and resting on a mode confirms it,
+// so the whole camera can be flown with a single button.
+
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SwitchMode {
+    Stop,
+    Forward,
+    Backward,
+    Left,
+    Right,
+    Up,
+    Down,
+    TurnLeft,
+    TurnRight,
+    LookUp,
+    LookDown,
+    PatchToggle, // One-shot, switches back to Stop once it ran
+}
+
+// Order the switch steps through, Stop first so a tap always halts the camera
+const MODES: [SwitchMode; 12] = [
+    SwitchMode::Stop,
+    SwitchMode::Forward,
+    SwitchMode::Backward,
+    SwitchMode::Left,
+    SwitchMode::Right,
+    SwitchMode::Up,
+    SwitchMode::Down,
+    SwitchMode::TurnLeft,
+    SwitchMode::TurnRight,
+    SwitchMode::LookUp,
+    SwitchMode::LookDown,
+    SwitchMode::PatchToggle,
+];
+
+impl SwitchMode {
+    pub fn name(self) -> &'static str {
+        match self {
+            SwitchMode::Stop => "Stop",
+            SwitchMode::Forward => "Move forward",
+            SwitchMode::Backward => "Move backward",
+            SwitchMode::Left => "Move left",
+            SwitchMode::Right => "Move right",
+            SwitchMode::Up => "Move up",
+            SwitchMode::Down => "Move down",
+            SwitchMode::TurnLeft => "Turn left",
+            SwitchMode::TurnRight => "Turn right",
+            SwitchMode::LookUp => "Look up",
+            SwitchMode::LookDown => "Look down",
+            SwitchMode::PatchToggle => "Toggle camera patch",
+        }
+    }
+    
+    // Movement as (forward, backward, left, right, up, down) keys
+    pub fn movement(self) -> [bool; 6] {
+        let mut keys = [false; 6];
+        match self {
+            SwitchMode::Forward => keys[0] = true,
+            SwitchMode::Backward => keys[1] = true,
+            SwitchMode::Left => keys[2] = true,
+            SwitchMode::Right => keys[3] = true,
+            SwitchMode::Up => keys[4] = true,
+            SwitchMode::Down => keys[5] = true,
+            _ => {}
+        }
+        keys
+    }
+    
+    // Turn direction as (yaw, pitch), with the same signs as mouse movement
+    pub fn turn(self) -> (f32, f32) {
+        match self {
+            SwitchMode::TurnLeft => (-1.0, 0.0),
+            SwitchMode::TurnRight => (1.0, 0.0),
+            SwitchMode::LookUp => (0.0, -1.0),
+            SwitchMode::LookDown => (0.0, 1.0),
+            _ => (0.0, 0.0),
+        }
+    }
+}
+
+pub struct SwitchControl {
+    dwell: Duration,
+    index: usize,
+    last_press: Option<Instant>, // None once the highlighted mode is confirmed
+    engaged: bool,
+}
+
+impl SwitchControl {
+    pub fn new(dwell_ms: u64) -> Self {
+        Self {
+            dwell: Duration::from_millis(dwell_ms),
+            index: 0,
+            last_press: None,
+            engaged: false,
+        }
+    }
+    
+    pub fn dwell(&self) -> Duration {
+        self.dwell
+    }
+    
+    // A tap stops whatever is running and highlights Stop, further taps step to the next mode
+    pub fn press(&mut self) -> SwitchMode {
+        if self.engaged {
+            self.engaged = false;
+            self.index = 0;
+        } else {
+            self.index = (self.index + 1) % MODES.len();
+        }
+        self.last_press = Some(Instant::now());
+        MODES[self.index]
+    }
+    
+    // Returns the highlighted mode once the switch has been left alone for the dwell time
+    pub fn update(&mut self) -> Option<SwitchMode> {
+        let last_press = self.last_press?;
+        if last_press.elapsed() < self.dwell {
+            return None;
+        }
+        
+        self.last_press = None;
+        let mode = MODES[self.index];
+        self.engaged = !matches!(mode, SwitchMode::Stop | SwitchMode::PatchToggle);
+        if !self.engaged {
+            self.index = 0;
+        }
+        Some(mode)
+    }
+}
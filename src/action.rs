@@ -0,0 +1,96 @@
+use serde::Deserialize;
+
+// Something a hot corner or voice command can trigger, named after the key binding it stands in for
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    MouseToggle,
+    PatchToggle,
+    FreezeToggle,
+    HudToggle,
+    SmoothToggle,
+    StepToggle,
+    OrbitToggle,
+    FollowToggle,
+    FramingToggle,
+    LookAtToggle,
+    TelemetryToggle,
+    JitterToggle,
+    ShakeToggle,
+    OverlayToggle,
+    InstanceNext,
+    MirrorToggle,
+    HoldToggle,
+    SkaterTeleport,
+    CameraToSkater,
+    Screenshot,
+    CopyTransform,
+    PathKeyframe,
+    PathRecord,
+    PathPlay,
+    PlaybackPause,
+    PathSave,
+    PathBake,
+    PathClear,
+    PathNext,
+    BookmarkSave,
+    BookmarkNext,
+    WaypointNext,
+    WaypointPrevious,
+    Undo,
+    Timelapse,
+    Help,
+}
+
+impl Action {
+    // Every action, in declaration order
+    pub const ALL: &[Action] = &[
+        Action::MouseToggle, Action::PatchToggle, Action::FreezeToggle, Action::HudToggle, Action::SmoothToggle, Action::StepToggle,
+        Action::OrbitToggle, Action::FollowToggle, Action::FramingToggle, Action::LookAtToggle,
+        Action::TelemetryToggle, Action::JitterToggle, Action::ShakeToggle, Action::OverlayToggle, Action::InstanceNext, Action::MirrorToggle, Action::HoldToggle, Action::SkaterTeleport,
+        Action::CameraToSkater, Action::Screenshot, Action::CopyTransform, Action::PathKeyframe, Action::PathRecord, Action::PathPlay, Action::PlaybackPause, Action::PathSave,
+        Action::PathBake, Action::PathClear, Action::PathNext, Action::BookmarkSave, Action::BookmarkNext,
+        Action::WaypointNext, Action::WaypointPrevious, Action::Undo, Action::Timelapse, Action::Help,
+    ];
+    
+    pub fn name(self) -> &'static str {
+        match self {
+            Action::MouseToggle => "mouse_toggle",
+            Action::PatchToggle => "patch_toggle",
+            Action::FreezeToggle => "freeze_toggle",
+            Action::HudToggle => "hud_toggle",
+            Action::SmoothToggle => "smooth_toggle",
+            Action::StepToggle => "step_toggle",
+            Action::OrbitToggle => "orbit_toggle",
+            Action::FollowToggle => "follow_toggle",
+            Action::FramingToggle => "framing_toggle",
+            Action::LookAtToggle => "look_at_toggle",
+            Action::TelemetryToggle => "telemetry_toggle",
+            Action::JitterToggle => "jitter_toggle",
+            Action::ShakeToggle => "shake_toggle",
+            Action::OverlayToggle => "overlay_toggle",
+            Action::InstanceNext => "instance_next",
+            Action::MirrorToggle => "mirror_toggle",
+            Action::HoldToggle => "hold_toggle",
+            Action::SkaterTeleport => "skater_teleport",
+            Action::CameraToSkater => "camera_to_skater",
+            Action::Screenshot => "screenshot",
+            Action::CopyTransform => "copy_transform",
+            Action::PathKeyframe => "path_keyframe",
+            Action::PathRecord => "path_record",
+            Action::PathPlay => "path_play",
+            Action::PlaybackPause => "playback_pause",
+            Action::PathSave => "path_save",
+            Action::PathBake => "path_bake",
+            Action::PathClear => "path_clear",
+            Action::PathNext => "path_next",
+            Action::BookmarkSave => "bookmark_save",
+            Action::BookmarkNext => "bookmark_next",
+            Action::WaypointNext => "waypoint_next",
+            Action::WaypointPrevious => "waypoint_previous",
+            Action::Undo => "undo",
+            Action::Timelapse => "timelapse",
+            Action::Help => "help",
+        }
+    }
+}
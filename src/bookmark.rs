@@ -0,0 +1,76 @@
+// Camera bookmarks: poses saved while flying that the camera can go back to. Going to one either jumps
+// straight there or eases the camera over, moving in a straight line and turning with slerp.
+
+use std::time::{Duration, Instant};
+
+use crate::camera::CameraPose;
+use crate::interpolation::blend;
+
+#[derive(Default)]
+pub struct Bookmarks {
+    poses: Vec<CameraPose>,
+    current: Option<usize>, // The bookmark last saved or gone to
+}
+
+impl Bookmarks {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    pub fn len(&self) -> usize {
+        self.poses.len()
+    }
+    
+    pub fn is_empty(&self) -> bool {
+        self.poses.is_empty()
+    }
+    
+    // Saves the camera's pose and returns its number, counting from 1
+    pub fn save(&mut self, pose: CameraPose) -> usize {
+        self.poses.push(pose);
+        self.current = Some(self.poses.len() - 1);
+        self.poses.len()
+    }
+    
+    // The bookmark after the current one, wrapping around to the first, with its number
+    pub fn select_next(&mut self) -> Option<(usize, &CameraPose)> {
+        if self.poses.is_empty() {
+            return None;
+        }
+        let index = self.current.map_or(0, |current| (current + 1) % self.poses.len());
+        self.current = Some(index);
+        Some((index + 1, &self.poses[index]))
+    }
+}
+
+pub struct BookmarkTransition {
+    from: CameraPose,
+    to: CameraPose,
+    started: Instant,
+    duration: Duration,
+}
+
+impl BookmarkTransition {
+    pub fn new(from: CameraPose, to: &CameraPose, duration: Duration) -> Self {
+        Self {
+            from,
+            to: to.clone(),
+            started: Instant::now(),
+            duration,
+        }
+    }
+    
+    fn progress(&self) -> f32 {
+        (self.started.elapsed().as_secs_f32() / self.duration.as_secs_f32().max(0.001)).min(1.0)
+    }
+    
+    // Where the camera should be now, easing in and out like the playback blend-out
+    pub fn pose(&self) -> CameraPose {
+        let t = self.progress();
+        blend(&self.from, &self.to, t * t * (3.0 - 2.0 * t))
+    }
+    
+    pub fn is_finished(&self) -> bool {
+        self.progress() >= 1.0
+    }
+}
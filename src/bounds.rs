@@ -0,0 +1,101 @@
+use serde::Deserialize;
+
+use crate::camera::CameraPosition;
+use crate::interpolation::InterpolationMode;
+use crate::path::CameraPath;
+
+// Seconds between the points checked along a path
+const PATH_CHECK_STEP: f32 = 0.1;
+
+// Box the camera should stay inside for the current level, set in the [bounds] table. Every limit is optional.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct Bounds {
+    pub min: Option<[f32; 3]>, // Lowest X, Y, Z
+    pub max: Option<[f32; 3]>, // Highest X, Y, Z
+    pub min_height: Option<f32>, // Floor the camera shouldn't dip under, on the Y axis
+    pub enforce: bool,           // Keep every camera write inside the limits instead of only warning about paths
+}
+
+// A stretch of a path that leaves the bounds
+#[derive(Debug, Clone)]
+pub struct BoundsViolation {
+    pub start: f32,
+    pub end: f32,
+    pub reason: String, // Why the first point of the stretch is out
+}
+
+impl Bounds {
+    pub fn is_set(&self) -> bool {
+        self.min.is_some() || self.max.is_some() || self.min_height.is_some()
+    }
+    
+    // Why `position` is out of bounds, None when it is inside
+    pub fn check(&self, position: &CameraPosition) -> Option<String> {
+        if let Some(min_height) = self.min_height
+            && position.y < min_height
+        {
+            return Some(format!("Y {:.1} is below min_height {:.1}", position.y, min_height));
+        }
+        
+        let coordinates = [("X", position.x), ("Y", position.y), ("Z", position.z)];
+        for (axis, (name, value)) in coordinates.into_iter().enumerate() {
+            if let Some(min) = self.min
+                && value < min[axis]
+            {
+                return Some(format!("{} {:.1} is below the minimum {:.1}", name, value, min[axis]));
+            }
+            if let Some(max) = self.max
+                && value > max[axis]
+            {
+                return Some(format!("{} {:.1} is above the maximum {:.1}", name, value, max[axis]));
+            }
+        }
+        None
+    }
+    
+    // Moves `position` onto the nearest point inside the limits, returns whether it had to
+    pub fn clamp(&self, position: &mut CameraPosition) -> bool {
+        let original = (position.x, position.y, position.z);
+        let coordinates = [&mut position.x, &mut position.y, &mut position.z];
+        for (axis, value) in coordinates.into_iter().enumerate() {
+            if let Some(min) = self.min {
+                *value = value.max(min[axis]);
+            }
+            if let Some(max) = self.max {
+                *value = value.min(max[axis]);
+            }
+        }
+        if let Some(min_height) = self.min_height {
+            position.y = position.y.max(min_height);
+        }
+        original != (position.x, position.y, position.z)
+    }
+    
+    // Samples `path` as it would play back and returns every stretch that leaves the bounds
+    pub fn check_path(&self, path: &CameraPath, mode: InterpolationMode) -> Vec<BoundsViolation> {
+        let mut violations: Vec<BoundsViolation> = Vec::new();
+        if !self.is_set() || path.is_empty() {
+            return violations;
+        }
+        
+        let steps = (path.duration() / PATH_CHECK_STEP).ceil() as usize;
+        let mut inside = true;
+        for step in 0..=steps {
+            let time = (step as f32 * PATH_CHECK_STEP).min(path.duration());
+            let Some(pose) = path.sample(time, mode) else { break };
+            
+            match self.check(&pose.position) {
+                Some(reason) => {
+                    match violations.last_mut() {
+                        Some(violation) if !inside => violation.end = time,
+                        _ => violations.push(BoundsViolation { start: time, end: time, reason }),
+                    }
+                    inside = false;
+                }
+                None => inside = true,
+            }
+        }
+        violations
+    }
+}
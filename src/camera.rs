@@ -1,3 +1,9 @@
+use std::ops::Range;
+
+use serde::Deserialize;
+
+use crate::math::{EulerRot, Mat3, Mat4, Quat, Vec3};
+
 #[derive(Debug, Clone)]
 pub struct CameraPosition {
     pub x: f32,
@@ -11,13 +17,174 @@ impl CameraPosition {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Quaternion {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+    pub w: f32,
+}
+
+impl Quaternion {
+    pub fn new(x: f32, y: f32, z: f32, w: f32) -> Self {
+        Self { x, y, z, w }
+    }
+    
+    pub fn dot(&self, other: &Quaternion) -> f32 {
+        Quat::from(*self).dot(Quat::from(*other))
+    }
+    
+    pub fn normalize(&self) -> Quaternion {
+        let q = Quat::from(*self);
+        if q.length() < 1e-6 {
+            return Quaternion::new(0.0, 0.0, 0.0, 1.0);
+        }
+        q.normalize().into()
+    }
+    
+    pub fn negate(&self) -> Quaternion {
+        Quaternion::new(-self.x, -self.y, -self.z, -self.w)
+    }
+    
+    pub fn conjugate(&self) -> Quaternion {
+        Quaternion::new(-self.x, -self.y, -self.z, self.w)
+    }
+    
+    pub fn multiply(&self, other: &Quaternion) -> Quaternion {
+        (Quat::from(*self) * Quat::from(*other)).into()
+    }
+    
+    // Rotation by `angle` radians around the unit vector `axis`
+    pub fn from_axis_angle(axis: &CameraPosition, angle: f32) -> Quaternion {
+        Quat::from_axis_angle(axis.into(), angle).into()
+    }
+    
+    // Inverse of `CameraMatrix::to_euler_xyz`, R = Rz * Ry * Rx with angles in radians
+    pub fn from_euler_xyz(x: f32, y: f32, z: f32) -> Quaternion {
+        Quat::from_euler(EulerRot::ZYX, z, y, x).into()
+    }
+    
+    // Logarithm of a unit quaternion (a pure quaternion)
+    pub fn log(&self) -> Quaternion {
+        let w = self.w.clamp(-1.0, 1.0);
+        let theta = w.acos();
+        let sin_theta = theta.sin();
+        if sin_theta.abs() < 1e-6 {
+            return Quaternion::new(0.0, 0.0, 0.0, 0.0);
+        }
+        let scale = theta / sin_theta;
+        Quaternion::new(self.x * scale, self.y * scale, self.z * scale, 0.0)
+    }
+    
+    // Exponential of a pure quaternion (a unit quaternion)
+    pub fn exp(&self) -> Quaternion {
+        let theta = (self.x * self.x + self.y * self.y + self.z * self.z).sqrt();
+        if theta < 1e-6 {
+            return Quaternion::new(self.x, self.y, self.z, 1.0).normalize();
+        }
+        let scale = theta.sin() / theta;
+        Quaternion::new(self.x * scale, self.y * scale, self.z * scale, theta.cos())
+    }
+    
+    // Takes the shortest way around
+    pub fn slerp(&self, other: &Quaternion, t: f32) -> Quaternion {
+        Quat::from(*self).slerp(Quat::from(*other), t).into()
+    }
+    
+    // Inner control point for SQUAD between `prev`, `self` and `next`
+    pub fn squad_control(&self, prev: &Quaternion, next: &Quaternion) -> Quaternion {
+        let inverse = self.conjugate();
+        let to_next = inverse.multiply(next).log();
+        let to_prev = inverse.multiply(prev).log();
+        let sum = Quaternion::new(
+            -(to_next.x + to_prev.x) / 4.0,
+            -(to_next.y + to_prev.y) / 4.0,
+            -(to_next.z + to_prev.z) / 4.0,
+            0.0,
+        );
+        self.multiply(&sum.exp())
+    }
+    
+    // Spherical quadrangle interpolation between `self` and `other` with control points `a` and `b`
+    pub fn squad(&self, other: &Quaternion, a: &Quaternion, b: &Quaternion, t: f32) -> Quaternion {
+        let outer = self.slerp(other, t);
+        let inner = a.slerp(b, t);
+        outer.slerp(&inner, 2.0 * t * (1.0 - t))
+    }
+}
+
+// Where the camera is and which way it faces, without the rest of the game's matrix. Paths, bookmarks and
+// the remote API pass these around, CameraMatrix only comes in when reading or writing the game.
+#[derive(Debug, Clone)]
+pub struct CameraPose {
+    pub position: CameraPosition,
+    pub rotation: Quaternion,
+}
+
+impl CameraPose {
+    pub fn new(position: CameraPosition, rotation: Quaternion) -> Self {
+        Self { position, rotation }
+    }
+    
+    // Same angles as `CameraMatrix::to_euler_xyz`, R = Rz * Ry * Rx in radians
+    pub fn to_euler_xyz(&self) -> (f32, f32, f32) {
+        let (z, y, x) = Quat::from(self.rotation).normalize().to_euler(EulerRot::ZYX);
+        (x, y, z)
+    }
+}
+
+impl From<&CameraMatrix> for CameraPose {
+    fn from(matrix: &CameraMatrix) -> Self {
+        matrix.pose()
+    }
+}
+
+// Which parts of the matrix camera writes touch, set with `write_channels` in config.toml
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WriteChannels {
+    #[default]
+    Full,     // Rotation and position
+    Position, // Translation only, the game keeps aiming the camera
+    Rotation, // Rotation only, the game keeps placing the camera
+}
+
+impl WriteChannels {
+    pub fn name(self) -> &'static str {
+        match self {
+            WriteChannels::Full => "full",
+            WriteChannels::Position => "position",
+            WriteChannels::Rotation => "rotation",
+        }
+    }
+    
+    // Indices into CameraMatrix::data that get written
+    pub fn floats(self) -> Range<usize> {
+        match self {
+            WriteChannels::Full => 0..16,
+            WriteChannels::Position => 12..16,
+            WriteChannels::Rotation => 0..12,
+        }
+    }
+    
+    // One bit per written float, the form the internal hook takes
+    pub fn mask(self) -> u32 {
+        self.floats().fold(0, |mask, i| mask | 1 << i)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct CameraMatrix {
     pub data: [f32; 16], // 4x4 matrix stored as a flat array
 }
 
+impl Default for CameraMatrix {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl CameraMatrix {
-    #[allow(dead_code)]
     pub fn new() -> Self {
         // Identity matrix
         let mut data = [0.0f32; 16];
@@ -28,6 +195,32 @@ impl CameraMatrix {
         Self { data }
     }
     
+    pub fn from_pose(pose: &CameraPose) -> Self {
+        let mut matrix = Self::new();
+        matrix.set_pose(pose);
+        matrix
+    }
+    
+    pub fn pose(&self) -> CameraPose {
+        CameraPose::new(self.get_position(), self.to_quaternion())
+    }
+    
+    // Writes the pose's rotation and position, leaving the other elements untouched
+    pub fn set_pose(&mut self, pose: &CameraPose) {
+        self.set_rotation(&pose.rotation);
+        self.set_position(&pose.position);
+    }
+    
+    // Lengths of the right, up and forward rows, all close to 1 for a real camera matrix
+    pub fn rotation_row_lengths(&self) -> [f32; 3] {
+        self.rotation_matrix().to_cols_array_2d().map(|row| Vec3::from(row).length())
+    }
+    
+    pub fn looks_like_camera(&self) -> bool {
+        self.rotation_row_lengths().iter().all(|length| (length - 1.0).abs() < 0.01)
+            && self.data[12..15].iter().all(|value| value.is_finite())
+    }
+    
     pub fn get_position(&self) -> CameraPosition {
         CameraPosition::new(self.data[12], self.data[13], self.data[14])
     }
@@ -38,101 +231,92 @@ impl CameraMatrix {
         self.data[14] = pos.z;
     }
     
+    // Rows 0/1/2 of the flat array, the right, up and forward axes, as the columns of a rotation matrix
+    pub fn rotation_matrix(&self) -> Mat3 {
+        Mat3::from_mat4(Mat4::from(self))
+    }
+    
+    // Replaces the rotation, the position and the unused fourth floats stay as they are
+    pub fn set_rotation_matrix(&mut self, rotation: &Mat3) {
+        for (row, axis) in rotation.to_cols_array_2d().iter().enumerate() {
+            self.data[row * 4..row * 4 + 3].copy_from_slice(axis);
+        }
+    }
+    
+    // Pitch around the world's X-axis, keeping the position
     pub fn apply_pitch(&mut self, angle: f32) {
-        // Apply pitch rotation around X-axis
-        let cos_a = angle.cos();
-        let sin_a = angle.sin();
-        
-        // Save current matrix
-        let old_matrix = self.data.clone();
-        
-        // Apply pitch rotation to the rotation part of the matrix
-        // Only modify the rotation components, keep position intact
-        let new_right_y = old_matrix[1] * cos_a - old_matrix[2] * sin_a;
-        let new_right_z = old_matrix[1] * sin_a + old_matrix[2] * cos_a;
-        
-        let new_up_y = old_matrix[5] * cos_a - old_matrix[6] * sin_a;
-        let new_up_z = old_matrix[5] * sin_a + old_matrix[6] * cos_a;
-        
-        let new_forward_y = old_matrix[9] * cos_a - old_matrix[10] * sin_a;
-        let new_forward_z = old_matrix[9] * sin_a + old_matrix[10] * cos_a;
-        
-        // Update the matrix
-        self.data[1] = new_right_y;
-        self.data[2] = new_right_z;
-        self.data[5] = new_up_y;
-        self.data[6] = new_up_z;
-        self.data[9] = new_forward_y;
-        self.data[10] = new_forward_z;
+        self.set_rotation_matrix(&(Mat3::from_rotation_x(angle) * self.rotation_matrix()));
     }
     
+    // Yaw around the world's Y-axis, keeping the position
     pub fn apply_yaw(&mut self, angle: f32) {
-        // Apply yaw rotation around Y-axis
-        let cos_a = angle.cos();
-        let sin_a = angle.sin();
-        
-        // Save current matrix
-        let old_matrix = self.data.clone();
-        
-        // Apply yaw rotation to the rotation part of the matrix
-        // Only modify the rotation components, keep position intact
-        let new_right_x = old_matrix[0] * cos_a + old_matrix[2] * sin_a;
-        let new_right_z = -old_matrix[0] * sin_a + old_matrix[2] * cos_a;
-        
-        let new_up_x = old_matrix[4] * cos_a + old_matrix[6] * sin_a;
-        let new_up_z = -old_matrix[4] * sin_a + old_matrix[6] * cos_a;
-        
-        let new_forward_x = old_matrix[8] * cos_a + old_matrix[10] * sin_a;
-        let new_forward_z = -old_matrix[8] * sin_a + old_matrix[10] * cos_a;
-        
-        // Update the matrix
-        self.data[0] = new_right_x;
-        self.data[2] = new_right_z;
-        self.data[4] = new_up_x;
-        self.data[6] = new_up_z;
-        self.data[8] = new_forward_x;
-        self.data[10] = new_forward_z;
+        self.set_rotation_matrix(&(Mat3::from_rotation_y(angle) * self.rotation_matrix()));
     }
     
+    // Moves along the camera's own right, up and forward axes
     pub fn apply_translation(&mut self, dx: f32, dy: f32, dz: f32) {
-        // Apply translation based on current rotation
-        let forward_x = self.data[8];
-        let forward_y = self.data[9];
-        let forward_z = self.data[10];
-        
-        let right_x = self.data[0];
-        let right_y = self.data[1];
-        let right_z = self.data[2];
-        
-        let up_x = self.data[4];
-        let up_y = self.data[5];
-        let up_z = self.data[6];
-        
-        self.data[12] += dx * right_x + dy * up_x + dz * forward_x;
-        self.data[13] += dx * right_y + dy * up_y + dz * forward_y;
-        self.data[14] += dx * right_z + dy * up_z + dz * forward_z;
+        let position = Vec3::from(&self.get_position()) + self.rotation_matrix() * Vec3::new(dx, dy, dz);
+        self.set_position(&position.into());
     }
     
+    // `b` applied after `a`
     pub fn multiply_matrix(&mut self, a: &[f32; 16], b: &[f32; 16]) {
-        let mut result = [0.0f32; 16];
+        self.data = (Mat4::from_cols_array(b) * Mat4::from_cols_array(a)).to_cols_array();
+    }
+    
+    // Decomposes R = Rz * Ry * Rx into (x, y, z) angles in radians
+    pub fn to_euler_xyz(&self) -> (f32, f32, f32) {
+        let (z, y, x) = self.rotation_matrix().to_euler(EulerRot::ZYX);
+        (x, y, z)
+    }
+    
+    pub fn to_quaternion(&self) -> Quaternion {
+        Quaternion::from(Quat::from_mat3(&self.rotation_matrix())).normalize()
+    }
+    
+    pub fn set_rotation(&mut self, q: &Quaternion) {
+        self.set_rotation_matrix(&Mat3::from_quat(q.normalize().into()));
+    }
+    
+    // Turns the camera by `yaw` around the world's up axis and `pitch` around its own right axis, in radians,
+    // the way mouse look does. Working on the quaternion keeps any roll, and pitch stops just short of
+    // straight up or down so the view can't flip over.
+    pub fn turn(&mut self, yaw: f32, pitch: f32) {
+        let limit = std::f32::consts::FRAC_PI_2 * 0.99;
+        let elevation = (-self.data[9]).clamp(-1.0, 1.0).asin();
+        let pitch = (elevation + pitch).clamp(-limit, limit) - elevation;
         
-        for i in 0..4 {
-            for j in 0..4 {
-                for k in 0..4 {
-                    result[i * 4 + j] += a[i * 4 + k] * b[k * 4 + j];
-                }
-            }
+        let right = self.rotation_matrix().x_axis;
+        let around_up = Quat::from_axis_angle(Vec3::Y, -yaw);
+        let around_right = Quat::from_axis_angle(right.normalize_or(Vec3::X), pitch);
+        self.set_rotation(&(around_up * around_right * Quat::from(self.to_quaternion())).into());
+    }
+    
+    // Rolls the camera by `angle` radians around its own forward axis, keeping the position
+    pub fn roll(&mut self, angle: f32) {
+        let forward = self.rotation_matrix().z_axis;
+        let around_forward = Quat::from_axis_angle(forward.normalize_or(Vec3::Z), angle);
+        self.set_rotation(&(around_forward * Quat::from(self.to_quaternion())).into());
+    }
+    
+    // Turns the camera so the view direction (the one the forward key moves along) points at `target`.
+    // The horizon stays level; nothing changes when the target is the camera position itself.
+    pub fn look_at(&mut self, target: &CameraPosition) {
+        let offset = Vec3::from(target) - Vec3::from(&self.get_position());
+        if offset.length() < 1e-6 {
+            return;
         }
+        let forward = offset.normalize();
         
-        self.data = result;
+        // Right axis is world up crossed with the view direction, keep the old one when looking straight up or down
+        let right = Vec3::Y.cross(forward);
+        let right = if right.length() > 1e-6 { right.normalize() } else { Vec3::new(self.data[0], 0.0, self.data[2]) };
+        let up = forward.cross(right);
+        self.set_rotation_matrix(&Mat3::from_cols(right, up, forward));
     }
     
+    // The view direction, the same one the forward key moves along and look_at points
     pub fn get_forward(&self) -> CameraPosition {
-        // Forward vector is the negative Z axis (third column, negated)
-        CameraPosition {
-            x: -self.data[8],
-            y: -self.data[9],
-            z: -self.data[10],
-        }
+        self.rotation_matrix().z_axis.into()
     }
 }
@@ -0,0 +1,130 @@
+// Cheat Engine tables (.CT) are how pointer chains get shared on forums. Only what offsets.toml needs is read
+// from the XML: each entry's description, its address relative to the game's executable and its offsets.
+// Everything else in the file, scripts included, is skipped.
+
+use crate::offsets::PointerChain;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CheatEntry {
+    pub description: String,
+    pub chain: PointerChain,
+}
+
+pub fn load_cheat_table(path: &str) -> Result<(Vec<CheatEntry>, Vec<String>), String> {
+    let contents = std::fs::read_to_string(path).map_err(|e| format!("Failed to read '{}': {}", path, e))?;
+    parse_cheat_table(&contents, path)
+}
+
+// The entries that are pointer chains from the executable, and why the others were skipped. `path` only names
+// the source in error messages.
+pub fn parse_cheat_table(contents: &str, path: &str) -> Result<(Vec<CheatEntry>, Vec<String>), String> {
+    if !contents.contains("<CheatTable") {
+        return Err(format!("'{}' isn't a Cheat Engine table", path));
+    }
+    
+    let mut entries = Vec::new();
+    let mut skipped = Vec::new();
+    let mut rest = contents;
+    while let Some(start) = rest.find("<CheatEntry>") {
+        rest = &rest[start + "<CheatEntry>".len()..];
+        // Groups hold their entries inside their own, so an entry's fields end where a nested one starts
+        let end = [rest.find("<CheatEntry>"), rest.find("</CheatEntry>")].into_iter().flatten().min().unwrap_or(rest.len());
+        let entry = &rest[..end];
+        
+        let description = element(entry, "Description").map(|text| unescape(text).trim_matches('"').to_string()).unwrap_or_default();
+        let Some(address) = element(entry, "Address") else {
+            continue; // A group header or a script, nothing to read
+        };
+        match parse_entry(&unescape(address), element(entry, "Offsets")) {
+            Ok(chain) => entries.push(CheatEntry { description, chain }),
+            Err(e) => skipped.push(format!("'{}': {}", description, e)),
+        }
+    }
+    Ok((entries, skipped))
+}
+
+// `"Skate3.exe"+0030A1B4` and the <Offset> elements, which Cheat Engine lists last one first
+fn parse_entry(address: &str, offsets: Option<&str>) -> Result<PointerChain, String> {
+    let (module, base) = address.split_once('+')
+        .filter(|(module, _)| module.trim().trim_matches('"').to_lowercase().ends_with(".exe"))
+        .ok_or_else(|| format!("{} isn't relative to the game's executable", address.trim()))?;
+    let base = parse_hex(base).ok_or_else(|| format!("{} isn't an offset from {}", base.trim(), module.trim()))?;
+    
+    let mut chain: Vec<usize> = Vec::new();
+    let mut rest = offsets.unwrap_or_default();
+    while let Some(offset) = element(rest, "Offset") {
+        chain.push(parse_hex(offset).ok_or_else(|| format!("{} isn't an offset", offset.trim()))?);
+        rest = &rest[rest.find("</Offset>").map_or(rest.len(), |end| end + "</Offset>".len())..];
+    }
+    chain.reverse();
+    
+    // Cheat Engine reads a pointer before every offset, PointerChain adds its last offset without reading
+    if !chain.is_empty() {
+        chain.push(0);
+    }
+    Ok(PointerChain { base, offsets: chain })
+}
+
+fn parse_hex(text: &str) -> Option<usize> {
+    usize::from_str_radix(text.trim().trim_start_matches("0x").trim_start_matches("0X"), 16).ok()
+}
+
+// The text of the first <name> element in `xml`
+fn element<'a>(xml: &'a str, name: &str) -> Option<&'a str> {
+    let start = xml.find(&format!("<{}>", name))? + name.len() + 2;
+    let end = xml[start..].find(&format!("</{}>", name))?;
+    Some(&xml[start..start + end])
+}
+
+fn unescape(text: &str) -> String {
+    text.replace("&quot;", "\"").replace("&apos;", "'").replace("&lt;", "<").replace("&gt;", ">").replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    const TABLE: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<CheatTable CheatEngineTableVersion="45">
+  <CheatEntries>
+    <CheatEntry>
+      <ID>0</ID>
+      <Description>"Camera"</Description>
+      <GroupHeader>1</GroupHeader>
+      <CheatEntries>
+        <CheatEntry>
+          <ID>1</ID>
+          <Description>"Camera X"</Description>
+          <VariableType>Float</VariableType>
+          <Address>"Skate3.exe"+0030A1B4</Address>
+          <Offsets>
+            <Offset>2F4</Offset>
+            <Offset>8</Offset>
+          </Offsets>
+        </CheatEntry>
+        <CheatEntry>
+          <ID>2</ID>
+          <Description>"Timer"</Description>
+          <Address>Skate3.exe+1000</Address>
+        </CheatEntry>
+        <CheatEntry>
+          <ID>3</ID>
+          <Description>"Heap value"</Description>
+          <Address>0A2B3C4D</Address>
+        </CheatEntry>
+      </CheatEntries>
+    </CheatEntry>
+  </CheatEntries>
+</CheatTable>"#;
+
+    #[test]
+    fn reads_chains_from_the_executable() {
+        let (entries, skipped) = parse_cheat_table(TABLE, "test.CT").unwrap();
+        assert_eq!(entries, vec![
+            CheatEntry { description: "Camera X".to_string(), chain: PointerChain { base: 0x30A1B4, offsets: vec![0x8, 0x2F4, 0x0] } },
+            CheatEntry { description: "Timer".to_string(), chain: PointerChain { base: 0x1000, offsets: vec![] } },
+        ]);
+        assert_eq!(skipped.len(), 1);
+        assert!(parse_cheat_table("base = 0x1", "offsets.toml").is_err());
+    }
+}
@@ -0,0 +1,147 @@
+// Splits long recordings into shorter path files with a manifest, so an hour of exploration can be
+// played back, edited and shared a piece at a time. Cuts go where the camera was nearly still.
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::path::{CameraPath, Keyframe};
+
+// Bumped whenever the manifest layout changes in a way older builds can't read
+pub const MANIFEST_FORMAT_VERSION: u32 = 1;
+
+// Cuts are searched from this fraction of the chunk length up to the full length
+const EARLIEST_CUT: f32 = 0.5;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChunkEntry {
+    pub file: String,   // Relative to the manifest
+    pub start: f32,     // Seconds into the original recording
+    pub duration: f32,
+    pub keyframes: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChunkManifest {
+    #[serde(default)]
+    format_version: u32,
+    pub source: String,    // File the chunks were split from
+    pub duration: f32,
+    #[serde(default, rename = "chunk")]
+    pub chunks: Vec<ChunkEntry>,
+}
+
+// Camera speed through keyframe `index`, in units per second
+fn speed_at(keyframes: &[Keyframe], index: usize) -> f32 {
+    let before = &keyframes[index.saturating_sub(1)];
+    let after = &keyframes[(index + 1).min(keyframes.len() - 1)];
+    let elapsed = after.time - before.time;
+    if elapsed <= 0.0 {
+        return 0.0;
+    }
+    
+    let distance = (0..3).map(|axis| (after.position[axis] - before.position[axis]).powi(2)).sum::<f32>().sqrt();
+    distance / elapsed
+}
+
+// Keyframe indices to cut at, each one the slowest point in its search window
+fn cut_points(keyframes: &[Keyframe], chunk_length: f32) -> Vec<usize> {
+    let mut cuts = Vec::new();
+    let Some(last) = keyframes.last() else { return cuts };
+    
+    let mut start = 0;
+    while last.time - keyframes[start].time > chunk_length {
+        let earliest = keyframes[start].time + chunk_length * EARLIEST_CUT;
+        let latest = keyframes[start].time + chunk_length;
+        let cut = (start + 1..keyframes.len() - 1)
+            .filter(|&index| (earliest..=latest).contains(&keyframes[index].time))
+            .min_by(|&a, &b| speed_at(keyframes, a).total_cmp(&speed_at(keyframes, b)))
+            // Sparse keyframes can leave the window empty, cut at the first one past it instead
+            .unwrap_or_else(|| keyframes.iter().position(|keyframe| keyframe.time > latest).unwrap_or(keyframes.len() - 1));
+        if cut >= keyframes.len() - 1 {
+            break;
+        }
+        cuts.push(cut);
+        start = cut;
+    }
+    cuts
+}
+
+// Splits `path` into chunks of at most about `chunk_length` seconds. Neighbouring chunks share
+// the keyframe at the cut, so each one plays from where the previous one stopped.
+pub fn split_path(path: &CameraPath, chunk_length: f32) -> Vec<(f32, CameraPath)> {
+    let keyframes = &path.keyframes;
+    if keyframes.is_empty() {
+        return Vec::new();
+    }
+    
+    let mut bounds = vec![0];
+    bounds.extend(cut_points(keyframes, chunk_length));
+    bounds.push(keyframes.len().saturating_sub(1));
+    
+    bounds.windows(2).map(|pair| {
+        let start = keyframes[pair[0]].time;
+        let mut chunk = CameraPath::from_keyframes(keyframes[pair[0]..=pair[1]].iter().map(|keyframe| Keyframe {
+            time: keyframe.time - start,
+            ..keyframe.clone()
+        }).collect());
+        chunk.metadata = path.metadata.clone();
+        (start, chunk)
+    }).collect()
+}
+
+// Writes the chunks as `<stem>_001.toml`, `<stem>_002.toml`, ... next to `manifest_file`
+pub fn write_chunks(path: &CameraPath, chunk_length: f32, source: &str, manifest_file: &str) -> Result<ChunkManifest, String> {
+    let directory = Path::new(manifest_file).parent().unwrap_or(Path::new(""));
+    let stem = Path::new(source).file_stem().and_then(|stem| stem.to_str()).unwrap_or("chunk");
+    
+    let mut manifest = ChunkManifest {
+        format_version: MANIFEST_FORMAT_VERSION,
+        source: source.to_string(),
+        duration: path.duration(),
+        chunks: Vec::new(),
+    };
+    for (number, (start, chunk)) in split_path(path, chunk_length).into_iter().enumerate() {
+        let file = format!("{}_{:03}.toml", stem, number + 1);
+        chunk.save(&directory.join(&file).to_string_lossy())?;
+        manifest.chunks.push(ChunkEntry { file, start, duration: chunk.duration(), keyframes: chunk.len() });
+    }
+    
+    let contents = toml::to_string(&manifest).map_err(|e| format!("Failed to serialize chunk manifest: {}", e))?;
+    std::fs::write(manifest_file, contents).map_err(|e| format!("Failed to write '{}': {}", manifest_file, e))?;
+    Ok(manifest)
+}
+
+// Loads the chunks listed in a manifest back into one path on the original timeline
+pub fn join_chunks(manifest_file: &str) -> Result<CameraPath, String> {
+    let contents = std::fs::read_to_string(manifest_file).map_err(|e| format!("Failed to read '{}': {}", manifest_file, e))?;
+    let manifest: ChunkManifest = toml::from_str(&contents).map_err(|e| format!("Failed to parse '{}': {}", manifest_file, e))?;
+    if manifest.format_version > MANIFEST_FORMAT_VERSION {
+        return Err(format!(
+            "'{}' uses manifest format {}, but this build only reads up to format {} - update the tool to load it",
+            manifest_file, manifest.format_version, MANIFEST_FORMAT_VERSION
+        ));
+    }
+    
+    let directory = Path::new(manifest_file).parent().unwrap_or(Path::new(""));
+    let mut joined = CameraPath::default();
+    for entry in &manifest.chunks {
+        let chunk = CameraPath::load(&directory.join(&entry.file).to_string_lossy())?;
+        if joined.is_empty() {
+            joined.metadata = chunk.metadata.clone();
+        }
+        for keyframe in chunk.keyframes {
+            let time = keyframe.time + entry.start;
+            // The keyframe at each cut is in both chunks
+            if joined.keyframes.last().is_some_and(|last| time <= last.time) {
+                continue;
+            }
+            joined.keyframes.push(Keyframe { time, ..keyframe });
+        }
+    }
+    
+    if joined.is_empty() {
+        return Err(format!("'{}' lists no chunks", manifest_file));
+    }
+    Ok(joined)
+}
@@ -0,0 +1,197 @@
+use clap::{Parser, Subcommand, ValueEnum};
+
+use thps3_free_cam::convert::PathFormat;
+use thps3_free_cam::offsets::OFFSETS_FILE;
+use thps3_free_cam::schedule::StartTime;
+
+#[derive(Debug, Parser)]
+#[command(name = "thps3_free_cam", version, about = "Free camera tool for Tony Hawk's Pro Skater 3")]
+pub struct Cli {
+    /// Keybindings and default settings
+    #[arg(long, global = true, default_value = thps3_free_cam::config::CONFIG_FILE)]
+    pub config: String,
+    
+    /// Inject the hook DLL and write the camera from inside the game loop (32-bit builds only)
+    #[arg(long, global = true)]
+    pub internal: bool,
+    
+    /// Also attach to every other running copy of the game, to switch between them or mirror the camera into them
+    #[arg(long, global = true)]
+    pub all_instances: bool,
+    
+    /// Run headless: attach and take JSON line requests on daemon_port until told to quit, for frontends in other languages
+    #[arg(long, global = true)]
+    pub daemon: bool,
+    
+    /// Log every memory read and write to thps3_free_cam.log, not just attaching, patches and errors
+    #[arg(long, global = true)]
+    pub verbose: bool,
+    
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Find the game by name and start the interactive free cam (default)
+    Run,
+    /// Attach to a specific process ID and start the interactive free cam, without --pid pick the process from a list
+    Attach {
+        #[arg(long)]
+        pid: Option<u32>,
+    },
+    /// Print the current camera position and matrix, then exit
+    DumpMatrix,
+    /// Move the camera to the given position, then exit
+    SetPos {
+        /// X Y Z as three values, or as one quoted value pasted from elsewhere, e.g. "1,5; -2; 30"
+        #[arg(allow_hyphen_values = true, num_args = 1..=3, required = true)]
+        position: Vec<String>,
+    },
+    /// Apply or restore the camera write patch, then exit
+    Patch {
+        #[arg(value_enum)]
+        state: PatchState,
+    },
+    /// Experimental: estimate the distance to the geometry in the middle of the screen
+    Probe {
+        /// Furthest distance to search
+        #[arg(long, default_value_t = 5000.0)]
+        max: f32,
+        /// Binary search steps, each one moves the camera once
+        #[arg(long, default_value_t = 12)]
+        iterations: u32,
+    },
+    /// Print every step of the camera pointer chain once a second, to see where it breaks
+    Diagnose {
+        /// Offsets file whose chains (FOV, skater, ...) are traced as well
+        #[arg(long, default_value = OFFSETS_FILE)]
+        offsets: String,
+    },
+    /// Read the camera and skater at a steady rate and print them as JSON lines, without ever writing to the game
+    Spectate {
+        /// Readings per second
+        #[arg(long, default_value_t = 30.0)]
+        rate: f32,
+        /// Also stream the readings over WebSocket on this port, commands are turned away
+        #[arg(long)]
+        port: Option<u16>,
+        /// Don't print the readings, e.g. when only the WebSocket stream is wanted
+        #[arg(long)]
+        quiet: bool,
+        /// Offsets file with the skater and FOV chains
+        #[arg(long, default_value = OFFSETS_FILE)]
+        offsets: String,
+    },
+    /// Find the camera in memory when the built-in pointer chain doesn't work on this game build
+    Scan {
+        /// Most pointers in a chain
+        #[arg(long, default_value_t = 6)]
+        depth: usize,
+        /// Largest offset from a pointer to the next address in the chain
+        #[arg(long, default_value_t = 4096)]
+        max_offset: usize,
+    },
+    /// Read everything attaching needs once and save that memory, for the smoke example to replay without the game
+    RecordImage {
+        /// Memory image to write, the offsets file is copied next to it with a .toml extension
+        output: String,
+        /// Offsets file whose chains (FOV, skater, ...) are recorded as well
+        #[arg(long, default_value = OFFSETS_FILE)]
+        offsets: String,
+    },
+    /// Print the pointer chains in a Cheat Engine table (.CT) the way offsets.toml writes them
+    ImportTable {
+        file: String,
+    },
+    /// Start the interactive free cam using a different offsets file
+    Profile {
+        file: String,
+    },
+    /// Start the interactive free cam with a camera animation made in another program (CSV, .chan or Blender JSON) as the path
+    Play {
+        file: String,
+        /// Input format, guessed from the file extension when omitted
+        #[arg(long, value_enum)]
+        from: Option<PathFormat>,
+        /// Frame rate for .chan files and CSV files with a frame column
+        #[arg(long, default_value_t = 60.0)]
+        fps: f32,
+        /// Local time to start playing at, HH:MM or HH:MM:SS. Without it the path play key starts it
+        #[arg(long, value_parser = StartTime::parse)]
+        at: Option<StartTime>,
+    },
+    /// Work with camera path files
+    Path {
+        #[command(subcommand)]
+        action: PathCommand,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum PathCommand {
+    /// Convert between path files (.toml), telemetry CSV, Blender JSON and .chan
+    Convert {
+        input: String,
+        output: String,
+        /// Input format, guessed from the file extension when omitted
+        #[arg(long, value_enum)]
+        from: Option<PathFormat>,
+        /// Output format, guessed from the file extension when omitted
+        #[arg(long, value_enum)]
+        to: Option<PathFormat>,
+        /// Frame rate for .chan input and for per-frame output
+        #[arg(long, default_value_t = 60.0)]
+        fps: f32,
+    },
+    /// Split a long recording into chunks at moments the camera was nearly still, with a manifest listing them
+    Split {
+        input: String,
+        /// Manifest to write, the chunks go next to it. Defaults to <input>_chunks.toml
+        #[arg(long)]
+        manifest: Option<String>,
+        /// Longest chunk in seconds
+        #[arg(long, default_value_t = 300.0)]
+        chunk_length: f32,
+        /// Input format, guessed from the file extension when omitted
+        #[arg(long, value_enum)]
+        from: Option<PathFormat>,
+        /// Frame rate for .chan input
+        #[arg(long, default_value_t = 60.0)]
+        fps: f32,
+    },
+    /// Put the chunks listed in a manifest back together into one path file
+    Join {
+        manifest: String,
+        output: String,
+    },
+    /// Cut a path file down to the stretch between two times, e.g. the usable part of a recorded move
+    Trim {
+        input: String,
+        output: String,
+        /// Seconds into the path where the kept part starts
+        #[arg(long, default_value_t = 0.0)]
+        start: f32,
+        /// Seconds into the path where the kept part ends, defaults to the end of the path
+        #[arg(long)]
+        end: Option<f32>,
+    },
+    /// Set the ease handles of a keyframe in a path file, so the camera speeds up and slows down gently around it
+    Ease {
+        file: String,
+        /// Keyframe number, counting from 1
+        keyframe: usize,
+        /// How gently the camera slows down into the keyframe, 0 to 1
+        #[arg(long = "in", default_value_t = 0.0)]
+        ease_in: f32,
+        /// How gently the camera speeds up out of the keyframe, 0 to 1
+        #[arg(long = "out", default_value_t = 0.0)]
+        ease_out: f32,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum PatchState {
+    On,
+    Off,
+}
@@ -0,0 +1,544 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use serde::Deserialize;
+
+use crate::action::Action;
+use crate::bounds::Bounds;
+use crate::camera::WriteChannels;
+use crate::gamepad::GamepadConfig;
+use crate::input::{
+    Key, SpaceMouseConfig, VK_A, VK_ADD, VK_B, VK_BACK, VK_C, VK_D, VK_E, VK_END, VK_F, VK_F1, VK_F2, VK_F3, VK_F4, VK_F5, VK_F6, VK_F7, VK_F8, VK_F9, VK_F10, VK_F11, VK_F12, VK_G, VK_H, VK_HOME, VK_I, VK_INSERT, VK_J, VK_K, VK_L, VK_M, VK_MULTIPLY, VK_N,
+    VK_CONTROL, VK_NEXT, VK_O, VK_OEM_1, VK_OEM_2, VK_OEM_3, VK_OEM_4, VK_OEM_5, VK_OEM_6, VK_OEM_7, VK_OEM_COMMA, VK_OEM_MINUS, VK_OEM_PERIOD, VK_OEM_PLUS, VK_P, VK_PRIOR, VK_Q, VK_R, VK_SHIFT, VK_SPACE, VK_SUBTRACT, VK_T, VK_TAB, VK_U,
+    VK_V, VK_X, VK_Y, VK_Z,
+};
+use crate::interpolation::InterpolationMode;
+use crate::midi::MidiConfig;
+use crate::playback::PlaybackRepeat;
+use crate::rumble::RumbleConfig;
+
+pub const CONFIG_FILE: &str = "config.toml";
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct KeyBindings {
+    pub forward: Key,
+    pub backward: Key,
+    pub left: Key,
+    pub right: Key,
+    pub up: Key,
+    pub down: Key,
+    pub mouse_toggle: Key,
+    pub patch_toggle: Key,
+    pub freeze_toggle: Key,
+    pub hud_toggle: Key,
+    pub smooth_toggle: Key,
+    pub step_toggle: Key,
+    pub orbit_toggle: Key,
+    pub follow_toggle: Key,
+    pub framing_toggle: Key,
+    pub look_at_toggle: Key,
+    pub telemetry_toggle: Key,
+    pub jitter_toggle: Key,
+    pub shake_toggle: Key,
+    pub overlay_toggle: Key,
+    pub instance_next: Key,
+    pub mirror_toggle: Key,
+    pub hold_toggle: Key,
+    pub skater_teleport: Key,
+    pub camera_to_skater: Key,
+    pub speed_up: Key,
+    pub speed_down: Key,
+    pub sprint: Key,
+    pub slow: Key,
+    pub zoom_in: Key,
+    pub zoom_out: Key,
+    pub zoom_reset: Key,
+    pub tunable_next: Key,
+    pub tunable_increase: Key,
+    pub tunable_decrease: Key,
+    pub tunable_release: Key,
+    pub path_keyframe: Key,
+    pub path_record: Key,
+    pub path_play: Key,
+    pub playback_pause: Key,
+    pub playback_faster: Key,
+    pub playback_slower: Key,
+    pub playback_repeat: Key,
+    pub scrub_back: Key,
+    pub scrub_forward: Key,
+    pub path_clear: Key,
+    pub path_save: Key,
+    pub path_bake: Key,
+    pub path_next: Key,
+    pub bookmark_save: Key,
+    pub bookmark_next: Key,
+    pub waypoint_next: Key,
+    pub waypoint_previous: Key,
+    pub undo: Key,
+    pub switch_access: Key,
+    pub screenshot: Key,
+    pub copy_transform: Key,
+    pub timelapse: Key,
+    pub help: Key,
+    pub palette: Key,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        Self {
+            forward: Key(VK_I),
+            backward: Key(VK_K),
+            left: Key(VK_J),
+            right: Key(VK_L),
+            up: Key(VK_U),
+            down: Key(VK_O),
+            mouse_toggle: Key(VK_M),
+            patch_toggle: Key(VK_P),
+            freeze_toggle: Key(VK_F),
+            hud_toggle: Key(VK_E),
+            smooth_toggle: Key(VK_N),
+            step_toggle: Key(VK_H),
+            orbit_toggle: Key(VK_R),
+            follow_toggle: Key(VK_G),
+            framing_toggle: Key(VK_X),
+            look_at_toggle: Key(VK_V),
+            telemetry_toggle: Key(VK_T),
+            jitter_toggle: Key(VK_B),
+            shake_toggle: Key(VK_Z),
+            overlay_toggle: Key(VK_Q),
+            instance_next: Key(VK_D),
+            mirror_toggle: Key(VK_A),
+            hold_toggle: Key(VK_END),
+            skater_teleport: Key(VK_Y),
+            camera_to_skater: Key(VK_HOME),
+            speed_up: Key(VK_PRIOR),
+            speed_down: Key(VK_NEXT),
+            sprint: Key(VK_SHIFT),
+            slow: Key(VK_CONTROL),
+            zoom_in: Key(VK_OEM_6),
+            zoom_out: Key(VK_OEM_4),
+            zoom_reset: Key(VK_OEM_5),
+            tunable_next: Key(VK_TAB),
+            tunable_increase: Key(VK_ADD),
+            tunable_decrease: Key(VK_SUBTRACT),
+            tunable_release: Key(VK_MULTIPLY),
+            path_keyframe: Key(VK_F5),
+            path_record: Key(VK_F12),
+            path_play: Key(VK_F6),
+            playback_pause: Key(VK_OEM_2),
+            playback_faster: Key(VK_OEM_PLUS),
+            playback_slower: Key(VK_OEM_MINUS),
+            playback_repeat: Key(VK_INSERT),
+            scrub_back: Key(VK_OEM_1),
+            scrub_forward: Key(VK_OEM_7),
+            path_clear: Key(VK_F7),
+            path_save: Key(VK_F8),
+            path_bake: Key(VK_F9),
+            path_next: Key(VK_F3),
+            bookmark_save: Key(VK_F2),
+            bookmark_next: Key(VK_F4),
+            waypoint_next: Key(VK_OEM_PERIOD),
+            waypoint_previous: Key(VK_OEM_COMMA),
+            undo: Key(VK_BACK),
+            switch_access: Key(VK_SPACE),
+            screenshot: Key(VK_F11),
+            copy_transform: Key(VK_C),
+            timelapse: Key(VK_F10),
+            help: Key(VK_F1),
+            palette: Key(VK_OEM_3),
+        }
+    }
+}
+
+// One row of the controls list, taken from the bindings actually loaded so remapped keys show up
+pub struct Binding {
+    pub name: &'static str, // Name in the [keys] table
+    pub key: Key,
+    pub description: &'static str,
+    pub matrix_only: bool, // Does nothing in position-only mode
+}
+
+impl KeyBindings {
+    // Every binding, in the order of the [keys] table
+    pub fn table(&self) -> Vec<Binding> {
+        vec![
+            Binding { name: "forward", key: self.forward, description: "Move Forward", matrix_only: false },
+            Binding { name: "backward", key: self.backward, description: "Move Backward", matrix_only: false },
+            Binding { name: "left", key: self.left, description: "Move Left", matrix_only: false },
+            Binding { name: "right", key: self.right, description: "Move Right", matrix_only: false },
+            Binding { name: "up", key: self.up, description: "Move Up", matrix_only: false },
+            Binding { name: "down", key: self.down, description: "Move Down", matrix_only: false },
+            Binding { name: "mouse_toggle", key: self.mouse_toggle, description: "Toggle Mouse Look", matrix_only: true },
+            Binding { name: "patch_toggle", key: self.patch_toggle, description: "Toggle Camera Write Patch", matrix_only: false },
+            Binding { name: "freeze_toggle", key: self.freeze_toggle, description: "Freeze/Unfreeze Game", matrix_only: false },
+            Binding { name: "hud_toggle", key: self.hud_toggle, description: "Hide/Show the Game's HUD", matrix_only: false },
+            Binding { name: "smooth_toggle", key: self.smooth_toggle, description: "Toggle Smooth Movement", matrix_only: true },
+            Binding { name: "step_toggle", key: self.step_toggle, description: "Toggle Step Mode (one fixed step per key tap)", matrix_only: true },
+            Binding { name: "orbit_toggle", key: self.orbit_toggle, description: "Toggle Orbit Mode (movement keys rotate around the pivot, forward/backward zoom)", matrix_only: true },
+            Binding { name: "follow_toggle", key: self.follow_toggle, description: "Toggle Follow Mode (movement keys adjust the offset to the skater)", matrix_only: true },
+            Binding { name: "framing_toggle", key: self.framing_toggle, description: "Keep Both Skaters in Frame", matrix_only: true },
+            Binding { name: "look_at_toggle", key: self.look_at_toggle, description: "Look At the Current Position While Flying (press again to release)", matrix_only: true },
+            Binding { name: "telemetry_toggle", key: self.telemetry_toggle, description: "Toggle Telemetry Recording", matrix_only: false },
+            Binding { name: "jitter_toggle", key: self.jitter_toggle, description: "Toggle VHS Camera Jitter", matrix_only: true },
+            Binding { name: "shake_toggle", key: self.shake_toggle, description: "Toggle Handheld Camera Shake", matrix_only: true },
+            Binding { name: "overlay_toggle", key: self.overlay_toggle, description: "Show/Hide the Crosshair and Thirds Overlay", matrix_only: true },
+            Binding { name: "instance_next", key: self.instance_next, description: "Drive the Next Game Instance (with --all-instances)", matrix_only: true },
+            Binding { name: "mirror_toggle", key: self.mirror_toggle, description: "Mirror the Camera Into Every Game Instance (with --all-instances)", matrix_only: true },
+            Binding { name: "hold_toggle", key: self.hold_toggle, description: "Hold the Camera Where It Is, Stopping All Camera Writes (press again to release)", matrix_only: false },
+            Binding { name: "skater_teleport", key: self.skater_teleport, description: "Teleport the Skater to the Camera", matrix_only: false },
+            Binding { name: "camera_to_skater", key: self.camera_to_skater, description: "Snap the Camera Back to the Skater", matrix_only: false },
+            Binding { name: "speed_up", key: self.speed_up, description: "Increase Speed", matrix_only: false },
+            Binding { name: "speed_down", key: self.speed_down, description: "Decrease Speed", matrix_only: false },
+            Binding { name: "sprint", key: self.sprint, description: "Hold to Move Faster", matrix_only: true },
+            Binding { name: "slow", key: self.slow, description: "Hold to Move Slower", matrix_only: true },
+            Binding { name: "zoom_in", key: self.zoom_in, description: "Zoom In", matrix_only: true },
+            Binding { name: "zoom_out", key: self.zoom_out, description: "Zoom Out", matrix_only: true },
+            Binding { name: "zoom_reset", key: self.zoom_reset, description: "Reset FOV", matrix_only: true },
+            Binding { name: "tunable_next", key: self.tunable_next, description: "Select Next Tunable", matrix_only: false },
+            Binding { name: "tunable_increase", key: self.tunable_increase, description: "Increase Selected Tunable", matrix_only: false },
+            Binding { name: "tunable_decrease", key: self.tunable_decrease, description: "Decrease Selected Tunable", matrix_only: false },
+            Binding { name: "tunable_release", key: self.tunable_release, description: "Release Held Tunable Value", matrix_only: false },
+            Binding { name: "path_keyframe", key: self.path_keyframe, description: "Add Path Keyframe", matrix_only: true },
+            Binding { name: "path_record", key: self.path_record, description: "Start/Stop Recording Camera Motion as a Path", matrix_only: true },
+            Binding { name: "path_play", key: self.path_play, description: "Play/Stop Path", matrix_only: true },
+            Binding { name: "playback_pause", key: self.playback_pause, description: "Pause/Resume Path Playback", matrix_only: true },
+            Binding { name: "playback_faster", key: self.playback_faster, description: "Play the Path Faster", matrix_only: true },
+            Binding { name: "playback_slower", key: self.playback_slower, description: "Play the Path Slower", matrix_only: true },
+            Binding { name: "playback_repeat", key: self.playback_repeat, description: "Switch Path Playback Between Once, Loop and Ping-Pong", matrix_only: true },
+            Binding { name: "scrub_back", key: self.scrub_back, description: "Hold to Scrub the Playing Path Back", matrix_only: true },
+            Binding { name: "scrub_forward", key: self.scrub_forward, description: "Hold to Scrub the Playing Path Forward", matrix_only: true },
+            Binding { name: "path_clear", key: self.path_clear, description: "Clear Path", matrix_only: true },
+            Binding { name: "path_save", key: self.path_save, description: "Save Path", matrix_only: true },
+            Binding { name: "path_bake", key: self.path_bake, description: "Play Path and Bake Manual Corrections Into a New Version", matrix_only: true },
+            Binding { name: "path_next", key: self.path_next, description: "Load the Next Path From the Path Directory", matrix_only: true },
+            Binding { name: "bookmark_save", key: self.bookmark_save, description: "Save a Bookmark", matrix_only: true },
+            Binding { name: "bookmark_next", key: self.bookmark_next, description: "Go to the Next Bookmark", matrix_only: true },
+            Binding { name: "waypoint_next", key: self.waypoint_next, description: "Fly to the Next Waypoint", matrix_only: true },
+            Binding { name: "waypoint_previous", key: self.waypoint_previous, description: "Fly to the Previous Waypoint", matrix_only: true },
+            Binding { name: "undo", key: self.undo, description: "Step the Camera Back Through Recent Positions", matrix_only: true },
+            Binding { name: "switch_access", key: self.switch_access, description: "Single-Switch Access (with switch_control on)", matrix_only: true },
+            Binding { name: "screenshot", key: self.screenshot, description: "Take a Screenshot", matrix_only: false },
+            Binding { name: "copy_transform", key: self.copy_transform, description: "Copy the Camera Position and Matrix to the Clipboard", matrix_only: true },
+            Binding { name: "timelapse", key: self.timelapse, description: "Capture a Timelapse Along the Path (press again to cancel)", matrix_only: true },
+            Binding { name: "help", key: self.help, description: "Show These Controls and What Is Switched On", matrix_only: false },
+            Binding { name: "palette", key: self.palette, description: "Search and Run a Command in the Console", matrix_only: false },
+        ]
+    }
+}
+
+// Hot corners, set in the [gestures] table. Off unless a modifier key is set.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct GestureConfig {
+    pub modifier: Option<Key>, // Held while flicking the cursor into a corner
+    pub corner_size: i32,      // Pixels from the screen edge that count as the corner
+    pub top_left: Option<Action>,
+    pub top_right: Option<Action>,
+    pub bottom_left: Option<Action>,
+    pub bottom_right: Option<Action>,
+}
+
+impl Default for GestureConfig {
+    fn default() -> Self {
+        Self {
+            modifier: None,
+            corner_size: 16,
+            top_left: None,
+            top_right: None,
+            bottom_left: None,
+            bottom_right: None,
+        }
+    }
+}
+
+// Settings for one level, in a [levels.<name>] table. They replace the ones from the rest of the file while
+// that level is loaded.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct LevelSettings {
+    #[serde(rename = "move_speed_per_second")]
+    pub move_speed: Option<f32>,
+    #[serde(rename = "basic_move_speed_per_second")]
+    pub basic_move_speed: Option<f32>,
+    pub fov: Option<f32>, // Horizontal FOV in degrees set when the level loads, needs [fov] in the offsets file
+    pub bounds: Option<Bounds>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    #[serde(rename = "move_speed_per_second")]
+    pub move_speed: f32,       // Units per second in matrix mode
+    #[serde(rename = "basic_move_speed_per_second")]
+    pub basic_move_speed: f32, // Units per second in position-only mode
+    pub mouse_sensitivity: f32,
+    pub horizontal_speed_scale: f32, // Multiplies the move speed for forward, backward, left and right in matrix mode
+    pub vertical_speed_scale: f32,   // Multiplies the move speed for up and down in matrix mode, lower for slow crane moves
+    pub sprint_multiplier: f32,      // Move speed multiplier while the sprint key is held
+    pub slow_multiplier: f32,        // Move speed multiplier while the slow key is held
+    pub wheel_speed_steps: u32,      // Speed steps per mouse wheel notch in matrix mode, 0 leaves the wheel alone
+    pub poll_interval_ms: u64,
+    pub idle_poll_interval_ms: u64, // Tick length while the patch is off and no keys are held, 0 to always use poll_interval_ms
+    pub status_threshold: f32, // Units the camera moves before the status line updates, it then follows until the camera settles
+    pub status_rate: f32,      // Most status line redraws per second, 0 for every tick
+    pub patch_guard_black_frames: bool, // Also hold the camera patch back while the game window is black, off because fullscreen captures are black too
+    pub idle_release_s: f32,   // Seconds without input before the camera patch is handed back to the game, 0 to keep it
+    pub smooth_movement: bool, // Start in smooth instead of snappy movement mode
+    pub acceleration: f32,     // Fraction of the move speed gained per 60th of a second in smooth mode
+    pub deceleration: f32,     // Fraction of the move speed lost per 60th of a second in smooth mode
+    pub step_distance: f32,    // Distance one key tap moves the camera in step mode
+    pub step_screenshot: bool, // Save a screenshot after every step
+    pub orbit_radius: f32,     // Distance to the pivot when orbiting the point in front of the camera
+    pub follow_smoothing: f32, // Fraction of the distance to the skater caught up per 60th of a second in follow mode
+    pub framing_margin: f32,   // Room kept around both skaters when framing them, in world units
+    pub framing_elevation: f32, // Degrees the framing camera looks down on the skaters from
+    pub framing_min_distance: f32, // Closest the framing camera gets, for when the skaters are together
+    pub teleport_height: f32,  // Skater teleports land this far below the camera, the camera snaps this far above the skater
+    pub interpolation: InterpolationMode, // How path playback blends between keyframes
+    pub write_channels: WriteChannels, // Parts of the matrix the camera writes in matrix mode
+    pub path_file: String,
+    pub path_dir: String,       // Paths in here are picked up while running and cycled with path_next, empty to turn it off
+    pub script_dir: String,     // Shot scripts (.rhai) are loaded from here when their key is pressed
+    pub plugin_dir: String,     // Plugin DLLs in here are loaded at startup, relative to the exe, empty to turn them off
+    pub screenshot_dir: String, // Where screenshots are saved, created when needed
+    pub shots_file: String,     // CSV that gets a row for every copied camera transform, empty to turn it off
+    pub timelapse_interval_ms: u64, // Path time between timelapse frames, 0 for one frame per keyframe
+    pub timelapse_settle_ms: u64,   // Time the game gets to render each timelapse pose before it is captured
+    pub session_log: String,        // CSV that gets a summary row per session, empty to turn it off
+    pub stats_file: String,         // Kept up to date with the skater stats for OBS, .json for JSON, empty to turn it off
+    pub author: Option<String>, // Written into saved paths, defaults to the Windows user name
+    pub keyframe_spacing: f32, // Seconds between keyframes added with the keyframe key
+    pub bookmark_transition_ms: u64, // Time the camera takes to fly to a bookmark, 0 to jump straight there
+    pub level: String,               // Level being played, its waypoints come from <waypoint_dir>/<level>.waypoints.json
+    pub waypoint_dir: String,
+    pub waypoint_transition_ms: u64, // Time the camera takes to fly to a waypoint, 0 to jump straight there
+    pub undo_history: usize,         // Camera positions the undo key can step back through
+    pub playback_interrupt: bool, // Movement keys take over from path playback
+    pub blend_out_ms: u64,        // Time to hand the camera back to manual control after an interrupt
+    pub playback_rate: f32,       // Path time per second of playback, 0.1 to 4
+    pub playback_repeat: PlaybackRepeat, // once, loop or ping_pong
+    pub scrub_speed: f32,         // Seconds of path time per second while a scrub key is held
+    pub switch_control: bool,     // Single-switch mode: the switch_access key steps through modes
+    pub switch_dwell_ms: u64,     // How long to rest on a mode before it is confirmed
+    #[serde(rename = "switch_turn_rate_per_second")]
+    pub switch_turn_rate: f32,    // Degrees per second while switch access turns the camera
+    pub remote_port: Option<u16>, // HTTP/WebSocket remote control, off unless a port is set
+    pub remote_bind: String,      // Address the remote control and OSC listen on, 0.0.0.0 for other machines
+    pub remote_token: String,     // Secret every remote control request has to carry, the remote control stays off without one
+    pub remote_origins: Vec<String>, // Web pages allowed to use the remote control from a browser
+    pub osc_port: Option<u16>,    // UDP port for OSC control, off unless set
+    pub daemon_port: u16,         // TCP port --daemon takes JSON line requests on, on this machine only
+    pub control_pipe: bool,       // Also take the daemon's requests on a named pipe while flying, for local scripts
+    pub control_panel: bool,      // Open the control panel window next to the console
+    pub overlay: bool,            // Start with the crosshair and thirds overlay over the game (overlay_toggle switches it)
+    pub osd: bool,                // With --internal, show the overlay's telemetry line inside the game, also in fullscreen
+    pub voice_commands: bool,     // Listen for spoken commands through Windows speech recognition
+    pub voice_confidence: f32,    // Recognitions below this confidence (0-1) are ignored
+    pub replay_sync: bool,        // Play the path when the game starts a replay, needs [replay] in the offsets file
+    pub vhs_jitter: bool,         // Start with camcorder-style jitter on (jitter_toggle switches it)
+    pub jitter_position: f32,     // Maximum jitter offset in world units
+    pub jitter_rotation: f32,     // Maximum jitter angle in degrees
+    pub jitter_frequency: f32,    // How often per second the jitter drifts somewhere new
+    pub handheld_shake: bool,     // Start with handheld camera shake on (shake_toggle switches it)
+    pub shake_position: f32,      // Largest shake offset in world units
+    pub shake_rotation: f32,      // Largest shake angle in degrees
+    pub shake_frequency: f32,     // Sways per second, the shake adds faster tremble on top
+    pub keys: KeyBindings,
+    pub gestures: GestureConfig,
+    pub bounds: Bounds,
+    pub rumble: RumbleConfig,
+    pub midi: MidiConfig,
+    pub space_mouse: SpaceMouseConfig,
+    pub gamepad: GamepadConfig,
+    pub scripts: HashMap<String, Key>, // Script name in script_dir, without .rhai, to the key that starts and stops it
+    pub levels: HashMap<String, LevelSettings>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            move_speed: 300.0,
+            basic_move_speed: 600.0,
+            mouse_sensitivity: 0.5,
+            horizontal_speed_scale: 1.0,
+            vertical_speed_scale: 1.0,
+            sprint_multiplier: 4.0,
+            slow_multiplier: 0.25,
+            wheel_speed_steps: 5,
+            poll_interval_ms: 16, // ~60 FPS
+            idle_poll_interval_ms: 0,
+            status_threshold: 0.1,
+            status_rate: 20.0,
+            patch_guard_black_frames: false,
+            idle_release_s: 0.0,
+            smooth_movement: false,
+            acceleration: 0.08,
+            deceleration: 0.05,
+            step_distance: 10.0,
+            step_screenshot: false,
+            orbit_radius: 200.0,
+            follow_smoothing: 0.15,
+            framing_margin: 100.0,
+            framing_elevation: 20.0,
+            framing_min_distance: 200.0,
+            teleport_height: 50.0,
+            interpolation: InterpolationMode::default(),
+            write_channels: WriteChannels::default(),
+            path_file: "camera_path.toml".to_string(),
+            path_dir: "paths".to_string(),
+            script_dir: "scripts".to_string(),
+            plugin_dir: "plugins".to_string(),
+            screenshot_dir: "screenshots".to_string(),
+            shots_file: String::new(),
+            timelapse_interval_ms: 100,
+            timelapse_settle_ms: 100,
+            session_log: "sessions.csv".to_string(),
+            stats_file: String::new(),
+            author: None,
+            keyframe_spacing: 2.0,
+            bookmark_transition_ms: 1000,
+            level: String::new(),
+            waypoint_dir: "waypoints".to_string(),
+            waypoint_transition_ms: 3000,
+            undo_history: 120,
+            playback_interrupt: true,
+            blend_out_ms: 500,
+            playback_rate: 1.0,
+            playback_repeat: PlaybackRepeat::default(),
+            scrub_speed: 2.0,
+            switch_control: false,
+            switch_dwell_ms: 1000,
+            switch_turn_rate: 60.0,
+            remote_port: None,
+            remote_bind: "127.0.0.1".to_string(),
+            remote_token: String::new(),
+            remote_origins: Vec::new(),
+            osc_port: None,
+            daemon_port: 8767,
+            control_pipe: false,
+            control_panel: false,
+            overlay: false,
+            osd: false,
+            voice_commands: false,
+            voice_confidence: 0.6,
+            replay_sync: false,
+            vhs_jitter: false,
+            jitter_position: 0.5,
+            jitter_rotation: 0.3,
+            jitter_frequency: 2.0,
+            handheld_shake: false,
+            shake_position: 2.0,
+            shake_rotation: 0.6,
+            shake_frequency: 0.5,
+            keys: KeyBindings::default(),
+            gestures: GestureConfig::default(),
+            bounds: Bounds::default(),
+            rumble: RumbleConfig::default(),
+            midi: MidiConfig::default(),
+            space_mouse: SpaceMouseConfig::default(),
+            gamepad: GamepadConfig::default(),
+            scripts: HashMap::new(),
+            levels: HashMap::new(),
+        }
+    }
+}
+
+impl Config {
+    // The settings while `level` is loaded: its [levels] table on top of the rest, and `level` set so its
+    // waypoints are used. None keeps the `level` from the file.
+    pub fn for_level(&self, level: Option<&str>) -> Config {
+        let mut config = self.clone();
+        if let Some(level) = level {
+            config.level = level.to_string();
+        }
+        if let Some(settings) = config.level_settings().cloned() {
+            config.move_speed = settings.move_speed.unwrap_or(config.move_speed);
+            config.basic_move_speed = settings.basic_move_speed.unwrap_or(config.basic_move_speed);
+            config.bounds = settings.bounds.unwrap_or(config.bounds);
+        }
+        config
+    }
+    
+    fn level_settings(&self) -> Option<&LevelSettings> {
+        self.levels.iter().find(|(name, _)| name.eq_ignore_ascii_case(&self.level)).map(|(_, settings)| settings)
+    }
+    
+    // Horizontal FOV the level asks for, applied when it loads
+    pub fn level_fov(&self) -> Option<f32> {
+        self.level_settings().and_then(|settings| settings.fov)
+    }
+}
+
+pub fn load_config(path: &str) -> Result<Config, String> {
+    let contents = match std::fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Config::default()),
+        Err(e) => return Err(format!("Failed to read '{}': {}", path, e)),
+    };
+    
+    let mut config: Config = toml::from_str(&contents).map_err(|e| format!("Failed to parse '{}': {}", path, e))?;
+    let table: toml::Table = toml::from_str(&contents).map_err(|e| format!("Failed to parse '{}': {}", path, e))?;
+    read_per_tick_keys(&table, path, &mut config);
+    check_durations(&config, path)?;
+    Ok(config)
+}
+
+// Settings that become a Duration, which has no room for an infinite or absurdly long one
+fn check_durations(config: &Config, path: &str) -> Result<(), String> {
+    if config.idle_release_s > 0.0 && Duration::try_from_secs_f32(config.idle_release_s).is_err() {
+        return Err(format!("'{}': idle_release_s = {} is too long, 0 keeps the camera patch", path, config.idle_release_s));
+    }
+    if config.status_rate > 0.0 && Duration::try_from_secs_f32(1.0 / config.status_rate).is_err() {
+        return Err(format!("'{}': status_rate = {} is too small, 0 redraws every tick", path, config.status_rate));
+    }
+    Ok(())
+}
+
+// Keys from before movement went by time, when they were per tick, and the per-second keys that replaced them
+const PER_TICK_KEYS: [(&str, &str); 3] = [
+    ("move_speed", "move_speed_per_second"),
+    ("basic_move_speed", "basic_move_speed_per_second"),
+    ("switch_turn_rate", "switch_turn_rate_per_second"),
+];
+
+// The loop ran at about 60 ticks a second back then
+const OLD_TICKS_PER_SECOND: f32 = 60.0;
+
+// An old per-tick key still counts, times 60, unless the file also has the new key. Both get a warning, so the
+// file can be updated.
+fn read_per_tick_keys(table: &toml::Table, path: &str, config: &mut Config) {
+    let per_second = |table: &toml::Table, prefix: &str, old: &str, new: &str| -> Option<f32> {
+        let value = table.get(old)?;
+        let per_tick = value.as_float().or_else(|| value.as_integer().map(|value| value as f64))? as f32;
+        if table.contains_key(new) {
+            println!("⚠️ {}: {}{} is from an older version and ignored, {}{} is used", path, prefix, old, prefix, new);
+            return None;
+        }
+        let value = per_tick * OLD_TICKS_PER_SECOND;
+        println!("⚠️ {}: {}{} = {} is per tick, read as {}{} = {}. Rename it to keep this value without the warning.",
+                 path, prefix, old, per_tick, prefix, new, value);
+        Some(value)
+    };
+    
+    for (old, new) in PER_TICK_KEYS {
+        let Some(value) = per_second(table, "", old, new) else { continue };
+        match old {
+            "move_speed" => config.move_speed = value,
+            "basic_move_speed" => config.basic_move_speed = value,
+            _ => config.switch_turn_rate = value,
+        }
+    }
+    
+    let Some(levels) = table.get("levels").and_then(toml::Value::as_table) else { return };
+    for (name, level) in levels {
+        let (Some(level), Some(settings)) = (level.as_table(), config.levels.get_mut(name)) else { continue };
+        let prefix = format!("levels.{}.", name);
+        if let Some(value) = per_second(level, &prefix, "move_speed", "move_speed_per_second") {
+            settings.move_speed = Some(value);
+        }
+        if let Some(value) = per_second(level, &prefix, "basic_move_speed", "basic_move_speed_per_second") {
+            settings.basic_move_speed = Some(value);
+        }
+    }
+}
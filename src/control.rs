@@ -0,0 +1,402 @@
+// Control protocol for frontends driving the headless daemon: one JSON request per line, answered with one JSON
+// line. Requests are the remote control's commands plus a few only a frontend needs, e.g. reading the camera or
+// telling the daemon to quit. Unlike the remote control, every request is answered once the main loop has
+// carried it out, so a frontend sees whether it worked. The same lines go over TCP or, for AutoHotkey and Python
+// scripts on the same machine, over a named pipe that opens no network port.
+
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::TcpStream;
+use std::ptr;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use serde::Deserialize;
+use winapi::shared::minwindef::{BOOL, DWORD, FALSE, TRUE};
+use winapi::shared::winerror::{
+    ERROR_BROKEN_PIPE, ERROR_IO_PENDING, ERROR_NO_DATA, ERROR_OPERATION_ABORTED, ERROR_PIPE_CONNECTED, ERROR_PIPE_NOT_CONNECTED,
+};
+use winapi::um::errhandlingapi::GetLastError;
+use winapi::um::fileapi::{ReadFile, WriteFile};
+use winapi::um::handleapi::{CloseHandle, INVALID_HANDLE_VALUE};
+use winapi::um::ioapiset::{CancelIoEx, GetOverlappedResult};
+use winapi::um::minwinbase::OVERLAPPED;
+use winapi::um::namedpipeapi::{ConnectNamedPipe, CreateNamedPipeW, DisconnectNamedPipe};
+use winapi::um::synchapi::{CreateEventW, WaitForSingleObject};
+use winapi::um::winbase::{
+    FILE_FLAG_FIRST_PIPE_INSTANCE, FILE_FLAG_OVERLAPPED, PIPE_ACCESS_DUPLEX, PIPE_READMODE_BYTE, PIPE_REJECT_REMOTE_CLIENTS,
+    PIPE_TYPE_BYTE, PIPE_UNLIMITED_INSTANCES, PIPE_WAIT, WAIT_OBJECT_0,
+};
+use winapi::um::winnt::HANDLE;
+
+use crate::remote::{self, RemoteCommand};
+use crate::shutdown::{self, Cancel, CancelToken};
+
+// How often the listener and idle connections check whether they should stop
+const ACCEPT_INTERVAL: Duration = Duration::from_millis(50);
+
+// Longest a request waits for the main loop before the client is told it wasn't carried out
+const REPLY_TIMEOUT: Duration = Duration::from_secs(2);
+
+// Longest request line, a matrix with every float written out fits many times over
+const MAX_LINE: usize = 64 * 1024;
+
+// Pipe buffer sizes, a reply with the whole camera state fits
+const PIPE_BUFFER: DWORD = 4096;
+
+// Where the TCP side listens, whatever remote_bind says
+pub const CONTROL_BIND: &str = "127.0.0.1";
+
+// Where local scripts connect, e.g. open(r'\\.\pipe\thps3_free_cam', 'r+b') in Python
+pub const PIPE_NAME: &str = r"\\.\pipe\thps3_free_cam";
+
+// Shorter names for requests typed by hand, e.g. {"cmd":"set_pos","x":0,"y":100,"z":0}
+const SHORT_NAMES: [(&str, &str); 3] = [("set_pos", "set_position"), ("set_rot", "set_rotation"), ("get_pos", "get_camera")];
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum ControlRequest {
+    GetCamera,                // Answered with the camera state, like GET /camera
+    Patch { on: bool },       // Hands the camera to the game (false) or takes it (true)
+    Quit,
+    #[serde(untagged)]
+    Remote(RemoteCommand),    // set_position, set_matrix, action, ...
+}
+
+// A request waiting for the main loop's answer
+pub struct PendingRequest {
+    pub request: ControlRequest,
+    reply: Sender<String>,
+}
+
+impl PendingRequest {
+    // Ok(None) answers {"ok":true}, Ok(Some) sends the value itself
+    pub fn answer(self, result: Result<Option<serde_json::Value>, String>) {
+        let reply = match result {
+            Ok(Some(value)) => value.to_string(),
+            Ok(None) => r#"{"ok":true}"#.to_string(),
+            Err(e) => error_reply(&e),
+        };
+        let _ = self.reply.send(reply);
+    }
+}
+
+fn error_reply(error: &str) -> String {
+    serde_json::json!({ "ok": false, "error": error }).to_string()
+}
+
+pub struct ControlServer {
+    receiver: Receiver<PendingRequest>,
+    cancel: Option<Cancel>,
+    listener: Option<JoinHandle<()>>,
+}
+
+impl ControlServer {
+    // Only on this machine: requests carry no token, so anything that could reach the port could move the camera
+    pub fn start(port: u16) -> Result<Self, String> {
+        let listener = remote::bind_listener(CONTROL_BIND, port)?;
+        let (sender, receiver) = mpsc::channel();
+        let (cancel, token) = shutdown::token();
+        
+        let listener = std::thread::spawn(move || {
+            let busy = format!("{}\n", error_reply("Too many connections"));
+            remote::accept_connections(listener, &token, &busy, |stream| {
+                let (sender, token) = (sender.clone(), token.clone());
+                std::thread::spawn(move || handle_connection(stream, sender, token))
+            });
+        });
+        
+        Ok(Self { receiver, cancel: Some(cancel), listener: Some(listener) })
+    }
+    
+    // Serves the same requests on a named pipe, see PIPE_NAME. Fails when another free cam already has it.
+    pub fn start_pipe(name: &str) -> Result<Self, String> {
+        let wide: Vec<u16> = name.encode_utf16().chain(Some(0)).collect();
+        let mut pipe = PipeConnection::create(&wide, true)
+            .map_err(|error_code| format!("Failed to create the pipe {} (Error: {}) - is another free cam using it?", name, error_code))?;
+        let (sender, receiver) = mpsc::channel();
+        let (cancel, token) = shutdown::token();
+        
+        let listener = std::thread::spawn(move || {
+            let mut connections: Vec<JoinHandle<()>> = Vec::new();
+            while !token.is_cancelled() {
+                match pipe.connect(ACCEPT_INTERVAL) {
+                    Ok(true) => {
+                        // The next client gets a new instance, this one is the connection's now
+                        let next = match PipeConnection::create(&wide, false) {
+                            Ok(next) => next,
+                            Err(_) => break,
+                        };
+                        let connection = std::mem::replace(&mut pipe, next);
+                        connections.retain(|connection| !connection.is_finished());
+                        if connections.len() >= remote::MAX_CONNECTIONS {
+                            let _ = writeln!(&connection, "{}", error_reply("Too many connections"));
+                            continue;
+                        }
+                        let (sender, token) = (sender.clone(), token.clone());
+                        connections.push(std::thread::spawn(move || serve_lines(&connection, sender, token)));
+                    }
+                    Ok(false) => {}
+                    // A client that connected and left before it was served
+                    Err(ERROR_NO_DATA) => {
+                        unsafe { DisconnectNamedPipe(pipe.handle) };
+                    }
+                    Err(_) => break,
+                }
+                connections.retain(|connection| !connection.is_finished());
+            }
+            
+            for connection in connections {
+                let _ = connection.join();
+            }
+        });
+        
+        Ok(Self { receiver, cancel: Some(cancel), listener: Some(listener) })
+    }
+    
+    // Requests received since the last call, each one to be answered
+    pub fn poll(&self) -> Vec<PendingRequest> {
+        self.receiver.try_iter().collect()
+    }
+}
+
+impl Drop for ControlServer {
+    // Stops accepting and closes the open connections before the port is given up
+    fn drop(&mut self) {
+        self.cancel = None;
+        if let Some(listener) = self.listener.take() {
+            let _ = listener.join();
+        }
+    }
+}
+
+fn handle_connection(stream: TcpStream, sender: Sender<PendingRequest>, token: CancelToken) {
+    // Short reads so an idle connection still notices the daemon stopping
+    let _ = stream.set_read_timeout(Some(ACCEPT_INTERVAL));
+    serve_lines(&stream, sender, token);
+}
+
+// Connections stay open for as many requests as the client sends. Reads that find nothing give up after a
+// moment, so an idle connection still notices the daemon stopping. A line that isn't a request closes the
+// connection: whatever sent it isn't a frontend, and could be a browser smuggling requests into an HTTP body.
+fn serve_lines<S>(stream: &S, sender: Sender<PendingRequest>, token: CancelToken)
+where
+    for<'a> &'a S: Read + Write,
+{
+    let mut moved = false;
+    read_requests(stream, &sender, &token, &mut moved);
+    
+    // A frontend that drops off while flying the camera doesn't leave it flying. Nobody waits for these answers.
+    if moved {
+        for command in remote::stop_commands() {
+            let (reply, _) = mpsc::channel();
+            let _ = sender.send(PendingRequest { request: ControlRequest::Remote(command), reply });
+        }
+    }
+}
+
+fn read_requests<S>(stream: &S, sender: &Sender<PendingRequest>, token: &CancelToken, moved: &mut bool)
+where
+    for<'a> &'a S: Read + Write,
+{
+    let mut reader = BufReader::new(stream);
+    let mut writer = stream;
+    
+    // A read that times out keeps what it got, the rest of the line follows
+    let mut line = Vec::new();
+    while !token.is_cancelled() {
+        // Never more than MAX_LINE, even from a client that sends no newline at all
+        let room = (MAX_LINE - line.len()) as u64;
+        match reader.by_ref().take(room).read_until(b'\n', &mut line) {
+            Ok(0) => return,
+            Ok(_) if line.ends_with(b"\n") => {}
+            Ok(_) => return, // Closed halfway through a line, or the line is too long
+            Err(e) if matches!(e.kind(), io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut) => continue,
+            Err(_) => return,
+        }
+        
+        let text = String::from_utf8_lossy(&line).trim().to_string();
+        line.clear();
+        if text.is_empty() {
+            continue;
+        }
+        let request = match parse_request(&text) {
+            Ok(request) => request,
+            Err(e) => {
+                let _ = writeln!(writer, "{}", error_reply(&format!("Invalid request: {}", e)));
+                return;
+            }
+        };
+        *moved |= matches!(&request, ControlRequest::Remote(command) if command.is_motion());
+        let reply = answer(request, sender);
+        if writeln!(writer, "{}", reply).and_then(|()| writer.flush()).is_err() {
+            return;
+        }
+    }
+}
+
+// A request line, with `cmd` accepted for `command` and the short names
+fn parse_request(text: &str) -> Result<ControlRequest, String> {
+    let mut value: serde_json::Value = serde_json::from_str(text).map_err(|e| e.to_string())?;
+    if let Some(object) = value.as_object_mut() {
+        if let Some(command) = object.remove("cmd") {
+            object.entry("command").or_insert(command);
+        }
+        if let Some(serde_json::Value::String(name)) = object.get_mut("command")
+            && let Some((_, full)) = SHORT_NAMES.iter().find(|(short, _)| short == name)
+        {
+            *name = full.to_string();
+        }
+    }
+    // The untagged fallback hides why a remote command didn't parse, its own error says
+    let request = serde_json::from_value::<ControlRequest>(value.clone())
+        .map_err(|e| serde_json::from_value::<RemoteCommand>(value).err().map_or(e.to_string(), |e| e.to_string()))?;
+    match request {
+        ControlRequest::Remote(command) => command.check().map(ControlRequest::Remote),
+        request => Ok(request),
+    }
+}
+
+// Hands a request to the main loop and waits for its answer
+fn answer(request: ControlRequest, sender: &Sender<PendingRequest>) -> String {
+    let (reply, answer) = mpsc::channel();
+    if sender.send(PendingRequest { request, reply }).is_err() {
+        return error_reply("The free cam is stopping");
+    }
+    answer.recv_timeout(REPLY_TIMEOUT).unwrap_or_else(|_| error_reply("The free cam didn't answer in time"))
+}
+
+// One instance of the named pipe, the server end of a client's connection once one connects. Overlapped, so
+// every wait has a timeout: reads that find nothing for ACCEPT_INTERVAL report WouldBlock like a TCP read timeout.
+struct PipeConnection {
+    handle: HANDLE,
+    event: HANDLE, // Signalled when the operation in progress finishes
+}
+
+// The handles are only ever used by the thread that owns the connection
+unsafe impl Send for PipeConnection {}
+
+impl PipeConnection {
+    // `first` claims the name, so a second free cam can't quietly take half the clients
+    fn create(name: &[u16], first: bool) -> Result<Self, DWORD> {
+        let open_mode = PIPE_ACCESS_DUPLEX | FILE_FLAG_OVERLAPPED | if first { FILE_FLAG_FIRST_PIPE_INSTANCE } else { 0 };
+        let pipe_mode = PIPE_TYPE_BYTE | PIPE_READMODE_BYTE | PIPE_WAIT | PIPE_REJECT_REMOTE_CLIENTS;
+        let handle = unsafe {
+            CreateNamedPipeW(name.as_ptr(), open_mode, pipe_mode, PIPE_UNLIMITED_INSTANCES, PIPE_BUFFER, PIPE_BUFFER, 0, ptr::null_mut())
+        };
+        if handle == INVALID_HANDLE_VALUE {
+            return Err(unsafe { GetLastError() });
+        }
+        // Manual reset, which ConnectNamedPipe needs
+        let event = unsafe { CreateEventW(ptr::null_mut(), TRUE, FALSE, ptr::null()) };
+        if event.is_null() {
+            let error_code = unsafe { GetLastError() };
+            unsafe { CloseHandle(handle) };
+            return Err(error_code);
+        }
+        Ok(Self { handle, event })
+    }
+    
+    // Waits up to `timeout` for a client, true once one is connected
+    fn connect(&self, timeout: Duration) -> Result<bool, DWORD> {
+        let mut overlapped = self.overlapped();
+        let started = unsafe { ConnectNamedPipe(self.handle, &mut overlapped) };
+        // A client that came between creating the instance and listening on it
+        if started == 0 && unsafe { GetLastError() } == ERROR_PIPE_CONNECTED {
+            return Ok(true);
+        }
+        self.finish(&mut overlapped, started, timeout).map(|connected| connected.is_some())
+    }
+    
+    fn overlapped(&self) -> OVERLAPPED {
+        let mut overlapped: OVERLAPPED = unsafe { std::mem::zeroed() };
+        overlapped.hEvent = self.event;
+        overlapped
+    }
+    
+    // Waits up to `timeout` for the operation `started` with `overlapped`, and returns how many bytes it moved.
+    // None when it didn't finish in time: it's cancelled then, so the caller's buffer is free again once this returns.
+    fn finish(&self, overlapped: &mut OVERLAPPED, started: BOOL, timeout: Duration) -> Result<Option<DWORD>, DWORD> {
+        if started == 0 {
+            match unsafe { GetLastError() } {
+                ERROR_IO_PENDING => {}
+                error_code => return Err(error_code),
+            }
+            if unsafe { WaitForSingleObject(self.event, timeout.as_millis() as DWORD) } != WAIT_OBJECT_0 {
+                unsafe { CancelIoEx(self.handle, overlapped) };
+            }
+        }
+        // Waits for the cancel to land too. An operation that finished first keeps what it did.
+        let mut transferred: DWORD = 0;
+        if unsafe { GetOverlappedResult(self.handle, overlapped, &mut transferred, TRUE) } == 0 {
+            return match unsafe { GetLastError() } {
+                ERROR_OPERATION_ABORTED => Ok(None),
+                error_code => Err(error_code),
+            };
+        }
+        Ok(Some(transferred))
+    }
+}
+
+impl Read for &PipeConnection {
+    fn read(&mut self, buffer: &mut [u8]) -> io::Result<usize> {
+        let mut overlapped = self.overlapped();
+        let started = unsafe { ReadFile(self.handle, buffer.as_mut_ptr().cast(), buffer.len() as DWORD, ptr::null_mut(), &mut overlapped) };
+        match self.finish(&mut overlapped, started, ACCEPT_INTERVAL) {
+            // Zero bytes from a successful read isn't the end of the connection, only a broken pipe is
+            Ok(Some(0)) | Ok(None) => Err(io::ErrorKind::WouldBlock.into()),
+            Ok(Some(read)) => Ok(read as usize),
+            Err(ERROR_BROKEN_PIPE | ERROR_PIPE_NOT_CONNECTED) => Ok(0),
+            Err(error_code) => Err(io::Error::from_raw_os_error(error_code as i32)),
+        }
+    }
+}
+
+impl Write for &PipeConnection {
+    // A full pipe takes nothing until the client reads, which it gets REPLY_TIMEOUT to do
+    fn write(&mut self, buffer: &[u8]) -> io::Result<usize> {
+        let mut overlapped = self.overlapped();
+        let started = unsafe { WriteFile(self.handle, buffer.as_ptr().cast(), buffer.len() as DWORD, ptr::null_mut(), &mut overlapped) };
+        match self.finish(&mut overlapped, started, REPLY_TIMEOUT) {
+            Ok(Some(written)) => Ok(written as usize),
+            Ok(None) => Err(io::ErrorKind::TimedOut.into()),
+            Err(error_code) => Err(io::Error::from_raw_os_error(error_code as i32)),
+        }
+    }
+    
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Drop for PipeConnection {
+    fn drop(&mut self) {
+        unsafe {
+            DisconnectNamedPipe(self.handle);
+            CloseHandle(self.handle);
+            CloseHandle(self.event);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn requests_take_short_names_and_cmd() {
+        assert!(matches!(parse_request(r#"{"command":"get_camera"}"#), Ok(ControlRequest::GetCamera)));
+        assert!(matches!(parse_request(r#"{"cmd":"get_pos"}"#), Ok(ControlRequest::GetCamera)));
+        assert!(matches!(parse_request(r#"{"command":"patch","on":true}"#), Ok(ControlRequest::Patch { on: true })));
+        assert!(matches!(parse_request(r#"{"cmd":"set_pos","x":1,"y":2,"z":3}"#),
+                         Ok(ControlRequest::Remote(RemoteCommand::SetPosition { x: 1.0, y: 2.0, z: 3.0 }))));
+    }
+    
+    #[test]
+    fn broken_requests_say_why() {
+        assert!(parse_request("not json").is_err());
+        assert!(parse_request(r#"{"command":"fly_away"}"#).is_err());
+        let error = parse_request(r#"{"command":"set_position","x":1,"y":2}"#).err().unwrap();
+        assert!(error.contains('z'), "{}", error);
+    }
+}
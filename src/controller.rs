@@ -1,32 +1,819 @@
-use crate::camera::{CameraMatrix, CameraPosition};
-use crate::input::{MovementInput, MouseHandler, get_speed_delta};
+use std::time::Instant;
+
+use crate::accessibility::SwitchMode;
+use crate::bounds::Bounds;
+use crate::camera::{CameraMatrix, CameraPose, CameraPosition, WriteChannels};
+use crate::config::KeyBindings;
+use crate::input::{CameraCommand, InputSource, KeyboardSource, MouseHandler};
+use crate::jitter::Jitter;
+use crate::math::Vec3;
+use crate::fov::{game_fov_from_preset, horizontal_to_vertical, preset_from_game_fov, screen_aspect};
+use crate::offsets::{FovOffsets, PointerChain};
 use crate::process::ProcessHandle;
+use crate::shake::Shake;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MovementMode {
+    Snappy, // Fixed step per tick while a key is held
+    Smooth, // Velocity based, accelerates and eases out
+}
+
+// Speeds are per second and scaled by the time since the last tick, so the poll interval doesn't change how
+// fast the camera moves. A stall is worked out as at most this long, rather than throwing the camera across the level.
+const MAX_TICK: f32 = 0.1;
+
+// The smoothing fractions in config.toml are per 60th of a second, the rate they were tuned at
+const REFERENCE_RATE: f32 = 60.0;
+
+// Seconds between calls to tick(), a first tick counts as one reference tick
+#[derive(Debug, Clone, Default)]
+struct TickClock {
+    last: Option<Instant>,
+}
+
+impl TickClock {
+    fn tick(&mut self) -> f32 {
+        let now = Instant::now();
+        let seconds = self.last.map_or(1.0 / REFERENCE_RATE, |last| (now - last).as_secs_f32().min(MAX_TICK));
+        self.last = Some(now);
+        seconds
+    }
+    
+    // After the camera was left alone (playback, a held camera), the next tick starts fresh
+    fn reset(&mut self) {
+        self.last = None;
+    }
+}
+
+// A fraction caught up per reference tick, as the fraction for a tick of `seconds`
+fn catch_up(fraction: f32, seconds: f32) -> f32 {
+    1.0 - (1.0 - fraction).powf(seconds * REFERENCE_RATE)
+}
+
+// Moves `current` towards `target` by at most `max_delta`
+fn approach(current: f32, target: f32, max_delta: f32) -> f32 {
+    if current < target {
+        (current + max_delta).min(target)
+    } else {
+        (current - max_delta).max(target)
+    }
+}
+
+// One tick of input commands added up
+#[derive(Debug, Clone, Copy, Default)]
+struct Motion {
+    translate: (f32, f32, f32), // Fractions of the move speed
+    rotate: (f32, f32, f32),    // Radians
+    turn: (f32, f32, f32),      // Radians per second
+    speed: i32,
+    zoom: i32,
+    zoom_reset: bool,
+}
+
+impl Motion {
+    fn new(commands: &[CameraCommand]) -> Self {
+        let add = |sum: &mut (f32, f32, f32), x: f32, y: f32, z: f32| *sum = (sum.0 + x, sum.1 + y, sum.2 + z);
+        let mut motion = Self::default();
+        for command in commands {
+            match *command {
+                CameraCommand::Translate(x, y, z) => add(&mut motion.translate, x, y, z),
+                CameraCommand::Rotate(yaw, pitch, roll) => add(&mut motion.rotate, yaw, pitch, roll),
+                CameraCommand::Turn(yaw, pitch, roll) => add(&mut motion.turn, yaw, pitch, roll),
+                CameraCommand::Speed(steps) => motion.speed += steps,
+                CameraCommand::Zoom(steps) => motion.zoom += steps,
+                CameraCommand::ZoomReset => motion.zoom_reset = true,
+                CameraCommand::Fov(_) | CameraCommand::Scrub(_) | CameraCommand::Toggle(_) => {}
+            }
+        }
+        motion
+    }
+    
+    // What turns the camera in a tick of `seconds`, as (yaw, pitch, roll) radians
+    fn turned(&self, seconds: f32) -> (f32, f32, f32) {
+        (self.rotate.0 + self.turn.0 * seconds, self.rotate.1 + self.turn.1 * seconds, self.rotate.2 + self.turn.2 * seconds)
+    }
+}
+
+// The directions `translate` moves in, as (forward, backward, left, right, up, down) like held keys
+fn directions(translate: (f32, f32, f32)) -> [bool; 6] {
+    let (x, y, z) = translate;
+    [z > 0.0, z < 0.0, x > 0.0, x < 0.0, y > 0.0, y < 0.0]
+}
+
+// Held directions as a movement of `distance` along each, the other way round from `directions`
+fn from_directions(held: [bool; 6], distance: f32) -> (f32, f32, f32) {
+    let [forward, backward, left, right, up, down] = held;
+    let axis = |positive: bool, negative: bool| (positive as i32 - negative as i32) as f32 * distance;
+    // Left is positive X in this game
+    (axis(left, right), axis(up, down), axis(forward, backward))
+}
+
+// Camera circles `pivot` at `radius`, always looking at it
+#[derive(Debug, Clone)]
+struct OrbitState {
+    pivot: CameraPosition,
+    radius: f32,
+}
+
+// Camera keeps `offset` from a smoothed copy of the skater position
+#[derive(Debug, Clone)]
+struct FollowState {
+    target: CameraPosition,
+    offset: (f32, f32, f32),
+    smoothing: f32, // Fraction of the distance to the skater caught up per reference tick
+}
+
+// Camera keeps both skaters in shot from the side, looking at the point between them
+#[derive(Debug, Clone)]
+struct FramingState {
+    center: CameraPosition,
+    distance: f32,
+    direction: (f32, f32), // Horizontal unit vector from the center to the camera
+    margin: f32,           // Room left around the skaters, in world units
+    elevation: f32,        // Radians above the skaters
+    min_distance: f32,
+    smoothing: f32,
+}
+
+// Assumed when the FOV isn't in the offsets file, degrees
+const DEFAULT_HORIZONTAL_FOV: f32 = 90.0;
 
 pub struct CameraController {
-    move_speed: f32,
+    move_speed: f32, // Units per second
     mouse_handler: MouseHandler,
     last_position: Option<CameraPosition>,
     min_speed: f32,
     max_speed: f32,
     speed_step: f32,
-    yaw: f32,   // Rotation around Y-axis (left/right)
-    pitch: f32, // Rotation around X-axis (up/down)
-    movement_input: MovementInput,
+    yaw: f32,   // Orbit angle around the pivot's Y-axis (left/right)
+    pitch: f32, // Orbit angle above or below the pivot (up/down)
+    keyboard: KeyboardSource,
+    typing: bool, // The console is being typed into, the keyboard and mouse don't fly
+    sources: Vec<Box<dyn InputSource>>, // Devices besides the keyboard and mouse, polled along with them
+    commands: Vec<CameraCommand>,       // This tick's input, carried out by the next update
+    fov_offsets: Option<FovOffsets>,
+    default_fov: Option<f32>,
+    original_fov: Option<f32>, // What the game had before the first FOV write, restored on exit
+    current_fov: Option<f32>,
+    screen_aspect: f32,
+    game_aspect: f32,
+    movement_mode: MovementMode,
+    velocity: (f32, f32, f32),
+    orbit: Option<OrbitState>,
+    follow: Option<FollowState>,
+    skater_chain: Option<PointerChain>,
+    second_skater_chain: Option<PointerChain>,
+    skater_matrix_chain: Option<PointerChain>,
+    framing: Option<FramingState>,
+    look_at_target: Option<CameraPosition>,
+    switch_mode: SwitchMode,
+    switch_turn_rate: f32, // Radians per second while switch access turns the camera
+    jitter: Option<Jitter>,
+    shake: Option<Shake>,
+    shaken: Option<(CameraMatrix, CameraMatrix)>, // Last write with shake or jitter on it as it went out, and the clean matrix
+    write_channels: WriteChannels,
+    bounds: Option<Bounds>, // Set when the bounds are enforced, every write is clamped into them
+    step_distance: Option<f32>, // Set in step mode, the distance one key tap moves
+    step_held: [bool; 6],
+    stepped: bool,
+    acceleration: f32, // Fraction of the move speed gained per reference tick in smooth mode
+    deceleration: f32, // Fraction of the move speed lost per reference tick in smooth mode
+    clock: TickClock,
+    horizontal_scale: f32, // Move speed multiplier on the camera's forward and side axes
+    vertical_scale: f32,   // Move speed multiplier on the camera's up axis
+    position_only: bool,   // Set when the matrix can't be read, the camera only moves along the world axes
 }
 
 impl CameraController {
-    pub fn new(move_speed: f32, mouse_sensitivity: f32) -> Self {
+    pub fn new(move_speed: f32, mouse_sensitivity: f32, bindings: KeyBindings) -> Self {
         Self {
             move_speed,
             mouse_handler: MouseHandler::new(mouse_sensitivity),
             last_position: None,
-            min_speed: 0.1,
-            max_speed: 100.0,
-            speed_step: 0.5,
+            min_speed: 6.0,
+            max_speed: 6000.0,
+            speed_step: 30.0,
             yaw: 0.0,
             pitch: 0.0,
-            movement_input: MovementInput::new(),
+            keyboard: KeyboardSource::new(bindings),
+            typing: false,
+            sources: Vec::new(),
+            commands: Vec::new(),
+            fov_offsets: None,
+            default_fov: None,
+            original_fov: None,
+            current_fov: None,
+            screen_aspect: 4.0 / 3.0,
+            game_aspect: 4.0 / 3.0,
+            movement_mode: MovementMode::Snappy,
+            velocity: (0.0, 0.0, 0.0),
+            orbit: None,
+            follow: None,
+            skater_chain: None,
+            second_skater_chain: None,
+            skater_matrix_chain: None,
+            framing: None,
+            look_at_target: None,
+            switch_mode: SwitchMode::Stop,
+            switch_turn_rate: 60.0f32.to_radians(),
+            jitter: None,
+            shake: None,
+            shaken: None,
+            write_channels: WriteChannels::Full,
+            bounds: None,
+            step_distance: None,
+            step_held: [false; 6],
+            stepped: false,
+            acceleration: 0.08,
+            deceleration: 0.05,
+            clock: TickClock::default(),
+            horizontal_scale: 1.0,
+            vertical_scale: 1.0,
+            position_only: false,
+        }
+    }
+    
+    // Flies a camera whose matrix can't be read by moving its position only. Position-only speeds are about twice
+    // the matrix ones, and so are the speed steps.
+    pub fn set_position_only(&mut self, position_only: bool) {
+        self.position_only = position_only;
+        self.speed_step = if position_only { 60.0 } else { 30.0 };
+    }
+    
+    pub fn is_position_only(&self) -> bool {
+        self.position_only
+    }
+    
+    // New key bindings from a reloaded config.toml
+    pub fn set_bindings(&mut self, bindings: KeyBindings) {
+        self.keyboard.set_bindings(bindings);
+    }
+    
+    pub fn set_smoothing(&mut self, acceleration: f32, deceleration: f32) {
+        self.acceleration = acceleration.max(0.001);
+        self.deceleration = deceleration.max(0.001);
+    }
+    
+    pub fn set_axis_scales(&mut self, horizontal: f32, vertical: f32) {
+        self.horizontal_scale = horizontal.max(0.0);
+        self.vertical_scale = vertical.max(0.0);
+    }
+    
+    pub fn set_speed_modifiers(&mut self, sprint: f32, slow: f32) {
+        self.keyboard.set_modifiers(sprint.max(0.0), slow.max(0.0));
+    }
+    
+    // While the console is typed into, letters would fly the camera and the mouse is on its way to the console
+    pub fn set_typing(&mut self, typing: bool) {
+        self.typing = typing;
+    }
+    
+    // Another device to fly with, polled along with the keyboard and mouse
+    pub fn add_source(&mut self, source: Box<dyn InputSource>) {
+        self.sources.push(source);
+    }
+    
+    // Reads every device once per tick. Movement is kept for the next update, what only the main loop can carry
+    // out (actions buttons asked for, FOV and scrub faders) is returned.
+    pub fn poll_inputs(&mut self) -> Vec<CameraCommand> {
+        let mut commands = Vec::new();
+        if !self.typing {
+            commands.extend(self.mouse_handler.poll());
+            commands.extend(self.keyboard.poll());
+        }
+        for source in &mut self.sources {
+            commands.extend(source.poll());
+        }
+        
+        // Switch access drives the camera as if the matching key were held
+        let (x, y, z) = from_directions(self.switch_mode.movement(), 1.0);
+        if (x, y, z) != (0.0, 0.0, 0.0) {
+            commands.push(CameraCommand::Translate(x, y, z));
+        }
+        let (turn_yaw, turn_pitch) = self.switch_mode.turn();
+        if turn_yaw != 0.0 || turn_pitch != 0.0 {
+            commands.push(CameraCommand::Turn(turn_yaw * self.switch_turn_rate, turn_pitch * self.switch_turn_rate, 0.0));
+        }
+        
+        let (for_main_loop, commands) = commands.into_iter()
+            .partition(|command| matches!(command, CameraCommand::Toggle(_) | CameraCommand::Fov(_) | CameraCommand::Scrub(_)));
+        self.commands = commands;
+        for_main_loop
+    }
+    
+    // Whether this tick's input keeps the camera moving or turning, like a held key. Mouse look doesn't count.
+    pub fn has_motion(&self) -> bool {
+        self.commands.iter().any(|command| match *command {
+            CameraCommand::Translate(x, y, z) | CameraCommand::Turn(x, y, z) => (x, y, z) != (0.0, 0.0, 0.0),
+            _ => false,
+        })
+    }
+    
+    // Whether any device asked for anything this tick, mouse look and speed or zoom steps included
+    pub fn has_input(&self) -> bool {
+        !self.commands.is_empty()
+    }
+    
+    // `translate` from the input at `speed`, scaled per axis
+    fn movement_vector(&self, translate: (f32, f32, f32), speed: f32) -> (f32, f32, f32) {
+        (translate.0 * speed * self.horizontal_scale, translate.1 * speed * self.vertical_scale, translate.2 * speed * self.horizontal_scale)
+    }
+    
+    pub fn get_movement_mode(&self) -> MovementMode {
+        self.movement_mode
+    }
+    
+    pub fn toggle_movement_mode(&mut self) {
+        self.movement_mode = match self.movement_mode {
+            MovementMode::Snappy => MovementMode::Smooth,
+            MovementMode::Smooth => MovementMode::Snappy,
+        };
+        self.velocity = (0.0, 0.0, 0.0);
+        log::info!("Movement mode {:?}", self.movement_mode);
+    }
+    
+    // Starts orbiting `pivot`, or the point `default_radius` in front of the camera, and returns the pivot.
+    // The camera keeps its position and turns to face the pivot.
+    pub fn enable_orbit(&mut self, camera_matrix: &CameraMatrix, pivot: Option<CameraPosition>, default_radius: f32) -> CameraPosition {
+        let position = camera_matrix.get_position();
+        let pivot = pivot.unwrap_or_else(|| {
+            (Vec3::from(&position) + Vec3::from(&camera_matrix.get_forward()) * default_radius).into()
+        });
+        
+        let offset = Vec3::from(&position) - Vec3::from(&pivot);
+        let radius = offset.length();
+        if radius > 1.0 {
+            self.yaw = offset.z.atan2(offset.x);
+            self.pitch = (offset.y / radius).asin();
+        }
+        
+        self.last_position = Some(position);
+        self.velocity = (0.0, 0.0, 0.0);
+        self.follow = None;
+        self.framing = None;
+        self.orbit = Some(OrbitState { pivot: pivot.clone(), radius: radius.max(1.0) });
+        log::info!("Orbit mode on, pivot at ({:.1}, {:.1}, {:.1})", pivot.x, pivot.y, pivot.z);
+        pivot
+    }
+    
+    // Keeps the camera pointed at `target` while flying, `None` hands rotation back to the mouse
+    pub fn set_look_at(&mut self, target: Option<CameraPosition>) {
+        // Mouse look turns whatever the game has, so it continues from wherever the camera ended up looking
+        match &target {
+            Some(target) => log::info!("Looking at ({:.1}, {:.1}, {:.1})", target.x, target.y, target.z),
+            None => log::info!("Look-at off"),
+        }
+        self.look_at_target = target;
+    }
+    
+    pub fn get_look_at(&self) -> Option<&CameraPosition> {
+        self.look_at_target.as_ref()
+    }
+    
+    // Switch access drives the camera as if the matching key were held, until another mode is set
+    pub fn set_switch_mode(&mut self, mode: SwitchMode) {
+        self.switch_mode = mode;
+    }
+    
+    pub fn set_switch_turn_rate(&mut self, degrees_per_second: f32) {
+        self.switch_turn_rate = degrees_per_second.to_radians();
+    }
+    
+    // Step mode moves the camera `distance` once per key tap instead of continuously while held
+    pub fn enable_steps(&mut self, distance: f32) {
+        self.step_distance = Some(distance.max(0.01));
+        // Keys already down when step mode starts shouldn't count as a tap
+        self.step_held = directions(Motion::new(&self.commands).translate);
+        self.velocity = (0.0, 0.0, 0.0);
+        log::info!("Step mode on, {:.1} units per step", distance);
+    }
+    
+    pub fn disable_steps(&mut self) {
+        if self.step_distance.take().is_some() {
+            log::info!("Step mode off");
+        }
+    }
+    
+    pub fn is_stepping(&self) -> bool {
+        self.step_distance.is_some()
+    }
+    
+    // Whether the camera made a step since the last call
+    pub fn take_step(&mut self) -> bool {
+        std::mem::take(&mut self.stepped)
+    }
+    
+    pub fn set_jitter(&mut self, jitter: Option<Jitter>) {
+        self.jitter = jitter;
+    }
+    
+    pub fn is_jittering(&self) -> bool {
+        self.jitter.is_some()
+    }
+    
+    pub fn set_shake(&mut self, shake: Option<Shake>) {
+        self.shake = shake;
+    }
+    
+    pub fn is_shaking(&self) -> bool {
+        self.shake.is_some()
+    }
+    
+    // Reads the camera without the jitter and shake this controller added to it. The written channels are
+    // compared, the others are the game's; when the game moved the camera since, its matrix is kept.
+    pub fn read_camera_matrix(&self, process: &ProcessHandle, base_addr: usize) -> Result<CameraMatrix, String> {
+        let mut camera_matrix = process.get_camera_matrix(base_addr)?;
+        if let Some((written, clean)) = &self.shaken {
+            let channels = self.write_channels.floats();
+            if channels.clone().all(|i| camera_matrix.data[i] == written.data[i]) {
+                for i in channels {
+                    camera_matrix.data[i] = clean.data[i];
+                }
+            }
+        }
+        Ok(camera_matrix)
+    }
+    
+    pub fn set_write_channels(&mut self, channels: WriteChannels) {
+        self.write_channels = channels;
+        self.shaken = None;
+    }
+    
+    pub fn set_bounds(&mut self, bounds: Option<Bounds>) {
+        self.bounds = bounds;
+    }
+    
+    // Writes the camera with this frame's shake and jitter on top, kept inside the bounds and limited to the configured
+    // channels. The clean matrix is clamped as well and kept for reads, so the offsets never build up in the game.
+    pub fn write_camera_matrix(&mut self, process: &ProcessHandle, base_addr: usize, camera_matrix: &CameraMatrix) -> Result<(), String> {
+        let clean = self.clamped(camera_matrix.clone());
+        let shaken = match &self.shake {
+            Some(shake) => shake.apply(&clean),
+            None => clean.clone(),
+        };
+        let jittered = match &mut self.jitter {
+            Some(jitter) => jitter.apply(&shaken),
+            None => shaken,
+        };
+        let matrix = self.clamped(jittered);
+        process.set_camera_matrix_channels(base_addr, &matrix, self.write_channels)?;
+        self.shaken = (self.shake.is_some() || self.jitter.is_some()).then_some((matrix, clean));
+        Ok(())
+    }
+    
+    fn clamped(&self, mut matrix: CameraMatrix) -> CameraMatrix {
+        if let Some(bounds) = &self.bounds {
+            let mut position = matrix.get_position();
+            if bounds.clamp(&mut position) {
+                matrix.set_position(&position);
+            }
+        }
+        matrix
+    }
+    
+    pub fn read_pose(&self, process: &ProcessHandle, base_addr: usize) -> Result<CameraPose, String> {
+        self.read_camera_matrix(process, base_addr).map(|matrix| matrix.pose())
+    }
+    
+    // Puts the camera at `pose`, the rest of the game's matrix stays as it is
+    pub fn write_pose(&mut self, process: &ProcessHandle, base_addr: usize, pose: &CameraPose) -> Result<(), String> {
+        let mut matrix = self.read_camera_matrix(process, base_addr)?;
+        matrix.set_pose(pose);
+        self.write_camera_matrix(process, base_addr, &matrix)
+    }
+    
+    pub fn set_skater_chain(&mut self, chain: Option<PointerChain>) {
+        self.skater_chain = chain;
+    }
+    
+    // Starts following the skater at the camera's current offset from them
+    pub fn enable_follow(&mut self, process: &ProcessHandle, base_addr: usize, smoothing: f32) -> Result<(), String> {
+        let chain = self.skater_chain.as_ref().ok_or("No skater pointer chain in the offsets file")?;
+        let skater = process.get_skater_position(base_addr, chain)?;
+        let camera = self.read_camera_matrix(process, base_addr)?.get_position();
+        
+        self.orbit = None;
+        self.framing = None;
+        self.follow = Some(FollowState {
+            offset: (camera.x - skater.x, camera.y - skater.y, camera.z - skater.z),
+            target: skater,
+            smoothing: smoothing.clamp(0.001, 1.0),
+        });
+        log::info!("Follow mode on");
+        Ok(())
+    }
+    
+    pub fn set_second_skater_chain(&mut self, chain: Option<PointerChain>) {
+        self.second_skater_chain = chain;
+    }
+    
+    pub fn set_skater_matrix_chain(&mut self, chain: Option<PointerChain>) {
+        self.skater_matrix_chain = chain;
+    }
+    
+    // Snaps the camera to `height` above the skater, facing the way they face when their orientation is known.
+    // Orbit, follow and framing would drag it straight back, so they are turned off.
+    pub fn reset_to_skater(&mut self, process: &ProcessHandle, base_addr: usize, height: f32) -> Result<CameraPosition, String> {
+        let chain = self.skater_chain.as_ref().ok_or("No skater pointer chain in the offsets file")?;
+        let skater = process.get_skater_position(base_addr, chain)?;
+        let mut camera_matrix = self.read_camera_matrix(process, base_addr)?;
+        
+        if let Some(matrix_chain) = &self.skater_matrix_chain {
+            let orientation = process.get_skater_matrix(base_addr, matrix_chain)?;
+            camera_matrix.set_rotation_matrix(&orientation.rotation_matrix());
+        }
+        let target = CameraPosition::new(skater.x, skater.y + height, skater.z);
+        camera_matrix.set_position(&target);
+        
+        self.orbit = None;
+        self.follow = None;
+        self.framing = None;
+        self.write_camera_matrix(process, base_addr, &camera_matrix)?;
+        self.resync();
+        Ok(target)
+    }
+    
+    // Starts keeping both skaters in frame, from the side of them the camera is on now
+    pub fn enable_framing(&mut self, process: &ProcessHandle, base_addr: usize, margin: f32, elevation_degrees: f32,
+                          min_distance: f32, smoothing: f32) -> Result<(), String> {
+        let first = self.skater_chain.as_ref().ok_or("No skater pointer chain in the offsets file")?;
+        let second = self.second_skater_chain.as_ref().ok_or("No skater2 pointer chain in the offsets file")?;
+        let a = process.get_skater_position(base_addr, first)?;
+        let b = process.get_skater_position(base_addr, second)?;
+        let camera = self.read_camera_matrix(process, base_addr)?.get_position();
+        // Distances are worked out from the FOV, so pick up whatever the game uses right now
+        let _ = self.refresh_fov(process, base_addr);
+        
+        let center = CameraPosition::new((a.x + b.x) / 2.0, (a.y + b.y) / 2.0, (a.z + b.z) / 2.0);
+        let offset = (camera.x - center.x, camera.y - center.y, camera.z - center.z);
+        let horizontal = (offset.0 * offset.0 + offset.2 * offset.2).sqrt();
+        let direction = if horizontal > 1.0 { (offset.0 / horizontal, offset.2 / horizontal) } else { (1.0, 0.0) };
+        
+        self.orbit = None;
+        self.follow = None;
+        self.framing = Some(FramingState {
+            distance: (horizontal * horizontal + offset.1 * offset.1).sqrt().max(min_distance),
+            center,
+            direction,
+            margin: margin.max(0.0),
+            elevation: elevation_degrees.clamp(-89.0, 89.0).to_radians(),
+            min_distance,
+            smoothing: smoothing.clamp(0.001, 1.0),
+        });
+        log::info!("Framing mode on");
+        Ok(())
+    }
+    
+    pub fn disable_framing(&mut self) {
+        if self.framing.take().is_some() {
+            log::info!("Framing mode off");
         }
+        self.resync();
+    }
+    
+    pub fn is_framing(&self) -> bool {
+        self.framing.is_some()
+    }
+    
+    // Moves the camera so both skaters fit the current FOV, side-on to the line between them
+    fn update_framing(&mut self, process: &ProcessHandle, base_addr: usize, camera_matrix: &mut CameraMatrix, seconds: f32) -> Result<(), String> {
+        let (Some(first), Some(second)) = (&self.skater_chain, &self.second_skater_chain) else {
+            return Ok(());
+        };
+        let a = process.get_skater_position(base_addr, first)?;
+        let b = process.get_skater_position(base_addr, second)?;
+        
+        let hfov = self.get_horizontal_fov().unwrap_or(DEFAULT_HORIZONTAL_FOV);
+        let vfov = horizontal_to_vertical(hfov, self.screen_aspect);
+        let Some(framing) = &mut self.framing else {
+            return Ok(());
+        };
+        let smoothing = catch_up(framing.smoothing, seconds);
+        
+        // Looking across the line between the skaters spreads them over the width of the frame.
+        // Stay on the side the camera is already on so it doesn't swing around when they cross.
+        let separation = (b.x - a.x, b.y - a.y, b.z - a.z);
+        let horizontal = (separation.0 * separation.0 + separation.2 * separation.2).sqrt();
+        if horizontal > 1.0 {
+            let mut side = (-separation.2 / horizontal, separation.0 / horizontal);
+            if side.0 * framing.direction.0 + side.1 * framing.direction.1 < 0.0 {
+                side = (-side.0, -side.1);
+            }
+            let eased = (
+                framing.direction.0 + (side.0 - framing.direction.0) * smoothing,
+                framing.direction.1 + (side.1 - framing.direction.1) * smoothing,
+            );
+            let length = (eased.0 * eased.0 + eased.1 * eased.1).sqrt();
+            if length > 1e-3 {
+                framing.direction = (eased.0 / length, eased.1 / length);
+            }
+        }
+        
+        // Far enough back that the gap plus the margin fits both the width and the height of the view
+        let half_width = horizontal / 2.0 + framing.margin;
+        let half_height = separation.1.abs() / 2.0 + framing.margin;
+        let distance = (half_width / (hfov.to_radians() / 2.0).tan())
+            .max(half_height / (vfov.to_radians() / 2.0).tan())
+            .max(framing.min_distance);
+        
+        let center = CameraPosition::new((a.x + b.x) / 2.0, (a.y + b.y) / 2.0, (a.z + b.z) / 2.0);
+        framing.center.x += (center.x - framing.center.x) * smoothing;
+        framing.center.y += (center.y - framing.center.y) * smoothing;
+        framing.center.z += (center.z - framing.center.z) * smoothing;
+        framing.distance += (distance - framing.distance) * smoothing;
+        
+        let (cos_elevation, sin_elevation) = (framing.elevation.cos(), framing.elevation.sin());
+        camera_matrix.set_position(&CameraPosition::new(
+            framing.center.x + framing.direction.0 * cos_elevation * framing.distance,
+            framing.center.y + sin_elevation * framing.distance,
+            framing.center.z + framing.direction.1 * cos_elevation * framing.distance,
+        ));
+        camera_matrix.look_at(&framing.center);
+        Ok(())
+    }
+    
+    pub fn disable_follow(&mut self) {
+        if self.follow.take().is_some() {
+            log::info!("Follow mode off");
+        }
+    }
+    
+    pub fn is_following(&self) -> bool {
+        self.follow.is_some()
+    }
+    
+    // Carries the camera along with the skater. Movement since `start_pos` shifts the offset instead.
+    fn update_follow(&mut self, process: &ProcessHandle, base_addr: usize, start_pos: &CameraPosition, camera_matrix: &mut CameraMatrix,
+                     seconds: f32) -> Result<(), String> {
+        let (Some(follow), Some(chain)) = (&mut self.follow, &self.skater_chain) else {
+            return Ok(());
+        };
+        let skater = process.get_skater_position(base_addr, chain)?;
+        
+        let moved_to = camera_matrix.get_position();
+        follow.offset.0 += moved_to.x - start_pos.x;
+        follow.offset.1 += moved_to.y - start_pos.y;
+        follow.offset.2 += moved_to.z - start_pos.z;
+        
+        // Ease towards the skater so landings and bails don't shake the camera
+        let smoothing = catch_up(follow.smoothing, seconds);
+        follow.target.x += (skater.x - follow.target.x) * smoothing;
+        follow.target.y += (skater.y - follow.target.y) * smoothing;
+        follow.target.z += (skater.z - follow.target.z) * smoothing;
+        
+        camera_matrix.set_position(&CameraPosition::new(
+            follow.target.x + follow.offset.0,
+            follow.target.y + follow.offset.1,
+            follow.target.z + follow.offset.2,
+        ));
+        Ok(())
+    }
+    
+    pub fn disable_orbit(&mut self) {
+        if self.orbit.take().is_some() {
+            log::info!("Orbit mode off");
+        }
+    }
+    
+    pub fn is_orbiting(&self) -> bool {
+        self.orbit.is_some()
+    }
+    
+    // Mouse and left/right/up/down rotate around the pivot, forward/backward zoom. Returns whether there was input.
+    fn update_orbit(&mut self, camera_matrix: &mut CameraMatrix, motion: &Motion, seconds: f32) -> bool {
+        let (turn_yaw, turn_pitch, _) = motion.turned(seconds);
+        let distance = self.move_speed * seconds;
+        let (dx, dy, dz) = (motion.translate.0 * distance, motion.translate.1 * distance, motion.translate.2 * distance);
+        
+        // Key rotation scales with the move speed so Page Up/Down affect both modes
+        let rotate_rate = self.move_speed * 0.004 / REFERENCE_RATE;
+        self.yaw += turn_yaw + dx * rotate_rate;
+        self.pitch = (self.pitch + turn_pitch + dy * rotate_rate)
+            .clamp(-std::f32::consts::FRAC_PI_2 * 0.99, std::f32::consts::FRAC_PI_2 * 0.99);
+        
+        let orbit = match &mut self.orbit {
+            Some(orbit) => orbit,
+            None => return false,
+        };
+        orbit.radius = (orbit.radius - dz).max(1.0);
+        
+        // Place the camera on the sphere around the pivot, looking back at it
+        let (cos_pitch, sin_pitch) = (self.pitch.cos(), self.pitch.sin());
+        let position = CameraPosition::new(
+            orbit.pivot.x + orbit.radius * cos_pitch * self.yaw.cos(),
+            orbit.pivot.y + orbit.radius * sin_pitch,
+            orbit.pivot.z + orbit.radius * cos_pitch * self.yaw.sin(),
+        );
+        camera_matrix.set_position(&position);
+        camera_matrix.look_at(&orbit.pivot);
+        
+        turn_yaw != 0.0 || turn_pitch != 0.0 || dx != 0.0 || dy != 0.0 || dz != 0.0
+    }
+    
+    // Velocity in units per second, the returned movement is for a tick of `seconds`
+    fn update_velocity(&mut self, target: (f32, f32, f32), seconds: f32) -> (f32, f32, f32) {
+        // Accelerate towards held keys, decelerate on axes that are released
+        let step = |current: f32, target: f32| {
+            let rate = if (target != 0.0 && target.signum() == current.signum()) || current == 0.0 {
+                self.acceleration
+            } else {
+                self.deceleration
+            };
+            approach(current, target, rate * self.move_speed * seconds * REFERENCE_RATE)
+        };
+        
+        self.velocity = (
+            step(self.velocity.0, target.0),
+            step(self.velocity.1, target.1),
+            step(self.velocity.2, target.2),
+        );
+        (self.velocity.0 * seconds, self.velocity.1 * seconds, self.velocity.2 * seconds)
+    }
+    
+    pub fn set_fov_offsets(&mut self, fov_offsets: Option<FovOffsets>) {
+        let Some(fov_offsets) = fov_offsets else {
+            self.fov_offsets = None;
+            return;
+        };
+        self.screen_aspect = fov_offsets.screen_aspect.unwrap_or_else(screen_aspect);
+        self.game_aspect = fov_offsets.game_aspect.unwrap_or(self.screen_aspect);
+        
+        // A horizontal FOV preset is converted for this resolution so it frames the same as on the author's setup
+        self.default_fov = match fov_offsets.target_hfov {
+            Some(hfov) => Some(game_fov_from_preset(
+                hfov,
+                fov_offsets.target_aspect,
+                self.game_aspect,
+                fov_offsets.axis,
+                fov_offsets.radians,
+            )),
+            None => fov_offsets.default,
+        };
+        self.fov_offsets = Some(fov_offsets);
+    }
+    
+    pub fn get_fov(&self) -> Option<f32> {
+        self.current_fov
+    }
+    
+    // Current FOV as horizontal degrees at the screen aspect ratio
+    pub fn get_horizontal_fov(&self) -> Option<f32> {
+        let fov_offsets = self.fov_offsets.as_ref()?;
+        let fov = self.current_fov?;
+        Some(preset_from_game_fov(fov, self.screen_aspect, self.game_aspect, fov_offsets.axis, fov_offsets.radians))
+    }
+    
+    // Sets the FOV from horizontal degrees at the screen aspect ratio, within the configured limits
+    pub fn set_horizontal_fov(&mut self, process: &ProcessHandle, base_addr: usize, hfov_deg: f32) -> Result<(), String> {
+        let fov_offsets = self.fov_offsets.as_ref().ok_or("No FOV offsets configured")?;
+        let fov = game_fov_from_preset(hfov_deg, self.screen_aspect, self.game_aspect, fov_offsets.axis, fov_offsets.radians)
+            .clamp(fov_offsets.min, fov_offsets.max);
+        
+        let chain = fov_offsets.chain.clone();
+        self.write_fov(process, base_addr, &chain, fov)
+    }
+    
+    fn write_fov(&mut self, process: &ProcessHandle, base_addr: usize, chain: &PointerChain, fov: f32) -> Result<(), String> {
+        if self.original_fov.is_none() {
+            self.original_fov = Some(process.get_fov(base_addr, chain)?);
+        }
+        process.set_fov(base_addr, chain, fov)?;
+        self.current_fov = Some(fov);
+        Ok(())
+    }
+    
+    // Puts back the FOV the game had before the tool changed it
+    pub fn restore_fov(&mut self, process: &ProcessHandle, base_addr: usize) -> Result<(), String> {
+        let (Some(fov_offsets), Some(original)) = (&self.fov_offsets, self.original_fov.take()) else {
+            return Ok(());
+        };
+        process.set_fov(base_addr, &fov_offsets.chain, original)?;
+        self.current_fov = Some(original);
+        Ok(())
+    }
+    
+    // The FOV chain and what the game had on it, once the tool has changed the FOV
+    pub fn original_fov(&self) -> Option<(PointerChain, f32)> {
+        let original = self.original_fov?;
+        Some((self.fov_offsets.as_ref()?.chain.clone(), original))
+    }
+    
+    // Re-reads the FOV from the game and returns (horizontal, vertical) degrees at the screen aspect
+    pub fn refresh_fov(&mut self, process: &ProcessHandle, base_addr: usize) -> Result<Option<(f32, f32)>, String> {
+        let fov_offsets = match &self.fov_offsets {
+            Some(offsets) => offsets,
+            None => return Ok(None),
+        };
+        
+        self.current_fov = Some(process.get_fov(base_addr, &fov_offsets.chain)?);
+        Ok(self.get_horizontal_fov().map(|hfov| (hfov, horizontal_to_vertical(hfov, self.screen_aspect))))
+    }
+    
+    // Re-reads position and orientation from the game on the next update, e.g. after path playback moved the camera
+    pub fn resync(&mut self) {
+        self.last_position = None;
+        self.velocity = (0.0, 0.0, 0.0);
+        self.clock.reset();
     }
     
     pub fn increase_speed(&mut self) {
@@ -37,6 +824,10 @@ impl CameraController {
         self.move_speed = (self.move_speed - self.speed_step).max(self.min_speed);
     }
     
+    pub fn set_speed(&mut self, speed: f32) {
+        self.move_speed = speed.clamp(self.min_speed, self.max_speed);
+    }
+    
     pub fn get_speed(&self) -> f32 {
         self.move_speed
     }
@@ -53,104 +844,141 @@ impl CameraController {
         self.mouse_handler.is_enabled()
     }
     
-    fn reconstruct_camera_matrix(&self, camera_matrix: &mut CameraMatrix) {
-        // Create rotation matrix from yaw and pitch
-        let cos_yaw = self.yaw.cos();
-        let sin_yaw = self.yaw.sin();
-        let cos_pitch = self.pitch.cos();
-        let sin_pitch = self.pitch.sin();
-        
-        // Calculate forward vector
-        let forward_x = cos_pitch * cos_yaw;
-        let forward_y = sin_pitch;
-        let forward_z = cos_pitch * sin_yaw;
-        
-        // Calculate right vector (cross product of world up and forward)
-        let right_x = -sin_yaw;
-        let right_y = 0.0;
-        let right_z = cos_yaw;
-        
-        // Calculate up vector (cross product of forward and right)
-        let up_x = -sin_pitch * cos_yaw;
-        let up_y = cos_pitch;
-        let up_z = -sin_pitch * sin_yaw;
+    pub fn set_mouse_sensitivity(&mut self, sensitivity: f32) {
+        self.mouse_handler.set_sensitivity(sensitivity.max(0.0));
+    }
+    
+    pub fn get_mouse_sensitivity(&self) -> f32 {
+        self.mouse_handler.sensitivity()
+    }
+    
+    // `fov_delta` steps wider when positive, `reset` goes back to the default FOV
+    fn update_fov(&mut self, process: &ProcessHandle, base_addr: usize, fov_delta: i32, reset: bool) -> Result<bool, String> {
+        let fov_offsets = match &self.fov_offsets {
+            Some(offsets) => offsets,
+            None => return Ok(false),
+        };
         
-        // Set the rotation part of the matrix (preserve position)
-        camera_matrix.data[0] = right_x;
-        camera_matrix.data[1] = right_y;
-        camera_matrix.data[2] = right_z;
+        if fov_delta == 0 && !reset {
+            return Ok(false);
+        }
         
-        camera_matrix.data[4] = up_x;
-        camera_matrix.data[5] = up_y;
-        camera_matrix.data[6] = up_z;
+        let fov = process.get_fov(base_addr, &fov_offsets.chain)?;
+        // Remember the game's own FOV the first time we touch it so it can be restored
+        let default_fov = *self.default_fov.get_or_insert(fov);
         
-        camera_matrix.data[8] = -forward_x;
-        camera_matrix.data[9] = -forward_y;
-        camera_matrix.data[10] = -forward_z;
+        let new_fov = if reset {
+            default_fov
+        } else {
+            (fov + fov_delta as f32 * fov_offsets.step).clamp(fov_offsets.min, fov_offsets.max)
+        };
         
-        // Keep existing position (data[12], data[13], data[14])
-        // Keep existing bottom row (data[3], data[7], data[11], data[15])
+        let chain = fov_offsets.chain.clone();
+        self.write_fov(process, base_addr, &chain, new_fov)?;
+        Ok(true)
     }
     
     pub fn update_camera(&mut self, process: &ProcessHandle, base_addr: usize) -> Result<bool, String> {
-        // Check for speed adjustment using Page Up/Down
-        let speed_delta = get_speed_delta();
-        if speed_delta > 0 {
-            self.increase_speed();
-        } else if speed_delta < 0 {
-            self.decrease_speed();
+        let motion = Motion::new(&std::mem::take(&mut self.commands));
+        
+        // Speed steps (Page Up/Down by default)
+        if motion.speed != 0 {
+            self.set_speed(self.move_speed + motion.speed as f32 * self.speed_step);
+        }
+        
+        let seconds = self.clock.tick();
+        if self.position_only {
+            return self.update_position(process, base_addr, motion.translate, seconds);
         }
         
+        // Zoom steps ([ and ] by default)
+        let fov_changed = match self.update_fov(process, base_addr, motion.zoom, motion.zoom_reset) {
+            Ok(changed) => changed,
+            Err(e) => return Err(format!("Failed to update FOV: {}", e)),
+        };
+        
         // Get current camera matrix
-        let mut camera_matrix = match process.get_camera_matrix(base_addr) {
+        let mut camera_matrix = match self.read_camera_matrix(process, base_addr) {
             Ok(matrix) => matrix,
             Err(e) => return Err(format!("Failed to read camera matrix: {}", e)),
         };
         
-        // Store the first position we read and initialize yaw/pitch from camera
+        // Store the first position we read
         let current_pos = camera_matrix.get_position();
         if self.last_position.is_none() {
             self.last_position = Some(current_pos.clone());
-            // Initialize yaw and pitch from current camera orientation
-            let forward = camera_matrix.get_forward();
-            self.yaw = forward.z.atan2(forward.x);
-            self.pitch = (-forward.y).asin();
+        }
+        
+        // Framing drives the camera on its own, the skaters decide where it goes
+        if self.framing.is_some() {
+            self.update_framing(process, base_addr, &mut camera_matrix, seconds)
+                .map_err(|e| format!("Failed to frame the skaters: {}", e))?;
+            self.write_camera_matrix(process, base_addr, &camera_matrix)
+                .map_err(|e| format!("Failed to set camera matrix: {}", e))?;
+            self.last_position = Some(camera_matrix.get_position());
+            return Ok(true);
+        }
+        
+        // Orbit mode rewrites the camera every tick so it stays locked onto the pivot
+        if self.orbit.is_some() {
+            let moved = self.update_orbit(&mut camera_matrix, &motion, seconds);
+            self.write_camera_matrix(process, base_addr, &camera_matrix)
+                .map_err(|e| format!("Failed to set camera matrix: {}", e))?;
+            self.last_position = Some(camera_matrix.get_position());
+            return Ok(moved || fov_changed);
         }
         
         let mut moved = false;
         
-        // Handle mouse movement for rotation
-        if self.mouse_handler.is_enabled() {
-            let (mouse_dx, mouse_dy) = self.mouse_handler.get_delta();
-            
-            if mouse_dx.abs() > 0.01 || mouse_dy.abs() > 0.01 {
-                // Update yaw and pitch (inverted controls for natural feel)
-                self.yaw += mouse_dx * 0.002; // Convert mouse delta to radians (inverted)
-                self.pitch += mouse_dy * 0.002; // (inverted)
-                
-                // Clamp pitch to prevent camera flipping
-                self.pitch = self.pitch.clamp(-std::f32::consts::FRAC_PI_2 * 0.99, 
-                                              std::f32::consts::FRAC_PI_2 * 0.99);
-                
-                // Reconstruct camera matrix from yaw and pitch
-                self.reconstruct_camera_matrix(&mut camera_matrix);
-                moved = true;
+        // Mouse look and knobs turn as far as they moved, sticks and switch access at a steady rate. Turning from the
+        // current orientation keeps roll from a path or the game.
+        let (yaw, pitch, roll) = motion.turned(seconds);
+        if yaw != 0.0 || pitch != 0.0 {
+            camera_matrix.turn(yaw, pitch);
+            moved = true;
+        }
+        if roll != 0.0 {
+            camera_matrix.roll(roll);
+            moved = true;
+        }
+        
+        // Apply movement if anything was held (or the camera is still gliding in smooth mode)
+        let (dx, dy, dz) = match (self.step_distance, self.movement_mode) {
+            (Some(distance), _) => {
+                let held = directions(motion.translate);
+                let tapped = std::array::from_fn(|index| held[index] && !self.step_held[index]);
+                self.step_held = held;
+                let step = from_directions(tapped, distance);
+                self.stepped |= step != (0.0, 0.0, 0.0);
+                step
             }
+            (None, MovementMode::Snappy) => self.movement_vector(motion.translate, self.move_speed * seconds),
+            (None, MovementMode::Smooth) => {
+                let target = self.movement_vector(motion.translate, self.move_speed);
+                self.update_velocity(target, seconds)
+            }
+        };
+        if dx != 0.0 || dy != 0.0 || dz != 0.0 {
+            camera_matrix.apply_translation(dx, dy, dz);
+            moved = true;
         }
         
-        // Read movement input
-        self.movement_input.read_input();
+        // Follow mode moves the camera every tick, even without input
+        if self.follow.is_some() {
+            self.update_follow(process, base_addr, &current_pos, &mut camera_matrix, seconds)
+                .map_err(|e| format!("Failed to follow skater: {}", e))?;
+            moved = true;
+        }
         
-        // Apply movement if any keys were pressed
-        if self.movement_input.has_movement() {
-            let (dx, dy, dz) = self.movement_input.get_movement_vector(self.move_speed);
-            camera_matrix.apply_translation(dx, dy, dz);
+        // Look-at overrides mouse rotation so the target stays centred while flying around it
+        if let Some(target) = &self.look_at_target {
+            camera_matrix.look_at(target);
             moved = true;
         }
         
-        // Update camera matrix if anything changed
-        if moved {
-            match process.set_camera_matrix(base_addr, &camera_matrix) {
+        // Jitter and shake change every frame, so the camera is written even when standing still
+        if moved || self.jitter.is_some() || self.shake.is_some() {
+            match self.write_camera_matrix(process, base_addr, &camera_matrix) {
                 Ok(_) => {
                     let new_pos = camera_matrix.get_position();
                     self.last_position = Some(new_pos);
@@ -160,100 +988,57 @@ impl CameraController {
             }
         }
         
-        Ok(false)
+        Ok(fov_changed)
+    }    
+    // Position-only flying: the movement keys move the camera along the world axes, the view doesn't turn
+    fn update_position(&mut self, process: &ProcessHandle, base_addr: usize, translate: (f32, f32, f32), seconds: f32) -> Result<bool, String> {
+        let (dx, dy, dz) = translate;
+        if (dx, dy, dz) == (0.0, 0.0, 0.0) {
+            return Ok(false);
+        }
+        let mut position = process.get_camera_position(base_addr).map_err(|e| format!("Failed to read camera position: {}", e))?;
+        let distance = self.move_speed * seconds;
+        position.x -= dx * distance; // J key moves left (negative X)
+        position.y += dy * distance;
+        position.z += dz * distance;
+        if let Some(bounds) = &self.bounds {
+            bounds.clamp(&mut position);
+        }
+        process.set_camera_position(base_addr, &position).map_err(|e| format!("Failed to set camera position: {}", e))?;
+        self.last_position = Some(position);
+        Ok(true)
     }
 }
 
-// Basic camera controller (fallback for position-only mode)
-pub struct BasicCameraController {
-    move_speed: f32,
-    last_position: Option<CameraPosition>,
-    min_speed: f32,
-    max_speed: f32,
-    speed_step: f32,
-    movement_input: MovementInput,
+// Puts the game's FOV back when it's dropped, in case the session ends without restore_fov, e.g. on a panic
+pub struct FovRestore<'a> {
+    process: &'a ProcessHandle,
+    base_addr: usize,
+    original: Option<(PointerChain, f32)>,
 }
 
-impl BasicCameraController {
-    pub fn new(move_speed: f32) -> Self {
-        Self {
-            move_speed,
-            last_position: None,
-            min_speed: 0.1,
-            max_speed: 100.0,
-            speed_step: 1.0,
-            movement_input: MovementInput::new(),
-        }
-    }
-    
-    pub fn increase_speed(&mut self) {
-        self.move_speed = (self.move_speed + self.speed_step).min(self.max_speed);
-    }
-    
-    pub fn decrease_speed(&mut self) {
-        self.move_speed = (self.move_speed - self.speed_step).max(self.min_speed);
-    }
-    
-    pub fn get_speed(&self) -> f32 {
-        self.move_speed
+impl<'a> FovRestore<'a> {
+    pub fn new(process: &'a ProcessHandle, base_addr: usize) -> Self {
+        Self { process, base_addr, original: None }
     }
     
-    pub fn update_camera(&mut self, process: &ProcessHandle, base_addr: usize) -> Result<bool, String> {
-        // Check for speed adjustment using Page Up/Down
-        let speed_delta = get_speed_delta();
-        if speed_delta > 0 {
-            self.increase_speed();
-        } else if speed_delta < 0 {
-            self.decrease_speed();
-        }
-        
-        // Get current camera position
-        let current_pos = match process.get_camera_position(base_addr) {
-            Ok(pos) => pos,
-            Err(e) => return Err(format!("Failed to read camera position: {}", e)),
-        };
-        
-        // Store the first position we read
-        if self.last_position.is_none() {
-            self.last_position = Some(current_pos.clone());
+    // Picks up the original FOV after the controller's first write, and lets go of it once restore_fov put it back
+    pub fn track(&mut self, controller: &CameraController) {
+        if controller.original_fov.is_none() {
+            self.original = None;
+        } else if self.original.is_none() {
+            self.original = controller.original_fov();
         }
-        
-        let mut new_pos = current_pos.clone();
-        
-        // Read movement input
-        self.movement_input.read_input();
-        
-        // Apply movement if any keys were pressed
-        if self.movement_input.has_movement() {
-            // For basic controller, apply movement directly to world coordinates
-            if self.movement_input.forward {
-                new_pos.z += self.move_speed;
-            }
-            if self.movement_input.backward {
-                new_pos.z -= self.move_speed;
-            }
-            if self.movement_input.left {
-                new_pos.x -= self.move_speed; // J key moves left (negative X)
-            }
-            if self.movement_input.right {
-                new_pos.x += self.move_speed; // L key moves right (positive X)
-            }
-            if self.movement_input.up {
-                new_pos.y += self.move_speed;
-            }
-            if self.movement_input.down {
-                new_pos.y -= self.move_speed;
-            }
-            
-            match process.set_camera_position(base_addr, &new_pos) {
-                Ok(_) => {
-                    self.last_position = Some(new_pos.clone());
-                    return Ok(true);
-                }
-                Err(e) => return Err(format!("Failed to set camera position: {}", e)),
+    }
+}
+
+impl Drop for FovRestore<'_> {
+    fn drop(&mut self) {
+        if let Some((chain, fov)) = self.original.take() {
+            match self.process.set_fov(self.base_addr, &chain, fov) {
+                Ok(()) => log::info!("Restored the FOV to {} on exit", fov),
+                Err(e) => log::error!("Failed to restore the FOV on exit: {}", e),
             }
         }
-        
-        Ok(false)
     }
 }
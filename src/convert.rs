@@ -0,0 +1,285 @@
+// Converts camera paths between the tool's own path file and the formats it exports,
+// so a move recorded in one format can be played back or edited in another.
+
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+use crate::camera::{CameraMatrix, Quaternion};
+use crate::interpolation::InterpolationMode;
+use crate::numbers::parse_number;
+use crate::path::{CameraPath, Keyframe};
+use crate::telemetry::csv_header;
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum PathFormat {
+    Path,    // Keyframe path file (.toml)
+    Csv,     // Telemetry CSV, or a camera animation with a row per frame
+    Blender, // Per-frame JSON for a Blender import script
+    Chan,    // Nuke/Blender camera channel file
+}
+
+impl PathFormat {
+    pub fn from_extension(file: &str) -> Option<Self> {
+        let extension = file.rsplit_once('.')?.1.to_ascii_lowercase();
+        match extension.as_str() {
+            "toml" => Some(PathFormat::Path),
+            "csv" => Some(PathFormat::Csv),
+            "json" => Some(PathFormat::Blender),
+            "chan" => Some(PathFormat::Chan),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BlenderCamera {
+    fps: f32,
+    frames: Vec<BlenderFrame>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BlenderFrame {
+    frame: u32,
+    location: [f32; 3],
+    rotation_quaternion: [f32; 4], // w, x, y, z like Blender
+}
+
+// Spreadsheets on decimal-comma systems save with ';' between fields, the numbers are read either way
+fn read_csv(contents: &str, fps: f32) -> Result<CameraPath, String> {
+    let mut lines = contents.lines();
+    let first = lines.next().ok_or("CSV file is empty")?;
+    let delimiter = if first.contains(';') { ';' } else { ',' };
+    let header: Vec<&str> = first.split(delimiter).map(str::trim).collect();
+    let column = |name: &str| header.iter().position(|&h| h == name).ok_or(format!("CSV has no '{}' column", name));
+    
+    // Telemetry recordings say what each row is, anything else is an animation made in another program
+    let Ok(kind) = column("kind") else {
+        return read_animation_csv(lines, delimiter, &header, fps);
+    };
+    let elapsed = column("elapsed_ms")?;
+    let position = [column("x")?, column("y")?, column("z")?];
+    let first_element = column("m0")?;
+    
+    let mut path = CameraPath::default();
+    for (number, line) in lines.enumerate() {
+        let fields: Vec<&str> = line.split(delimiter).collect();
+        if fields.get(kind) != Some(&"pose") {
+            continue; // Sync markers
+        }
+        
+        let parse = |index: usize| -> Result<f32, String> {
+            let value = fields.get(index).ok_or(format!("CSV line {} has too few fields", number + 2))?;
+            parse_number(value).map_err(|e| format!("CSV line {}: {}", number + 2, e))
+        };
+        
+        // Position-only recordings leave the matrix empty, keep the default orientation for those
+        let mut matrix = CameraMatrix::new();
+        if fields.get(first_element).is_some_and(|value| !value.is_empty()) {
+            for i in 0..16 {
+                matrix.data[i] = parse(first_element + i)?;
+            }
+        }
+        matrix.data[12] = parse(position[0])?;
+        matrix.data[13] = parse(position[1])?;
+        matrix.data[14] = parse(position[2])?;
+        
+        path.keyframes.push(Keyframe::from_matrix(parse(elapsed)? / 1000.0, &matrix));
+    }
+    
+    // Recordings start a few milliseconds in, paths start at zero
+    let start = path.keyframes.first().map_or(0.0, |keyframe| keyframe.time);
+    for keyframe in &mut path.keyframes {
+        keyframe.time -= start;
+    }
+    Ok(path)
+}
+
+// One row per frame with a `time` in seconds or a `frame` number, `x`, `y`, `z` and either the rotation as a
+// quaternion (`qx`, `qy`, `qz`, `qw`, like shots_file) or as XYZ Euler angles in degrees (`rx`, `ry`, `rz`, like
+// .chan files). Without rotation columns the camera keeps the default orientation.
+fn read_animation_csv<'a>(lines: impl Iterator<Item = &'a str>, delimiter: char, header: &[&str], fps: f32) -> Result<CameraPath, String> {
+    let find = |name: &str| header.iter().position(|&h| h.eq_ignore_ascii_case(name));
+    let column = |name: &str| find(name).ok_or(format!("CSV has no '{}' column", name));
+    // Column with the timing and what it is divided by to get seconds
+    let (time, per_second) = match (find("time"), find("frame")) {
+        (Some(time), _) => (time, 1.0),
+        (None, Some(frame)) => (frame, fps),
+        (None, None) => return Err("CSV needs a 'time' column in seconds or a 'frame' column".to_string()),
+    };
+    let position = [column("x")?, column("y")?, column("z")?];
+    let quaternion = ["qx", "qy", "qz", "qw"].map(find);
+    let euler = ["rx", "ry", "rz"].map(find);
+    
+    let mut path = CameraPath::default();
+    for (number, line) in lines.enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let fields: Vec<&str> = line.split(delimiter).collect();
+        let parse = |index: usize| -> Result<f32, String> {
+            let value = fields.get(index).ok_or(format!("CSV line {} has too few fields", number + 2))?;
+            parse_number(value).map_err(|e| format!("CSV line {}: {}", number + 2, e))
+        };
+        
+        let rotation = if let [Some(x), Some(y), Some(z), Some(w)] = quaternion {
+            Quaternion::new(parse(x)?, parse(y)?, parse(z)?, parse(w)?).normalize()
+        } else if let [Some(x), Some(y), Some(z)] = euler {
+            Quaternion::from_euler_xyz(parse(x)?.to_radians(), parse(y)?.to_radians(), parse(z)?.to_radians())
+        } else {
+            CameraMatrix::new().to_quaternion()
+        };
+        path.keyframes.push(Keyframe {
+            time: parse(time)? / per_second,
+            position: [parse(position[0])?, parse(position[1])?, parse(position[2])?],
+            rotation: [rotation.x, rotation.y, rotation.z, rotation.w],
+            ease_in: 0.0,
+            ease_out: 0.0,
+        });
+    }
+    
+    // Animations may start on any frame, paths start at zero
+    path.keyframes.sort_by(|a, b| a.time.total_cmp(&b.time));
+    let start = path.keyframes.first().map_or(0.0, |keyframe| keyframe.time);
+    for keyframe in &mut path.keyframes {
+        keyframe.time -= start;
+    }
+    Ok(path)
+}
+
+fn write_csv(frames: &[Keyframe]) -> String {
+    let mut out = csv_header();
+    out.push('\n');
+    for (tick, frame) in frames.iter().enumerate() {
+        let matrix = CameraMatrix::from_pose(&frame.pose());
+        out.push_str(&format!(
+            "pose,{},{},,{:.6},{:.6},{:.6}",
+            tick + 1, (frame.time * 1000.0).round() as u64, frame.position[0], frame.position[1], frame.position[2]
+        ));
+        for value in matrix.data {
+            out.push_str(&format!(",{:.6}", value));
+        }
+        out.push_str(",,,\n");
+    }
+    out
+}
+
+fn read_chan(contents: &str, fps: f32) -> Result<CameraPath, String> {
+    let mut path = CameraPath::default();
+    let mut first_frame = None;
+    
+    for (number, line) in contents.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let values: Vec<f32> = line.split_whitespace()
+            .map(parse_number)
+            .collect::<Result<_, _>>()
+            .map_err(|e| format!("Chan line {}: {}", number + 1, e))?;
+        if values.len() < 7 {
+            return Err(format!("Chan line {} needs at least 7 columns (frame tx ty tz rx ry rz)", number + 1));
+        }
+        
+        let frame = values[0];
+        let start = *first_frame.get_or_insert(frame);
+        let rotation = Quaternion::from_euler_xyz(values[4].to_radians(), values[5].to_radians(), values[6].to_radians());
+        path.keyframes.push(Keyframe {
+            time: (frame - start) / fps,
+            position: [values[1], values[2], values[3]],
+            rotation: [rotation.x, rotation.y, rotation.z, rotation.w],
+            ease_in: 0.0,
+            ease_out: 0.0,
+        });
+    }
+    Ok(path)
+}
+
+fn write_chan(frames: &[Keyframe]) -> String {
+    frames.iter().enumerate().map(|(frame, keyframe)| {
+        let (rx, ry, rz) = keyframe.pose().to_euler_xyz();
+        format!(
+            "{} {:.6} {:.6} {:.6} {:.6} {:.6} {:.6}\n",
+            frame + 1, keyframe.position[0], keyframe.position[1], keyframe.position[2],
+            rx.to_degrees(), ry.to_degrees(), rz.to_degrees()
+        )
+    }).collect()
+}
+
+fn read_blender(contents: &str) -> Result<CameraPath, String> {
+    let camera: BlenderCamera = serde_json::from_str(contents).map_err(|e| format!("Invalid Blender JSON: {}", e))?;
+    let fps = if camera.fps > 0.0 { camera.fps } else { return Err("Blender JSON has no valid fps".to_string()) };
+    let start = camera.frames.first().map_or(0, |frame| frame.frame);
+    
+    Ok(CameraPath::from_keyframes(camera.frames.iter().map(|frame| {
+        let [w, x, y, z] = frame.rotation_quaternion;
+        Keyframe {
+            time: frame.frame.saturating_sub(start) as f32 / fps,
+            position: frame.location,
+            rotation: [x, y, z, w],
+            ease_in: 0.0,
+            ease_out: 0.0,
+        }
+    }).collect()))
+}
+
+fn write_blender(frames: &[Keyframe], fps: f32) -> Result<String, String> {
+    let camera = BlenderCamera {
+        fps,
+        frames: frames.iter().enumerate().map(|(frame, keyframe)| {
+            let [x, y, z, w] = keyframe.rotation;
+            BlenderFrame {
+                frame: frame as u32 + 1,
+                location: keyframe.position,
+                rotation_quaternion: [w, x, y, z],
+            }
+        }).collect(),
+    };
+    serde_json::to_string_pretty(&camera).map_err(|e| format!("Failed to serialize Blender JSON: {}", e))
+}
+
+// One pose per frame at `fps`, for the formats that store every frame instead of keyframes
+fn bake_frames(path: &CameraPath, fps: f32, mode: InterpolationMode) -> Vec<Keyframe> {
+    let frame_count = (path.duration() * fps).round() as usize + 1;
+    (0..frame_count).filter_map(|frame| {
+        let time = frame as f32 / fps;
+        path.sample(time, mode).map(|pose| Keyframe::from_pose(time, &pose))
+    }).collect()
+}
+
+pub fn read_path(file: &str, format: PathFormat, fps: f32) -> Result<CameraPath, String> {
+    let contents = std::fs::read_to_string(file).map_err(|e| format!("Failed to read '{}': {}", file, e))?;
+    parse_path(&contents, file, format, fps)
+}
+
+// `file` only names the source in error messages
+pub fn parse_path(contents: &str, file: &str, format: PathFormat, fps: f32) -> Result<CameraPath, String> {
+    let path = match format {
+        PathFormat::Path => CameraPath::parse(contents, file)?,
+        PathFormat::Csv => read_csv(contents, fps)?,
+        PathFormat::Blender => read_blender(contents)?,
+        PathFormat::Chan => read_chan(contents, fps)?,
+    };
+    
+    if path.is_empty() {
+        return Err(format!("'{}' contains no camera poses", file));
+    }
+    Ok(path)
+}
+
+// Returns the number of poses written
+pub fn write_path(path: &CameraPath, file: &str, format: PathFormat, fps: f32, mode: InterpolationMode) -> Result<usize, String> {
+    if format == PathFormat::Path {
+        path.save(file)?;
+        return Ok(path.len());
+    }
+    
+    let frames = bake_frames(path, fps, mode);
+    let contents = match format {
+        PathFormat::Path => unreachable!("keyframe paths are saved above"),
+        PathFormat::Csv => write_csv(&frames),
+        PathFormat::Blender => write_blender(&frames, fps)?,
+        PathFormat::Chan => write_chan(&frames),
+    };
+    
+    std::fs::write(file, contents).map_err(|e| format!("Failed to write '{}': {}", file, e))?;
+    Ok(frames.len())
+}
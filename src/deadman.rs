@@ -0,0 +1,88 @@
+// Optional dead man's switch for the camera patch: the game only loses its camera while someone is flying it.
+// After `idle_release_s` seconds without input, or as soon as neither the game nor one of the tool's own windows
+// has the focus, the patch is restored so the game camera carries on; the next input puts it back.
+
+use std::time::{Duration, Instant};
+
+use winapi::shared::windef::POINT;
+use winapi::um::winuser::GetCursorPos;
+
+use crate::input::MouseMotion;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DeadMansEvent {
+    Idle(Duration), // Release the patch, nobody touched anything for this long
+    FocusLost,      // Release the patch, the game isn't in the foreground any more
+    Resume,         // Input again with the game focused, apply the patch again
+}
+
+pub struct DeadMansSwitch {
+    timeout: Duration,
+    last_input: Instant,
+    mouse: Option<MouseMotion>, // Raw mouse motion, the cursor position is compared without it
+    cursor: Option<(i32, i32)>,
+    released: bool, // The switch restored the patch, as opposed to the user toggling it off
+}
+
+impl DeadMansSwitch {
+    // None when the timeout is 0, the patch then stays until it is toggled off. One too long for a Duration never
+    // runs out.
+    pub fn new(timeout_s: f32, mouse: Option<MouseMotion>) -> Option<Self> {
+        (timeout_s > 0.0).then(|| Self {
+            timeout: Duration::try_from_secs_f32(timeout_s).unwrap_or(Duration::MAX),
+            last_input: Instant::now(),
+            mouse,
+            cursor: None,
+            released: false,
+        })
+    }
+    
+    // `input` is whether a key, action or automated move (playback, follow, ...) happened this tick. The mouse is
+    // checked here, from raw input when there is some. Otherwise from the cursor: mouse look re-centres it, so any
+    // movement shows up as a different position.
+    pub fn tick(&mut self, input: bool, focused: bool, patch_active: bool) -> Option<DeadMansEvent> {
+        let mouse_moved = match &self.mouse {
+            Some(mouse) => mouse.take(),
+            None => {
+                let cursor = cursor_position();
+                let moved = cursor.is_some() && self.cursor.is_some() && cursor != self.cursor;
+                self.cursor = cursor;
+                moved
+            }
+        };
+        if input || mouse_moved {
+            self.last_input = Instant::now();
+        }
+        
+        if self.released {
+            if patch_active {
+                // Toggled back on by hand
+                self.released = false;
+            } else if (input || mouse_moved) && focused {
+                self.released = false;
+                return Some(DeadMansEvent::Resume);
+            }
+            return None;
+        }
+        if !patch_active {
+            return None;
+        }
+        
+        let event = if !focused {
+            DeadMansEvent::FocusLost
+        } else if self.last_input.elapsed() >= self.timeout {
+            DeadMansEvent::Idle(self.timeout)
+        } else {
+            return None;
+        };
+        // A restore that fails is tried again after another timeout, not every tick
+        self.last_input = Instant::now();
+        self.released = true;
+        Some(event)
+    }
+}
+
+fn cursor_position() -> Option<(i32, i32)> {
+    let mut point = POINT { x: 0, y: 0 };
+    (unsafe { GetCursorPos(&mut point) } != 0).then_some((point.x, point.y))
+}
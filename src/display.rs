@@ -0,0 +1,78 @@
+// The "📍 Camera" status line that gets redrawn while flying. The camera has to move `threshold` units away
+// from what the line shows before it updates, then the line follows every move until the camera settles.
+// Slow moves still show up, and the line doesn't flicker from tiny drift at rest. Redraws are capped at `rate`
+// a second so fast flying doesn't flood the console. Changed skater stats redraw it too, at the same rate.
+
+use std::io::Write;
+use std::time::{Duration, Instant};
+
+use crate::camera::CameraPosition;
+
+// How long the camera has to stay put before the line needs the full threshold again
+const SETTLE_TIME: Duration = Duration::from_millis(500);
+
+pub struct StatusLine {
+    threshold: f32,
+    interval: Duration,                           // Shortest time between redraws
+    shown: Option<(CameraPosition, Option<f32>, Option<String>)>, // Position, FOV and stats on the line
+    last_redraw: Option<Instant>,
+    last_move: Option<Instant>, // Last time the camera moved away from the line
+    pending: bool,              // The camera moved but the rate held the redraw back
+}
+
+impl StatusLine {
+    // A `rate` of 0 redraws as often as the camera moves, one too small for a Duration between redraws never redraws
+    // again after the first
+    pub fn new(threshold: f32, rate: f32) -> Self {
+        Self {
+            threshold: threshold.max(0.0),
+            interval: if rate > 0.0 { Duration::try_from_secs_f32(1.0 / rate).unwrap_or(Duration::MAX) } else { Duration::ZERO },
+            shown: None,
+            last_redraw: None,
+            last_move: None,
+            pending: false,
+        }
+    }
+    
+    // Whether a redraw is waiting for the rate, it should be retried even when the camera has stopped
+    pub fn is_pending(&self) -> bool {
+        self.pending
+    }
+    
+    // Whether the line should be redrawn for the camera at `position`. A changed `fov` or `stats` always counts.
+    pub fn update(&mut self, position: &CameraPosition, fov: Option<f32>, stats: Option<&str>) -> bool {
+        let now = Instant::now();
+        let following = self.last_move.is_some_and(|at| now - at < SETTLE_TIME);
+        let (moved, stats_changed) = match &self.shown {
+            None => (true, false),
+            Some((shown, shown_fov, shown_stats)) => {
+                let distance = (position.x - shown.x).abs().max((position.y - shown.y).abs()).max((position.z - shown.z).abs());
+                let threshold = if following { 0.0 } else { self.threshold };
+                (distance > threshold || *shown_fov != fov, shown_stats.as_deref() != stats)
+            }
+        };
+        if !moved && !stats_changed {
+            self.pending = false;
+            return false;
+        }
+        
+        // Only the camera moving makes the line follow small moves
+        if moved {
+            self.last_move = Some(now);
+        }
+        if self.last_redraw.is_some_and(|at| now - at < self.interval) {
+            self.pending = true;
+            return false;
+        }
+        self.shown = Some((position.clone(), fov, stats.map(str::to_string)));
+        self.last_redraw = Some(now);
+        self.pending = false;
+        true
+    }
+    
+    // Overwrites the line in place
+    pub fn show(&self, text: &str) {
+        print!("\r{}   ", text);
+        let _ = std::io::stdout().flush();
+    }
+}
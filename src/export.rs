@@ -0,0 +1,90 @@
+// Writes down where the camera is, for documenting spots in a level: the position, rotation and full matrix
+// go to the clipboard as text, and a timestamped row goes into shots_file when one is set. Numbers always use
+// a decimal point, so the text reads the same on every system.
+
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::ptr;
+
+use winapi::um::errhandlingapi::GetLastError;
+use winapi::um::winbase::{GlobalAlloc, GlobalFree, GlobalLock, GlobalUnlock, GMEM_MOVEABLE};
+use winapi::um::winuser::{CloseClipboard, EmptyClipboard, OpenClipboard, SetClipboardData, CF_UNICODETEXT};
+
+use crate::camera::CameraMatrix;
+use crate::telemetry::{format_utc_datetime, unix_millis};
+
+const SHOTS_HEADER: &str = "time,x,y,z,qx,qy,qz,qw,hfov";
+
+// The camera as a few lines of text. The set-pos line can be pasted into a console to go back there.
+pub fn format_transform(matrix: &CameraMatrix, hfov: Option<f32>) -> String {
+    let [x, y, z] = [matrix.data[12], matrix.data[13], matrix.data[14]];
+    let rotation = matrix.to_quaternion();
+    let mut text = format!("Position: {:.3} {:.3} {:.3}\r\n", x, y, z);
+    text.push_str(&format!("Rotation (x y z w): {:.6} {:.6} {:.6} {:.6}\r\n", rotation.x, rotation.y, rotation.z, rotation.w));
+    if let Some(hfov) = hfov {
+        text.push_str(&format!("Horizontal FOV: {:.2}\r\n", hfov));
+    }
+    text.push_str("Matrix (column-major):\r\n");
+    for row in 0..4 {
+        let values: Vec<String> = (0..4).map(|column| format!("{:.6}", matrix.data[column * 4 + row])).collect();
+        text.push_str(&format!("  {}\r\n", values.join(" ")));
+    }
+    text.push_str(&format!("thps3_free_cam set-pos {:.3} {:.3} {:.3}\r\n", x, y, z));
+    text
+}
+
+// Replaces the clipboard contents with `text`
+pub fn copy_to_clipboard(text: &str) -> Result<(), String> {
+    let wide: Vec<u16> = text.encode_utf16().chain(Some(0)).collect();
+    if unsafe { OpenClipboard(ptr::null_mut()) } == 0 {
+        let error_code = unsafe { GetLastError() };
+        return Err(format!("Failed to open the clipboard (Error: {})", error_code));
+    }
+    
+    let result = unsafe {
+        EmptyClipboard();
+        let memory = GlobalAlloc(GMEM_MOVEABLE, wide.len() * 2);
+        let target = if memory.is_null() { ptr::null_mut() } else { GlobalLock(memory) as *mut u16 };
+        if target.is_null() {
+            Err(format!("Failed to allocate clipboard memory (Error: {})", GetLastError()))
+        } else {
+            ptr::copy_nonoverlapping(wide.as_ptr(), target, wide.len());
+            GlobalUnlock(memory);
+            // The clipboard owns the memory once this succeeds
+            if SetClipboardData(CF_UNICODETEXT, memory).is_null() {
+                let error_code = GetLastError();
+                GlobalFree(memory);
+                Err(format!("Failed to set the clipboard (Error: {})", error_code))
+            } else {
+                Ok(())
+            }
+        }
+    };
+    unsafe { CloseClipboard() };
+    result
+}
+
+// Adds one row per shot, the header is written when the file is new
+pub fn append_shot(path: &str, matrix: &CameraMatrix, hfov: Option<f32>) -> Result<(), String> {
+    let is_new = !std::path::Path::new(path).exists();
+    let mut file = OpenOptions::new().create(true).append(true).open(path)
+        .map_err(|e| format!("Failed to open '{}': {}", path, e))?;
+    
+    let mut rows = String::new();
+    if is_new {
+        rows.push_str(SHOTS_HEADER);
+        for i in 0..16 {
+            rows.push_str(&format!(",m{}", i));
+        }
+        rows.push('\n');
+    }
+    let rotation = matrix.to_quaternion();
+    rows.push_str(&format!("{},{:.3},{:.3},{:.3},{:.6},{:.6},{:.6},{:.6},{}", format_utc_datetime(unix_millis()),
+                           matrix.data[12], matrix.data[13], matrix.data[14], rotation.x, rotation.y, rotation.z, rotation.w,
+                           hfov.map_or(String::new(), |hfov| format!("{:.2}", hfov))));
+    for value in matrix.data {
+        rows.push_str(&format!(",{:.6}", value));
+    }
+    rows.push('\n');
+    file.write_all(rows.as_bytes()).map_err(|e| format!("Failed to write '{}': {}", path, e))
+}
@@ -0,0 +1,57 @@
+use serde::Deserialize;
+use winapi::um::winuser::{GetSystemMetrics, SM_CXSCREEN, SM_CYSCREEN};
+
+// Which axis the game's FOV value spans
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FovAxis {
+    #[default]
+    Horizontal,
+    Vertical,
+}
+
+pub fn horizontal_to_vertical(hfov_deg: f32, aspect: f32) -> f32 {
+    (2.0 * ((hfov_deg.to_radians() / 2.0).tan() / aspect).atan()).to_degrees()
+}
+
+pub fn vertical_to_horizontal(vfov_deg: f32, aspect: f32) -> f32 {
+    (2.0 * ((vfov_deg.to_radians() / 2.0).tan() * aspect).atan()).to_degrees()
+}
+
+pub fn screen_aspect() -> f32 {
+    let width = unsafe { GetSystemMetrics(SM_CXSCREEN) };
+    let height = unsafe { GetSystemMetrics(SM_CYSCREEN) };
+    if width <= 0 || height <= 0 {
+        return 4.0 / 3.0;
+    }
+    width as f32 / height as f32
+}
+
+// Converts a horizontal FOV authored at `target_aspect` into the value the game expects.
+// The vertical FOV is what stays identical across setups, so everything goes through it.
+// `game_aspect` is the aspect the game's horizontal value refers to: the screen aspect for
+// Hor+ rendering, or a fixed 4:3 when a widescreen hack stretches the original projection.
+pub fn game_fov_from_preset(hfov_deg: f32, target_aspect: f32, game_aspect: f32, axis: FovAxis, radians: bool) -> f32 {
+    let vfov = horizontal_to_vertical(hfov_deg, target_aspect);
+    let value = match axis {
+        FovAxis::Vertical => vfov,
+        FovAxis::Horizontal => vertical_to_horizontal(vfov, game_aspect),
+    };
+    
+    if radians { value.to_radians() } else { value }
+}
+
+// Inverse of `game_fov_from_preset`, reporting a game value as horizontal degrees at `aspect`
+pub fn preset_from_game_fov(value: f32, aspect: f32, game_aspect: f32, axis: FovAxis, radians: bool) -> f32 {
+    let value = if radians { value.to_degrees() } else { value };
+    let vfov = match axis {
+        FovAxis::Vertical => value,
+        FovAxis::Horizontal => horizontal_to_vertical(value, game_aspect),
+    };
+    vertical_to_horizontal(vfov, aspect)
+}
+
+// Focal length on a 36mm wide (full frame) sensor, the default film back in Blender and Nuke
+pub fn focal_length_mm(hfov_deg: f32) -> f32 {
+    18.0 / (hfov_deg.to_radians() / 2.0).tan()
+}
@@ -0,0 +1,60 @@
+// The parts of a game the free cam can't work without: what its process is called, where the camera matrix
+// is and which instruction copies the game's camera over it every frame. Everything optional (FOV, skater,
+// freeze, ...) comes from offsets.toml instead. Supporting another game (THPS4, THUG, ...) is a GameCamera
+// implementation with that game's chain and instruction, added to GAMES.
+
+use thps3_free_cam_hook::{CAMERA_COPY_BYTES, CAMERA_COPY_OFFSET};
+
+use crate::offsets::{PatchOffsets, PointerChain};
+
+pub trait GameCamera: Sync {
+    fn name(&self) -> &'static str;
+    
+    // Executable name, matched case-insensitively
+    fn process_name(&self) -> &'static str;
+    
+    // Points at the first float of the 4x4 camera matrix, the position is floats 12/13/14
+    fn camera_chain(&self) -> PointerChain;
+    
+    // The instruction that copies the game's camera into the matrix, NOPed by the camera patch
+    fn camera_write(&self) -> PatchOffsets;
+    
+    // Whether thps3_free_cam_hook.dll knows this game's camera copy, `--internal` needs it
+    fn supports_hook(&self) -> bool {
+        false
+    }
+}
+
+// Tony Hawk's Pro Skater 3 for PC, Neversoft's port whose executable is Skate3.exe
+pub struct Thps3;
+
+impl GameCamera for Thps3 {
+    fn name(&self) -> &'static str {
+        "THPS3"
+    }
+    
+    fn process_name(&self) -> &'static str {
+        "Skate3.exe"
+    }
+    
+    fn camera_chain(&self) -> PointerChain {
+        // "Skate3.exe"+004E1E78+34C+8+4+8C+0+2F4
+        PointerChain { base: 0x004E1E78, offsets: vec![0x34C, 0x8, 0x4, 0x8C, 0x0, 0x2F4] }
+    }
+    
+    fn camera_write(&self) -> PatchOffsets {
+        // "repe movsd" at Skate3.exe.text+16B2E4, found via Cheat Engine disassembler
+        PatchOffsets { offset: CAMERA_COPY_OFFSET, original_bytes: CAMERA_COPY_BYTES.to_vec() }
+    }
+    
+    fn supports_hook(&self) -> bool {
+        true
+    }
+}
+
+// Every supported game, the first one is assumed when a process can't be told apart by name
+pub static GAMES: [&dyn GameCamera; 1] = [&Thps3];
+
+pub fn find_game(process_name: &str) -> Option<&'static dyn GameCamera> {
+    GAMES.iter().copied().find(|game| game.process_name().eq_ignore_ascii_case(process_name))
+}
@@ -0,0 +1,196 @@
+// Flying with an XInput gamepad: the left stick moves, the right stick looks around and the triggers go up
+// and down. Buttons trigger whatever action the [gamepad] table binds them to.
+
+use std::collections::HashMap;
+
+use serde::Deserialize;
+
+use crate::action::Action;
+use crate::input::{CameraCommand, InputSource};
+
+// Gamepad settings, set in the [gamepad] table. Off unless enabled.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct GamepadConfig {
+    pub enabled: bool,
+    pub controller: u32, // XInput slot 0-3
+    pub speed: f32,      // Fraction of the move speed with a stick pushed all the way
+    pub turn_rate: f32,  // Degrees per second with the right stick pushed all the way
+    pub dead_zone: f32,  // Part of a stick's travel around the centre that doesn't move, 0-1
+    pub invert_y: bool,  // Right stick up looks down
+    pub buttons: HashMap<GamepadButton, Action>,
+}
+
+impl Default for GamepadConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            controller: 0,
+            speed: 1.0,
+            turn_rate: 120.0,
+            dead_zone: 0.15,
+            invert_y: false,
+            buttons: HashMap::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GamepadButton {
+    A,
+    B,
+    X,
+    Y,
+    Lb,
+    Rb,
+    Back,
+    Start,
+    LeftStick,
+    RightStick,
+    DpadUp,
+    DpadDown,
+    DpadLeft,
+    DpadRight,
+}
+
+// Where the sticks and triggers are and which buttons are down
+#[derive(Debug, Clone, Copy, Default)]
+struct PadState {
+    buttons: u16,
+    left_stick: (i16, i16),
+    right_stick: (i16, i16),
+    triggers: (u8, u8), // Left, right
+}
+
+pub struct GamepadSource {
+    config: GamepadConfig,
+    held: u16, // Buttons down at the last poll, so holding one triggers it once
+    connected: bool, // A gamepad answered in the slot at the last poll
+}
+
+impl GamepadSource {
+    pub fn start(config: &GamepadConfig) -> Result<Self, String> {
+        if !cfg!(feature = "gamepad") {
+            return Err("This build has no gamepad support".to_string());
+        }
+        if config.controller > 3 {
+            return Err(format!("Gamepad slot {} doesn't exist, use 0-3", config.controller));
+        }
+        Ok(Self { config: config.clone(), held: 0, connected: false })
+    }
+    
+    // A stick or trigger value as -1 to 1, 0 inside the dead zone
+    fn axis(&self, value: f32) -> f32 {
+        let offset = value.clamp(-1.0, 1.0);
+        let dead_zone = self.config.dead_zone.clamp(0.0, 0.99);
+        if offset.abs() <= dead_zone {
+            return 0.0;
+        }
+        offset.signum() * (offset.abs() - dead_zone) / (1.0 - dead_zone)
+    }
+}
+
+impl InputSource for GamepadSource {
+    fn poll(&mut self) -> Vec<CameraCommand> {
+        let Some(state) = device::read(self.config.controller) else {
+            if self.connected {
+                println!("\n⚠️ Gamepad {} disconnected", self.config.controller);
+            }
+            self.connected = false;
+            self.held = 0;
+            return Vec::new();
+        };
+        if !self.connected {
+            println!("\n🎮 Gamepad {} connected", self.config.controller);
+            self.connected = true;
+        }
+        
+        let mut commands = Vec::new();
+        let stick = |value: i16| self.axis(value as f32 / i16::MAX as f32);
+        let speed = self.config.speed;
+        // Positive X is to the left in this game
+        let right = stick(state.left_stick.0);
+        let up = self.axis((state.triggers.1 as f32 - state.triggers.0 as f32) / u8::MAX as f32);
+        let forward = stick(state.left_stick.1);
+        if (right, up, forward) != (0.0, 0.0, 0.0) {
+            commands.push(CameraCommand::Translate(-right * speed, up * speed, forward * speed));
+        }
+        
+        let turn_rate = self.config.turn_rate.to_radians();
+        // Stick up looks up, mouse look has it the other way round
+        let pitch = if self.config.invert_y { 1.0 } else { -1.0 } * stick(state.right_stick.1);
+        let yaw = stick(state.right_stick.0);
+        if (yaw, pitch) != (0.0, 0.0) {
+            commands.push(CameraCommand::Turn(yaw * turn_rate, pitch * turn_rate, 0.0));
+        }
+        
+        let pressed = state.buttons & !self.held;
+        self.held = state.buttons;
+        for (button, action) in &self.config.buttons {
+            if pressed & device::mask(*button) != 0 {
+                commands.push(CameraCommand::Toggle(*action));
+            }
+        }
+        commands
+    }
+}
+
+#[cfg(feature = "gamepad")]
+mod device {
+    use winapi::um::xinput::{
+        XInputGetState, XINPUT_GAMEPAD_A, XINPUT_GAMEPAD_B, XINPUT_GAMEPAD_BACK, XINPUT_GAMEPAD_DPAD_DOWN, XINPUT_GAMEPAD_DPAD_LEFT,
+        XINPUT_GAMEPAD_DPAD_RIGHT, XINPUT_GAMEPAD_DPAD_UP, XINPUT_GAMEPAD_LEFT_SHOULDER, XINPUT_GAMEPAD_LEFT_THUMB,
+        XINPUT_GAMEPAD_RIGHT_SHOULDER, XINPUT_GAMEPAD_RIGHT_THUMB, XINPUT_GAMEPAD_START, XINPUT_GAMEPAD_X, XINPUT_GAMEPAD_Y, XINPUT_STATE,
+    };
+    
+    use super::{GamepadButton, PadState};
+    
+    // What's in the slot, None when nothing is plugged in
+    pub fn read(controller: u32) -> Option<PadState> {
+        let mut state: XINPUT_STATE = unsafe { std::mem::zeroed() };
+        if unsafe { XInputGetState(controller, &mut state) } != 0 {
+            return None;
+        }
+        let pad = state.Gamepad;
+        Some(PadState {
+            buttons: pad.wButtons,
+            left_stick: (pad.sThumbLX, pad.sThumbLY),
+            right_stick: (pad.sThumbRX, pad.sThumbRY),
+            triggers: (pad.bLeftTrigger, pad.bRightTrigger),
+        })
+    }
+    
+    pub fn mask(button: GamepadButton) -> u16 {
+        match button {
+            GamepadButton::A => XINPUT_GAMEPAD_A,
+            GamepadButton::B => XINPUT_GAMEPAD_B,
+            GamepadButton::X => XINPUT_GAMEPAD_X,
+            GamepadButton::Y => XINPUT_GAMEPAD_Y,
+            GamepadButton::Lb => XINPUT_GAMEPAD_LEFT_SHOULDER,
+            GamepadButton::Rb => XINPUT_GAMEPAD_RIGHT_SHOULDER,
+            GamepadButton::Back => XINPUT_GAMEPAD_BACK,
+            GamepadButton::Start => XINPUT_GAMEPAD_START,
+            GamepadButton::LeftStick => XINPUT_GAMEPAD_LEFT_THUMB,
+            GamepadButton::RightStick => XINPUT_GAMEPAD_RIGHT_THUMB,
+            GamepadButton::DpadUp => XINPUT_GAMEPAD_DPAD_UP,
+            GamepadButton::DpadDown => XINPUT_GAMEPAD_DPAD_DOWN,
+            GamepadButton::DpadLeft => XINPUT_GAMEPAD_DPAD_LEFT,
+            GamepadButton::DpadRight => XINPUT_GAMEPAD_DPAD_RIGHT,
+        }
+    }
+}
+
+// Built without the gamepad feature there's never a gamepad to read
+#[cfg(not(feature = "gamepad"))]
+mod device {
+    use super::{GamepadButton, PadState};
+    
+    pub fn read(_controller: u32) -> Option<PadState> {
+        None
+    }
+    
+    pub fn mask(_button: GamepadButton) -> u16 {
+        0
+    }
+}
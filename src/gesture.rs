@@ -0,0 +1,78 @@
+use winapi::shared::windef::POINT;
+use winapi::um::winuser::{GetCursorPos, GetSystemMetrics, SM_CXSCREEN, SM_CYSCREEN};
+
+use crate::action::Action;
+use crate::config::GestureConfig;
+use crate::input::Key;
+
+// Flicking the cursor into a screen corner while holding the modifier fires that corner's action
+pub struct GestureDetector {
+    config: GestureConfig,
+    screen_width: i32,
+    screen_height: i32,
+    in_corner: bool,
+}
+
+impl GestureDetector {
+    pub fn new(config: GestureConfig) -> Self {
+        let screen_width = unsafe { GetSystemMetrics(SM_CXSCREEN) };
+        let screen_height = unsafe { GetSystemMetrics(SM_CYSCREEN) };
+        
+        Self {
+            config,
+            screen_width,
+            screen_height,
+            in_corner: false,
+        }
+    }
+    
+    pub fn modifier(&self) -> Option<Key> {
+        self.config.modifier
+    }
+    
+    // Configured corners for the controls overview
+    pub fn corners(&self) -> Vec<(&'static str, Action)> {
+        [
+            ("top left", self.config.top_left),
+            ("top right", self.config.top_right),
+            ("bottom left", self.config.bottom_left),
+            ("bottom right", self.config.bottom_right),
+        ]
+        .into_iter()
+        .filter_map(|(corner, action)| action.map(|action| (corner, action)))
+        .collect()
+    }
+    
+    fn corner_at(&self, cursor: &POINT) -> Option<Option<Action>> {
+        let size = self.config.corner_size.max(1);
+        let left = cursor.x < size;
+        let right = cursor.x >= self.screen_width - size;
+        let top = cursor.y < size;
+        let bottom = cursor.y >= self.screen_height - size;
+        
+        match (left, right, top, bottom) {
+            (true, _, true, _) => Some(self.config.top_left),
+            (_, true, true, _) => Some(self.config.top_right),
+            (true, _, _, true) => Some(self.config.bottom_left),
+            (_, true, _, true) => Some(self.config.bottom_right),
+            _ => None,
+        }
+    }
+    
+    // Fires once when the cursor reaches a corner, then not again until it has left the corner
+    pub fn poll(&mut self) -> Option<Action> {
+        let modifier = self.config.modifier?;
+        
+        let mut cursor = POINT { x: 0, y: 0 };
+        if unsafe { GetCursorPos(&mut cursor) } == 0 {
+            return None;
+        }
+        
+        let corner = self.corner_at(&cursor);
+        let was_in_corner = std::mem::replace(&mut self.in_corner, corner.is_some());
+        if was_in_corner || !modifier.is_pressed() {
+            return None;
+        }
+        corner.flatten()
+    }
+}
@@ -0,0 +1,99 @@
+// Applying the camera patch while the game plays a movie or loads a level crashes some builds, so the
+// patch waits until gameplay is running. Gameplay is recognised by the [game_state] value from
+// offsets.toml when it is known, and optionally by the game window showing more than a black frame.
+
+use std::time::{Duration, Instant};
+
+use winapi::shared::windef::{HWND, RECT};
+use winapi::um::wingdi::{GetBValue, GetGValue, GetPixel, GetRValue, CLR_INVALID};
+use winapi::um::winuser::{GetClientRect, GetDC, IsIconic, ReleaseDC};
+
+use crate::offsets::GameStateOffsets;
+use crate::process::ProcessHandle;
+
+// How often a waiting patch looks at the game again
+const RECHECK_INTERVAL: Duration = Duration::from_millis(250);
+
+// Brightest channel value that still counts as black, video black is rarely exactly zero
+const BLACK_LEVEL: u8 = 12;
+
+pub struct PatchGuard {
+    game_state: Option<GameStateOffsets>,
+    black_frames: bool,
+    window: Option<HWND>,
+    last_check: Option<Instant>,
+}
+
+impl PatchGuard {
+    pub fn new(game_state: Option<GameStateOffsets>, black_frames: bool, window: Option<HWND>) -> Self {
+        Self { game_state, black_frames, window, last_check: None }
+    }
+    
+    // Why the patch has to wait, None while the game is being played
+    pub fn blocker(&self, process: &ProcessHandle, base_addr: usize) -> Option<String> {
+        if let Some(window) = self.window
+            && unsafe { IsIconic(window) } != 0
+        {
+            return Some("the game is minimized".to_string());
+        }
+        
+        if let Some(state) = &self.game_state {
+            match process.get_state_value(base_addr, &state.chain) {
+                Ok(value) if !state.gameplay.contains(&value) => {
+                    return Some(format!("the game is in state {}, a movie or loading screen", value));
+                }
+                Ok(_) => {}
+                Err(_) => return Some("the game state can't be read yet, the game is probably loading".to_string()),
+            }
+        }
+        
+        if self.black_frames
+            && let Some(window) = self.window
+            && is_black(window)
+        {
+            return Some("the game window is black, a movie or loading screen".to_string());
+        }
+        None
+    }
+    
+    // For a waiting patch: true once a recheck finds the game playable, checks at most every RECHECK_INTERVAL
+    pub fn ready(&mut self, process: &ProcessHandle, base_addr: usize) -> bool {
+        if self.last_check.is_some_and(|last_check| last_check.elapsed() < RECHECK_INTERVAL) {
+            return false;
+        }
+        self.last_check = Some(Instant::now());
+        self.blocker(process, base_addr).is_none()
+    }
+}
+
+// Samples a 3x3 grid over the client area, a frame that can't be read doesn't count as black
+fn is_black(window: HWND) -> bool {
+    let mut rect = RECT { left: 0, top: 0, right: 0, bottom: 0 };
+    if unsafe { GetClientRect(window, &mut rect) } == 0 {
+        return false;
+    }
+    
+    let dc = unsafe { GetDC(window) };
+    if dc.is_null() {
+        return false;
+    }
+    
+    let (width, height) = (rect.right - rect.left, rect.bottom - rect.top);
+    let mut samples = 0;
+    let mut black = true;
+    for row in 1..=3 {
+        for column in 1..=3 {
+            let color = unsafe { GetPixel(dc, width * column / 4, height * row / 4) };
+            if color == CLR_INVALID {
+                continue;
+            }
+            samples += 1;
+            if GetRValue(color).max(GetGValue(color)).max(GetBValue(color)) > BLACK_LEVEL {
+                black = false;
+            }
+        }
+    }
+    
+    unsafe { ReleaseDC(window, dc) };
+    black && samples > 0
+}
@@ -0,0 +1,113 @@
+// Undo history for the camera. While it moves, the matrix is snapshotted about once a second, and the pose
+// right before a jump (a teleport, a snap to the skater, a fall through the floor) is kept as well, so the undo
+// key can step back through recent positions instead of flying back by hand.
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use crate::camera::CameraMatrix;
+use crate::math::Vec3;
+
+const SNAPSHOT_INTERVAL: Duration = Duration::from_secs(1);
+
+// Less than this since the last snapshot isn't a move worth keeping
+const MIN_MOVE: f32 = 1.0;
+const MIN_TURN: f32 = 0.01;
+
+// Moving further than this in one tick is a jump, the pose before it is kept right away
+const JUMP_DISTANCE: f32 = 500.0;
+
+fn position(matrix: &CameraMatrix) -> Vec3 {
+    Vec3::new(matrix.data[12], matrix.data[13], matrix.data[14])
+}
+
+fn distance(a: &CameraMatrix, b: &CameraMatrix) -> f32 {
+    position(a).distance(position(b))
+}
+
+fn differs(a: &CameraMatrix, b: &CameraMatrix) -> bool {
+    distance(a, b) > MIN_MOVE || (0..11).any(|index| (a.data[index] - b.data[index]).abs() > MIN_TURN)
+}
+
+pub struct CameraHistory {
+    snapshots: VecDeque<CameraMatrix>, // Oldest first
+    capacity: usize,
+    last: Option<CameraMatrix>, // The camera at the previous tick
+    last_snapshot: Option<Instant>,
+    undo_index: Option<usize>, // The snapshot undo went back to, while the camera is still there
+}
+
+impl CameraHistory {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            snapshots: VecDeque::with_capacity(capacity),
+            capacity: capacity.max(1),
+            last: None,
+            last_snapshot: None,
+            undo_index: None,
+        }
+    }
+    
+    pub fn len(&self) -> usize {
+        self.snapshots.len()
+    }
+    
+    pub fn is_empty(&self) -> bool {
+        self.snapshots.is_empty()
+    }
+    
+    fn push(&mut self, matrix: CameraMatrix) {
+        if self.snapshots.len() == self.capacity {
+            self.snapshots.pop_front();
+        }
+        self.snapshots.push_back(matrix);
+        self.last_snapshot = Some(Instant::now());
+    }
+    
+    // Called every tick with where the camera is
+    pub fn track(&mut self, matrix: &CameraMatrix) {
+        let last = self.last.replace(matrix.clone());
+        
+        // After an undo the history stays as it is until the camera moves on. Moving on from there drops the
+        // snapshots that were undone, like undo in an editor.
+        if let Some(index) = self.undo_index {
+            if !differs(&self.snapshots[index], matrix) {
+                return;
+            }
+            self.snapshots.truncate(index + 1);
+            self.undo_index = None;
+        }
+        
+        if let Some(last) = last
+            && distance(&last, matrix) > JUMP_DISTANCE
+        {
+            if self.snapshots.back().is_none_or(|latest| differs(latest, &last)) {
+                self.push(last);
+            }
+            return;
+        }
+        
+        let due = self.last_snapshot.is_none_or(|taken| taken.elapsed() >= SNAPSHOT_INTERVAL);
+        if due && self.snapshots.back().is_none_or(|latest| differs(latest, matrix)) {
+            self.push(matrix.clone());
+        }
+    }
+    
+    // The snapshot before `current`, with how many steps back it is. Each call goes one further back.
+    pub fn undo(&mut self, current: &CameraMatrix) -> Option<(usize, CameraMatrix)> {
+        let index = match self.undo_index {
+            Some(index) => index.checked_sub(1)?,
+            None => {
+                // The newest snapshot is where the camera is now when it hasn't moved since
+                let newest = self.snapshots.len().checked_sub(1)?;
+                if differs(&self.snapshots[newest], current) { newest } else { newest.checked_sub(1)? }
+            }
+        };
+        
+        self.undo_index = Some(index);
+        let matrix = self.snapshots[index].clone();
+        // The undo itself is a jump, it shouldn't be kept as one
+        self.last = Some(matrix.clone());
+        Some((self.snapshots.len() - index, matrix))
+    }
+}
@@ -0,0 +1,166 @@
+// Toggle keys arrive as key-down events from a raw input thread instead of being polled, so a
+// quick tap between two ticks isn't missed and holding a key never fires its toggle twice.
+
+use std::mem;
+use std::sync::mpsc::{self, Receiver};
+use std::thread::JoinHandle;
+
+use winapi::shared::minwindef::{DWORD, LPARAM, UINT};
+use winapi::shared::windef::HWND;
+use winapi::um::processthreadsapi::GetCurrentProcessId;
+use winapi::um::wincon::GetConsoleWindow;
+use winapi::um::winuser::{
+    GetForegroundWindow, GetRawInputData, GetWindowThreadProcessId, PostThreadMessageW, HRAWINPUT, RAWINPUT, RAWINPUTHEADER, RID_INPUT,
+    RIM_TYPEKEYBOARD, RI_KEY_BREAK, WM_QUIT,
+};
+
+use crate::config::KeyBindings;
+use crate::input::{raw_input_thread, Key};
+
+pub struct KeyEvents {
+    receiver: Option<Receiver<i32>>, // None when raw input couldn't be set up
+    input_thread: Option<(DWORD, JoinHandle<()>)>, // Win32 thread ID to post WM_QUIT to
+    focus_windows: Vec<HWND>,         // Presses only count while one of these is in the foreground
+    watched: Vec<Key>,                // Polled instead when there is no raw input
+    extra: Vec<Key>,                  // Polled too, for keys bound outside [keys]
+    held: Vec<Key>,
+}
+
+impl KeyEvents {
+    pub fn start(keys: &KeyBindings, game_window: Option<HWND>) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        let (ready_sender, ready) = mpsc::channel();
+        let thread = std::thread::spawn(move || raw_input_thread("keyboard", 0x06, key_downs(), sender, ready_sender));
+        
+        let (receiver, input_thread) = match ready.recv() {
+            Ok(Ok(thread_id)) => (Some(receiver), Some((thread_id, thread))),
+            Ok(Err(e)) => {
+                println!("⚠️ {} - polling toggle keys instead", e);
+                (None, None)
+            }
+            Err(_) => (None, None),
+        };
+        
+        // Without the game window every foreground window counts, like plain polling
+        let focus_windows = match game_window {
+            Some(window) => vec![window, unsafe { GetConsoleWindow() }],
+            None => Vec::new(),
+        };
+        
+        Self {
+            receiver,
+            input_thread,
+            focus_windows,
+            watched: watched_keys(keys),
+            extra: Vec::new(),
+            held: Vec::new(),
+        }
+    }
+    
+    // Raw input reports every key, only the polling fallback needs to know the new bindings
+    pub fn watch(&mut self, keys: &KeyBindings) {
+        self.watched = watched_keys(keys);
+        self.held.clear();
+    }
+    
+    // Keys bound outside [keys], e.g. to scripts. Replaces the last set.
+    pub fn watch_also(&mut self, keys: Vec<Key>) {
+        self.extra = keys;
+        self.held.clear();
+    }
+    
+    // Keys pressed down since the last call
+    pub fn poll(&mut self) -> Vec<Key> {
+        let pressed = match &self.receiver {
+            Some(receiver) => receiver.try_iter().map(Key).collect(),
+            None => {
+                let down: Vec<Key> = self.watched.iter().chain(&self.extra).copied().filter(|key| key.is_pressed()).collect();
+                let pressed = down.iter().copied().filter(|key| !self.held.contains(key)).collect();
+                self.held = down;
+                pressed
+            }
+        };
+        
+        // Typing in another window, or in the control panel, shouldn't toggle the camera
+        if !self.focus_windows.is_empty() && !self.focus_windows.contains(&unsafe { GetForegroundWindow() }) {
+            return Vec::new();
+        }
+        pressed
+    }
+    
+    // Whether toggle keys are polled each tick because raw input couldn't be set up. A tap between two polls is missed.
+    pub fn is_polling(&self) -> bool {
+        self.receiver.is_none()
+    }
+    
+    // Whether the game, the console or one of the tool's own windows (control panel, overlay) is in the
+    // foreground, always true when the game window wasn't found. Keys only count with the game or the console.
+    pub fn has_focus(&self) -> bool {
+        let foreground = unsafe { GetForegroundWindow() };
+        self.focus_windows.is_empty() || self.focus_windows.contains(&foreground) || is_own_window(foreground)
+    }
+}
+
+impl Drop for KeyEvents {
+    // GetMessage only wakes up for messages, so ask the input thread to quit and wait for it
+    fn drop(&mut self) {
+        if let Some((thread_id, thread)) = self.input_thread.take() {
+            unsafe { PostThreadMessageW(thread_id, WM_QUIT, 0, 0) };
+            let _ = thread.join();
+        }
+    }
+}
+
+fn is_own_window(window: HWND) -> bool {
+    let mut process_id = 0;
+    !window.is_null() && unsafe { GetWindowThreadProcessId(window, &mut process_id) } != 0
+        && process_id == unsafe { GetCurrentProcessId() }
+}
+
+fn watched_keys(keys: &KeyBindings) -> Vec<Key> {
+    vec![
+        keys.mouse_toggle, keys.patch_toggle, keys.freeze_toggle, keys.hud_toggle, keys.smooth_toggle, keys.step_toggle,
+        keys.orbit_toggle, keys.follow_toggle, keys.framing_toggle, keys.look_at_toggle, keys.telemetry_toggle,
+        keys.jitter_toggle, keys.shake_toggle, keys.overlay_toggle, keys.instance_next, keys.mirror_toggle, keys.hold_toggle, keys.skater_teleport, keys.camera_to_skater, keys.path_keyframe, keys.path_record, keys.path_play,
+        keys.playback_pause, keys.playback_faster, keys.playback_slower, keys.playback_repeat,
+        keys.path_clear, keys.path_save, keys.path_bake, keys.path_next,
+        keys.bookmark_save, keys.bookmark_next, keys.waypoint_next, keys.waypoint_previous, keys.undo, keys.switch_access, keys.screenshot, keys.copy_transform, keys.timelapse, keys.help, keys.palette,
+    ]
+}
+
+// The virtual key code of every key pressed down in a WM_INPUT message. Key repeat sends more key-downs while a
+// key is held, only the first one counts.
+fn key_downs() -> impl FnMut(LPARAM) -> Vec<i32> {
+    let mut held: Vec<i32> = Vec::new();
+    move |lparam| match read_keyboard(lparam) {
+        Some((vk_code, false)) => {
+            held.retain(|&held_code| held_code != vk_code);
+            Vec::new()
+        }
+        Some((vk_code, true)) if !held.contains(&vk_code) => {
+            held.push(vk_code);
+            vec![vk_code]
+        }
+        _ => Vec::new(),
+    }
+}
+
+fn read_keyboard(lparam: LPARAM) -> Option<(i32, bool)> {
+    let mut input: RAWINPUT = unsafe { mem::zeroed() };
+    let mut size = mem::size_of::<RAWINPUT>() as UINT;
+    let result = unsafe {
+        GetRawInputData(
+            lparam as HRAWINPUT,
+            RID_INPUT,
+            &mut input as *mut _ as *mut _,
+            &mut size,
+            mem::size_of::<RAWINPUTHEADER>() as UINT,
+        )
+    };
+    if result == UINT::MAX || input.header.dwType != RIM_TYPEKEYBOARD {
+        return None;
+    }
+    
+    let keyboard = unsafe { input.data.keyboard() };
+    Some((keyboard.VKey as i32, keyboard.Flags as DWORD & RI_KEY_BREAK == 0))
+}
@@ -0,0 +1,298 @@
+// A recorded copy of the game memory the free cam reads while attaching, so the attach/resolve/read pipeline
+// can run without the game. `record-image` runs the pipeline once against the live game and saves every 4 KB
+// page it read; a ProcessHandle made from the image answers the same reads from those pages. The `smoke`
+// example replays the images in images/ after a change to the offset tables, and CI runs it on every push.
+
+use std::collections::btree_map::Entry;
+use std::collections::BTreeMap;
+
+use crate::offsets::{GameOffsets, PatchOffsets, PointerChain};
+use crate::process::{format_bytes, ProcessHandle};
+use crate::tunables::TunableSet;
+
+const IMAGE_MAGIC: &[u8; 8] = b"T3CAMIMG";
+const IMAGE_VERSION: u32 = 1;
+const PAGE_SIZE: usize = 0x1000;
+
+pub struct MemoryImage {
+    pub executable: String, // Recognised the same way as a running process's name
+    pub module_base: usize,
+    pages: BTreeMap<usize, Vec<u8>>, // PAGE_SIZE bytes each, by page address
+}
+
+// Little-endian reader over an image file, every read fails cleanly at the end of the data
+struct ImageReader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> ImageReader<'a> {
+    fn take(&mut self, length: usize) -> Result<&'a [u8], String> {
+        if self.bytes.len() < length {
+            return Err("the file ends early".to_string());
+        }
+        let (taken, rest) = self.bytes.split_at(length);
+        self.bytes = rest;
+        Ok(taken)
+    }
+    
+    fn u32(&mut self) -> Result<u32, String> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+}
+
+impl MemoryImage {
+    // Copies the pages under `ranges` (address and length) from the running game
+    pub fn capture(process: &ProcessHandle, module_base: usize, ranges: &[(usize, usize)]) -> Result<Self, String> {
+        let mut pages = BTreeMap::new();
+        for &(address, length) in ranges {
+            let first = address / PAGE_SIZE * PAGE_SIZE;
+            for page in (first..address + length.max(1)).step_by(PAGE_SIZE) {
+                if let Entry::Vacant(entry) = pages.entry(page) {
+                    entry.insert(process.read_bytes(page, PAGE_SIZE)?);
+                }
+            }
+        }
+        Ok(Self { executable: process.executable().to_string(), module_base, pages })
+    }
+    
+    pub fn len(&self) -> usize {
+        self.pages.len()
+    }
+    
+    pub fn is_empty(&self) -> bool {
+        self.pages.is_empty()
+    }
+    
+    // `length` bytes at `address`, which may span pages. Memory that wasn't recorded can't be read, the same
+    // as an unmapped address in the game.
+    pub fn read(&self, address: usize, length: usize) -> Result<Vec<u8>, String> {
+        let mut bytes = Vec::with_capacity(length);
+        let mut current = address;
+        while bytes.len() < length {
+            let page = current / PAGE_SIZE * PAGE_SIZE;
+            let data = self.pages.get(&page)
+                .ok_or_else(|| format!("0x{:X} isn't in the memory image", current))?;
+            let start = current - page;
+            let end = (start + length - bytes.len()).min(PAGE_SIZE);
+            bytes.extend_from_slice(&data[start..end]);
+            current = page + PAGE_SIZE;
+        }
+        Ok(bytes)
+    }
+    
+    pub fn load(path: &str) -> Result<Self, String> {
+        let bytes = std::fs::read(path).map_err(|e| format!("Failed to read '{}': {}", path, e))?;
+        Self::parse(&bytes).map_err(|e| format!("Failed to load memory image '{}': {}", path, e))
+    }
+    
+    pub fn parse(bytes: &[u8]) -> Result<Self, String> {
+        let mut reader = ImageReader { bytes };
+        if reader.take(IMAGE_MAGIC.len())? != IMAGE_MAGIC {
+            return Err("not a memory image".to_string());
+        }
+        let version = reader.u32()?;
+        if version != IMAGE_VERSION {
+            return Err(format!("version {} images aren't supported, this build reads version {}", version, IMAGE_VERSION));
+        }
+        
+        let name_length = reader.u32()? as usize;
+        let executable = String::from_utf8(reader.take(name_length)?.to_vec()).map_err(|_| "the executable name isn't UTF-8".to_string())?;
+        let module_base = reader.u32()? as usize;
+        let page_count = reader.u32()?;
+        let mut pages = BTreeMap::new();
+        for _ in 0..page_count {
+            let page = reader.u32()? as usize;
+            if !page.is_multiple_of(PAGE_SIZE) {
+                return Err(format!("page 0x{:X} isn't page aligned", page));
+            }
+            pages.insert(page, reader.take(PAGE_SIZE)?.to_vec());
+        }
+        Ok(Self { executable, module_base, pages })
+    }
+    
+    // The game is 32-bit, so addresses fit in four bytes
+    pub fn save(&self, path: &str) -> Result<(), String> {
+        let mut bytes = Vec::with_capacity(self.pages.len() * (PAGE_SIZE + 4) + 64);
+        bytes.extend_from_slice(IMAGE_MAGIC);
+        bytes.extend_from_slice(&IMAGE_VERSION.to_le_bytes());
+        bytes.extend_from_slice(&(self.executable.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(self.executable.as_bytes());
+        bytes.extend_from_slice(&(self.module_base as u32).to_le_bytes());
+        bytes.extend_from_slice(&(self.pages.len() as u32).to_le_bytes());
+        for (&page, data) in &self.pages {
+            bytes.extend_from_slice(&(page as u32).to_le_bytes());
+            bytes.extend_from_slice(data);
+        }
+        std::fs::write(path, bytes).map_err(|e| format!("Failed to write '{}': {}", path, e))
+    }
+}
+
+// One step of the pipeline: what was checked and what it found, or why it failed
+pub struct PipelineStep {
+    pub name: String,
+    pub result: Result<String, String>,
+}
+
+fn patch_site(process: &ProcessHandle, address: Result<usize, String>, patch: &PatchOffsets) -> Result<String, String> {
+    let address = address?;
+    let bytes = process.read_bytes(address, patch.original_bytes.len())?;
+    if bytes != patch.original_bytes {
+        return Err(format!("Unexpected bytes {} at 0x{:X} (expected {})", format_bytes(&bytes), address, format_bytes(&patch.original_bytes)));
+    }
+    Ok(format!("{} at 0x{:X}", format_bytes(&bytes), address))
+}
+
+fn finite_position(values: [f32; 3]) -> Result<String, String> {
+    let [x, y, z] = values;
+    if values.iter().all(|value| value.is_finite()) {
+        Ok(format!("X:{:.1}, Y:{:.1}, Z:{:.1}", x, y, z))
+    } else {
+        Err(format!("X:{}, Y:{}, Z:{} isn't a position", x, y, z))
+    }
+}
+
+fn chain_value(process: &ProcessHandle, base_addr: usize, chain: &PointerChain) -> Result<String, String> {
+    process.get_state_value(base_addr, chain).map(|value| value.to_string())
+}
+
+// Everything attaching reads, without writing anything: the module base, the camera write instruction, the
+// camera matrix and every chain in the offsets file. Runs the same against the game and a memory image.
+pub fn run_pipeline(process: &ProcessHandle, offsets: &GameOffsets) -> Vec<PipelineStep> {
+    let mut steps = Vec::new();
+    let mut step = |name: &str, result: Result<String, String>| steps.push(PipelineStep { name: name.to_string(), result });
+    
+    let base_addr = match process.get_base_address() {
+        Ok(base_addr) => base_addr,
+        Err(e) => {
+            step("module base", Err(e));
+            return steps;
+        }
+    };
+    step("module base", Ok(format!("0x{:08X}", base_addr)));
+    if let Some(camera) = &offsets.camera {
+        process.set_camera_chain(camera.clone());
+    }
+    
+    step("camera write", patch_site(process, process.get_camera_write_patch_address(base_addr), &process.game().camera_write()));
+    step("camera matrix", process.get_camera_matrix(base_addr).and_then(|matrix| {
+        if !matrix.looks_like_camera() {
+            let lengths = matrix.rotation_row_lengths();
+            return Err(format!("rotation row lengths {:.3} {:.3} {:.3} - not a camera matrix", lengths[0], lengths[1], lengths[2]));
+        }
+        finite_position([matrix.data[12], matrix.data[13], matrix.data[14]])
+    }));
+    if let Some(freeze) = &offsets.freeze {
+        step("freeze", patch_site(process, process.get_offsets_patch_address(base_addr, "freeze", freeze), freeze));
+    }
+    if let Some(hud) = &offsets.hud {
+        step("hud", patch_site(process, process.get_offsets_patch_address(base_addr, "hud", hud), hud));
+    }
+    if let Some(fov) = &offsets.fov {
+        step("fov", process.get_fov(base_addr, &fov.chain).and_then(|value| {
+            if value.is_finite() && value > 0.0 { Ok(format!("{:.3}", value)) } else { Err(format!("{} isn't a field of view", value)) }
+        }));
+    }
+    for (name, chain) in [("skater", &offsets.skater), ("skater2", &offsets.skater2)] {
+        if let Some(chain) = chain {
+            step(name, process.get_skater_position(base_addr, chain).and_then(|position| finite_position([position.x, position.y, position.z])));
+        }
+    }
+    if let Some(chain) = &offsets.skater_matrix {
+        step("skater_matrix", process.get_skater_matrix(base_addr, chain).and_then(|matrix| finite_position([matrix.data[12], matrix.data[13], matrix.data[14]])));
+    }
+    if let Some(state) = &offsets.game_state {
+        step("game_state", chain_value(process, base_addr, &state.chain));
+    }
+    if let Some(replay) = &offsets.replay {
+        step("replay", chain_value(process, base_addr, &replay.chain));
+    }
+    for tunable in &offsets.tunables {
+        step(&format!("tunable {}", tunable.name), TunableSet::read(process, base_addr, tunable).map(|value| format!("{:.3}", value)));
+    }
+    steps
+}
+
+pub fn print_pipeline(steps: &[PipelineStep]) {
+    for step in steps {
+        match &step.result {
+            Ok(found) => println!("   ✅ {:<20} {}", step.name, found),
+            Err(e) => println!("   ❌ {:<20} {}", step.name, e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    // Two neighbouring pages with every byte set to its offset in the page, and one further away
+    fn image() -> MemoryImage {
+        let page = |fill: u8| (0..PAGE_SIZE).map(|i| (i as u8).wrapping_add(fill)).collect::<Vec<u8>>();
+        let pages = BTreeMap::from([(0x1000, page(0)), (0x2000, page(1)), (0x8000, page(2))]);
+        MemoryImage { executable: "Skate3.exe".to_string(), module_base: 0x400000, pages }
+    }
+    
+    fn bytes(image: &MemoryImage) -> Vec<u8> {
+        let path = std::env::temp_dir().join(format!("thps3_free_cam_image_{}.img", std::process::id()));
+        let path = path.to_string_lossy();
+        image.save(&path).unwrap();
+        let bytes = std::fs::read(&*path).unwrap();
+        let _ = std::fs::remove_file(&*path);
+        bytes
+    }
+    
+    #[test]
+    fn saved_image_loads_the_same() {
+        let original = image();
+        let loaded = MemoryImage::parse(&bytes(&original)).unwrap();
+        assert_eq!(loaded.executable, "Skate3.exe");
+        assert_eq!(loaded.module_base, 0x400000);
+        assert_eq!(loaded.pages, original.pages);
+    }
+    
+    #[test]
+    fn read_spans_pages() {
+        let image = image();
+        assert_eq!(image.read(0x1FFE, 4).unwrap(), vec![0xFE, 0xFF, 0x01, 0x02]);
+        assert_eq!(image.read(0x8010, 2).unwrap(), vec![0x12, 0x13]);
+        assert!(image.read(0x1000, 0).unwrap().is_empty());
+    }
+    
+    #[test]
+    fn read_outside_the_image_fails() {
+        let image = image();
+        assert!(image.read(0x3000, 4).is_err());
+        // Starts in a recorded page and runs into one that wasn't
+        assert!(image.read(0x2FFE, 4).is_err());
+    }
+    
+    #[test]
+    fn parse_rejects_broken_files() {
+        let good = bytes(&image());
+        assert!(MemoryImage::parse(b"NOTANIMG").is_err());
+        
+        let mut version = good.clone();
+        version[8] = 99;
+        assert!(MemoryImage::parse(&version).is_err());
+        
+        for length in [0, 7, 12, 30, good.len() - 1] {
+            assert!(MemoryImage::parse(&good[..length]).is_err(), "{} bytes parsed", length);
+        }
+        
+        // The first page address follows the magic, version, name, module base and page count
+        let page_address = 8 + 4 + 4 + "Skate3.exe".len() + 4 + 4;
+        let mut misaligned = good.clone();
+        misaligned[page_address] = 0x10;
+        assert!(MemoryImage::parse(&misaligned).is_err());
+    }
+    
+    #[test]
+    fn committed_image_passes_the_pipeline() {
+        let image = MemoryImage::load(concat!(env!("CARGO_MANIFEST_DIR"), "/images/thps3_synthetic.img")).unwrap();
+        let process = ProcessHandle::from_image(image);
+        let steps = run_pipeline(&process, &GameOffsets::default());
+        assert!(steps.iter().all(|step| step.result.is_ok()), "{:?}", steps.iter().map(|step| &step.result).collect::<Vec<_>>());
+        assert_eq!(process.get_camera_matrix(0x400000).unwrap().get_position().x, 120.0);
+    }
+}
@@ -1,5 +1,27 @@
+use std::mem;
+use std::ptr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+
 use winapi::um::winuser::{GetAsyncKeyState, GetCursorPos, SetCursorPos, GetSystemMetrics, SM_CXSCREEN, SM_CYSCREEN};
-use winapi::shared::windef::POINT;
+use winapi::um::winuser::{
+    CreateWindowExW, DestroyWindow, DispatchMessageW, GetMessageW, GetRawInputData, GetRawInputDeviceInfoW, GetRawInputDeviceList,
+    PostThreadMessageW, RegisterRawInputDevices, HRAWINPUT, HWND_MESSAGE, MSG, RAWINPUT, RAWINPUTDEVICE, RAWINPUTDEVICELIST,
+    RAWINPUTHEADER, RIDEV_INPUTSINK, RIDI_DEVICEINFO, RID_DEVICE_INFO, RID_INPUT, RIM_TYPEHID, RIM_TYPEMOUSE, RI_MOUSE_WHEEL,
+    GetForegroundWindow, WHEEL_DELTA, WM_INPUT, WM_QUIT,
+};
+use winapi::shared::minwindef::{DWORD, LPARAM, UINT};
+use winapi::shared::windef::{HWND, POINT};
+use winapi::um::errhandlingapi::GetLastError;
+use winapi::um::processthreadsapi::GetCurrentThreadId;
+use winapi::um::wincon::GetConsoleWindow;
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer};
+
+use crate::action::Action;
+use crate::config::KeyBindings;
 
 // Virtual key codes for movement keys
 pub const VK_I: i32 = 0x49; // I key
@@ -10,6 +32,151 @@ pub const VK_U: i32 = 0x55; // U key (up)
 pub const VK_O: i32 = 0x4F; // O key (down)
 pub const VK_M: i32 = 0x4D; // M key (toggle mouse)
 pub const VK_P: i32 = 0x50; // P key (toggle patch)
+pub const VK_N: i32 = 0x4E; // N key (toggle smooth movement)
+pub const VK_T: i32 = 0x54; // T key (toggle telemetry recording)
+pub const VK_F: i32 = 0x46; // F key (toggle game freeze)
+pub const VK_E: i32 = 0x45; // E key (show/hide the game's HUD)
+pub const VK_C: i32 = 0x43; // C key (copy the camera transform)
+pub const VK_Q: i32 = 0x51; // Q key (show/hide the framing overlay)
+pub const VK_D: i32 = 0x44; // D key (drive the next game instance)
+pub const VK_A: i32 = 0x41; // A key (mirror the camera into every game instance)
+pub const VK_R: i32 = 0x52; // R key (toggle orbit mode)
+pub const VK_G: i32 = 0x47; // G key (toggle follow mode)
+pub const VK_V: i32 = 0x56; // V key (set/clear look-at target)
+pub const VK_B: i32 = 0x42; // B key (toggle VHS jitter)
+pub const VK_H: i32 = 0x48; // H key (toggle step mode)
+pub const VK_X: i32 = 0x58; // X key (toggle two-skater framing)
+pub const VK_Y: i32 = 0x59; // Y key (teleport the skater to the camera)
+pub const VK_Z: i32 = 0x5A; // Z key (toggle handheld shake)
+pub const VK_OEM_4: i32 = 0xDB; // [ key (zoom out)
+pub const VK_OEM_6: i32 = 0xDD; // ] key (zoom in)
+pub const VK_OEM_5: i32 = 0xDC; // \ key (reset FOV)
+pub const VK_TAB: i32 = 0x09; // Tab key (cycle tunables)
+pub const VK_ADD: i32 = 0x6B; // Numpad + (increase tunable)
+pub const VK_SUBTRACT: i32 = 0x6D; // Numpad - (decrease tunable)
+pub const VK_MULTIPLY: i32 = 0x6A; // Numpad * (release held tunable)
+pub const VK_OEM_3: i32 = 0xC0; // ` key (command palette)
+pub const VK_OEM_COMMA: i32 = 0xBC; // , key (previous waypoint)
+pub const VK_OEM_PERIOD: i32 = 0xBE; // . key (next waypoint)
+pub const VK_OEM_1: i32 = 0xBA; // ; key (scrub the path back)
+pub const VK_OEM_7: i32 = 0xDE; // ' key (scrub the path forward)
+pub const VK_OEM_MINUS: i32 = 0xBD; // - key (slower path playback)
+pub const VK_OEM_PLUS: i32 = 0xBB; // = key (faster path playback)
+pub const VK_OEM_2: i32 = 0xBF; // / key (pause path playback)
+
+pub const VK_F1: i32 = 0x70; // F1 (help)
+pub const VK_F2: i32 = 0x71; // F2 (save bookmark)
+pub const VK_F3: i32 = 0x72; // F3 (next path from the path directory)
+pub const VK_F4: i32 = 0x73; // F4 (next bookmark)
+pub const VK_F5: i32 = 0x74; // F5 (add path keyframe)
+pub const VK_F6: i32 = 0x75; // F6 (play/stop path)
+pub const VK_F7: i32 = 0x76; // F7 (clear path)
+pub const VK_F8: i32 = 0x77; // F8 (save path)
+pub const VK_F9: i32 = 0x78; // F9 (play path and bake corrections)
+pub const VK_F10: i32 = 0x79; // F10 (timelapse along the path)
+pub const VK_F11: i32 = 0x7A; // F11 (screenshot)
+pub const VK_F12: i32 = 0x7B; // F12 (record camera motion)
+
+pub const VK_BACK: i32 = 0x08; // Backspace (undo a camera move)
+pub const VK_SHIFT: i32 = 0x10; // Shift (hold to move faster)
+pub const VK_CONTROL: i32 = 0x11; // Ctrl (hold to move slower)
+pub const VK_SPACE: i32 = 0x20; // Space (switch access)
+pub const VK_END: i32 = 0x23; // End (hold the camera)
+pub const VK_INSERT: i32 = 0x2D; // Insert (path playback repeat mode)
+pub const VK_HOME: i32 = 0x24; // Home (snap the camera to the skater)
+pub const VK_PRIOR: i32 = 0x21; // Page Up (increase speed)
+pub const VK_NEXT: i32 = 0x22; // Page Down (decrease speed)
+
+// Names accepted in config.toml, also used to print bindings
+const KEY_NAMES: &[(&str, i32)] = &[
+    ("Backspace", 0x08), ("Tab", 0x09), ("Enter", 0x0D), ("Shift", 0x10), ("Ctrl", 0x11), ("Alt", 0x12),
+    ("Escape", 0x1B), ("Space", 0x20), ("PageUp", 0x21), ("PageDown", 0x22), ("End", 0x23), ("Home", 0x24),
+    ("Left", 0x25), ("Up", 0x26), ("Right", 0x27), ("Down", 0x28), ("Insert", 0x2D), ("Delete", 0x2E),
+    ("Numpad0", 0x60), ("Numpad1", 0x61), ("Numpad2", 0x62), ("Numpad3", 0x63), ("Numpad4", 0x64),
+    ("Numpad5", 0x65), ("Numpad6", 0x66), ("Numpad7", 0x67), ("Numpad8", 0x68), ("Numpad9", 0x69),
+    ("NumpadMultiply", 0x6A), ("NumpadAdd", 0x6B), ("NumpadSubtract", 0x6D), ("NumpadDecimal", 0x6E),
+    ("NumpadDivide", 0x6F), ("F1", 0x70), ("F2", 0x71), ("F3", 0x72), ("F4", 0x73), ("F5", 0x74),
+    ("F6", 0x75), ("F7", 0x76), ("F8", 0x77), ("F9", 0x78), ("F10", 0x79), ("F11", 0x7A), ("F12", 0x7B),
+    (";", 0xBA), ("=", 0xBB), (",", 0xBC), ("-", 0xBD), (".", 0xBE), ("/", 0xBF), ("`", 0xC0),
+    ("[", 0xDB), ("\\", 0xDC), ("]", 0xDD), ("'", 0xDE),
+];
+
+pub fn parse_key_name(name: &str) -> Option<i32> {
+    // Single letters and digits map directly to their virtual key code
+    let mut chars = name.chars();
+    if let (Some(c), None) = (chars.next(), chars.next())
+        && c.is_ascii_alphanumeric()
+    {
+        return Some(c.to_ascii_uppercase() as i32);
+    }
+    
+    KEY_NAMES.iter()
+        .find(|(key_name, _)| key_name.eq_ignore_ascii_case(name))
+        .map(|&(_, code)| code)
+}
+
+pub fn key_name(vk_code: i32) -> String {
+    if (0x30..=0x39).contains(&vk_code) || (0x41..=0x5A).contains(&vk_code) {
+        return (vk_code as u8 as char).to_string();
+    }
+    
+    match KEY_NAMES.iter().find(|&&(_, code)| code == vk_code) {
+        Some((name, _)) => name.to_string(),
+        None => format!("0x{:02X}", vk_code),
+    }
+}
+
+// A bound virtual key, written in config.toml as a key name ("I", "PageUp") or a VK code (0x49)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Key(pub i32);
+
+impl Key {
+    pub fn is_pressed(self) -> bool {
+        is_key_pressed(self.0)
+    }
+    
+    pub fn name(self) -> String {
+        key_name(self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for Key {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum RawKey {
+            Code(i32),
+            Name(String),
+        }
+        
+        match RawKey::deserialize(deserializer)? {
+            RawKey::Code(code) => Ok(Key(code)),
+            RawKey::Name(name) => parse_key_name(&name)
+                .map(Key)
+                .ok_or_else(|| D::Error::custom(format!("unknown key name '{}'", name))),
+        }
+    }
+}
+
+// What an input device asks of the camera for one tick. Every device is turned into these, so the controller
+// never needs to know which one it was.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CameraCommand {
+    Translate(f32, f32, f32), // (dx, dy, dz) as fractions of the move speed, along the camera's own axes
+    Rotate(f32, f32, f32),    // (yaw, pitch, roll) radians to turn right away, with the same signs as mouse look
+    Turn(f32, f32, f32),      // (yaw, pitch, roll) radians per second, for as long as it's sent
+    Speed(i32),               // Move speed steps, up when positive
+    Zoom(i32),                // FOV steps, wider when positive
+    ZoomReset,
+    Fov(f32),                 // Horizontal degrees to set right away, handled by the main loop
+    Scrub(f32),               // Fraction of the path to put playback at, handled by the main loop
+    Toggle(Action),           // Handled by the main loop like the key bound to it
+}
+
+// A device that drives the camera, polled once per tick
+pub trait InputSource {
+    fn poll(&mut self) -> Vec<CameraCommand>;
+}
 
 pub fn is_key_pressed(vk_code: i32) -> bool {
     unsafe {
@@ -17,20 +184,39 @@ pub fn is_key_pressed(vk_code: i32) -> bool {
     }
 }
 
-// Speed control using Page Up/Down
-pub fn get_speed_delta() -> i32 {
-    const VK_PRIOR: i32 = 0x21; // Page Up
-    const VK_NEXT: i32 = 0x22;  // Page Down
-    
-    if is_key_pressed(VK_PRIOR) {
+// Speed control, Page Up/Down by default
+pub fn get_speed_delta(bindings: &KeyBindings) -> i32 {
+    if bindings.speed_up.is_pressed() {
         return 1; // Increase speed
-    } else if is_key_pressed(VK_NEXT) {
+    } else if bindings.speed_down.is_pressed() {
         return -1; // Decrease speed
     }
     
     0
 }
 
+// Path scrubbing, ; and ' by default
+pub fn get_scrub_delta(bindings: &KeyBindings) -> i32 {
+    if bindings.scrub_forward.is_pressed() {
+        return 1;
+    } else if bindings.scrub_back.is_pressed() {
+        return -1;
+    }
+    
+    0
+}
+
+// FOV control, [ and ] by default
+pub fn get_fov_delta(bindings: &KeyBindings) -> i32 {
+    if bindings.zoom_in.is_pressed() {
+        return -1; // Zoom in (narrower FOV)
+    } else if bindings.zoom_out.is_pressed() {
+        return 1; // Zoom out (wider FOV)
+    }
+    
+    0
+}
+
 pub struct MouseHandler {
     screen_center_x: i32,
     screen_center_y: i32,
@@ -67,6 +253,14 @@ impl MouseHandler {
         self.enabled
     }
     
+    pub fn set_sensitivity(&mut self, sensitivity: f32) {
+        self.sensitivity = sensitivity;
+    }
+    
+    pub fn sensitivity(&self) -> f32 {
+        self.sensitivity
+    }
+    
     pub fn get_delta(&self) -> (f32, f32) {
         if !self.enabled {
             return (0.0, 0.0);
@@ -93,66 +287,443 @@ impl MouseHandler {
     }
 }
 
-#[derive(Debug)]
-pub struct MovementInput {
-    pub forward: bool,
-    pub backward: bool,
-    pub left: bool,
-    pub right: bool,
-    pub up: bool,
-    pub down: bool,
+impl InputSource for MouseHandler {
+    fn poll(&mut self) -> Vec<CameraCommand> {
+        let (delta_x, delta_y) = self.get_delta();
+        if delta_x.abs() > 0.01 || delta_y.abs() > 0.01 {
+            // Convert mouse delta to radians
+            vec![CameraCommand::Rotate(delta_x * 0.002, delta_y * 0.002, 0.0)]
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+// The movement, speed and zoom keys. Toggle keys come from KeyEvents instead, so a tap between two ticks isn't
+// missed.
+#[derive(Debug, Clone)]
+pub struct KeyboardSource {
+    bindings: KeyBindings,
+    sprint_multiplier: f32,
+    slow_multiplier: f32,
 }
 
-impl MovementInput {
-    pub fn new() -> Self {
-        Self {
-            forward: false,
-            backward: false,
-            left: false,
-            right: false,
-            up: false,
-            down: false,
-        }
+impl KeyboardSource {
+    pub fn new(bindings: KeyBindings) -> Self {
+        Self { bindings, sprint_multiplier: 4.0, slow_multiplier: 0.25 }
     }
     
-    pub fn read_input(&mut self) {
-        self.forward = is_key_pressed(VK_I);
-        self.backward = is_key_pressed(VK_K);
-        // Fixed J/L mapping: J should move left, L should move right
-        self.left = is_key_pressed(VK_J);
-        self.right = is_key_pressed(VK_L);
-        self.up = is_key_pressed(VK_U);
-        self.down = is_key_pressed(VK_O);
+    pub fn set_bindings(&mut self, bindings: KeyBindings) {
+        self.bindings = bindings;
     }
     
-    pub fn has_movement(&self) -> bool {
-        self.forward || self.backward || self.left || self.right || self.up || self.down
+    pub fn set_modifiers(&mut self, sprint: f32, slow: f32) {
+        self.sprint_multiplier = sprint;
+        self.slow_multiplier = slow;
     }
-    
-    pub fn get_movement_vector(&self, speed: f32) -> (f32, f32, f32) {
-        let mut dx = 0.0;
-        let mut dy = 0.0;
-        let mut dz = 0.0;
+}
+
+impl InputSource for KeyboardSource {
+    fn poll(&mut self) -> Vec<CameraCommand> {
+        let mut commands = Vec::new();
+        let axis = |positive: Key, negative: Key| positive.is_pressed() as i32 as f32 - negative.is_pressed() as i32 as f32;
+        // J moves left, which is positive X in this game
+        let movement = (
+            axis(self.bindings.left, self.bindings.right),
+            axis(self.bindings.up, self.bindings.down),
+            axis(self.bindings.forward, self.bindings.backward),
+        );
+        if movement != (0.0, 0.0, 0.0) {
+            let modifier = if self.bindings.sprint.is_pressed() {
+                self.sprint_multiplier
+            } else if self.bindings.slow.is_pressed() {
+                self.slow_multiplier
+            } else {
+                1.0
+            };
+            commands.push(CameraCommand::Translate(movement.0 * modifier, movement.1 * modifier, movement.2 * modifier));
+        }
+        
+        let speed = get_speed_delta(&self.bindings);
+        if speed != 0 {
+            commands.push(CameraCommand::Speed(speed));
+        }
+        let zoom = get_fov_delta(&self.bindings);
+        if zoom != 0 {
+            commands.push(CameraCommand::Zoom(zoom));
+        }
+        if self.bindings.zoom_reset.is_pressed() {
+            commands.push(CameraCommand::ZoomReset);
+        }
+        commands
+    }
+}
+
+// SpaceMouse settings, set in the [space_mouse] table. Off unless enabled.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct SpaceMouseConfig {
+    pub enabled: bool,
+    pub speed: f32,                  // Move speed multiplier with the puck pushed all the way
+    pub turn_rate: f32,              // Degrees per second with the puck tilted or twisted all the way
+    pub dead_zone: f32,              // Part of each axis's travel that doesn't move the camera, 0-1
+    pub roll: bool,                  // Tilting sideways rolls the camera, off keeps the horizon level
+    pub invert: Vec<SpaceMouseAxis>, // Axes that go the other way
+}
+
+impl Default for SpaceMouseConfig {
+    fn default() -> Self {
+        Self { enabled: false, speed: 1.0, turn_rate: 90.0, dead_zone: 0.05, roll: false, invert: Vec::new() }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SpaceMouseAxis {
+    Right,
+    Up,
+    Forward,
+    Yaw,
+    Pitch,
+    Roll,
+}
+
+// How far a 3Dconnexion puck reports when pushed all the way, a little more comes through on a hard push
+const SPACE_MOUSE_RANGE: f32 = 350.0;
+
+// A 3Dconnexion SpaceMouse, or another 6-axis controller speaking the same HID reports, read through raw
+// input on its own thread. The puck is a rate control: how far it's pushed moves the camera, how far it's
+// tilted or twisted turns it, and letting go stops it.
+pub struct SpaceMouse {
+    config: SpaceMouseConfig,
+    receiver: Receiver<SpaceMouseReport>,
+    input_thread: Option<(DWORD, JoinHandle<()>)>, // Win32 thread ID to post WM_QUIT to
+    translation: [i16; 3],                         // Latest X, Y, Z as the device reports them
+    rotation: [i16; 3],                            // Latest Rx, Ry, Rz
+}
+
+// The axes one HID report carried
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct SpaceMouseReport {
+    translation: Option<[i16; 3]>,
+    rotation: Option<[i16; 3]>,
+}
+
+impl SpaceMouse {
+    // Fails when raw input can't be set up. No SpaceMouse plugged in yet isn't an error, see `connected`.
+    pub fn start(config: &SpaceMouseConfig) -> Result<Self, String> {
+        let (sender, receiver) = mpsc::channel();
+        let (ready_sender, ready) = mpsc::channel();
+        // Multi-axis controllers are usage 8
+        let thread = std::thread::spawn(move || raw_input_thread("SpaceMouse", 0x08, read_space_mouse, sender, ready_sender));
+        let thread_id = ready.recv().map_err(|_| "The SpaceMouse input thread stopped".to_string())??;
         
-        if self.forward {
-            dz += speed;
+        Ok(Self {
+            config: config.clone(),
+            receiver,
+            input_thread: Some((thread_id, thread)),
+            translation: [0; 3],
+            rotation: [0; 3],
+        })
+    }
+    
+    // How many multi-axis controllers Windows knows about
+    pub fn connected() -> usize {
+        let mut count: UINT = 0;
+        let entry_size = mem::size_of::<RAWINPUTDEVICELIST>() as UINT;
+        if unsafe { GetRawInputDeviceList(ptr::null_mut(), &mut count, entry_size) } == UINT::MAX {
+            return 0;
         }
-        if self.backward {
-            dz -= speed;
+        let mut devices: Vec<RAWINPUTDEVICELIST> = vec![unsafe { mem::zeroed() }; count as usize];
+        let listed = unsafe { GetRawInputDeviceList(devices.as_mut_ptr(), &mut count, entry_size) };
+        if listed == UINT::MAX {
+            return 0;
         }
-        if self.left {
-            dx += speed; // J key moves left (positive X in this game)
+        
+        devices.iter().take(listed as usize).filter(|device| device.dwType == RIM_TYPEHID).filter(|device| {
+            let mut info: RID_DEVICE_INFO = unsafe { mem::zeroed() };
+            info.cbSize = mem::size_of::<RID_DEVICE_INFO>() as DWORD;
+            let mut size = info.cbSize as UINT;
+            let result = unsafe { GetRawInputDeviceInfoW(device.hDevice, RIDI_DEVICEINFO, &mut info as *mut _ as *mut _, &mut size) };
+            let hid = unsafe { info.u.hid() };
+            result != UINT::MAX && hid.usUsagePage == 0x01 && hid.usUsage == 0x08
+        }).count()
+    }
+    
+    // A reported value as -1 to 1, 0 inside the dead zone
+    fn axis(&self, axis: SpaceMouseAxis, value: i16) -> f32 {
+        let offset = (value as f32 / SPACE_MOUSE_RANGE).clamp(-1.0, 1.0);
+        let dead_zone = self.config.dead_zone.clamp(0.0, 0.99);
+        if offset.abs() <= dead_zone {
+            return 0.0;
         }
-        if self.right {
-            dx -= speed; // L key moves right (negative X in this game)
+        let scaled = offset.signum() * (offset.abs() - dead_zone) / (1.0 - dead_zone);
+        if self.config.invert.contains(&axis) { -scaled } else { scaled }
+    }
+}
+
+impl InputSource for SpaceMouse {
+    // Takes in the reports since the last poll and moves and turns as far as the puck is off centre now
+    fn poll(&mut self) -> Vec<CameraCommand> {
+        for report in self.receiver.try_iter() {
+            if let Some(translation) = report.translation {
+                self.translation = translation;
+            }
+            if let Some(rotation) = report.rotation {
+                self.rotation = rotation;
+            }
         }
-        if self.up {
-            dy += speed;
+        
+        // The device has X to the right, Y towards you and Z down, and rotates around the same axes
+        let [x, y, z] = self.translation;
+        let [rx, ry, rz] = self.rotation;
+        // Twisting clockwise turns right, tilting the puck forward looks down and tilting it right rolls right
+        let speed = self.config.speed;
+        let turn_rate = self.config.turn_rate.to_radians();
+        let roll = if self.config.roll { self.axis(SpaceMouseAxis::Roll, -ry) * turn_rate } else { 0.0 };
+        vec![
+            // Positive X is to the left in this game
+            CameraCommand::Translate(-self.axis(SpaceMouseAxis::Right, x) * speed, -self.axis(SpaceMouseAxis::Up, z) * speed,
+                                     -self.axis(SpaceMouseAxis::Forward, y) * speed),
+            CameraCommand::Turn(self.axis(SpaceMouseAxis::Yaw, rz) * turn_rate, self.axis(SpaceMouseAxis::Pitch, -rx) * turn_rate, roll),
+        ]
+    }
+}
+
+impl Drop for SpaceMouse {
+    // Same as the key events thread: GetMessage only wakes up for messages
+    fn drop(&mut self) {
+        if let Some((thread_id, thread)) = self.input_thread.take() {
+            unsafe { PostThreadMessageW(thread_id, WM_QUIT, 0, 0) };
+            let _ = thread.join();
         }
-        if self.down {
-            dy -= speed;
+    }
+}
+
+// Whether the mouse moved since the last `take`, from raw mouse input. Mouse look puts the cursor back every tick,
+// which raw input doesn't see, so only a hand on the mouse counts.
+#[derive(Clone, Default)]
+pub struct MouseMotion {
+    moved: Arc<AtomicBool>,
+}
+
+impl MouseMotion {
+    pub fn take(&self) -> bool {
+        self.moved.swap(false, Ordering::Relaxed)
+    }
+}
+
+// Mouse wheel notches change the move speed: `steps` speed steps a notch, a single step with Shift held. With Ctrl
+// held they zoom instead. Only scrolling while the game or the console is in front counts. A process only gets
+// raw mouse input in one window, so this also keeps track of mouse motion for the dead man's switch.
+pub struct MouseWheel {
+    steps: i32, // 0 leaves the wheel alone and only watches motion
+    receiver: Receiver<i16>,
+    input_thread: Option<(DWORD, JoinHandle<()>)>, // Win32 thread ID to post WM_QUIT to
+    focus_windows: Vec<HWND>,
+    remainder: i32, // Delta short of a whole notch, high-resolution wheels send a notch in parts
+    motion: MouseMotion,
+}
+
+impl MouseWheel {
+    pub fn start(steps: u32, game_window: Option<HWND>) -> Result<Self, String> {
+        let (sender, receiver) = mpsc::channel();
+        let (ready_sender, ready) = mpsc::channel();
+        let motion = MouseMotion::default();
+        let moved = Arc::clone(&motion.moved);
+        let read = move |lparam| read_mouse(lparam, &moved);
+        // Mice are usage 2
+        let thread = std::thread::spawn(move || raw_input_thread("mouse", 0x02, read, sender, ready_sender));
+        let thread_id = ready.recv().map_err(|_| "The mouse input thread stopped".to_string())??;
+        
+        // Without the game window every foreground window counts, like the hotkeys
+        let focus_windows = match game_window {
+            Some(window) => vec![window, unsafe { GetConsoleWindow() }],
+            None => Vec::new(),
+        };
+        Ok(Self {
+            steps: steps.min(i32::MAX as u32) as i32,
+            receiver,
+            input_thread: Some((thread_id, thread)),
+            focus_windows,
+            remainder: 0,
+            motion,
+        })
+    }
+    
+    pub fn motion(&self) -> MouseMotion {
+        self.motion.clone()
+    }
+}
+
+impl InputSource for MouseWheel {
+    fn poll(&mut self) -> Vec<CameraCommand> {
+        let delta: i32 = self.receiver.try_iter().map(i32::from).sum();
+        // Scrolling a web page shouldn't change the camera
+        if self.steps == 0 || !self.focus_windows.is_empty() && !self.focus_windows.contains(&unsafe { GetForegroundWindow() }) {
+            self.remainder = 0;
+            return Vec::new();
         }
         
-        (dx, dy, dz)
+        self.remainder += delta;
+        let notches = self.remainder / WHEEL_DELTA as i32;
+        self.remainder -= notches * WHEEL_DELTA as i32;
+        if notches == 0 {
+            return Vec::new();
+        }
+        // Scrolling up speeds up and zooms in
+        if is_key_pressed(VK_CONTROL) {
+            vec![CameraCommand::Zoom(-notches)]
+        } else if is_key_pressed(VK_SHIFT) {
+            vec![CameraCommand::Speed(notches)]
+        } else {
+            vec![CameraCommand::Speed(notches.saturating_mul(self.steps))]
+        }
+    }
+}
+
+impl Drop for MouseWheel {
+    fn drop(&mut self) {
+        if let Some((thread_id, thread)) = self.input_thread.take() {
+            unsafe { PostThreadMessageW(thread_id, WM_QUIT, 0, 0) };
+            let _ = thread.join();
+        }
+    }
+}
+
+// The wheel delta in a WM_INPUT message, positive when turned away from you. Movement only sets `moved`.
+fn read_mouse(lparam: LPARAM, moved: &AtomicBool) -> Vec<i16> {
+    let mut input: RAWINPUT = unsafe { mem::zeroed() };
+    let mut size = mem::size_of::<RAWINPUT>() as UINT;
+    let result = unsafe {
+        GetRawInputData(lparam as HRAWINPUT, RID_INPUT, &mut input as *mut _ as *mut _, &mut size, mem::size_of::<RAWINPUTHEADER>() as UINT)
+    };
+    if result == UINT::MAX || input.header.dwType != RIM_TYPEMOUSE {
+        return Vec::new();
+    }
+    
+    let mouse = unsafe { input.data.mouse() };
+    if mouse.lLastX != 0 || mouse.lLastY != 0 {
+        moved.store(true, Ordering::Relaxed);
+    }
+    if mouse.usButtonFlags & RI_MOUSE_WHEEL == 0 {
+        return Vec::new();
+    }
+    vec![mouse.usButtonData as i16]
+}
+
+// Raw input from one kind of device on usage page 1, delivered even while the game has focus. Whatever `read`
+// makes of each WM_INPUT message is passed on until WM_QUIT or the receiver is gone.
+pub fn raw_input_thread<T>(name: &str, usage: u16, mut read: impl FnMut(LPARAM) -> Vec<T>, sender: Sender<T>, ready: Sender<Result<DWORD, String>>) {
+    // Raw input needs a window to deliver to, a message-only one is enough
+    let class: Vec<u16> = "STATIC".encode_utf16().chain(Some(0)).collect();
+    let window = unsafe {
+        CreateWindowExW(0, class.as_ptr(), ptr::null(), 0, 0, 0, 0, 0, HWND_MESSAGE, ptr::null_mut(), ptr::null_mut(), ptr::null_mut())
+    };
+    if window.is_null() {
+        let error_code = unsafe { GetLastError() };
+        let _ = ready.send(Err(format!("Failed to create the {} input window (Error: {})", name, error_code)));
+        return;
+    }
+    
+    let device = RAWINPUTDEVICE {
+        usUsagePage: 0x01,
+        usUsage: usage,
+        dwFlags: RIDEV_INPUTSINK,
+        hwndTarget: window,
+    };
+    if unsafe { RegisterRawInputDevices(&device, 1, mem::size_of::<RAWINPUTDEVICE>() as UINT) } == 0 {
+        let error_code = unsafe { GetLastError() };
+        let _ = ready.send(Err(format!("Failed to register for {} input (Error: {})", name, error_code)));
+        unsafe { DestroyWindow(window) };
+        return;
+    }
+    let _ = ready.send(Ok(unsafe { GetCurrentThreadId() }));
+    
+    let mut message: MSG = unsafe { mem::zeroed() };
+    'messages: while unsafe { GetMessageW(&mut message, ptr::null_mut(), 0, 0) } > 0 {
+        if message.message == WM_INPUT {
+            for item in read(message.lParam) {
+                if sender.send(item).is_err() {
+                    break 'messages; // Free cam is done
+                }
+            }
+        }
+        unsafe { DispatchMessageW(&message) };
+    }
+    
+    unsafe { DestroyWindow(window) };
+}
+
+// The SpaceMouse reports in a WM_INPUT message
+fn read_space_mouse(lparam: LPARAM) -> Vec<SpaceMouseReport> {
+    read_hid_reports(lparam).iter().map(|report| parse_space_mouse_report(report)).collect()
+}
+
+// The HID reports in a WM_INPUT message, which can hold several
+fn read_hid_reports(lparam: LPARAM) -> Vec<Vec<u8>> {
+    let header_size = mem::size_of::<RAWINPUTHEADER>() as UINT;
+    let mut size: UINT = 0;
+    if unsafe { GetRawInputData(lparam as HRAWINPUT, RID_INPUT, ptr::null_mut(), &mut size, header_size) } == UINT::MAX {
+        return Vec::new();
+    }
+    // HID input is longer than RAWINPUT says, u64s keep the buffer aligned for it
+    let length = (size as usize).max(mem::size_of::<RAWINPUT>());
+    let mut buffer = vec![0u64; length.div_ceil(8)];
+    if unsafe { GetRawInputData(lparam as HRAWINPUT, RID_INPUT, buffer.as_mut_ptr().cast(), &mut size, header_size) } == UINT::MAX {
+        return Vec::new();
+    }
+    
+    let input = unsafe { &*(buffer.as_ptr() as *const RAWINPUT) };
+    if input.header.dwType != RIM_TYPEHID {
+        return Vec::new();
+    }
+    let hid = unsafe { input.data.hid() };
+    let report_size = hid.dwSizeHid as usize;
+    let reports_length = report_size * hid.dwCount as usize;
+    let offset = hid.bRawData.as_ptr() as usize - buffer.as_ptr() as usize;
+    if report_size == 0 || offset + reports_length > buffer.len() * 8 {
+        return Vec::new();
+    }
+    let reports = unsafe { std::slice::from_raw_parts(hid.bRawData.as_ptr(), reports_length) };
+    reports.chunks(report_size).map(<[u8]>::to_vec).collect()
+}
+
+// 3Dconnexion reports are a report ID and little-endian i16 values: ID 1 holds X, Y and Z, and on newer models
+// Rx, Ry and Rz after them, ID 2 holds Rx, Ry and Rz. Buttons and anything else are left alone.
+fn parse_space_mouse_report(report: &[u8]) -> SpaceMouseReport {
+    let axes = |bytes: &[u8]| -> Option<[i16; 3]> {
+        (bytes.len() >= 6).then(|| [0, 1, 2].map(|i| i16::from_le_bytes([bytes[i * 2], bytes[i * 2 + 1]])))
+    };
+    match report.split_first() {
+        Some((1, data)) => SpaceMouseReport { translation: axes(data), rotation: data.get(6..).and_then(axes) },
+        Some((2, data)) => SpaceMouseReport { translation: None, rotation: axes(data) },
+        _ => SpaceMouseReport::default(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn report(id: u8, values: &[i16]) -> Vec<u8> {
+        std::iter::once(id).chain(values.iter().flat_map(|value| value.to_le_bytes())).collect()
+    }
+    
+    #[test]
+    fn space_mouse_reports_split_by_id() {
+        assert_eq!(parse_space_mouse_report(&report(1, &[1, -2, 300])),
+                   SpaceMouseReport { translation: Some([1, -2, 300]), rotation: None });
+        assert_eq!(parse_space_mouse_report(&report(1, &[1, 2, 3, -4, 5, -6])),
+                   SpaceMouseReport { translation: Some([1, 2, 3]), rotation: Some([-4, 5, -6]) });
+        assert_eq!(parse_space_mouse_report(&report(2, &[7, 8, 9])),
+                   SpaceMouseReport { translation: None, rotation: Some([7, 8, 9]) });
+    }
+    
+    #[test]
+    fn other_space_mouse_reports_are_ignored() {
+        assert_eq!(parse_space_mouse_report(&report(3, &[1, 2, 3])), SpaceMouseReport::default());
+        assert_eq!(parse_space_mouse_report(&[1, 0, 0]), SpaceMouseReport::default());
+        assert_eq!(parse_space_mouse_report(&[]), SpaceMouseReport::default());
     }
 }
@@ -0,0 +1,135 @@
+// Game instances attached next to the one the controls drive, e.g. two copies of the game side by side for
+// comparison captures. The instance key hands the controls to the next copy. In mirror mode the driven
+// camera is written into every other copy as well, with their camera patch on and off together with the
+// driven one. Mirrored copies only get external matrix writes: the hook, FOV and freeze stay with the
+// driven instance.
+
+use crate::camera::CameraMatrix;
+use crate::process::{CodePatch, ProcessHandle};
+
+struct Instance {
+    process: ProcessHandle,
+    base_addr: Option<usize>, // Looked up on the first mirrored write
+    patch: Option<CodePatch>, // The camera write patch while applied
+    failed: bool,             // The last write failed, so the next failure isn't reported again
+}
+
+impl Instance {
+    fn new(process: ProcessHandle) -> Self {
+        Self { process, base_addr: None, patch: None, failed: false }
+    }
+    
+    fn write(&mut self, matrix: &CameraMatrix, patch: bool) -> Result<(), String> {
+        let base_addr = match self.base_addr {
+            Some(base_addr) => base_addr,
+            None => *self.base_addr.insert(self.process.get_base_address()?),
+        };
+        if patch && self.patch.is_none() {
+            let address = self.process.get_camera_write_patch_address(base_addr)?;
+            self.patch = Some(self.process.patch_with_nops(address, &self.process.game().camera_write().original_bytes)?);
+        } else if !patch {
+            self.release()?;
+        }
+        self.process.set_camera_matrix(base_addr, matrix)
+    }
+    
+    fn release(&mut self) -> Result<(), String> {
+        if let Some(patch) = &mut self.patch {
+            self.process.restore_patch(patch)?;
+            self.patch = None;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+pub struct Instances {
+    others: Vec<Instance>, // In the order the controls go round them
+    mirroring: bool,
+}
+
+impl Instances {
+    pub fn new(processes: Vec<ProcessHandle>) -> Self {
+        Self { others: processes.into_iter().map(Instance::new).collect(), mirroring: false }
+    }
+    
+    // How many instances there are besides the driven one
+    pub fn len(&self) -> usize {
+        self.others.len()
+    }
+    
+    pub fn is_empty(&self) -> bool {
+        self.others.is_empty()
+    }
+    
+    pub fn pids(&self) -> Vec<u32> {
+        self.others.iter().map(|instance| instance.process.pid()).collect()
+    }
+    
+    pub fn is_mirroring(&self) -> bool {
+        self.mirroring
+    }
+    
+    // Returns whether mirroring is on afterwards. Turning it off gives the other instances their cameras back.
+    pub fn toggle_mirror(&mut self) -> Result<bool, String> {
+        self.mirroring = !self.mirroring;
+        if !self.mirroring {
+            self.release()?;
+        }
+        Ok(self.mirroring)
+    }
+    
+    // Writes the driven instance's matrix into every other one while mirroring. `patch` is whether the driven
+    // instance has its camera patch on. Returns what went wrong, once per instance until a write works again.
+    pub fn mirror(&mut self, matrix: &CameraMatrix, patch: bool) -> Vec<String> {
+        if !self.mirroring {
+            return Vec::new();
+        }
+        let mut errors = Vec::new();
+        for instance in &mut self.others {
+            match instance.write(matrix, patch) {
+                Ok(()) => instance.failed = false,
+                Err(e) => {
+                    if !instance.failed {
+                        errors.push(format!("PID {}: {}", instance.process.pid(), e));
+                    }
+                    instance.failed = true;
+                }
+            }
+        }
+        errors
+    }
+    
+    // Restores the camera patch in every other instance
+    pub fn release(&mut self) -> Result<(), String> {
+        let mut result = Ok(());
+        for instance in &mut self.others {
+            if let Err(e) = instance.release() {
+                result = Err(format!("PID {}: {}", instance.process.pid(), e));
+            }
+        }
+        result
+    }
+    
+    // Hands the controls to the next instance: `driven` swaps places with the first of the others, and the one
+    // that was driven goes to the back of the queue
+    pub fn next(&mut self, driven: &mut ProcessHandle) -> Result<(), String> {
+        self.release()?;
+        let Some(first) = self.others.first_mut() else {
+            return Err("Only one game instance is attached".to_string());
+        };
+        std::mem::swap(&mut first.process, driven);
+        first.base_addr = None;
+        first.failed = false;
+        self.others.rotate_left(1);
+        Ok(())
+    }
+}
+
+impl Drop for Instances {
+    fn drop(&mut self) {
+        if let Err(e) = self.release() {
+            log::error!("Failed to restore a mirrored instance's camera patch on exit: {}", e);
+        }
+    }
+}
@@ -0,0 +1,126 @@
+// Connection to the hook DLL injected with --internal. Camera writes go through shared memory
+// and the hook applies them from inside the game loop, so they can't race the game's own update.
+
+use std::cell::RefCell;
+use std::ffi::CString;
+use std::ptr;
+
+use thps3_free_cam_hook::{mapping_name, SharedCamera, OSD_FAILED, OSD_TEXT_LEN, SHARED_MAGIC, SHARED_VERSION};
+use winapi::um::handleapi::CloseHandle;
+use winapi::um::memoryapi::{MapViewOfFile, UnmapViewOfFile, FILE_MAP_ALL_ACCESS};
+use winapi::um::winbase::OpenFileMappingA;
+use winapi::um::winnt::HANDLE;
+
+use crate::camera::{CameraMatrix, WriteChannels};
+
+pub const HOOK_DLL: &str = "thps3_free_cam_hook.dll";
+
+pub struct InternalCamera {
+    mapping: HANDLE,
+    shared: *mut SharedCamera,
+    osd_text: RefCell<String>, // Last status line written, so an unchanged one isn't written again
+}
+
+impl InternalCamera {
+    // Opens the shared block of an already injected hook
+    pub fn connect(pid: u32) -> Result<Self, String> {
+        let name = CString::new(mapping_name(pid)).map_err(|e| e.to_string())?;
+        let mapping = unsafe { OpenFileMappingA(FILE_MAP_ALL_ACCESS, 0, name.as_ptr()) };
+        if mapping.is_null() {
+            return Err("Hook is not loaded in the game".to_string());
+        }
+        
+        let shared = unsafe { MapViewOfFile(mapping, FILE_MAP_ALL_ACCESS, 0, 0, 0) } as *mut SharedCamera;
+        if shared.is_null() {
+            unsafe { CloseHandle(mapping) };
+            return Err("Failed to map the hook's shared memory".to_string());
+        }
+        
+        // Checked before there's an InternalCamera, whose drop writes the active flag and the status line into the
+        // block as if it knew the layout. A hook from another version lays it out differently.
+        let (magic, version) = unsafe { (ptr::read_volatile(&(*shared).magic), ptr::read_volatile(&(*shared).version)) };
+        if magic != SHARED_MAGIC || version != SHARED_VERSION {
+            unsafe {
+                UnmapViewOfFile(shared as *const _);
+                CloseHandle(mapping);
+            }
+            if magic != SHARED_MAGIC {
+                return Err("Hook shared memory has an unknown layout - rebuild the hook DLL".to_string());
+            }
+            return Err("The hook loaded in the game is from another version of the free cam - restart the game to load this one".to_string());
+        }
+        Ok(InternalCamera { mapping, shared, osd_text: RefCell::new(String::new()) })
+    }
+    
+    // Instruction the hook patched, None when it couldn't find the camera copy
+    pub fn hook_address(&self) -> Option<usize> {
+        match unsafe { ptr::read_volatile(&(*self.shared).hook_address) } {
+            0 => None,
+            address => Some(address as usize),
+        }
+    }
+    
+    // How many camera updates the hook has seen, to tell whether it's actually running
+    pub fn frames(&self) -> u32 {
+        unsafe { ptr::read_volatile(&(*self.shared).frames) }
+    }
+    
+    pub fn is_active(&self) -> bool {
+        unsafe { ptr::read_volatile(&(*self.shared).active) != 0 }
+    }
+    
+    pub fn set_active(&self, active: bool) {
+        unsafe { ptr::write_volatile(&mut (*self.shared).active, active as u32) };
+    }
+    
+    // Whether the hook couldn't get into the game's Direct3D frames, so the status line never shows
+    pub fn osd_failed(&self) -> bool {
+        unsafe { ptr::read_volatile(&(*self.shared).osd_state) == OSD_FAILED }
+    }
+    
+    // Status line the hook draws in the top left corner of the game, an empty one hides it. The sequence number
+    // is odd while the text is written, so the hook skips a half-written line.
+    pub fn set_osd(&self, text: &str) {
+        let mut last = self.osd_text.borrow_mut();
+        if *last == text {
+            return;
+        }
+        
+        let mut bytes = [0u8; OSD_TEXT_LEN];
+        let mut length = text.len().min(OSD_TEXT_LEN - 1);
+        while !text.is_char_boundary(length) {
+            length -= 1;
+        }
+        bytes[..length].copy_from_slice(&text.as_bytes()[..length]);
+        unsafe {
+            let sequence = ptr::read_volatile(&(*self.shared).osd_sequence);
+            ptr::write_volatile(&mut (*self.shared).osd_sequence, sequence.wrapping_add(1));
+            ptr::write_volatile(&mut (*self.shared).osd_text, bytes);
+            ptr::write_volatile(&mut (*self.shared).osd_sequence, sequence.wrapping_add(2));
+        }
+        *last = text.to_string();
+    }
+    
+    // Writes into the buffer the hook isn't reading, then flips to it. `channels` picks the floats the hook copies.
+    pub fn write_matrix(&self, matrix_address: usize, matrix: &CameraMatrix, channels: WriteChannels) {
+        unsafe {
+            ptr::write_volatile(&mut (*self.shared).channels, channels.mask());
+            let back = (ptr::read_volatile(&(*self.shared).front) & 1) ^ 1;
+            ptr::write_volatile(&mut (*self.shared).matrices[back as usize], matrix.data);
+            ptr::write_volatile(&mut (*self.shared).matrix_address, matrix_address as u32);
+            ptr::write_volatile(&mut (*self.shared).front, back);
+        }
+    }
+}
+
+impl Drop for InternalCamera {
+    fn drop(&mut self) {
+        // Hand the camera back to the game, the hook itself stays loaded for the next session
+        self.set_active(false);
+        self.set_osd("");
+        unsafe {
+            UnmapViewOfFile(self.shared as *const _);
+            CloseHandle(self.mapping);
+        }
+    }
+}
@@ -0,0 +1,132 @@
+use serde::Deserialize;
+
+use crate::camera::{CameraPose, CameraPosition, Quaternion};
+
+// How poses are blended between keyframes, set with `interpolation` in config.toml
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InterpolationMode {
+    Linear, // Straight lines between keyframes, slerp for rotation
+    #[default]
+    Spline, // Catmull-Rom through the keyframes, slerp for rotation
+    Squad,  // Catmull-Rom through the keyframes, SQUAD for rotation
+}
+
+impl InterpolationMode {
+    pub fn name(self) -> &'static str {
+        match self {
+            InterpolationMode::Linear => "linear",
+            InterpolationMode::Spline => "spline",
+            InterpolationMode::Squad => "squad",
+        }
+    }
+}
+
+fn lerp(a: &CameraPosition, b: &CameraPosition, t: f32) -> CameraPosition {
+    CameraPosition::new(a.x + (b.x - a.x) * t, a.y + (b.y - a.y) * t, a.z + (b.z - a.z) * t)
+}
+
+// Uniform Catmull-Rom, passes through p1 at t=0 and p2 at t=1
+fn catmull_rom(p0: f32, p1: f32, p2: f32, p3: f32, t: f32) -> f32 {
+    let t2 = t * t;
+    let t3 = t2 * t;
+    0.5 * ((2.0 * p1)
+        + (-p0 + p2) * t
+        + (2.0 * p0 - 5.0 * p1 + 4.0 * p2 - p3) * t2
+        + (-p0 + 3.0 * p1 - 3.0 * p2 + p3) * t3)
+}
+
+// Time on a segment with ease handles, like a cubic Bezier timing curve from (0, 0) to (1, 1) with its control
+// points at (ease_out / 2, 0) and (1 - ease_in / 2, 1). Handles of 0 leave the time as it is; longer handles
+// make the camera start off and come to rest more gently, and a handle of 1 reaches the middle of the segment.
+pub fn ease(t: f32, ease_out: f32, ease_in: f32) -> f32 {
+    let t = t.clamp(0.0, 1.0);
+    let (x1, x2) = (ease_out.clamp(0.0, 1.0) * 0.5, 1.0 - ease_in.clamp(0.0, 1.0) * 0.5);
+    if x1 == 0.0 && x2 == 1.0 {
+        return t;
+    }
+    let bezier = |a: f32, b: f32, s: f32| 3.0 * a * s * (1.0 - s) * (1.0 - s) + 3.0 * b * s * s * (1.0 - s) + s * s * s;
+    
+    // The curve's x only grows along it, so halving finds the point at time t
+    let (mut low, mut high) = (0.0, 1.0);
+    for _ in 0..24 {
+        let middle = (low + high) * 0.5;
+        if bezier(x1, x2, middle) < t { low = middle } else { high = middle }
+    }
+    bezier(0.0, 1.0, (low + high) * 0.5)
+}
+
+// Straight blend between two poses, a `weight` of 0 gives `a` and 1 gives `b`
+pub fn blend(a: &CameraPose, b: &CameraPose, weight: f32) -> CameraPose {
+    let weight = weight.clamp(0.0, 1.0);
+    CameraPose {
+        position: lerp(&a.position, &b.position, weight),
+        rotation: a.rotation.slerp(&b.rotation, weight),
+    }
+}
+
+// q and -q are the same rotation, pick the one on the same side as `reference`
+fn align(q: &Quaternion, reference: &Quaternion) -> Quaternion {
+    if q.dot(reference) < 0.0 { q.negate() } else { *q }
+}
+
+// Blends from `from` to `to` at `t` (0..1). `prev` and `next` are the neighbouring keyframes,
+// pass the end keyframe itself when there is no neighbour on that side.
+pub fn interpolate(prev: &CameraPose, from: &CameraPose, to: &CameraPose, next: &CameraPose, t: f32, mode: InterpolationMode) -> CameraPose {
+    let t = t.clamp(0.0, 1.0);
+    
+    let position = match mode {
+        InterpolationMode::Linear => lerp(&from.position, &to.position, t),
+        InterpolationMode::Spline | InterpolationMode::Squad => CameraPosition::new(
+            catmull_rom(prev.position.x, from.position.x, to.position.x, next.position.x, t),
+            catmull_rom(prev.position.y, from.position.y, to.position.y, next.position.y, t),
+            catmull_rom(prev.position.z, from.position.z, to.position.z, next.position.z, t),
+        ),
+    };
+    
+    let rotation = match mode {
+        InterpolationMode::Linear | InterpolationMode::Spline => from.rotation.slerp(&to.rotation, t),
+        InterpolationMode::Squad => {
+            let q1 = from.rotation;
+            let q0 = align(&prev.rotation, &q1);
+            let q2 = align(&to.rotation, &q1);
+            let q3 = align(&next.rotation, &q2);
+            let a = q1.squad_control(&q0, &q2);
+            let b = q2.squad_control(&q1, &q3);
+            q1.squad(&q2, &a, &b, t).normalize()
+        }
+    };
+    
+    CameraPose { position, rotation }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn ease_without_handles_is_linear() {
+        for t in [0.0, 0.25, 0.5, 1.0] {
+            assert_eq!(ease(t, 0.0, 0.0), t);
+        }
+    }
+    
+    #[test]
+    fn ease_keeps_its_ends_and_grows() {
+        for (ease_out, ease_in) in [(1.0, 0.0), (0.0, 1.0), (1.0, 1.0), (0.3, 0.7)] {
+            assert!(ease(0.0, ease_out, ease_in).abs() < 1e-4);
+            assert!((ease(1.0, ease_out, ease_in) - 1.0).abs() < 1e-4);
+            let samples: Vec<f32> = (0..=20).map(|i| ease(i as f32 / 20.0, ease_out, ease_in)).collect();
+            assert!(samples.windows(2).all(|pair| pair[1] >= pair[0]));
+        }
+    }
+    
+    #[test]
+    fn ease_handles_slow_the_ends() {
+        assert!(ease(0.1, 1.0, 0.0) < 0.1);
+        assert!(ease(0.9, 0.0, 1.0) > 0.9);
+        // Handles past 0..1 and times outside the path are clamped
+        assert_eq!(ease(-1.0, 5.0, 5.0), ease(0.0, 1.0, 1.0));
+        assert_eq!(ease(2.0, 0.0, 0.0), 1.0);
+    }
+}
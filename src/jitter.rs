@@ -0,0 +1,77 @@
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use crate::camera::{CameraMatrix, Quaternion};
+
+// Share of the amplitude that changes every frame; the rest wanders slowly like a hand-held camcorder
+const FRAME_NOISE: f32 = 0.25;
+
+// Low-amplitude random pose offsets for VHS-style footage. The offset is added when the matrix is
+// written, and the controller hands out the clean matrix when it is read back, so it never builds up in the
+// game's camera.
+pub struct Jitter {
+    position: f32, // Maximum offset along the camera axes, in world units
+    rotation: f32, // Maximum pitch/yaw/roll offset, in radians
+    frequency: f32, // New wander targets per second
+    seed: u64,
+    phase: f32,
+    from: [f32; 6],
+    to: [f32; 6],
+    last_tick: Option<Instant>,
+}
+
+impl Jitter {
+    pub fn new(position: f32, rotation_degrees: f32, frequency: f32) -> Self {
+        let seed = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |time| time.as_nanos() as u64);
+        
+        Self {
+            position: position.max(0.0),
+            rotation: rotation_degrees.max(0.0).to_radians(),
+            frequency: frequency.max(0.01),
+            seed: seed | 1, // xorshift gets stuck on zero
+            phase: 0.0,
+            from: [0.0; 6],
+            to: [0.0; 6],
+            last_tick: None,
+        }
+    }
+    
+    // xorshift64, uniform in -1..1
+    fn random(&mut self) -> f32 {
+        self.seed ^= self.seed << 13;
+        self.seed ^= self.seed >> 7;
+        self.seed ^= self.seed << 17;
+        (self.seed >> 40) as f32 / (1u64 << 23) as f32 - 1.0
+    }
+    
+    // Offsets as (right, up, forward, pitch, yaw, roll), each in -1..1
+    fn next_offsets(&mut self) -> [f32; 6] {
+        let now = Instant::now();
+        let elapsed = self.last_tick.map_or(0.0, |last| now.duration_since(last).as_secs_f32());
+        self.last_tick = Some(now);
+        
+        self.phase += elapsed * self.frequency;
+        while self.phase >= 1.0 {
+            self.phase -= 1.0;
+            self.from = self.to;
+            self.to = std::array::from_fn(|_| self.random());
+        }
+        
+        let t = self.phase * self.phase * (3.0 - 2.0 * self.phase); // Ease between wander targets
+        std::array::from_fn(|axis| {
+            let wander = self.from[axis] + (self.to[axis] - self.from[axis]) * t;
+            wander * (1.0 - FRAME_NOISE) + self.random() * FRAME_NOISE
+        })
+    }
+    
+    // Returns `matrix` with this frame's offset applied, the controller keeps the clean one to hand back on reads
+    pub fn apply(&mut self, matrix: &CameraMatrix) -> CameraMatrix {
+        let [right, up, forward, pitch, yaw, roll] = self.next_offsets();
+        
+        let mut jittered = matrix.clone();
+        let wobble = Quaternion::from_euler_xyz(pitch * self.rotation, yaw * self.rotation, roll * self.rotation);
+        jittered.set_rotation(&matrix.to_quaternion().multiply(&wobble));
+        jittered.apply_translation(right * self.position, up * self.position, forward * self.position);
+        
+        jittered
+    }
+}
@@ -0,0 +1,67 @@
+// Follows which level the game has loaded through the [level] chain from the offsets file, so waypoints,
+// bounds and camera settings can change with it. Checked about once a second, loading a level takes longer.
+
+use std::time::{Duration, Instant};
+
+use crate::offsets::LevelOffsets;
+use crate::process::ProcessHandle;
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+// A level name as it can go into a file name. The name comes from game memory or the config, so path separators,
+// characters Windows doesn't allow and dots at either end (`..`) are replaced or dropped, and it can't point
+// outside the directory it's joined to.
+pub fn file_name(level: &str) -> String {
+    let name: String = level.chars()
+        .map(|c| if c.is_control() || matches!(c, '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|') { '_' } else { c })
+        .collect();
+    let name = name.trim_matches(|c: char| c == '.' || c.is_whitespace());
+    if name.is_empty() { "_".to_string() } else { name.to_string() }
+}
+
+pub struct LevelWatch {
+    offsets: LevelOffsets,
+    current: Option<String>,
+    last_check: Option<Instant>,
+}
+
+impl LevelWatch {
+    pub fn new(offsets: LevelOffsets) -> Self {
+        Self { offsets, current: None, last_check: None }
+    }
+    
+    pub fn current(&self) -> Option<&str> {
+        self.current.as_deref()
+    }
+    
+    // The level when it changed since the last check, None inside the option for leaving a level. A value that
+    // can't be read, for example halfway through loading, leaves the level as it was.
+    pub fn poll(&mut self, process: &ProcessHandle, base_addr: usize) -> Option<Option<String>> {
+        if self.last_check.is_some_and(|checked| checked.elapsed() < CHECK_INTERVAL) {
+            return None;
+        }
+        self.last_check = Some(Instant::now());
+        
+        let level = process.get_current_level(base_addr, &self.offsets).ok()?;
+        if level == self.current {
+            return None;
+        }
+        self.current = level.clone();
+        Some(level)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn file_name_stays_in_its_directory() {
+        assert_eq!(file_name("foundry"), "foundry");
+        assert_eq!(file_name("levels\\foundry"), "levels_foundry");
+        assert_eq!(file_name("../../windows/system32"), "_.._windows_system32");
+        assert_eq!(file_name(".."), "_");
+        assert_eq!(file_name("c:rio"), "c_rio");
+        assert_eq!(file_name(" school ii. "), "school ii");
+    }
+}
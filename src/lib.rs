@@ -0,0 +1,66 @@
+// Everything the free cam is made of, usable from other tools. The thps3_free_cam binary and the
+// programs in examples/ are built on top of it.
+
+pub mod accessibility;
+pub mod action;
+pub mod bookmark;
+pub mod bounds;
+pub mod camera;
+pub mod cheat_table;
+pub mod chunk;
+pub mod config;
+pub mod control;
+pub mod controller;
+pub mod deadman;
+pub mod convert;
+pub mod display;
+pub mod export;
+pub mod fov;
+pub mod game;
+pub mod gamepad;
+pub mod gesture;
+pub mod guard;
+pub mod history;
+pub mod hotkeys;
+pub mod image;
+pub mod input;
+pub mod instances;
+pub mod internal;
+pub mod interpolation;
+pub mod jitter;
+pub mod level;
+pub mod library;
+pub mod logging;
+pub mod math;
+pub mod midi;
+pub mod numbers;
+pub mod offsets;
+pub mod osc;
+pub mod overlay;
+pub mod palette;
+pub mod panel;
+pub mod patches;
+pub mod path;
+pub mod playback;
+pub mod plugin;
+pub mod probe;
+pub mod process;
+pub mod reload;
+pub mod remote;
+pub mod replay;
+pub mod scan;
+pub mod rumble;
+pub mod schedule;
+pub mod script;
+pub mod screenshot;
+pub mod shake;
+pub mod skater;
+pub mod shutdown;
+pub mod spectate;
+pub mod stats;
+pub mod telemetry;
+pub mod timelapse;
+pub mod title;
+pub mod tunables;
+pub mod voice;
+pub mod waypoint;
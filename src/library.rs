@@ -0,0 +1,134 @@
+// Paths dropped into `path_dir` while the free cam runs - Blender exports, a collaborator's share - become
+// available without a restart. The directory is scanned every second and only new or changed files are read.
+
+use std::path::Path;
+use std::time::SystemTime;
+
+use crate::convert::{read_path, PathFormat};
+use crate::path::CameraPath;
+use crate::reload::{self, CheckInterval};
+
+// Frame rate for .chan files, the same default as `convert --fps`
+const CHAN_FPS: f32 = 60.0;
+
+pub enum LibraryChange {
+    Added(String),
+    Changed(String),
+    Removed(String),
+    Failed(String, String), // File name and why it couldn't be loaded, retried once it changes again
+}
+
+struct LibraryEntry {
+    name: String,
+    modified: SystemTime,
+    path: Option<CameraPath>, // None while the file doesn't load
+}
+
+pub struct PathLibrary {
+    directory: String,
+    entries: Vec<LibraryEntry>, // Sorted by file name
+    selected: Option<String>,
+    interval: CheckInterval,
+}
+
+impl PathLibrary {
+    pub fn new(directory: &str) -> Self {
+        Self { directory: directory.to_string(), entries: Vec::new(), selected: None, interval: CheckInterval::default() }
+    }
+    
+    pub fn directory(&self) -> &str {
+        &self.directory
+    }
+    
+    // Paths that loaded
+    pub fn len(&self) -> usize {
+        self.entries.iter().filter(|entry| entry.path.is_some()).count()
+    }
+    
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    
+    pub fn selected(&self) -> Option<&str> {
+        self.selected.as_deref()
+    }
+    
+    // What changed in the directory since the last scan, nothing until the scan interval has passed
+    pub fn poll(&mut self) -> Vec<LibraryChange> {
+        if !self.interval.due() {
+            return Vec::new();
+        }
+        
+        // A missing directory is the same as an empty one, it may be created later
+        let mut files: Vec<(String, SystemTime)> = std::fs::read_dir(&self.directory)
+            .map(|listing| {
+                listing.filter_map(Result::ok)
+                    .filter_map(|item| {
+                        let name = item.file_name().to_string_lossy().into_owned();
+                        PathFormat::from_extension(&name)?;
+                        Some((name, reload::modified(item.path())?))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        files.sort_by(|a, b| a.0.cmp(&b.0));
+        
+        let mut changes = Vec::new();
+        self.entries.retain(|entry| {
+            let kept = files.iter().any(|(name, _)| *name == entry.name);
+            if !kept && entry.path.is_some() {
+                changes.push(LibraryChange::Removed(entry.name.clone()));
+            }
+            kept
+        });
+        
+        for (name, modified) in files {
+            let existing = self.entries.iter().position(|entry| entry.name == name);
+            if existing.is_some_and(|index| self.entries[index].modified == modified) {
+                continue;
+            }
+            
+            let file = Path::new(&self.directory).join(&name).to_string_lossy().into_owned();
+            let format = PathFormat::from_extension(&name).unwrap_or(PathFormat::Path);
+            let path = match read_path(&file, format, CHAN_FPS) {
+                Ok(path) => {
+                    changes.push(if existing.is_some() { LibraryChange::Changed(name.clone()) } else { LibraryChange::Added(name.clone()) });
+                    Some(path)
+                }
+                Err(e) => {
+                    changes.push(LibraryChange::Failed(name.clone(), e));
+                    None
+                }
+            };
+            
+            let entry = LibraryEntry { name, modified, path };
+            match existing {
+                Some(index) => self.entries[index] = entry,
+                None => {
+                    let index = self.entries.partition_point(|other| other.name < entry.name);
+                    self.entries.insert(index, entry);
+                }
+            }
+        }
+        
+        if self.selected.as_ref().is_some_and(|selected| self.get(selected).is_none()) {
+            self.selected = None;
+        }
+        changes
+    }
+    
+    pub fn get(&self, name: &str) -> Option<&CameraPath> {
+        self.entries.iter().find(|entry| entry.name == name)?.path.as_ref()
+    }
+    
+    // Selects the path after the selected one, wrapping around to the first
+    pub fn select_next(&mut self) -> Option<(&str, &CameraPath)> {
+        let loaded: Vec<&str> = self.entries.iter().filter(|entry| entry.path.is_some()).map(|entry| entry.name.as_str()).collect();
+        let position = self.selected.as_ref().and_then(|selected| loaded.iter().position(|name| name == selected));
+        let next = loaded.get(position.map_or(0, |position| (position + 1) % loaded.len()))?.to_string();
+        
+        let entry = self.entries.iter().find(|entry| entry.name == next)?;
+        self.selected = Some(next);
+        Some((entry.name.as_str(), entry.path.as_ref()?))
+    }
+}
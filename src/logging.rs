@@ -0,0 +1,27 @@
+// Timestamped log of what the free cam does to the game: attaching, patches, camera modes and errors with
+// their Win32 codes. Bug reports can come with thps3_free_cam.log instead of console screenshots, and
+// `--verbose` adds every memory read and write for the hard cases.
+
+use log::LevelFilter;
+
+use crate::telemetry::{format_utc_datetime, unix_millis};
+
+pub const LOG_FILE: &str = "thps3_free_cam.log";
+
+// Appends to `path` for the rest of the run. Dependencies only get to log warnings.
+pub fn init(path: &str, verbose: bool) -> Result<(), String> {
+    let file = fern::log_file(path).map_err(|e| format!("Failed to open log file '{}': {}", path, e))?;
+    fern::Dispatch::new()
+        .format(|out, message, record| {
+            let now = unix_millis();
+            // UTC with milliseconds, 2024-05-01T12:00:00.123Z
+            let datetime = format_utc_datetime(now);
+            out.finish(format_args!("{}.{:03}Z {:<5} {}: {}",
+                                    datetime.trim_end_matches('Z'), now % 1000, record.level(), record.target(), message))
+        })
+        .level(LevelFilter::Warn)
+        .level_for("thps3_free_cam", if verbose { LevelFilter::Trace } else { LevelFilter::Info })
+        .chain(file)
+        .apply()
+        .map_err(|e| format!("Failed to start logging: {}", e))
+}
@@ -1,57 +1,1422 @@
-mod camera;
-mod controller;
-mod input;
-mod process;
+mod cli;
 
-use controller::{CameraController, BasicCameraController};
-use input::{is_key_pressed, VK_M, VK_P};
-use process::{ProcessHandle, CodePatch, list_all_processes};
-use winapi::um::winuser::GetAsyncKeyState;
+use clap::Parser;
+use thps3_free_cam::accessibility::{SwitchControl, SwitchMode};
+use thps3_free_cam::action::Action;
+use thps3_free_cam::bookmark::{BookmarkTransition, Bookmarks};
+use thps3_free_cam::bounds::Bounds;
+use thps3_free_cam::camera::{CameraMatrix, CameraPose, CameraPosition, Quaternion, WriteChannels};
+use thps3_free_cam::cheat_table::load_cheat_table;
+use thps3_free_cam::chunk::{join_chunks, write_chunks};
+use thps3_free_cam::config::{load_config, Config, KeyBindings};
+use thps3_free_cam::control::{ControlRequest, ControlServer, CONTROL_BIND, PIPE_NAME};
+use thps3_free_cam::controller::{CameraController, FovRestore, MovementMode};
+use thps3_free_cam::convert::{read_path, write_path, PathFormat};
+use thps3_free_cam::deadman::{DeadMansEvent, DeadMansSwitch};
+use thps3_free_cam::display::StatusLine;
+use thps3_free_cam::export::{append_shot, copy_to_clipboard, format_transform};
+use thps3_free_cam::game::GAMES;
+use thps3_free_cam::gamepad::GamepadSource;
+use thps3_free_cam::gesture::GestureDetector;
+use thps3_free_cam::guard::PatchGuard;
+use thps3_free_cam::history::CameraHistory;
+use thps3_free_cam::hotkeys::KeyEvents;
+use thps3_free_cam::image::{print_pipeline, run_pipeline, MemoryImage};
+use thps3_free_cam::input::{get_scrub_delta, CameraCommand, MouseWheel, SpaceMouse};
+use thps3_free_cam::internal::HOOK_DLL;
+use thps3_free_cam::interpolation::{blend, InterpolationMode};
+use thps3_free_cam::jitter::Jitter;
+use thps3_free_cam::level::LevelWatch;
+use thps3_free_cam::library::{LibraryChange, PathLibrary};
+use thps3_free_cam::logging::{self, LOG_FILE};
+use thps3_free_cam::midi::MidiController;
+use thps3_free_cam::numbers;
+use thps3_free_cam::offsets::{load_offsets, GameOffsets, PatchOffsets, PointerChain, OFFSETS_FILE};
+use thps3_free_cam::osc::OscListener;
+use thps3_free_cam::instances::Instances;
+use thps3_free_cam::overlay::{Overlay, OverlayState};
+use thps3_free_cam::palette::{self, Palette, PalettePoll};
+use thps3_free_cam::panel::{ControlPanel, PanelCommand, PanelState};
+use thps3_free_cam::playback::{step_rate, PlaybackClock, PlaybackRepeat};
+use thps3_free_cam::plugin::{CameraContext, Plugins};
+use thps3_free_cam::path::{next_version_path, CameraPath, MotionRecording, PathBake, PathMetadata};
+use thps3_free_cam::probe::DepthProbe;
+use thps3_free_cam::reload::FileWatch;
+use thps3_free_cam::patches::{PatchManager, CAMERA_WRITE, FREEZE, HUD};
+use thps3_free_cam::process::{ProcessAccess, ProcessHandle, CodePatch, candidate_processes, find_processes_by_name, list_all_processes};
+use thps3_free_cam::remote::{CameraState, NetworkInput, RemoteAccess, RemoteCommand, RemoteServer};
+use thps3_free_cam::replay::{ReplayEvent, ReplayWatch};
+use thps3_free_cam::rumble::Rumble;
+use thps3_free_cam::scan::{camera_matrix_at, PointerMap, ScanFilter, ValueScan};
+use thps3_free_cam::schedule::{StartEvent, StartTime};
+use thps3_free_cam::script::{ScriptEvent, ScriptRunner, ScriptState, SCRIPT_EXTENSION};
+use thps3_free_cam::screenshot::take_screenshot;
+use thps3_free_cam::shake::Shake;
+use thps3_free_cam::skater::{GameState, GameStateReader, StatsFile};
+use thps3_free_cam::shutdown;
+use thps3_free_cam::spectate::{SpectateFrame, Spectator};
+use thps3_free_cam::stats::SessionStats;
+use thps3_free_cam::telemetry::TelemetryRecorder;
+use thps3_free_cam::timelapse::Timelapse;
+use thps3_free_cam::title::{set_console_title, ConsoleTitle, APP_TITLE};
+use thps3_free_cam::tunables::TunableSet;
+use thps3_free_cam::voice::{VoiceCommand, VoiceCommands};
+use thps3_free_cam::waypoint::{waypoint_file, WaypointTour};
 
-fn main() {
-    println!("THPS3 Free Cam Tool");
-    println!("===================");
+use cli::{Cli, Command, PatchState, PathCommand};
+
+fn update_tunables(tunables: &mut TunableSet, process: &ProcessHandle, base_addr: usize, bindings: &KeyBindings) {
+    match tunables.update(process, base_addr, bindings) {
+        Ok(Some((name, value))) => {
+            print!("\r🎛️ {}: {:.3}   ", name, value);
+            std::io::Write::flush(&mut std::io::stdout()).unwrap();
+        }
+        Ok(None) => {}
+        Err(e) => println!("\n❌ Failed to update tunable: {}", e),
+    }
+}
+
+// Lists the loaded bindings, leaving out the ones that need something this game or mode doesn't have.
+// `patches` names the offsets file patches this game has, see offsets_patches.
+fn print_controls(keys: &KeyBindings, matrix_mode: bool, has_fov: bool, patches: &[&str], has_skater: bool, has_second_skater: bool,
+                  has_tunables: bool) {
+    let table = keys.table();
+    println!("Controls:");
+    for binding in &table {
+        let available = match binding.name {
+            _ if binding.matrix_only && !matrix_mode => false,
+            "freeze_toggle" => patches.contains(&FREEZE),
+            "hud_toggle" => patches.contains(&HUD),
+            "follow_toggle" | "skater_teleport" | "camera_to_skater" => has_skater,
+            "framing_toggle" => has_skater && has_second_skater,
+            "zoom_in" | "zoom_out" | "zoom_reset" => has_fov,
+            "tunable_next" | "tunable_increase" | "tunable_decrease" | "tunable_release" => has_tunables,
+            _ => true,
+        };
+        if !available {
+            continue;
+        }
+        
+        // A key bound twice fires both, which is easy to miss in config.toml
+        let shared: Vec<&str> = table.iter()
+            .filter(|other| other.key == binding.key && other.name != binding.name)
+            .map(|other| other.name)
+            .collect();
+        let warning = if shared.is_empty() { String::new() } else { format!(" ⚠️ also bound to {}", shared.join(", ")) };
+        println!("   {} - {}{}", binding.key.name(), binding.description, warning);
+    }
+}
+
+fn on_off(on: bool) -> String {
+    if on { "on".to_string() } else { "off".to_string() }
+}
+
+// The rest of the help key's cheat sheet, next to the controls
+fn print_states(states: &[(&str, String)]) {
+    println!("Current state:");
+    for (name, state) in states {
+        println!("   {}: {}", name, state);
+    }
+}
+
+fn print_gestures(gestures: &GestureDetector) {
+    let Some(modifier) = gestures.modifier() else { return };
+    println!("Hot corners (hold {} and flick the cursor into the corner, mouse look off):", modifier.name());
+    for (corner, action) in gestures.corners() {
+        println!("   {} - {}", corner, action.name());
+    }
+}
+
+// Lists where a path leaves the level bounds, so it can be fixed before a take is wasted on it
+fn warn_path_bounds(camera_path: &CameraPath, bounds: &Bounds, mode: InterpolationMode) {
+    let violations = bounds.check_path(camera_path, mode);
+    if violations.is_empty() {
+        return;
+    }
+    
+    println!("⚠️ The path leaves the level bounds {} time(s):", violations.len());
+    for violation in &violations {
+        println!("   {:.1}s - {:.1}s: {}", violation.start, violation.end, violation.reason);
+    }
+}
+
+// The bounds the controllers should clamp to, None when they only produce warnings
+fn enforced_bounds(bounds: &Bounds) -> Option<Bounds> {
+    if !bounds.enforce || !bounds.is_set() {
+        return None;
+    }
+    println!("🧱 Camera kept inside the level bounds");
+    Some(bounds.clone())
+}
+
+// Returns whether a screenshot was saved
+fn screenshot(process: &ProcessHandle, base_addr: usize, directory: &str) -> bool {
+    let Some(window) = process.find_main_window() else {
+        println!("\n❌ Screenshot failed: game window not found");
+        return false;
+    };
+    let result = process.get_camera_position(base_addr)
+        .and_then(|position| take_screenshot(window, directory, &position));
+    match result {
+        Ok(path) => {
+            println!("\n📸 Screenshot saved to {}", path);
+            true
+        }
+        Err(e) => {
+            println!("\n❌ Screenshot failed: {}", e);
+            false
+        }
+    }
+}
+
+fn toggle_telemetry(recorder: &mut Option<TelemetryRecorder>) {
+    match recorder.take() {
+        Some(active) => {
+            let log_path = active.log_path().to_string();
+            let chan_path = active.chan_path().map(|path| path.to_string());
+            match active.finish() {
+                Ok(_) => {
+                    println!("\n📼 Telemetry recording stopped - saved to {}", log_path);
+                    if let Some(chan_path) = chan_path {
+                        println!("   🎬 Match-move camera exported to {}", chan_path);
+                    }
+                }
+                Err(e) => println!("\n❌ Failed to finish telemetry recording: {}", e),
+            }
+        }
+        None => match TelemetryRecorder::start("telemetry") {
+            Ok(new_recorder) => {
+                println!("\n📼 Telemetry recording started - {} (sync markers: {})",
+                         new_recorder.log_path(), new_recorder.sync_path());
+                *recorder = Some(new_recorder);
+            }
+            Err(e) => println!("\n❌ Failed to start telemetry recording: {}", e),
+        },
+    }
+}
+
+// Freezes the game simulation while leaving the camera writable, mirroring the camera patch toggle
+fn toggle_freeze(patches: &mut PatchManager) {
+    if !patches.contains(FREEZE) {
+        println!("\n⚠️ Freezing is unavailable - it needs a [freeze] instruction in the offsets file that matches this game");
+        return;
+    }
+    
+    match patches.toggle(FREEZE) {
+        Ok(true) => println!("\n⏸️ Game frozen - the camera can still be moved"),
+        Ok(false) => println!("\n▶️ Game unfrozen"),
+        Err(e) => println!("\n❌ Failed to toggle the freeze: {}", e),
+    }
+}
+
+// Hides the HUD for clean shots, the game keeps running
+fn toggle_hud(patches: &mut PatchManager) {
+    if !patches.contains(HUD) {
+        println!("\n⚠️ Hiding the HUD is unavailable - it needs a [hud] instruction in the offsets file that matches this game");
+        return;
+    }
+    
+    match patches.toggle(HUD) {
+        Ok(true) => println!("\n🙈 HUD hidden"),
+        Ok(false) => println!("\n👁️ HUD shown"),
+        Err(e) => println!("\n❌ Failed to toggle the HUD: {}", e),
+    }
+}
+
+// Without the hook the patch key NOPs the game's camera write
+fn toggle_camera_patch(patches: &mut PatchManager) {
+    match patches.toggle(CAMERA_WRITE) {
+        Ok(true) => println!("\n🔧 Camera patch enabled - free camera active!"),
+        Ok(false) => println!("\n🔧 Camera patch disabled - game will overwrite camera"),
+        Err(e) => println!("\n❌ Failed to toggle the camera patch: {}", e),
+    }
+}
+
+// Toggles the camera patch, or the hook when `hook` is set. A failed toggle buzzes like any other error.
+fn toggle_patch(process: &ProcessHandle, base_addr: usize, patches: &mut PatchManager, rumble: &Rumble, stats: &mut SessionStats, hook: bool) {
+    let was_active = camera_patch_active(process, patches);
+    if hook {
+        toggle_hook(process, base_addr);
+    } else {
+        toggle_camera_patch(patches);
+    }
+    
+    let active = camera_patch_active(process, patches);
+    if active != was_active {
+        rumble.patch_toggled(active);
+        stats.patch_toggled();
+    } else {
+        rumble.error();
+        stats.error();
+    }
+}
+
+// Hands the camera to the game or takes it back, by the write patch or the hook when `hook` is set
+fn set_camera_patch(process: &ProcessHandle, base_addr: usize, patches: &mut PatchManager, active: bool, hook: bool) -> Result<(), String> {
+    if hook {
+        process.set_hook_active(base_addr, active)
+    } else if active {
+        patches.apply(CAMERA_WRITE)
+    } else {
+        patches.restore(CAMERA_WRITE)
+    }
+}
+
+// Acts on the dead man's switch. Taking the camera back waits for gameplay the same way the patch key does.
+fn dead_mans_event(event: DeadMansEvent, process: &ProcessHandle, base_addr: usize, patches: &mut PatchManager, guard: &PatchGuard,
+                   patch_pending: &mut bool, hook: bool) {
+    let reason = match event {
+        DeadMansEvent::Idle(timeout) => format!("No input for {:.0}s", timeout.as_secs_f32()),
+        DeadMansEvent::FocusLost => "The game lost focus".to_string(),
+        DeadMansEvent::Resume => {
+            if let Some(reason) = guard.blocker(process, base_addr) {
+                println!("\n⏳ The camera patch waits until gameplay is running: {}", reason);
+                *patch_pending = true;
+                return;
+            }
+            match set_camera_patch(process, base_addr, patches, true, hook) {
+                Ok(()) => println!("\n🔧 Input again - free camera active"),
+                Err(e) => println!("\n❌ Failed to take the camera back: {}", e),
+            }
+            return;
+        }
+    };
+    match set_camera_patch(process, base_addr, patches, false, hook) {
+        Ok(()) => {
+            println!("\n💤 {} - the game has its camera back until you move again", reason);
+            log::info!("{} - camera patch released", reason);
+        }
+        Err(e) => println!("\n❌ Failed to hand the camera back: {}", e),
+    }
+}
+
+// Why turning the camera patch on has to wait for gameplay, None when it can be toggled right away
+fn patch_blocker(process: &ProcessHandle, base_addr: usize, patches: &PatchManager, guard: &PatchGuard) -> Option<String> {
+    if camera_patch_active(process, patches) {
+        return None;
+    }
+    guard.blocker(process, base_addr)
+}
+
+// The patches a session can toggle. A freeze or HUD instruction that isn't found only leaves that patch unavailable.
+fn game_patches<'a>(process: &'a ProcessHandle, base_addr: usize, offsets: &GameOffsets) -> PatchManager<'a> {
+    let mut patches = PatchManager::new(process);
+    match process.get_camera_write_patch_address(base_addr) {
+        Ok(patch_addr) => patches.add(CAMERA_WRITE, patch_addr, &process.game().camera_write().original_bytes),
+        Err(e) => println!("⚠️ {} - the camera patch is unavailable", e),
+    }
+    add_offsets_patch(process, base_addr, &mut patches, FREEZE, "freeze", offsets.freeze.as_ref());
+    add_offsets_patch(process, base_addr, &mut patches, HUD, "hud", offsets.hud.as_ref());
+    patches
+}
+
+// The offsets file patches in `offsets`, named like in PatchManager
+fn offsets_patches(offsets: &GameOffsets) -> Vec<&'static str> {
+    [(FREEZE, offsets.freeze.is_some()), (HUD, offsets.hud.is_some())].into_iter()
+        .filter_map(|(name, known)| known.then_some(name))
+        .collect()
+}
+
+// `table` is where the patch lives in the offsets file
+fn add_offsets_patch(process: &ProcessHandle, base_addr: usize, patches: &mut PatchManager, name: &str, table: &str, patch: Option<&PatchOffsets>) {
+    if let Some(patch) = patch {
+        match process.get_offsets_patch_address(base_addr, table, patch) {
+            Ok(patch_addr) => patches.add(name, patch_addr, &patch.original_bytes),
+            Err(e) => println!("⚠️ {} - the {} patch is unavailable", e, name),
+        }
+    }
+}
+
+// Registers a freeze or HUD patch again after the offsets file moved or removed it. An applied one is restored
+// first, the old address may not be that instruction any more.
+fn replace_offsets_patch(process: &ProcessHandle, base_addr: usize, patches: &mut PatchManager, name: &str, table: &str,
+                         patch: Option<&PatchOffsets>) {
+    if patches.remove(name) {
+        println!("\n🔧 The {} patch changed - the game's instruction is back", name);
+    }
+    add_offsets_patch(process, base_addr, patches, name, table, patch);
+}
+
+// Chains from the offsets file that the controller follows itself
+fn set_controller_offsets(controller: &mut CameraController, offsets: &GameOffsets) {
+    controller.set_fov_offsets(offsets.fov.clone());
+    controller.set_skater_chain(offsets.skater.clone());
+    controller.set_second_skater_chain(offsets.skater2.clone());
+    controller.set_skater_matrix_chain(offsets.skater_matrix.clone());
+}
+
+// Settings the controller copied at startup. Speed and sensitivity only change when the edit changed them, so
+// an unrelated edit doesn't undo what was adjusted while flying.
+fn apply_config(controller: &mut CameraController, old: &Config, new: &Config) {
+    controller.set_bindings(new.keys.clone());
+    let speeds = if controller.is_position_only() { (old.basic_move_speed, new.basic_move_speed) } else { (old.move_speed, new.move_speed) };
+    if speeds.1 != speeds.0 {
+        controller.set_speed(speeds.1);
+    }
+    if new.mouse_sensitivity != old.mouse_sensitivity {
+        controller.set_mouse_sensitivity(new.mouse_sensitivity);
+    }
+    controller.set_smoothing(new.acceleration, new.deceleration);
+    controller.set_axis_scales(new.horizontal_speed_scale, new.vertical_speed_scale);
+    controller.set_speed_modifiers(new.sprint_multiplier, new.slow_multiplier);
+    controller.set_switch_turn_rate(new.switch_turn_rate);
+    controller.set_write_channels(new.write_channels);
+    controller.set_bounds(enforced_bounds(&new.bounds));
+    let jitter_changed = (new.jitter_position, new.jitter_rotation, new.jitter_frequency) != (old.jitter_position, old.jitter_rotation, old.jitter_frequency);
+    if controller.is_jittering() && jitter_changed {
+        controller.set_jitter(Some(Jitter::new(new.jitter_position, new.jitter_rotation, new.jitter_frequency)));
+    }
+    let shake_changed = (new.shake_position, new.shake_rotation, new.shake_frequency) != (old.shake_position, old.shake_rotation, old.shake_frequency);
+    if controller.is_shaking() && shake_changed {
+        controller.set_shake(Some(Shake::new(new.shake_position, new.shake_rotation, new.shake_frequency)));
+    }
+}
+
+// Tick length while nothing needs the camera, None when adaptive polling is off
+fn idle_interval(config: &Config) -> Option<std::time::Duration> {
+    (config.idle_poll_interval_ms > config.poll_interval_ms).then(|| std::time::Duration::from_millis(config.idle_poll_interval_ms))
+}
+
+// Reads a watched file again after it was saved. A broken edit or a deleted file keeps the `kept` from before.
+fn reload<T>(watch: &FileWatch, load: fn(&str) -> Result<T, String>, kept: &str) -> Option<T> {
+    match watch.reload(load) {
+        Ok(loaded) => {
+            println!("\n🔄 Reloaded {}", watch.path());
+            log::info!("Reloaded {}", watch.path());
+            Some(loaded)
+        }
+        Err(e) => {
+            println!("\n⚠️ {} - keeping the {} from before", e, kept);
+            log::warn!("Reloading {} failed: {}", watch.path(), e);
+            None
+        }
+    }
+}
+
+// Warps the skater to just below the camera, to scout a spot with the free cam and then skate from there
+fn teleport_skater(process: &ProcessHandle, base_addr: usize, skater: Option<&PointerChain>, height: f32) {
+    let Some(chain) = skater else {
+        println!("\n⚠️ No [skater] pointer chain in the offsets file - teleporting is unavailable");
+        return;
+    };
+    
+    let result = process.get_camera_position(base_addr).and_then(|camera| {
+        let target = CameraPosition::new(camera.x, camera.y - height, camera.z);
+        process.set_skater_position(base_addr, chain, &target).map(|_| target)
+    });
+    match result {
+        Ok(target) => println!("\n🛹 Skater teleported to X:{:.1}, Y:{:.1}, Z:{:.1}", target.x, target.y, target.z),
+        Err(e) => println!("\n❌ Failed to teleport the skater: {}", e),
+    }
+}
+
+// Position-only version of the controller's reset_to_skater, for when the matrix can't be found
+fn snap_camera_to_skater(process: &ProcessHandle, base_addr: usize, skater: Option<&PointerChain>, height: f32) {
+    let Some(chain) = skater else {
+        println!("\n⚠️ No [skater] pointer chain in the offsets file - can't find the skater");
+        return;
+    };
     
+    let result = process.get_skater_position(base_addr, chain).and_then(|skater| {
+        let target = CameraPosition::new(skater.x, skater.y + height, skater.z);
+        process.set_camera_position(base_addr, &target).map(|_| target)
+    });
+    match result {
+        Ok(target) => println!("\n📍 Camera snapped to the skater at X:{:.1}, Y:{:.1}, Z:{:.1}", target.x, target.y, target.z),
+        Err(e) => println!("\n❌ Failed to move the camera to the skater: {}", e),
+    }
+}
+
+// One playback tick with the manual corrections layered on top of the path, recorded for baking
+fn bake_tick(process: &ProcessHandle, base_addr: usize, controller: &mut CameraController, camera_path: &CameraPath,
+             bake: &mut PathBake, elapsed: f32, mode: InterpolationMode) -> Result<(), String> {
+    let path_pose = match camera_path.sample(elapsed, mode) {
+        Some(pose) => pose,
+        None => return Ok(()),
+    };
+    
+    let mut matrix = controller.read_camera_matrix(process, base_addr)?;
+    matrix.set_pose(&bake.corrected(&path_pose));
+    process.set_camera_matrix(base_addr, &matrix)?;
+    
+    // Let the controller nudge the camera from wherever the corrected path put it
+    controller.resync();
+    controller.update_camera(process, base_addr)?;
+    let matrix = controller.read_camera_matrix(process, base_addr)?;
+    bake.update_correction(&path_pose, &matrix.pose());
+    bake.record(elapsed, &matrix);
+    Ok(())
+}
+
+// Falls back to external writes when the hook can't be used, the free cam works either way
+fn attach_hook(process: &mut ProcessHandle) {
+    let dll_path = std::env::current_exe()
+        .map(|exe| exe.with_file_name(HOOK_DLL))
+        .unwrap_or_else(|_| HOOK_DLL.into());
+    
+    match process.attach_hook(&dll_path.to_string_lossy()) {
+        Ok(internal) => {
+            println!("🪝 Internal hook attached at 0x{:X}", internal.hook_address().unwrap_or(0));
+            log::info!("Internal hook attached at 0x{:X}", internal.hook_address().unwrap_or(0));
+            
+            // The hook only runs while the game updates the camera, so a stuck counter is worth a hint
+            let frames = internal.frames();
+            std::thread::sleep(std::time::Duration::from_millis(250));
+            if internal.frames() == frames {
+                println!("   ⚠️ No camera updates seen yet - the hook starts working once the game is in a level");
+            }
+        }
+        Err(e) => {
+            println!("⚠️ {} - using external camera writes", e);
+            log::warn!("Internal hook not attached: {}", e);
+        }
+    }
+}
+
+// Whether the game is kept off the camera, by the write patch or the internal hook
+fn camera_patch_active(process: &ProcessHandle, patches: &PatchManager) -> bool {
+    patches.is_applied(CAMERA_WRITE) || process.internal().is_some_and(|hook| hook.is_active())
+}
+
+// Carries out a remote pose or FOV command. Actions and speed changes are left to the caller.
+fn remote_write(process: &ProcessHandle, base_addr: usize, controller: &mut CameraController, command: RemoteCommand) -> Result<(), String> {
+    match command {
+        RemoteCommand::SetPosition { x, y, z } => controller.read_camera_matrix(process, base_addr)
+            .and_then(|mut matrix| {
+                matrix.set_position(&CameraPosition::new(x, y, z));
+                controller.write_camera_matrix(process, base_addr, &matrix)
+            }),
+        RemoteCommand::SetMatrix { matrix } => controller.write_camera_matrix(process, base_addr, &CameraMatrix { data: matrix }),
+        RemoteCommand::SetRotation { rotation: [x, y, z, w] } => controller.read_camera_matrix(process, base_addr)
+            .and_then(|mut matrix| {
+                matrix.set_rotation(&Quaternion::new(x, y, z, w));
+                controller.write_camera_matrix(process, base_addr, &matrix)
+            }),
+        RemoteCommand::SetPose { position: [x, y, z], rotation: [qx, qy, qz, qw] } => {
+            let pose = CameraPose::new(CameraPosition::new(x, y, z), Quaternion::new(qx, qy, qz, qw));
+            controller.write_pose(process, base_addr, &pose)
+        }
+        RemoteCommand::SetFov { fov } => controller.set_horizontal_fov(process, base_addr, fov),
+        RemoteCommand::SetSpeed { .. } | RemoteCommand::Action { .. } => Ok(()),
+        RemoteCommand::Move { .. } | RemoteCommand::Turn { .. } => Err("moving and turning need the interactive free cam".to_string()),
+    }
+}
+
+// Who may use the remote control and the spectate stream
+fn remote_access(config: &Config) -> RemoteAccess {
+    RemoteAccess { token: config.remote_token.clone(), origins: config.remote_origins.clone() }
+}
+
+// What remote clients and daemon frontends read
+fn camera_state(process: &ProcessHandle, base_addr: usize, controller: &CameraController, patches: &PatchManager,
+                stats: Option<GameState>) -> Result<CameraState, String> {
+    let matrix = controller.read_camera_matrix(process, base_addr)?;
+    let CameraPose { position, rotation } = matrix.pose();
+    Ok(CameraState {
+        position: [position.x, position.y, position.z],
+        rotation: [rotation.x, rotation.y, rotation.z, rotation.w],
+        matrix: matrix.data.to_vec(),
+        speed: controller.get_speed(),
+        patch: camera_patch_active(process, patches),
+        fov: controller.get_horizontal_fov(),
+        stats,
+    })
+}
+
+// What a running script sees of the camera and the skater
+fn script_state(process: &ProcessHandle, base_addr: usize, controller: &CameraController, patches: &PatchManager,
+                skater: Option<&PointerChain>) -> Result<ScriptState, String> {
+    Ok(ScriptState {
+        matrix: controller.read_camera_matrix(process, base_addr)?,
+        patch: camera_patch_active(process, patches),
+        fov: controller.get_horizontal_fov(),
+        skater: skater.and_then(|chain| process.get_skater_position(base_addr, chain).ok()),
+    })
+}
+
+// Console title while attached: process, patch state and where the camera is
+fn attached_title(process: &ProcessHandle, base_addr: usize, patches: &PatchManager, held: bool) -> String {
+    let patch = if camera_patch_active(process, patches) { "patch ON" } else { "patch OFF" };
+    let held = if held { " | HELD" } else { "" };
+    let position = match process.get_camera_position(base_addr) {
+        Ok(position) => format!("X:{:.0} Y:{:.0} Z:{:.0}", position.x, position.y, position.z),
+        Err(_) => "camera unreadable".to_string(),
+    };
+    format!("{} | PID {} | {}{} | {}", APP_TITLE, process.pid(), patch, held, position)
+}
+
+// Leaves the game as it was found: camera back to the game, game unfrozen. Dropping the patches would
+// restore them too, this says what happened.
+fn restore_game(process: &ProcessHandle, base_addr: usize, patches: &mut PatchManager) {
+    if let Some(internal) = process.internal() {
+        internal.set_osd("");
+    }
+    if process.internal().is_some_and(|internal| internal.is_active()) {
+        match process.set_hook_active(base_addr, false) {
+            Ok(()) => println!("🪝 Internal hook released"),
+            Err(e) => println!("❌ Failed to release the internal hook: {}", e),
+        }
+    }
+    if patches.is_applied(CAMERA_WRITE) {
+        match patches.restore(CAMERA_WRITE) {
+            Ok(()) => println!("🔧 Camera patch disabled"),
+            Err(e) => println!("❌ Failed to disable the camera patch: {}", e),
+        }
+    }
+    
+    if patches.is_applied(HUD) {
+        match patches.restore(HUD) {
+            Ok(()) => println!("👁️ HUD shown"),
+            Err(e) => println!("❌ Failed to show the HUD: {}", e),
+        }
+    }
+    
+    // Never leave the game frozen behind
+    if patches.is_applied(FREEZE) {
+        match patches.restore(FREEZE) {
+            Ok(()) => println!("▶️ Game unfrozen"),
+            Err(e) => println!("❌ Failed to unfreeze the game: {}", e),
+        }
+    }
+}
+
+// With the hook attached the patch key hands the camera between the game and the hook instead of NOPing
+fn toggle_hook(process: &ProcessHandle, base_addr: usize) {
+    let active = process.internal().is_some_and(|internal| internal.is_active());
+    match process.set_hook_active(base_addr, !active) {
+        Ok(()) if active => println!("\n🪝 Internal hook released - game will overwrite camera"),
+        Ok(()) => println!("\n🪝 Internal hook engaged - free camera applied every frame!"),
+        Err(e) => println!("\n❌ Failed to toggle internal hook: {}", e),
+    }
+}
+
+fn find_game_process(access: ProcessAccess) -> Option<ProcessHandle> {
     // First, let's see what processes are running
-    println!("🔍 Scanning for Tony Hawk Pro Skater 3 process...");
+    set_console_title(&format!("{} | searching for THPS3", APP_TITLE));
+    println!("🔍 Scanning for a supported game process...");
     if let Err(e) = list_all_processes() {
         println!("❌ Failed to list processes: {}", e);
     }
     
-    // Try to find and attach to Skate3 process
-    let process_names = vec!["skate3.exe", "Skate3.exe", "SKATE3.EXE"];
-    let mut process_handle = None;
+    // Try to find and attach to each supported game, process names are matched case-insensitively
+    for game in GAMES {
+        match ProcessHandle::new(game.process_name(), access) {
+            Ok(handle) => return Some(handle),
+            Err(e) => {
+                println!("Could not find process '{}': {}", game.process_name(), e);
+            }
+        }
+    }
     
-    for name in process_names {
-        match ProcessHandle::new(name) {
-            Ok(handle) => {
-                process_handle = Some(handle);
-                break;
+    set_console_title(&format!("{} | not attached", APP_TITLE));
+    println!("❌ Could not attach to a game process!");
+    println!("💡 Renamed the game executable? `thps3_free_cam attach` lets you pick the process from a list");
+    println!("This is likely due to insufficient privileges.");
+    println!("💡 Try running this program as Administrator:");
+    println!("   1. Right-click on PowerShell/Command Prompt");
+    println!("   2. Select 'Run as administrator'");
+    println!("   3. Navigate to the project folder and run: cargo run");
+    println!("   4. Make sure THPS3 is running before starting this tool");
+    None
+}
+
+// Lists the processes that look like the game and asks which one to attach to, by number or by any PID
+fn pick_process(access: ProcessAccess) -> Option<ProcessHandle> {
+    set_console_title(&format!("{} | pick the game process", APP_TITLE));
+    let candidates = match candidate_processes() {
+        Ok(candidates) => candidates,
+        Err(e) => {
+            println!("❌ Failed to list processes: {}", e);
+            Vec::new()
+        }
+    };
+    
+    if candidates.is_empty() {
+        println!("🔍 Nothing running looks like the game - type its PID from Task Manager");
+    } else {
+        println!("🔍 Processes that look like the game:");
+        for (index, candidate) in candidates.iter().enumerate() {
+            println!("   {}. {} (PID {})", index + 1, candidate.name, candidate.pid);
+            if let Some(title) = &candidate.window_title {
+                println!("      Window: {}", title);
+            }
+            if let Some(path) = &candidate.path {
+                println!("      {}", path);
+            }
+        }
+    }
+    println!("Type a number from the list or any PID, Enter on an empty line cancels");
+    
+    loop {
+        let line = palette::read_line()?;
+        if line.is_empty() {
+            return None;
+        }
+        let Ok(number) = line.parse::<u32>() else {
+            println!("   '{}' is not a number", line);
+            continue;
+        };
+        // Small numbers are places in the list, PIDs are much larger
+        let pid = number.checked_sub(1)
+            .and_then(|index| candidates.get(index as usize))
+            .map_or(number, |candidate| candidate.pid);
+        match ProcessHandle::from_pid(pid, access) {
+            Ok(process) => return Some(process),
+            Err(e) => println!("   ❌ {}", e),
+        }
+    }
+}
+
+// Runs headless for a frontend: answers JSON line requests on daemon_port and the named pipe until a quit request
+// or Ctrl+C. The keyboard isn't read, everything goes through the requests.
+fn daemon(mut process: ProcessHandle, offsets_path: &str, config: &Config, internal: bool) -> Result<(), String> {
+    let _session = shutdown::session();
+    if internal {
+        attach_hook(&mut process);
+    }
+    let base_addr = process.get_base_address()?;
+    let game_offsets = match load_offsets(offsets_path) {
+        Ok(offsets) => offsets,
+        Err(e) => {
+            println!("⚠️ {} - continuing without extra offsets", e);
+            Default::default()
+        }
+    };
+    if let Some(camera) = &game_offsets.camera {
+        process.set_camera_chain(camera.clone());
+    }
+    let mut controller = CameraController::new(config.move_speed, config.mouse_sensitivity, config.keys.clone());
+    set_controller_offsets(&mut controller, &game_offsets);
+    controller.set_bounds(enforced_bounds(&config.bounds));
+    let mut game_state = GameStateReader::new(game_offsets.stats.clone());
+    let mut patches = game_patches(&process, base_addr, &game_offsets);
+    let mut fov_restore = FovRestore::new(&process, base_addr);
+    let hook = process.internal().is_some();
+    let server = ControlServer::start(config.daemon_port)?;
+    let pipe = match ControlServer::start_pipe(PIPE_NAME) {
+        Ok(pipe) => Some(pipe),
+        Err(e) => {
+            println!("⚠️ {} - continuing without the pipe", e);
+            None
+        }
+    };
+    
+    println!("🛰️ Daemon attached to {} (PID {}), taking requests on {}:{} - a quit request or Ctrl+C stops it",
+             process.game().name(), process.pid(), CONTROL_BIND, config.daemon_port);
+    log::info!("Daemon on {}:{} for PID {}", CONTROL_BIND, config.daemon_port, process.pid());
+    let interval = std::time::Duration::from_millis(config.poll_interval_ms.max(1));
+    let mut quit = false;
+    while !quit && !shutdown::requested() {
+        for pending in server.poll().into_iter().chain(pipe.iter().flat_map(ControlServer::poll)) {
+            let result = match &pending.request {
+                ControlRequest::Quit => {
+                    quit = true;
+                    Ok(None)
+                }
+                ControlRequest::GetCamera => {
+                    let stats = (!game_state.is_empty()).then(|| game_state.read(&process, base_addr));
+                    camera_state(&process, base_addr, &controller, &patches, stats)
+                        .and_then(|state| serde_json::to_value(state).map_err(|e| e.to_string()))
+                        .map(Some)
+                }
+                ControlRequest::Patch { on } => set_camera_patch(&process, base_addr, &mut patches, *on, hook).map(|()| None),
+                ControlRequest::Remote(RemoteCommand::SetSpeed { speed }) => {
+                    controller.set_speed(*speed);
+                    Ok(None)
+                }
+                ControlRequest::Remote(RemoteCommand::Action { action: Action::PatchToggle }) => {
+                    let active = camera_patch_active(&process, &patches);
+                    set_camera_patch(&process, base_addr, &mut patches, !active, hook).map(|()| None)
+                }
+                ControlRequest::Remote(RemoteCommand::Action { action: Action::FreezeToggle }) => patches.toggle(FREEZE).map(|_| None),
+                ControlRequest::Remote(RemoteCommand::Action { action: Action::HudToggle }) => patches.toggle(HUD).map(|_| None),
+                ControlRequest::Remote(RemoteCommand::Action { action }) => Err(format!("{} needs the interactive free cam", action.name())),
+                ControlRequest::Remote(command) => remote_write(&process, base_addr, &mut controller, command.clone()).map(|()| None),
+            };
+            if let Err(e) = &result {
+                log::warn!("Daemon request {:?} failed: {}", pending.request, e);
+            }
+            pending.answer(result);
+        }
+        fov_restore.track(&controller);
+        std::thread::sleep(interval);
+    }
+    
+    println!("🛑 Stopping - handing the camera back to the game");
+    if let Err(e) = controller.restore_fov(&process, base_addr) {
+        println!("❌ Failed to restore the FOV: {}", e);
+    }
+    fov_restore.track(&controller);
+    restore_game(&process, base_addr, &mut patches);
+    drop(server);
+    drop(pipe);
+    Ok(())
+}
+
+fn wait_for_enter() {
+    println!("\nPress Enter to exit...");
+    let mut input = String::new();
+    std::io::stdin().read_line(&mut input).unwrap();
+}
+
+// Reads the camera and skater `rate` times a second until Ctrl+C. Only ever reads: no patch, no hook, no writes.
+fn spectate(process: &ProcessHandle, offsets_path: &str, rate: f32, port: Option<u16>, config: &Config, quiet: bool) -> Result<(), String> {
+    if !(rate > 0.0 && rate <= 1000.0) {
+        return Err("--rate must be more than 0 and at most 1000 readings a second".to_string());
+    }
+    let base_addr = process.get_base_address()?;
+    let game_offsets = load_offsets(offsets_path)?;
+    if let Some(camera) = &game_offsets.camera {
+        process.set_camera_chain(camera.clone());
+    }
+    let mut spectator = Spectator::new(&game_offsets);
+    let server = match port {
+        Some(port) => {
+            let server = RemoteServer::<SpectateFrame>::start_read_only(&config.remote_bind, port, remote_access(config))?;
+            println!("🌐 Streaming readings on ws://{}:{} (read-only)", config.remote_bind, port);
+            Some(server)
+        }
+        None => None,
+    };
+    let mut stats_file = (!config.stats_file.is_empty()).then(|| StatsFile::new(&config.stats_file));
+    if stats_file.is_some() && GameStateReader::new(game_offsets.stats.clone()).is_empty() {
+        println!("⚠️ stats_file is set but the offsets file has no [stats] chains");
+    }
+    
+    let _session = shutdown::session();
+    println!("👀 Spectating {} at {} readings a second without writing to it - Ctrl+C to stop", process.game().name(), rate);
+    log::info!("Spectating PID {} at {}/s", process.pid(), rate);
+    let interval = std::time::Duration::from_secs_f32(1.0 / rate);
+    let mut failing = false;
+    while !shutdown::requested() {
+        let started = std::time::Instant::now();
+        match spectator.read(process, base_addr) {
+            Ok(frame) => {
+                failing = false;
+                if !quiet {
+                    println!("{}", serde_json::to_string(&frame).map_err(|e| e.to_string())?);
+                }
+                if let (Some(file), Some(stats)) = (&mut stats_file, &frame.stats)
+                    && let Some(e) = file.write(stats)
+                {
+                    println!("⚠️ {}", e);
+                }
+                if let Some(server) = &server {
+                    server.publish(frame);
+                }
             }
+            // Loading screens break the camera chain for a moment, so each failure is reported once
             Err(e) => {
-                println!("Could not find process '{}': {}", name, e);
+                if !failing {
+                    println!("⚠️ {}", e);
+                    log::warn!("Spectating: {}", e);
+                }
+                failing = true;
             }
         }
+        std::thread::sleep(interval.saturating_sub(started.elapsed()));
     }
+    drop(server);
+    Ok(())
+}
+
+fn dump_matrix(process: &ProcessHandle) -> Result<(), String> {
+    let base_addr = process.get_base_address()?;
+    let cam_pos = process.get_camera_position(base_addr)?;
+    println!("📍 Camera position: X:{:.6}, Y:{:.6}, Z:{:.6}", cam_pos.x, cam_pos.y, cam_pos.z);
     
-    let process = match process_handle {
-        Some(p) => p,
-        None => {
-            println!("❌ Could not attach to THPS3 process!");
-            println!("This is likely due to insufficient privileges.");
-            println!("💡 Try running this program as Administrator:");
-            println!("   1. Right-click on PowerShell/Command Prompt");
-            println!("   2. Select 'Run as administrator'");
-            println!("   3. Navigate to the project folder and run: cargo run");
-            println!("   4. Make sure THPS3 is running before starting this tool");
-            println!("\nPress Enter to exit...");
-            let mut input = String::new();
-            std::io::stdin().read_line(&mut input).unwrap();
-            return;
+    let cam_matrix = process.get_camera_matrix(base_addr)?;
+    println!("🎮 Camera matrix:");
+    for row in cam_matrix.data.chunks(4) {
+        println!("   [{:>12.6} {:>12.6} {:>12.6} {:>12.6}]", row[0], row[1], row[2], row[3]);
+    }
+    Ok(())
+}
+
+// Follows the camera chain and the chains from the offsets file once a second and prints every pointer on the
+// way, so a read that fails in a menu or on another game build shows which step went wrong
+fn diagnose(process: &ProcessHandle, offsets_path: &str) -> Result<(), String> {
+    let base_addr = process.get_base_address()?;
+    let game_offsets = load_offsets(offsets_path)?;
+    if let Some(camera) = &game_offsets.camera {
+        process.set_camera_chain(camera.clone());
+    }
+    
+    let mut chains = vec![("camera matrix".to_string(), process.camera_chain())];
+    let named = [
+        ("fov", game_offsets.fov.as_ref().map(|fov| &fov.chain)),
+        ("skater", game_offsets.skater.as_ref()),
+        ("skater2", game_offsets.skater2.as_ref()),
+        ("skater_matrix", game_offsets.skater_matrix.as_ref()),
+        ("game_state", game_offsets.game_state.as_ref().map(|state| &state.chain)),
+        ("replay", game_offsets.replay.as_ref().map(|replay| &replay.chain)),
+        ("level", game_offsets.level.as_ref().map(|level| &level.chain)),
+        ("stats.speed", game_offsets.stats.speed.as_ref()),
+        ("stats.air", game_offsets.stats.air.as_ref()),
+        ("stats.grind_balance", game_offsets.stats.grind_balance.as_ref()),
+        ("stats.manual_balance", game_offsets.stats.manual_balance.as_ref()),
+        ("stats.score", game_offsets.stats.score.as_ref()),
+        ("stats.multiplier", game_offsets.stats.multiplier.as_ref()),
+        ("stats.special", game_offsets.stats.special.as_ref()),
+    ];
+    for (name, chain) in named {
+        if let Some(chain) = chain {
+            chains.push((name.to_string(), chain.clone()));
         }
+    }
+    for tunable in &game_offsets.tunables {
+        chains.push((format!("tunable {}", tunable.name), tunable.chain.clone()));
+    }
+    
+    let _session = shutdown::session();
+    println!("🩺 Module base 0x{:08X}, tracing {} chain(s) once a second - Ctrl+C to stop", base_addr, chains.len());
+    let mut reads = 0;
+    while !shutdown::requested() {
+        let started = std::time::Instant::now();
+        reads += 1;
+        println!("\n── Read {} ──", reads);
+        for (name, chain) in &chains {
+            print_chain_trace(process, base_addr, name, chain);
+        }
+        
+        match process.get_camera_matrix(base_addr) {
+            Ok(matrix) => {
+                println!("🎮 Camera matrix:");
+                for row in matrix.data.chunks(4) {
+                    println!("   [{:>12.6} {:>12.6} {:>12.6} {:>12.6}]", row[0], row[1], row[2], row[3]);
+                }
+                // The rotation rows of a real camera matrix are unit length, anything else isn't the camera
+                let lengths = matrix.rotation_row_lengths();
+                if matrix.looks_like_camera() {
+                    println!("   ✅ Rotation rows are unit length");
+                } else {
+                    println!("   ⚠️ Rotation row lengths {:.3} {:.3} {:.3} - this doesn't look like a camera matrix", lengths[0], lengths[1], lengths[2]);
+                }
+            }
+            Err(e) => println!("❌ Camera matrix: {}", e),
+        }
+        
+        while !shutdown::requested() && started.elapsed() < std::time::Duration::from_secs(1) {
+            std::thread::sleep(std::time::Duration::from_millis(50));
+        }
+    }
+    Ok(())
+}
+
+// Chains shared as a Cheat Engine table, ready to paste under a table like [camera] or [[tunable]]
+fn import_table(file: &str) -> Result<(), String> {
+    let (entries, skipped) = load_cheat_table(file)?;
+    for e in skipped {
+        println!("⚠️ Skipped {}", e);
+    }
+    if entries.is_empty() {
+        return Err(format!("No pointer chains from the game's executable in '{}'", file));
+    }
+    
+    for entry in &entries {
+        let offsets: Vec<String> = entry.chain.offsets.iter().map(|offset| format!("0x{:X}", offset)).collect();
+        println!("\n# {}", entry.description);
+        println!("base = 0x{:08X}", entry.chain.base);
+        println!("offsets = [{}]", offsets.join(", "));
+    }
+    Ok(())
+}
+
+// Runs the attach pipeline once against the game and saves the memory it read, with the offsets file next to it
+fn record_image(process: &ProcessHandle, offsets_path: &str, output: &str) -> Result<(), String> {
+    let game_offsets = load_offsets(offsets_path)?;
+    process.start_recording();
+    let steps = run_pipeline(process, &game_offsets);
+    let recorded = process.take_recorded();
+    println!("🩺 Pipeline on {}:", process.game().name());
+    print_pipeline(&steps);
+    if steps.iter().any(|step| step.result.is_err()) {
+        return Err("Not recording a pipeline that fails - load into a level, or fix the offsets first".to_string());
+    }
+    
+    let image = MemoryImage::capture(process, process.get_base_address()?, &recorded)?;
+    image.save(output)?;
+    println!("💾 Saved {} page(s) of memory to {}", image.len(), output);
+    Ok(())
+}
+
+// The scan lists its candidates once there are this few, and prints at most this many pointer chains
+const SCAN_LIST_LENGTH: usize = 10;
+const SCAN_CHAIN_LIMIT: usize = 20;
+
+// A value typed with two decimals may be off by up to 0.01 from the float in memory, whether the game or
+// trainer showing it rounded or cut off the rest
+fn typed_tolerance(text: &str) -> f32 {
+    1.0 / 10f32.powi(numbers::decimals(text) as i32)
+}
+
+// Finds the camera position by value, then pointer chains from the executable to the matrix around it
+fn scan_camera(process: &ProcessHandle, depth: usize, max_offset: usize) -> Result<(), String> {
+    let base_addr = process.get_base_address()?;
+    println!("🔎 Camera scan - for game builds where the built-in camera chain doesn't resolve");
+    println!("Type the camera's X, Y or Z as the game or a trainer shows it, or nothing to start from every value:");
+    let first = palette::read_line().ok_or("No input")?;
+    let mut scan = if first.is_empty() {
+        ValueScan::unknown(process)
+    } else {
+        let value = numbers::parse_number(&first)?;
+        ValueScan::exact(process, value, typed_tolerance(&first))
     };
     
-    println!("✅ Successfully attached to THPS3!");
+    loop {
+        println!("\n{} candidate(s)", scan.len());
+        if scan.is_empty() {
+            return Err("No candidates left - start over and keep the camera still between scans unless asked to move it".to_string());
+        }
+        if scan.len() <= SCAN_LIST_LENGTH {
+            for (i, &(address, value)) in scan.candidates().iter().enumerate() {
+                match camera_matrix_at(process, address) {
+                    Some((matrix, index)) => println!("   [{}] 0x{:08X} = {:.3}, float {} of a camera matrix at 0x{:08X}", i + 1, address, value, index, matrix),
+                    None => println!("   [{}] 0x{:08X} = {:.3}", i + 1, address, value),
+                }
+            }
+        }
+        println!("Move the camera or keep it still, then type = <value>, c (changed), u (unchanged), + (increased), - (decreased),");
+        println!("p <n> to find pointers to candidate n, or q to quit:");
+        
+        let line = palette::read_line().ok_or("No input")?;
+        let filter = match line.as_str() {
+            "q" => return Ok(()),
+            "c" => ScanFilter::Changed,
+            "u" => ScanFilter::Unchanged,
+            "+" => ScanFilter::Increased,
+            "-" => ScanFilter::Decreased,
+            _ if line.starts_with('=') => {
+                let text = line[1..].trim();
+                match numbers::parse_number(text) {
+                    Ok(value) => ScanFilter::Equals(value, typed_tolerance(text)),
+                    Err(e) => {
+                        println!("⚠️ {}", e);
+                        continue;
+                    }
+                }
+            }
+            _ if line.starts_with('p') => {
+                match line[1..].trim().parse::<usize>().ok().and_then(|n| scan.candidates().get(n.wrapping_sub(1))) {
+                    Some(&(address, _)) => print_camera_chains(process, base_addr, address, depth, max_offset),
+                    None => println!("⚠️ Pick one of the listed candidates, e.g. p 1"),
+                }
+                continue;
+            }
+            _ => {
+                println!("⚠️ Unknown answer '{}'", line);
+                continue;
+            }
+        };
+        scan.filter(process, filter);
+    }
+}
+
+fn print_camera_chains(process: &ProcessHandle, base_addr: usize, position_address: usize, depth: usize, max_offset: usize) {
+    let Some((matrix_address, _)) = camera_matrix_at(process, position_address) else {
+        println!("⚠️ 0x{:08X} isn't part of a camera matrix - narrow the candidates down further", position_address);
+        return;
+    };
+    
+    println!("🧭 Mapping every pointer in the game's memory, this takes a while...");
+    let map = PointerMap::build(process, base_addr);
+    let chains = map.find_chains(matrix_address, depth, max_offset, SCAN_CHAIN_LIMIT);
+    if chains.is_empty() {
+        println!("❌ No chain from the executable to 0x{:08X} among {} pointers - try a larger --depth or --max-offset", matrix_address, map.len());
+        return;
+    }
+    
+    println!("🧭 {} chain(s) to the camera matrix at 0x{:08X}, shortest first:", chains.len(), matrix_address);
+    for chain in &chains {
+        let offsets: Vec<String> = chain.offsets.iter().map(|offset| format!("0x{:X}", offset)).collect();
+        println!("   base = 0x{:08X}  offsets = [{}]", chain.base, offsets.join(", "));
+    }
+    println!("Put one under [camera] in offsets.toml, with `base` and `offsets` as shown.");
+    println!("💡 Most chains break when the level changes. Load another level and run `diagnose` to see which one still works.");
+}
+
+fn print_chain_trace(process: &ProcessHandle, base_addr: usize, name: &str, chain: &PointerChain) {
+    let trace = process.trace_pointer_chain(base_addr + chain.base, &chain.offsets);
+    println!("🔗 {}: base+0x{:X} = 0x{:08X}", name, chain.base, base_addr + chain.base);
+    for (i, step) in trace.steps.iter().enumerate() {
+        match &step.pointer {
+            Ok(pointer) => println!("   [{}] 0x{:08X} -> 0x{:08X} + 0x{:X} = 0x{:08X}", i, step.address, pointer, step.offset, pointer + step.offset),
+            Err(e) => println!("   [{}] 0x{:08X} -> ❌ {}", i, step.address, e),
+        }
+    }
+    match trace.result {
+        Ok(address) => println!("   ✅ Final address 0x{:08X}", address),
+        Err(e) => println!("   ❌ {}", e),
+    }
+}
+
+fn set_position(process: &ProcessHandle, x: f32, y: f32, z: f32) -> Result<(), String> {
+    let base_addr = process.get_base_address()?;
+    let mut cam_matrix = process.get_camera_matrix(base_addr)?;
+    cam_matrix.set_position(&CameraPosition::new(x, y, z));
+    process.set_camera_matrix(base_addr, &cam_matrix)?;
+    println!("✅ Camera moved to X:{:.3}, Y:{:.3}, Z:{:.3}", x, y, z);
+    println!("   The game will overwrite this unless the camera patch is on (patch on)");
+    Ok(())
+}
+
+fn set_patch(process: &ProcessHandle, state: PatchState) -> Result<(), String> {
+    let base_addr = process.get_base_address()?;
+    let patch_addr = process.get_camera_write_patch_address(base_addr)?;
+    let original_bytes = process.game().camera_write().original_bytes;
+    let current = process.read_bytes(patch_addr, original_bytes.len())?;
+    let is_applied = current.iter().all(|&b| b == 0x90);
+    
+    match state {
+        PatchState::On if is_applied => println!("🔧 Camera patch is already enabled"),
+        PatchState::On => {
+            process.patch_with_nops(patch_addr, &original_bytes)?;
+            println!("🔧 Camera patch enabled - free camera active!");
+        }
+        PatchState::Off if !is_applied => println!("🔧 Camera patch is already disabled"),
+        PatchState::Off => {
+            let mut patch = CodePatch {
+                address: patch_addr,
+                original_bytes,
+                is_applied: true,
+            };
+            process.restore_patch(&mut patch)?;
+            println!("🔧 Camera patch disabled - game will overwrite camera");
+        }
+    }
+    Ok(())
+}
+
+fn probe_depth(process: &ProcessHandle, max_distance: f32, iterations: u32) -> Result<(), String> {
+    let base_addr = process.get_base_address()?;
+    
+    // The game must not move the camera back while probing, so hold the patch for the duration
+    let patch_addr = process.get_camera_write_patch_address(base_addr)?;
+    let original_bytes = process.game().camera_write().original_bytes;
+    let current = process.read_bytes(patch_addr, original_bytes.len())?;
+    let temporary_patch = if current == original_bytes {
+        Some(process.patch_with_nops(patch_addr, &original_bytes)?)
+    } else {
+        None
+    };
+    
+    println!("📏 Probing up to {:.0} units along the view ray (keep the game window visible)...", max_distance);
+    let result = DepthProbe::new(max_distance, iterations).measure(process, base_addr);
+    
+    if let Some(mut patch) = temporary_patch {
+        process.restore_patch(&mut patch)?;
+    }
+    
+    let distance = result?;
+    println!("✅ Estimated distance to geometry: {:.1} units", distance);
+    Ok(())
+}
+
+fn convert_path(input: &str, output: &str, from: Option<PathFormat>, to: Option<PathFormat>, fps: f32, mode: InterpolationMode) -> Result<(), String> {
+    let from = from.or_else(|| PathFormat::from_extension(input))
+        .ok_or(format!("Can't tell the format of '{}' from its extension, pass --from", input))?;
+    let to = to.or_else(|| PathFormat::from_extension(output))
+        .ok_or(format!("Can't tell the format of '{}' from its extension, pass --to", output))?;
+    if fps <= 0.0 {
+        return Err("--fps must be positive".to_string());
+    }
+    
+    let path = read_path(input, from, fps)?;
+    let written = write_path(&path, output, to, fps, mode)?;
+    println!("✅ Converted {} ({} poses, {:.1}s) to {} ({} poses)", input, path.len(), path.duration(), output, written);
+    Ok(())
+}
+
+fn split_path(input: &str, manifest: Option<String>, chunk_length: f32, from: Option<PathFormat>, fps: f32) -> Result<(), String> {
+    let from = from.or_else(|| PathFormat::from_extension(input))
+        .ok_or(format!("Can't tell the format of '{}' from its extension, pass --from", input))?;
+    if chunk_length <= 0.0 || fps <= 0.0 {
+        return Err("--chunk-length and --fps must be positive".to_string());
+    }
+    
+    let path = read_path(input, from, fps)?;
+    let manifest_file = manifest.unwrap_or_else(|| {
+        let stem = input.rsplit_once('.').map_or(input, |(stem, _)| stem);
+        format!("{}_chunks.toml", stem)
+    });
+    let manifest = write_chunks(&path, chunk_length, input, &manifest_file)?;
+    
+    println!("✅ Split {} ({:.1}s) into {} chunks, listed in {}", input, path.duration(), manifest.chunks.len(), manifest_file);
+    for entry in &manifest.chunks {
+        println!("   {}  {:>8.1}s  {:>6.1}s  {} keyframes", entry.file, entry.start, entry.duration, entry.keyframes);
+    }
+    Ok(())
+}
+
+fn join_path(manifest: &str, output: &str) -> Result<(), String> {
+    let path = join_chunks(manifest)?;
+    path.save(output)?;
+    println!("✅ Joined the chunks in {} into {} ({} keyframes, {:.1}s)", manifest, output, path.len(), path.duration());
+    Ok(())
+}
+
+fn trim_path(input: &str, output: &str, start: f32, end: Option<f32>, mode: InterpolationMode) -> Result<(), String> {
+    let mut path = CameraPath::load(input)?;
+    let duration = path.duration();
+    path.trim(start, end.unwrap_or(duration), mode)?;
+    path.save(output)?;
+    println!("✅ Trimmed {} ({:.1}s) to {:.1}s in {} ({} keyframes)", input, duration, path.duration(), output, path.len());
+    Ok(())
+}
+
+fn ease_keyframe(file: &str, keyframe: usize, ease_in: f32, ease_out: f32) -> Result<(), String> {
+    let mut path = CameraPath::load(file)?;
+    let index = keyframe.checked_sub(1).ok_or("Keyframes are numbered from 1")?;
+    path.set_ease(index, ease_in, ease_out)?;
+    path.save(file)?;
+    let eased = &path.keyframes[index];
+    println!("✅ Keyframe {} of {} eases in {:.2} and out {:.2}", keyframe, file, eased.ease_in, eased.ease_out);
+    Ok(())
+}
+
+// The overlay sits on the game window, so it needs to find it first
+fn open_overlay(process: &ProcessHandle) -> Result<Overlay, String> {
+    let window = process.find_main_window().ok_or("Can't find the game window to put the overlay on")?;
+    Overlay::start(window)
+}
+
+// A camera animation made in another program, played in place of the saved path
+struct Animation {
+    file: String,
+    path: CameraPath,
+    start: Option<StartTime>,
+}
+
+fn load_animation(file: &str, from: Option<PathFormat>, fps: f32, start: Option<StartTime>) -> Result<Animation, String> {
+    let from = from.or_else(|| PathFormat::from_extension(file))
+        .ok_or(format!("Can't tell the format of '{}' from its extension, pass --from", file))?;
+    if fps <= 0.0 {
+        return Err("--fps must be positive".to_string());
+    }
+    Ok(Animation { file: file.to_string(), path: read_path(file, from, fps)?, start })
+}
+
+fn main() {
+    let cli = Cli::parse();
+    
+    println!("THPS3 Free Cam Tool");
+    println!("===================");
+    
+    // The free cam works without a log, e.g. from a read-only folder
+    if let Err(e) = logging::init(LOG_FILE, cli.verbose) {
+        println!("⚠️ {} - continuing without a log file", e);
+    }
+    log::info!("THPS3 Free Cam {} started: {:?}", env!("CARGO_PKG_VERSION"), cli.command.as_ref().unwrap_or(&Command::Run));
+    shutdown::install();
+    
+    let config = match load_config(&cli.config) {
+        Ok(config) => config,
+        Err(e) => {
+            println!("⚠️ {} - using default settings", e);
+            Config::default()
+        }
+    };
+    
+    // Only --internal needs to start a thread in the game
+    let access = if cli.internal { ProcessAccess::Inject } else { ProcessAccess::Write };
+    
+    // Headless, so nothing waits for Enter and no process is picked from a list
+    if cli.daemon {
+        let (process, offsets) = match cli.command.unwrap_or(Command::Run) {
+            Command::Run => (find_game_process(access), OFFSETS_FILE.to_string()),
+            Command::Attach { pid: Some(pid) } => match ProcessHandle::from_pid(pid, access) {
+                Ok(process) => (Some(process), OFFSETS_FILE.to_string()),
+                Err(e) => {
+                    println!("❌ Could not attach to PID {}: {}", pid, e);
+                    (None, String::new())
+                }
+            },
+            Command::Profile { file } => (find_game_process(access), file),
+            _ => {
+                println!("❌ --daemon works with run, attach --pid and profile");
+                (None, String::new())
+            }
+        };
+        if let Some(process) = process
+            && let Err(e) = daemon(process, &offsets, &config, cli.internal)
+        {
+            println!("❌ {}", e);
+        }
+        return;
+    }
+    
+    match cli.command.unwrap_or(Command::Run) {
+        Command::Run => match find_game_process(access) {
+            Some(process) => run_instances(process, cli.all_instances, OFFSETS_FILE, &cli.config, &config, cli.internal, None),
+            None => wait_for_enter(),
+        },
+        Command::Attach { pid: Some(pid) } => match ProcessHandle::from_pid(pid, access) {
+            Ok(process) => run_instances(process, cli.all_instances, OFFSETS_FILE, &cli.config, &config, cli.internal, None),
+            Err(e) => println!("❌ Could not attach to PID {}: {}", pid, e),
+        },
+        Command::Attach { pid: None } => {
+            if let Some(process) = pick_process(access) {
+                run_instances(process, cli.all_instances, OFFSETS_FILE, &cli.config, &config, cli.internal, None);
+            }
+        }
+        Command::Profile { file } => match find_game_process(access) {
+            Some(process) => run_instances(process, cli.all_instances, &file, &cli.config, &config, cli.internal, None),
+            None => wait_for_enter(),
+        },
+        Command::Play { file, from, fps, at } => match load_animation(&file, from, fps, at) {
+            Ok(animation) => match find_game_process(access) {
+                Some(process) => run_instances(process, cli.all_instances, OFFSETS_FILE, &cli.config, &config, cli.internal, Some(animation)),
+                None => wait_for_enter(),
+            },
+            Err(e) => println!("❌ {}", e),
+        },
+        Command::DumpMatrix => {
+            if let Some(process) = find_game_process(ProcessAccess::Read)
+                && let Err(e) = dump_matrix(&process)
+            {
+                println!("❌ {}", e);
+            }
+        }
+        Command::Diagnose { offsets } => {
+            if let Some(process) = find_game_process(ProcessAccess::Read)
+                && let Err(e) = diagnose(&process, &offsets)
+            {
+                println!("❌ {}", e);
+            }
+        }
+        Command::Spectate { rate, port, quiet, offsets } => {
+            if cli.internal {
+                println!("⚠️ --internal is ignored while spectating, nothing is injected into the game");
+            }
+            if let Some(process) = find_game_process(ProcessAccess::Read)
+                && let Err(e) = spectate(&process, &offsets, rate, port, &config, quiet)
+            {
+                println!("❌ {}", e);
+            }
+        }
+        Command::RecordImage { output, offsets } => {
+            if let Some(process) = find_game_process(ProcessAccess::Read)
+                && let Err(e) = record_image(&process, &offsets, &output)
+            {
+                println!("❌ {}", e);
+            }
+        }
+        Command::ImportTable { file } => {
+            if let Err(e) = import_table(&file) {
+                println!("❌ {}", e);
+            }
+        }
+        Command::Scan { depth, max_offset } => {
+            if let Some(process) = find_game_process(ProcessAccess::Read)
+                && let Err(e) = scan_camera(&process, depth, max_offset)
+            {
+                println!("❌ {}", e);
+            }
+        }
+        Command::SetPos { position } => match numbers::parse_coordinates(&position.join(" ")) {
+            Ok([x, y, z]) => {
+                if let Some(process) = find_game_process(ProcessAccess::Write)
+                    && let Err(e) = set_position(&process, x, y, z)
+                {
+                    println!("❌ {}", e);
+                }
+            }
+            Err(e) => println!("❌ {}", e),
+        },
+        Command::Probe { max, iterations } => {
+            if let Some(process) = find_game_process(ProcessAccess::Write)
+                && let Err(e) = probe_depth(&process, max, iterations)
+            {
+                println!("❌ {}", e);
+            }
+        }
+        Command::Path { action: PathCommand::Convert { input, output, from, to, fps } } => {
+            if let Err(e) = convert_path(&input, &output, from, to, fps, config.interpolation) {
+                println!("❌ {}", e);
+            }
+        }
+        Command::Path { action: PathCommand::Split { input, manifest, chunk_length, from, fps } } => {
+            if let Err(e) = split_path(&input, manifest, chunk_length, from, fps) {
+                println!("❌ {}", e);
+            }
+        }
+        Command::Path { action: PathCommand::Join { manifest, output } } => {
+            if let Err(e) = join_path(&manifest, &output) {
+                println!("❌ {}", e);
+            }
+        }
+        Command::Path { action: PathCommand::Trim { input, output, start, end } } => {
+            if let Err(e) = trim_path(&input, &output, start, end, config.interpolation) {
+                println!("❌ {}", e);
+            }
+        }
+        Command::Path { action: PathCommand::Ease { file, keyframe, ease_in, ease_out } } => {
+            if let Err(e) = ease_keyframe(&file, keyframe, ease_in, ease_out) {
+                println!("❌ {}", e);
+            }
+        }
+        Command::Patch { state } => {
+            if let Some(process) = find_game_process(ProcessAccess::Write)
+                && let Err(e) = set_patch(&process, state)
+            {
+                println!("❌ {}", e);
+            }
+        }
+    }
+}
+
+// One free cam session on `process`. Hands the process back when the instance key asked for the next instance.
+fn run_free_cam(mut process: ProcessHandle, instances: &mut Instances, offsets_path: &str, config_path: &str, config: &Config,
+                internal: bool, mut animation: Option<&mut Animation>) -> Option<ProcessHandle> {
+    // Both are replaced when config.toml changes while attached. `config` adds the loaded level's settings on top of the file.
+    let mut file_config = config.clone();
+    let mut config = file_config.for_level(None);
+    let mut keys = config.keys.clone();
+    let mut poll_interval = std::time::Duration::from_millis(config.poll_interval_ms);
+    let mut idle_poll_interval = idle_interval(&config);
+    let mut config_watch = FileWatch::new(config_path);
+    let mut offsets_watch = FileWatch::new(offsets_path);
+    // Kept across matrix sessions, so flying in and out before the start time doesn't lose it
+    let mut start_time = animation.as_mut().and_then(|animation| animation.start.take());
+    let mut switch_instance = false;
+    
+    println!("✅ Successfully attached to {}!", process.game().name());
+    log::info!("Attached to {} (PID {})", process.game().name(), process.pid());
+    
+    if internal {
+        attach_hook(&mut process);
+    }
+    let mut gestures = GestureDetector::new(config.gestures.clone());
+    let mut key_events = KeyEvents::start(&keys, process.find_main_window());
+    let (mut plugins, errors) = if config.plugin_dir.is_empty() { Default::default() } else { Plugins::load(&config.plugin_dir) };
+    for e in errors {
+        println!("⚠️ {}", e);
+    }
+    let rumble = Rumble::new(&config.rumble);
+    let mut title = ConsoleTitle::new();
+    let mut stats = SessionStats::new();
+    title.set(format!("{} | PID {} | attached", APP_TITLE, process.pid()));
+    
+    // Load optional offsets for features without hardcoded addresses (FOV, ...)
+    let mut game_offsets = match load_offsets(offsets_path) {
+        Ok(offsets) => offsets,
+        Err(e) => {
+            println!("⚠️ {} - continuing without extra offsets", e);
+            Default::default()
+        }
+    };
+    if let Some(camera) = &game_offsets.camera {
+        println!("📍 Using the [camera] chain from {}", offsets_path);
+        process.set_camera_chain(camera.clone());
+    }
+    let mut tunables = TunableSet::new(game_offsets.tunables.clone());
+    let mut level_watch = game_offsets.level.clone().map(LevelWatch::new);
+    if !tunables.is_empty() {
+        println!("🎛️ Loaded {} tunable(s) from {}:", tunables.tunables().len(), offsets_path);
+        for tunable in tunables.tunables() {
+            println!("   {} ({:?}, {} - {})", tunable.name, tunable.value_type, tunable.min, tunable.max);
+        }
+    }
     
     // Get the base address of the process
     match process.get_base_address() {
@@ -69,271 +1434,1650 @@ fn main() {
                     
                     // Test camera matrix reading
                     println!("\n🎮 Testing camera matrix access...");
-                    match process.get_camera_matrix(base_addr) {
+                    // Without the matrix the camera still flies, moving only its position along the world axes
+                    let matrix_mode = match process.get_camera_matrix(base_addr) {
                         Ok(cam_matrix) => {
                             println!("✅ Successfully read camera matrix!");
                             let matrix_pos = cam_matrix.get_position();
                             println!("   📍 Matrix Position X: {:.6}", matrix_pos.x);
                             println!("   📍 Matrix Position Y: {:.6}", matrix_pos.y);
                             println!("   📍 Matrix Position Z: {:.6}", matrix_pos.z);
+                            true
+                        }
+                        Err(e) => {
+                            println!("❌ Failed to read camera matrix: {}", e);
+                            // This might mean the matrix pointer chain is incorrect.
                             
-                            // Show the actual memory addresses
-                            match process.get_camera_addresses(base_addr) {
-                                Ok((x_addr, y_addr, z_addr)) => {
-                                    println!("\n🔍 Memory addresses:");
-                                    println!("   X: 0x{:X}", x_addr);
-                                    println!("   Y: 0x{:X}", y_addr);
-                                    println!("   Z: 0x{:X}", z_addr);
+                            // Fall back to position-only mode
+                            println!("\n📍 Falling back to position-only mode...");
+                            false
+                        }
+                    };
+                    
+                    // Show the actual memory addresses
+                    match process.get_camera_addresses(base_addr) {
+                        Ok((x_addr, y_addr, z_addr)) => {
+                            println!("\n🔍 Memory addresses:");
+                            println!("   X: 0x{:X}", x_addr);
+                            println!("   Y: 0x{:X}", y_addr);
+                            println!("   Z: 0x{:X}", z_addr);
+                        }
+                        Err(e) => println!("❌ Failed to get camera addresses: {}", e),
+                    }
+                    
+                    // Start real-time camera control
+                    println!("\n🎮 Starting {} Camera Mode!", if matrix_mode { "Free" } else { "Basic" });
+                    println!("===============================");
+                    print_controls(&keys, matrix_mode, matrix_mode && game_offsets.fov.is_some(), &offsets_patches(&game_offsets),
+                                   game_offsets.skater.is_some(), matrix_mode && game_offsets.skater2.is_some(), !tunables.is_empty());
+                    print_gestures(&gestures);
+                    println!("");
+                    println!("💡 Switch to the {} window and use the controls!", process.game().name());
+                    println!("   Camera will respond to key presses in real-time.");
+                    println!("   Press Ctrl+C or close this terminal window to stop, the game is restored either way.");
+                    println!("");
+                    
+                    let move_speed = if matrix_mode { config.move_speed } else { config.basic_move_speed };
+                    let mut controller = CameraController::new(move_speed, config.mouse_sensitivity, keys.clone());
+                    controller.set_position_only(!matrix_mode);
+                    controller.set_smoothing(config.acceleration, config.deceleration);
+                    controller.set_axis_scales(config.horizontal_speed_scale, config.vertical_speed_scale);
+                    controller.set_speed_modifiers(config.sprint_multiplier, config.slow_multiplier);
+                    if config.smooth_movement {
+                        controller.toggle_movement_mode();
+                    }
+                    set_controller_offsets(&mut controller, &game_offsets);
+                    controller.set_switch_turn_rate(config.switch_turn_rate);
+                    controller.set_write_channels(config.write_channels);
+                    if config.write_channels != WriteChannels::Full {
+                        println!("📐 Camera writes limited to {} - the game keeps the rest", config.write_channels.name());
+                        // The patch stops the game's whole camera copy, only the hook can let part of it through
+                        if process.internal().is_none() {
+                            println!("   ⚠️ Without --internal the game only updates its part while the camera patch is off");
+                        }
+                    }
+                    controller.set_bounds(enforced_bounds(&config.bounds));
+                    if config.vhs_jitter {
+                        controller.set_jitter(Some(Jitter::new(config.jitter_position, config.jitter_rotation, config.jitter_frequency)));
+                    }
+                    if config.handheld_shake {
+                        controller.set_shake(Some(Shake::new(config.shake_position, config.shake_rotation, config.shake_frequency)));
+                    }
+                    let mut switch_control = config.switch_control.then(|| SwitchControl::new(config.switch_dwell_ms));
+                    if let Some(switch) = &switch_control {
+                        println!("🔘 Switch access: tap {} to stop and step through the modes, rest {:.1}s on one to start it",
+                                 keys.switch_access.name(), switch.dwell().as_secs_f32());
+                    }
+                    let mut voice = if config.voice_commands {
+                        match VoiceCommands::start(config.voice_confidence) {
+                            Ok(voice) => {
+                                println!("🎙️ Voice commands: {}", VoiceCommands::phrases().collect::<Vec<_>>().join(", "));
+                                for (name, first, last) in VoiceCommands::settings() {
+                                    println!("   \"{} <{}-{}>\"", name, first, last);
+                                }
+                                Some(voice)
+                            }
+                            Err(e) => {
+                                println!("⚠️ {} - continuing without voice commands", e);
+                                None
+                            }
+                        }
+                    } else {
+                        None
+                    };
+                    let remote = config.remote_port.and_then(|port| match RemoteServer::start(&config.remote_bind, port, remote_access(&config)) {
+                        Ok(remote) => {
+                            println!("🌐 Remote control on http://{}:{} (WebSocket on the same port)", config.remote_bind, port);
+                            Some(remote)
+                        }
+                        Err(e) => {
+                            println!("⚠️ {} - continuing without remote control", e);
+                            None
+                        }
+                    });
+                    let osc = config.osc_port.and_then(|port| match OscListener::start(&config.remote_bind, port) {
+                        Ok(osc) => {
+                            println!("🎛️ Listening for OSC on udp://{}:{}", config.remote_bind, port);
+                            Some(osc)
+                        }
+                        Err(e) => {
+                            println!("⚠️ {} - continuing without OSC", e);
+                            None
+                        }
+                    });
+                    if config.midi.enabled {
+                        match MidiController::start(&config.midi) {
+                            Ok(midi) => {
+                                println!("🎹 MIDI controller on {}", midi.port_name());
+                                if config.midi.fov.is_some() && game_offsets.fov.is_none() {
+                                    println!("⚠️ The MIDI FOV fader needs [fov] in {} - it does nothing", offsets_path);
+                                }
+                                controller.add_source(Box::new(midi));
+                            }
+                            Err(e) => println!("⚠️ {} - continuing without MIDI", e),
+                        }
+                    }
+                    if config.space_mouse.enabled {
+                        match SpaceMouse::start(&config.space_mouse) {
+                            Ok(space_mouse) => {
+                                match SpaceMouse::connected() {
+                                    0 => println!("⚠️ No SpaceMouse found - it starts working once it's plugged in"),
+                                    _ => println!("🕹️ SpaceMouse on - push, tilt and twist the puck to fly"),
+                                }
+                                controller.add_source(Box::new(space_mouse));
+                            }
+                            Err(e) => println!("⚠️ {} - continuing without the SpaceMouse", e),
+                        }
+                    }
+                    if config.gamepad.enabled {
+                        match GamepadSource::start(&config.gamepad) {
+                            Ok(gamepad) => {
+                                println!("🎮 Gamepad on in slot {} - sticks fly, triggers go up and down", config.gamepad.controller);
+                                controller.add_source(Box::new(gamepad));
+                            }
+                            Err(e) => println!("⚠️ {} - continuing without the gamepad", e),
+                        }
+                    }
+                    // The dead man's switch counts raw mouse motion, which comes through the wheel's raw input
+                    let mut mouse_motion = None;
+                    if config.wheel_speed_steps > 0 || config.idle_release_s > 0.0 {
+                        match MouseWheel::start(config.wheel_speed_steps, process.find_main_window()) {
+                            Ok(wheel) => {
+                                mouse_motion = Some(wheel.motion());
+                                controller.add_source(Box::new(wheel));
+                            }
+                            Err(e) => println!("⚠️ {} - continuing without the mouse wheel", e),
+                        }
+                    }
+                    // Remote move and turn commands are handed to this one, the controller polls its copy like any other device
+                    let network_input = NetworkInput::default();
+                    controller.add_source(Box::new(network_input.clone()));
+                    let pipe = if config.control_pipe {
+                        match ControlServer::start_pipe(PIPE_NAME) {
+                            Ok(pipe) => {
+                                println!("🔌 Taking requests on {}", PIPE_NAME);
+                                Some(pipe)
+                            }
+                            Err(e) => {
+                                println!("⚠️ {} - continuing without the pipe", e);
+                                None
+                            }
+                        }
+                    } else {
+                        None
+                    };
+                    let mut panel = if config.control_panel {
+                        match ControlPanel::start() {
+                            Ok(panel) => {
+                                println!("🪟 Control panel open, closing it leaves the free cam running");
+                                Some(panel)
+                            }
+                            Err(e) => {
+                                println!("⚠️ {} - continuing without the control panel", e);
+                                None
+                            }
+                        }
+                    } else {
+                        None
+                    };
+                    let mut overlay = if config.overlay {
+                        match open_overlay(&process) {
+                            Ok(overlay) => {
+                                println!("🎯 Overlay on - {} hides it", keys.overlay_toggle.name());
+                                Some(overlay)
+                            }
+                            Err(e) => {
+                                println!("⚠️ {} - continuing without the overlay", e);
+                                None
+                            }
+                        }
+                    } else {
+                        None
+                    };
+                    if config.osd {
+                        match process.internal() {
+                            Some(internal) if internal.osd_failed() => {
+                                println!("⚠️ The hook can't draw into the game's Direct3D frames - no in-game status line");
+                            }
+                            Some(_) => println!("📟 In-game status line on"),
+                            None => println!("⚠️ The in-game status line needs --internal"),
+                        }
+                    }
+                    let mut game_state = GameStateReader::new(game_offsets.stats.clone());
+                    let mut stats_file = (!config.stats_file.is_empty()).then(|| StatsFile::new(&config.stats_file));
+                    if let Some(file) = &stats_file {
+                        if game_state.is_empty() {
+                            println!("⚠️ stats_file is set but the offsets file has no [stats] chains");
+                        } else {
+                            println!("📊 Writing the skater stats to {}", file.path());
+                        }
+                    }
+                    let mut status_line = StatusLine::new(config.status_threshold, config.status_rate);
+                    let mut patches = game_patches(&process, base_addr, &game_offsets);
+                    let mut fov_restore = FovRestore::new(&process, base_addr);
+                    let mut guard = PatchGuard::new(game_offsets.game_state.clone(), config.patch_guard_black_frames, process.find_main_window());
+                    let mut patch_pending = false;
+                    let mut dead_mans_switch = DeadMansSwitch::new(config.idle_release_s, mouse_motion.clone());
+                    let mut telemetry: Option<TelemetryRecorder> = None;
+                    
+                    // Pick up the path saved in an earlier session, or the animation passed to `play`
+                    let mut camera_path = CameraPath::default();
+                    if let Some(animation) = &animation {
+                        println!("🎞️ Loaded {} ({} poses, {:.1}s) as the path", animation.file, animation.path.len(), animation.path.duration());
+                        match &start_time {
+                            Some(start) => println!("   ⏰ It plays at {} (in {}s) - {} plays it now",
+                                                    start.label(), start.remaining().as_secs(), keys.path_play.name()),
+                            None => println!("   {} plays it", keys.path_play.name()),
+                        }
+                        warn_path_bounds(&animation.path, &config.bounds, config.interpolation);
+                        camera_path = animation.path.clone();
+                    } else if std::path::Path::new(&config.path_file).exists() {
+                        match CameraPath::load(&config.path_file) {
+                            Ok(path) => {
+                                println!("📌 Loaded {} keyframe(s) from {}", path.len(), config.path_file);
+                                if let Some(author) = &path.metadata.author {
+                                    println!("   by {}{}", author, path.metadata.created.as_ref().map_or(String::new(), |created| format!(", {}", created)));
+                                }
+                                warn_path_bounds(&path, &config.bounds, config.interpolation);
+                                camera_path = path;
+                            }
+                            Err(e) => println!("⚠️ {} - starting with an empty path", e),
+                        }
+                    }
+                    
+                    // Paths dropped into path_dir show up while flying, the first scan just says how many there are
+                    let mut library = (!config.path_dir.is_empty()).then(|| PathLibrary::new(&config.path_dir));
+                    if let Some(library) = &mut library {
+                        for change in library.poll() {
+                            if let LibraryChange::Failed(_, e) = change {
+                                println!("⚠️ {}", e);
+                            }
+                        }
+                        if !library.is_empty() {
+                            println!("📚 {} path(s) in {} - {} loads the next one", library.len(), config.path_dir, keys.path_next.name());
+                        }
+                    }
+                    // Script keys aren't in [keys], the polling fallback has to be told about them
+                    let mut script: Option<ScriptRunner> = None;
+                    // Whether a plugin wrote the camera last tick, plugins run after the dead man's switch looks
+                    let mut plugin_wrote = false;
+                    key_events.watch_also(config.scripts.values().copied().collect());
+                    if !config.scripts.is_empty() {
+                        let bound: Vec<String> = config.scripts.iter().map(|(name, key)| format!("{} {}", key.name(), name)).collect();
+                        println!("📜 Scripts in {}: {}", config.script_dir, bound.join(", "));
+                    }
+                    // Replay sync needs to know where the game keeps its replay state
+                    let mut replay_watch = match (&game_offsets.replay, config.replay_sync) {
+                        (Some(replay), true) => {
+                            println!("🎬 Replay sync on - the path plays when the game starts a replay");
+                            Some(ReplayWatch::new(replay.clone()))
+                        }
+                        (None, true) => {
+                            println!("⚠️ replay_sync needs a [replay] value in {} - replay sync is off", offsets_path);
+                            None
+                        }
+                        _ => None,
+                    };
+                    let mut replay_started_path = false;
+                    let author = config.author.clone().or_else(|| std::env::var("USERNAME").ok());
+                    let mut playback: Option<PlaybackClock> = None;
+                    let mut playback_rate = config.playback_rate.clamp(0.1, 4.0);
+                    let mut playback_repeat = config.playback_repeat;
+                    let mut last_countdown_display: Option<(usize, u32)> = None;
+                    let mut blend_out_start: Option<std::time::Instant> = None;
+                    let mut bake: Option<PathBake> = None;
+                    let mut recording: Option<MotionRecording> = None;
+                    let mut timelapse: Option<Timelapse> = None;
+                    let mut step_shot_at: Option<std::time::Instant> = None;
+                    let mut bookmarks = Bookmarks::new();
+                    let mut bookmark_transition: Option<BookmarkTransition> = None; // Also used on the way to a waypoint
+                    let mut waypoints = WaypointTour::new();
+                    let mut history = CameraHistory::new(config.undo_history);
+                    let mut palette_entries = palette::entries(&keys, matrix_mode, game_offsets.fov.is_some());
+                    let mut open_palette: Option<Palette> = None;
+                    let mut held = false;
+                    
+                    loop {
+                        if shutdown::requested() {
+                            println!("\n🛑 Stopping - handing the camera back to the game");
+                            break;
+                        }
+                        
+                        // Edits to config.toml and the offsets file take effect without detaching, the patches stay as they are
+                        if config_watch.changed()
+                            && let Some(new_file_config) = reload(&config_watch, load_config, "settings")
+                        {
+                            let new_config = new_file_config.for_level(level_watch.as_ref().and_then(|watch| watch.current()));
+                            apply_config(&mut controller, &config, &new_config);
+                            keys = new_config.keys.clone();
+                            key_events.watch(&keys);
+                            key_events.watch_also(new_config.scripts.values().copied().collect());
+                            gestures = GestureDetector::new(new_config.gestures.clone());
+                            palette_entries = palette::entries(&keys, matrix_mode, game_offsets.fov.is_some());
+                            status_line = StatusLine::new(new_config.status_threshold, new_config.status_rate);
+                            guard = PatchGuard::new(game_offsets.game_state.clone(), new_config.patch_guard_black_frames, process.find_main_window());
+                            poll_interval = std::time::Duration::from_millis(new_config.poll_interval_ms);
+                            idle_poll_interval = idle_interval(&new_config);
+                            if new_config.idle_release_s != config.idle_release_s {
+                                dead_mans_switch = DeadMansSwitch::new(new_config.idle_release_s, mouse_motion.clone());
+                            }
+                            if new_config.playback_rate != config.playback_rate {
+                                playback_rate = new_config.playback_rate.clamp(0.1, 4.0);
+                            }
+                            if new_config.playback_repeat != config.playback_repeat {
+                                playback_repeat = new_config.playback_repeat;
+                            }
+                            file_config = new_file_config;
+                            config = new_config;
+                        }
+                        if offsets_watch.changed()
+                            && let Some(new_offsets) = reload(&offsets_watch, load_offsets, "offsets")
+                        {
+                            process.set_camera_chain(new_offsets.camera.clone().unwrap_or_else(|| process.game().camera_chain()));
+                            set_controller_offsets(&mut controller, &new_offsets);
+                            if new_offsets.freeze != game_offsets.freeze {
+                                replace_offsets_patch(&process, base_addr, &mut patches, FREEZE, "freeze", new_offsets.freeze.as_ref());
+                            }
+                            if new_offsets.hud != game_offsets.hud {
+                                replace_offsets_patch(&process, base_addr, &mut patches, HUD, "hud", new_offsets.hud.as_ref());
+                            }
+                            tunables = TunableSet::new(new_offsets.tunables.clone());
+                            guard = PatchGuard::new(new_offsets.game_state.clone(), config.patch_guard_black_frames, process.find_main_window());
+                            replay_watch = new_offsets.replay.clone().filter(|_| config.replay_sync).map(ReplayWatch::new);
+                            level_watch = new_offsets.level.clone().map(LevelWatch::new);
+                            game_state = GameStateReader::new(new_offsets.stats.clone());
+                            game_offsets = new_offsets;
+                        }
+                        
+                        // Waypoints, bounds, speed and FOV follow the level the game has loaded
+                        if let Some(level) = level_watch.as_mut().and_then(|watch| watch.poll(&process, base_addr)) {
+                            let new_config = file_config.for_level(level.as_deref());
+                            apply_config(&mut controller, &config, &new_config);
+                            waypoints = WaypointTour::new();
+                            match &level {
+                                Some(level) => println!("\n🗺️ Level: {}", level),
+                                None => println!("\n🗺️ Left the level"),
+                            }
+                            if let Some(hfov) = new_config.level_fov()
+                                && let Err(e) = controller.set_horizontal_fov(&process, base_addr, hfov)
+                            {
+                                println!("   ⚠️ Failed to set the level's FOV: {}", e);
+                            }
+                            config = new_config;
+                        }
+                        
+                        let mut pressed = key_events.poll();
+                        // The command palette runs what was picked like the control panel would. While it's open the
+                        // letters typed into the console reach the hotkeys and movement keys too, so they are ignored.
+                        let mut palette_pick = None;
+                        if let Some(palette) = &mut open_palette {
+                            pressed.clear();
+                            match palette.poll(&palette_entries) {
+                                PalettePoll::Open => {}
+                                PalettePoll::Closed => open_palette = None,
+                                PalettePoll::Picked(command) => {
+                                    palette_pick = Some(command);
+                                    open_palette = None;
                                 }
-                                Err(e) => println!("❌ Failed to get camera addresses: {}", e),
                             }
-                            
-                            // Start real-time camera control
-                            println!("\n🎮 Starting Free Camera Mode!");
-                            println!("===============================");
-                            println!("Controls:");
-                            println!("   I/K - Move Forward/Backward");
-                            println!("   J/L - Move Left/Right");
-                            println!("   U/O - Move Up/Down");
-                            println!("   M   - Toggle Mouse Look");
-                            println!("   P   - Toggle Camera Write Patch");
-                            println!("   Page Up/Down - Increase/Decrease Speed");
-                            println!("");
-                            println!("💡 Switch to Skate3 window and use the controls!");
-                            println!("   Camera will respond to key presses in real-time.");
-                            println!("   Close this terminal window to stop the program.");
-                            println!("");
-                            
-                            let mut controller = CameraController::new(5.0, 0.5); // Move speed: 5 units per press, mouse sensitivity: 0.1 (perfect responsiveness)
-                            let mut last_pos_display = cam_pos.clone();
-                            let mut mouse_toggle_pressed = false;
-                            let mut patch_toggle_pressed = false;
-                            let mut camera_patch: Option<CodePatch> = None;
-                            
-                            loop {
-                                // Check for mouse toggle
-                                if is_key_pressed(VK_M) {
-                                    if !mouse_toggle_pressed {
-                                        if controller.is_mouse_enabled() {
-                                            controller.disable_mouse();
-                                            println!("\n🖱️ Mouse look disabled");
-                                        } else {
-                                            controller.enable_mouse();
-                                            println!("\n🖱️ Mouse look enabled - move mouse to look around");
+                            controller.set_typing(open_palette.is_some());
+                        } else if pressed.contains(&keys.palette) {
+                            open_palette = Some(Palette::open());
+                            controller.set_typing(true);
+                            pressed.clear();
+                        }
+                        let mut actions: Vec<Action> = gestures.poll().into_iter().collect();
+                        // Every device is read here. Buttons come back as actions, the FOV and scrub faders are handled below.
+                        let polled = controller.poll_inputs();
+                        actions.extend(polled.iter().filter_map(|command| match command {
+                            CameraCommand::Toggle(action) => Some(*action),
+                            _ => None,
+                        }));
+                        for &key in &pressed {
+                            plugins.hotkey(key);
+                        }
+                        
+                        // Single-switch access: a tap stops the camera and steps the highlight, resting on a mode confirms it
+                        if let Some(switch) = &mut switch_control {
+                            if pressed.contains(&keys.switch_access) {
+                                controller.set_switch_mode(SwitchMode::Stop);
+                                print!("\r🔘 {}...                    ", switch.press().name());
+                                std::io::Write::flush(&mut std::io::stdout()).unwrap();
+                            }
+                            if let Some(mode) = switch.update() {
+                                match mode {
+                                    SwitchMode::PatchToggle => actions.push(Action::PatchToggle),
+                                    _ => controller.set_switch_mode(mode),
+                                }
+                                println!("\r✅ {}                    ", mode.name());
+                            }
+                        }
+                        
+                        // Voice commands say which state they want, so "patch on" leaves an applied patch alone. Spoken
+                        // settings run with the control panel's commands below.
+                        let mut spoken_settings = Vec::new();
+                        if let Some(voice) = &mut voice {
+                            for command in voice.poll() {
+                                let (action, wanted) = match command {
+                                    VoiceCommand::Action(action, wanted) => (action, wanted),
+                                    VoiceCommand::Setting(setting, value) => {
+                                        spoken_settings.push(setting.command(value));
+                                        continue;
+                                    }
+                                };
+                                let current = match action {
+                                    Action::PatchToggle => camera_patch_active(&process, &patches),
+                                    Action::MouseToggle => controller.is_mouse_enabled(),
+                                    Action::FreezeToggle => patches.is_applied(FREEZE),
+                                    Action::HudToggle => patches.is_applied(HUD),
+                                    Action::SmoothToggle => matches!(controller.get_movement_mode(), MovementMode::Smooth),
+                                    Action::StepToggle => controller.is_stepping(),
+                                    Action::OrbitToggle => controller.is_orbiting(),
+                                    Action::FollowToggle => controller.is_following(),
+                                    Action::FramingToggle => controller.is_framing(),
+                                    Action::LookAtToggle => controller.get_look_at().is_some(),
+                                    Action::TelemetryToggle => telemetry.is_some(),
+                                    Action::JitterToggle => controller.is_jittering(),
+                                    Action::ShakeToggle => controller.is_shaking(),
+                                    Action::OverlayToggle => overlay.is_some(),
+                                    Action::MirrorToggle => instances.is_mirroring(),
+                                    Action::HoldToggle => held,
+                                    Action::PathPlay => playback.is_some(),
+                                    Action::PlaybackPause => playback.as_ref().is_some_and(PlaybackClock::is_paused),
+                                    Action::PathRecord => recording.is_some(),
+                                    Action::Timelapse => timelapse.is_some(),
+                                    _ => false,
+                                };
+                                if wanted != Some(current) {
+                                    actions.push(action);
+                                }
+                            }
+                        }
+                        
+                        // Replay sync presses the path play key for you, and stops only a path it started
+                        if let Some(watch) = &mut replay_watch {
+                            match watch.poll(&process, base_addr) {
+                                Some(ReplayEvent::Started) if playback.is_none() => {
+                                    println!("\n🎬 Replay started");
+                                    actions.push(Action::PathPlay);
+                                    replay_started_path = true;
+                                }
+                                Some(ReplayEvent::Ended) => {
+                                    if replay_started_path && playback.is_some() {
+                                        println!("\n🎬 Replay ended");
+                                        actions.push(Action::PathPlay);
+                                    }
+                                    replay_started_path = false;
+                                }
+                                _ => {}
+                            }
+                        }
+                        
+                        // A start time presses the path play key once the clock gets there
+                        if let Some(event) = start_time.as_ref().and_then(StartTime::poll) {
+                            match event {
+                                StartEvent::Start if playback.is_none() => {
+                                    println!("\n⏰ {} - playing the animation", start_time.as_ref().map_or("", StartTime::label));
+                                    actions.push(Action::PathPlay);
+                                }
+                                StartEvent::Start => println!("\n⏰ Start time reached, the path is already playing"),
+                                StartEvent::Missed(late) => println!("\n⚠️ Missed the start time by {:.1}s while out of matrix mode - {} plays the animation",
+                                                                     late.as_secs_f32(), keys.path_play.name()),
+                            }
+                            start_time = None;
+                        }
+                        
+                        // What the running script asked for since the last tick
+                        let mut script_commands = Vec::new();
+                        if let Some(runner) = &script {
+                            let mut finished = false;
+                            for event in runner.poll() {
+                                match event {
+                                    ScriptEvent::Command(command) => script_commands.push(command),
+                                    ScriptEvent::Finished(result) => {
+                                        match result {
+                                            Ok(()) => println!("\n✅ Script {} finished", runner.name()),
+                                            Err(e) => println!("\n❌ Script {} failed: {}", runner.name(), e),
+                                        }
+                                        finished = true;
+                                    }
+                                }
+                            }
+                            if finished {
+                                script = None;
+                            }
+                        }
+                        
+                        // Remote control, OSC and script requests: actions go through the same checks as keys, moves
+                        // and turns are kept as network input
+                        let remote_commands: Vec<RemoteCommand> = remote.iter().flat_map(RemoteServer::poll)
+                            .chain(osc.iter().flat_map(OscListener::poll))
+                            .chain(script_commands)
+                            .filter_map(|command| network_input.take(command))
+                            .collect();
+                        actions.extend(remote_commands.iter().filter_map(|command| match command {
+                            RemoteCommand::Action { action } => Some(*action),
+                            _ => None,
+                        }));
+                        // Pipe requests are answered further down, a patch request only toggles when it changes something
+                        let pipe_requests = pipe.as_ref().map(ControlServer::poll).unwrap_or_default();
+                        actions.extend(pipe_requests.iter().filter_map(|pending| match &pending.request {
+                            ControlRequest::Remote(RemoteCommand::Action { action }) => Some(*action),
+                            ControlRequest::Patch { on } if *on != camera_patch_active(&process, &patches) => Some(Action::PatchToggle),
+                            _ => None,
+                        }));
+                        let mut panel_commands = panel.as_ref().map(ControlPanel::poll).unwrap_or_default();
+                        panel_commands.extend(palette_pick);
+                        panel_commands.extend(spoken_settings);
+                        actions.extend(panel_commands.iter().filter_map(|command| match command {
+                            PanelCommand::Action(action) => Some(*action),
+                            _ => None,
+                        }));
+                        
+                        // Dead-man hold: nothing writes the camera until it is released, the patch stays as it is
+                        if pressed.contains(&keys.hold_toggle) || actions.contains(&Action::HoldToggle) {
+                            held = !held;
+                            log::info!("Camera {}", if held { "held" } else { "released" });
+                            if held {
+                                let stopped = playback.take().is_some() | timelapse.take().is_some() | script.take().is_some();
+                                bake = None;
+                                bookmark_transition = None;
+                                blend_out_start = None;
+                                step_shot_at = None;
+                                println!("\n🛑 Camera held{} - press {} to release", if stopped { ", playback stopped" } else { "" }, keys.hold_toggle.name());
+                            } else {
+                                controller.resync();
+                                println!("\n🎮 Camera released");
+                            }
+                        }
+                        
+                        // The FOV fader sets the FOV unless the camera is held, the scrub fader puts playback anywhere on
+                        // the path except in a bake
+                        for command in &polled {
+                            match *command {
+                                CameraCommand::Fov(fov) if !held && game_offsets.fov.is_some() => {
+                                    if let Err(e) = controller.set_horizontal_fov(&process, base_addr, fov) {
+                                        println!("\n❌ FOV fader failed: {}", e);
+                                    }
+                                }
+                                CameraCommand::Scrub(fraction) => {
+                                    if let (Some(clock), None) = (&mut playback, &bake) {
+                                        clock.seek(fraction * camera_path.duration());
+                                    }
+                                }
+                                _ => {}
+                            }
+                        }
+                        
+                        // Script keys start their script, or stop it while it runs. Starting one stops whatever moves the camera.
+                        for (name, key) in &config.scripts {
+                            if !pressed.contains(key) {
+                                continue;
+                            }
+                            if script.as_ref().is_some_and(|runner| runner.name() == name) {
+                                script = None;
+                                println!("\n⏹️ Stopped script {}", name);
+                            } else if held {
+                                println!("\n⚠️ The camera is held - release it ({}) before running a script", keys.hold_toggle.name());
+                            } else if !matrix_mode {
+                                println!("\n⚠️ Scripts pose the camera matrix, they can't run in position-only mode");
+                            } else {
+                                let path = std::path::Path::new(&config.script_dir).join(format!("{}.{}", name, SCRIPT_EXTENSION));
+                                let started = script_state(&process, base_addr, &controller, &patches, game_offsets.skater.as_ref())
+                                    .and_then(|state| ScriptRunner::start(&path.to_string_lossy(), config.interpolation, state));
+                                match started {
+                                    Ok(runner) => {
+                                        script = Some(runner);
+                                        playback = None;
+                                        timelapse = None;
+                                        bake = None;
+                                        bookmark_transition = None;
+                                        println!("\n📜 Running script {} - {} stops it", name, key.name());
+                                    }
+                                    Err(e) => println!("\n❌ {}", e),
+                                }
+                            }
+                        }
+                        
+                        // Remote poses are written right away
+                        for command in remote_commands {
+                            let result = match command {
+                                RemoteCommand::Action { .. } => continue,
+                                RemoteCommand::SetSpeed { speed } => {
+                                    controller.set_speed(speed);
+                                    Ok(())
+                                }
+                                _ if held => Err("the camera is held".to_string()),
+                                command => remote_write(&process, base_addr, &mut controller, command),
+                            };
+                            // Carry on flying from wherever the remote put the camera
+                            controller.resync();
+                            if let Err(e) = result {
+                                println!("\n❌ Remote command failed: {}", e);
+                            }
+                        }
+                        
+                        // Pipe requests, answered once they're done. Actions and patch requests were queued above.
+                        for pending in pipe_requests {
+                            let result = match &pending.request {
+                                ControlRequest::Remote(RemoteCommand::Action { .. }) | ControlRequest::Patch { .. } => Ok(None),
+                                ControlRequest::Quit => Err("quit only stops --daemon".to_string()),
+                                ControlRequest::GetCamera => {
+                                    let stats = (!game_state.is_empty()).then(|| game_state.read(&process, base_addr));
+                                    camera_state(&process, base_addr, &controller, &patches, stats)
+                                        .and_then(|state| serde_json::to_value(state).map_err(|e| e.to_string()))
+                                        .map(Some)
+                                }
+                                ControlRequest::Remote(RemoteCommand::SetSpeed { speed }) => {
+                                    controller.set_speed(*speed);
+                                    Ok(None)
+                                }
+                                ControlRequest::Remote(command @ (RemoteCommand::Move { .. } | RemoteCommand::Turn { .. })) => {
+                                    network_input.take(command.clone());
+                                    Ok(None)
+                                }
+                                _ if held => Err("the camera is held".to_string()),
+                                ControlRequest::Remote(command) => {
+                                    let result = remote_write(&process, base_addr, &mut controller, command.clone()).map(|()| None);
+                                    controller.resync();
+                                    result
+                                }
+                            };
+                            if let Err(e) = &result {
+                                log::warn!("Pipe request {:?} failed: {}", pending.request, e);
+                            }
+                            pending.answer(result);
+                        }
+                        
+                        // Control panel sliders and keyframe list, and settings from the palette
+                        for command in panel_commands {
+                            let result = match command {
+                                PanelCommand::Action(_) => continue,
+                                PanelCommand::Speed(speed) => {
+                                    controller.set_speed(speed);
+                                    Ok(())
+                                }
+                                PanelCommand::Sensitivity(sensitivity) => {
+                                    controller.set_mouse_sensitivity(sensitivity);
+                                    Ok(())
+                                }
+                                PanelCommand::DeleteKeyframe(index) => {
+                                    if camera_path.remove_keyframe(index).is_some() {
+                                        // Playback would jump, the path it was following changed under it
+                                        playback = None;
+                                        timelapse = None;
+                                        bake = None;
+                                        println!("\n🗑️ Keyframe {} removed, {} left", index + 1, camera_path.len());
+                                    }
+                                    Ok(())
+                                }
+                                PanelCommand::SetEase(index, ease_in, ease_out) => camera_path.set_ease(index, ease_in, ease_out).map(|()| {
+                                    println!("\n〰️ Keyframe {} eases in {:.2} and out {:.2}", index + 1, ease_in, ease_out);
+                                }),
+                                _ if held => Err("the camera is held".to_string()),
+                                PanelCommand::Fov(fov) => controller.set_horizontal_fov(&process, base_addr, fov),
+                                PanelCommand::GoToKeyframe(index) => match camera_path.keyframes.get(index) {
+                                    Some(keyframe) => controller.write_pose(&process, base_addr, &keyframe.pose()),
+                                    None => Ok(()),
+                                },
+                            };
+                            controller.resync();
+                            if let Err(e) = result {
+                                println!("\n❌ Panel, palette or voice command failed: {}", e);
+                            }
+                        }
+                        
+                        // Cheat sheet with the bindings from config.toml, so remapped keys can be looked up while flying
+                        if pressed.contains(&keys.help) || actions.contains(&Action::Help) {
+                            println!();
+                            print_controls(&keys, matrix_mode, matrix_mode && game_offsets.fov.is_some(), &patches.names(),
+                                           game_offsets.skater.is_some(), matrix_mode && game_offsets.skater2.is_some(), !tunables.is_empty());
+                            print_gestures(&gestures);
+                            print_states(&[
+                                ("Camera patch", on_off(camera_patch_active(&process, &patches))),
+                                ("Game frozen", on_off(patches.is_applied(FREEZE))),
+                                ("HUD hidden", on_off(patches.is_applied(HUD))),
+                                ("Camera held", on_off(held)),
+                                ("Mouse look", on_off(controller.is_mouse_enabled())),
+                                ("Movement", format!("{:?}", controller.get_movement_mode()).to_lowercase()),
+                                ("Speed", format!("{:.1}", controller.get_speed())),
+                                ("Step mode", on_off(controller.is_stepping())),
+                                ("Orbit", on_off(controller.is_orbiting())),
+                                ("Follow", on_off(controller.is_following())),
+                                ("Two-skater framing", on_off(controller.is_framing())),
+                                ("Look-at target", on_off(controller.get_look_at().is_some())),
+                                ("VHS jitter", on_off(controller.is_jittering())),
+                                ("Handheld shake", on_off(controller.is_shaking())),
+                                ("Overlay", on_off(overlay.is_some())),
+                                ("Game instances", format!("{} attached{}", instances.len() + 1,
+                                                           if instances.is_mirroring() { ", mirroring" } else { "" })),
+                                ("In-game status", on_off(config.osd && process.internal().is_some_and(|internal| !internal.osd_failed()))),
+                                ("Telemetry", on_off(telemetry.is_some())),
+                                ("Path", format!("{} keyframe(s){}", camera_path.len(), match &playback {
+                                    Some(clock) if clock.is_paused() => ", paused",
+                                    Some(_) => ", playing",
+                                    None => "",
+                                })),
+                                ("Path playback", format!("{}x, {}", playback_rate, playback_repeat.name())),
+                                ("Timelapse", on_off(timelapse.is_some())),
+                                ("Script", script.as_ref().map_or("off".to_string(), |runner| runner.name().to_string())),
+                                ("Replay sync", match &replay_watch {
+                                    Some(watch) if watch.is_playing() => "on, replay playing".to_string(),
+                                    Some(_) => "on".to_string(),
+                                    None => "off".to_string(),
+                                }),
+                                ("Bookmarks", bookmarks.len().to_string()),
+                                ("Motion recording", match &recording {
+                                    Some(recording) => format!("{:.1}s", recording.elapsed()),
+                                    None => "off".to_string(),
+                                }),
+                            ]);
+                        }
+                        
+                        // Check for telemetry toggle
+                        if pressed.contains(&keys.telemetry_toggle) || actions.contains(&Action::TelemetryToggle) {
+                            toggle_telemetry(&mut telemetry);
+                        }
+                        
+                        // Check for screenshot
+                        if (pressed.contains(&keys.screenshot) || actions.contains(&Action::Screenshot))
+                            && screenshot(&process, base_addr, &config.screenshot_dir)
+                        {
+                            stats.screenshot_taken();
+                        }
+                        
+                        // Check for copying the camera transform
+                        if pressed.contains(&keys.copy_transform) || actions.contains(&Action::CopyTransform) {
+                            match controller.read_camera_matrix(&process, base_addr) {
+                                Ok(matrix) => {
+                                    let hfov = controller.get_horizontal_fov();
+                                    match copy_to_clipboard(&format_transform(&matrix, hfov)) {
+                                        Ok(()) => {
+                                            rumble.saved();
+                                            println!("\n📋 Camera at ({:.1}, {:.1}, {:.1}) copied to the clipboard", matrix.data[12], matrix.data[13], matrix.data[14]);
+                                        }
+                                        Err(e) => println!("\n❌ {}", e),
+                                    }
+                                    if !config.shots_file.is_empty() {
+                                        match append_shot(&config.shots_file, &matrix, hfov) {
+                                            Ok(()) => println!("   Added to {}", config.shots_file),
+                                            Err(e) => println!("   ❌ {}", e),
                                         }
-                                        mouse_toggle_pressed = true;
                                     }
+                                }
+                                Err(e) => println!("\n❌ Failed to read camera matrix: {}", e),
+                            }
+                        }
+                        
+                        // Check for path keyframe
+                        if pressed.contains(&keys.path_keyframe) || actions.contains(&Action::PathKeyframe) {
+                            match controller.read_camera_matrix(&process, base_addr) {
+                                Ok(matrix) => {
+                                    camera_path.add_keyframe(&matrix, config.keyframe_spacing);
+                                    rumble.saved();
+                                    println!("\n📌 Keyframe {} added at {:.1}s", camera_path.len(), camera_path.duration());
+                                }
+                                Err(e) => println!("\n❌ Failed to read camera matrix: {}", e),
+                            }
+                        }
+                        
+                        // Record the camera every tick until pressed again, the take becomes the current path
+                        if pressed.contains(&keys.path_record) || actions.contains(&Action::PathRecord) {
+                            if let Some(finished) = recording.take() {
+                                let recorded = finished.len();
+                                let mut take = finished.finish();
+                                if take.len() < 2 {
+                                    println!("\n⏹️ Recording stopped - the camera didn't move, nothing was kept");
                                 } else {
-                                    mouse_toggle_pressed = false;
+                                    take.metadata.stamp(offsets_path, author.as_deref());
+                                    let take_file = next_version_path(&config.path_file);
+                                    println!("\n⏹️ Recorded {:.1}s of motion ({} of {} samples after trimming the still ends)",
+                                             take.duration(), take.len(), recorded);
+                                    match take.save(&take_file) {
+                                        Ok(_) => {
+                                            rumble.saved();
+                                            println!("💾 Saved to {} - {} plays it back", take_file, keys.path_play.name());
+                                        }
+                                        Err(e) => println!("❌ {}", e),
+                                    }
+                                    camera_path = take;
                                 }
-                                
-                                // Check for patch toggle
-                                let p_key_state = unsafe { GetAsyncKeyState(VK_P) };
-                                let p_pressed = (p_key_state & 0x8000u16 as i16) != 0;
-                                let p_just_pressed = (p_key_state & 0x0001u16 as i16) != 0;
-                                
-                                if p_pressed || p_just_pressed {
-                                    if !patch_toggle_pressed {
-                                        match &mut camera_patch {
-                                            Some(patch) => {
-                                                if patch.is_applied {
-                                                    match process.restore_patch(patch) {
-                                                        Ok(_) => println!("\n🔧 Camera patch disabled - game will overwrite camera"),
-                                                        Err(e) => println!("\n❌ Failed to disable patch: {}", e),
-                                                    }
-                                                } else {
-                                                    // Re-apply the patch
-                                                    match process.get_camera_write_patch_address(base_addr) {
-                                                        Ok(patch_addr) => {
-                                                            match process.patch_with_nops(patch_addr, 2) {
-                                                                Ok(new_patch) => {
-                                                                    *patch = new_patch;
-                                                                    println!("\n🔧 Camera patch re-enabled - free camera active!");
-                                                                }
-                                                                Err(e) => println!("\n❌ Failed to re-apply patch: {}", e),
-                                                            }
-                                                        }
-                                                        Err(e) => println!("\n❌ Failed to get patch address: {}", e),
-                                                    }
-                                                }
-                                            }
-                                            None => {
-                                                // First time applying patch
-                                                match process.get_camera_write_patch_address(base_addr) {
-                                                    Ok(patch_addr) => {
-                                                        match process.patch_with_nops(patch_addr, 2) {
-                                                            Ok(patch) => {
-                                                                camera_patch = Some(patch);
-                                                                println!("\n🔧 Camera patch enabled - free camera active!");
-                                                            }
-                                                            Err(e) => println!("\n❌ Failed to apply patch: {}", e),
-                                                        }
-                                                    }
-                                                    Err(e) => println!("\n❌ Failed to get patch address: {}", e),
-                                                }
-                                            }
+                            } else if playback.is_some() || timelapse.is_some() {
+                                println!("\n⚠️ Stop the current playback ({}) before recording", keys.path_play.name());
+                            } else {
+                                recording = Some(MotionRecording::start());
+                                println!("\n⏺️ Recording the camera - fly the move, {} stops", keys.path_record.name());
+                            }
+                        }
+                        
+                        // Check for path playback toggle
+                        if pressed.contains(&keys.path_play) || actions.contains(&Action::PathPlay) {
+                            if playback.take().is_some() {
+                                controller.resync();
+                                println!("\n⏹️ Path playback stopped");
+                                if bake.take().is_some() {
+                                    println!("   Bake discarded");
+                                }
+                            } else if held {
+                                println!("\n⚠️ The camera is held - release it ({}) before playing the path", keys.hold_toggle.name());
+                            } else if camera_path.len() < 2 {
+                                println!("\n⚠️ Add at least two keyframes ({}) before playing the path", keys.path_keyframe.name());
+                            } else {
+                                playback = Some(PlaybackClock::new());
+                                stats.path_played();
+                                last_countdown_display = None;
+                                blend_out_start = None;
+                                bake = None;
+                                bookmark_transition = None;
+                                let cancelled = timelapse.take();
+                                println!("\n▶️ Playing {} keyframes over {:.1}s ({} interpolation, {}x, {})",
+                                         camera_path.len(), camera_path.duration(), config.interpolation.name(), playback_rate, playback_repeat.name());
+                                if let Some(cancelled) = cancelled {
+                                    println!("   Timelapse cancelled after {} of {} frames", cancelled.frames_done(), cancelled.frame_count());
+                                }
+                                warn_path_bounds(&camera_path, &config.bounds, config.interpolation);
+                                if !patches.is_applied(CAMERA_WRITE) {
+                                    println!("   💡 Enable the camera patch ({}) or the game will overwrite the playback", keys.patch_toggle.name());
+                                }
+                            }
+                        }
+                        
+                        // Pause, rate and repeat for path playback, the rate and repeat also apply to the next play
+                        if pressed.contains(&keys.playback_pause) || actions.contains(&Action::PlaybackPause) {
+                            match playback.as_mut().map(PlaybackClock::toggle_pause) {
+                                Some(true) => println!("\n⏸️ Path playback paused - {} resumes", keys.playback_pause.name()),
+                                Some(false) => println!("\n▶️ Path playback resumed"),
+                                None => println!("\n⚠️ No path is playing - {} plays it", keys.path_play.name()),
+                            }
+                            last_countdown_display = None;
+                        }
+                        if pressed.contains(&keys.playback_faster) || pressed.contains(&keys.playback_slower) {
+                            playback_rate = step_rate(playback_rate, if pressed.contains(&keys.playback_faster) { 1 } else { -1 });
+                            println!("\n⏩ Path playback at {}x", playback_rate);
+                            last_countdown_display = None;
+                        }
+                        if pressed.contains(&keys.playback_repeat) {
+                            playback_repeat = playback_repeat.next();
+                            println!("\n🔁 Path playback repeats: {}", playback_repeat.name());
+                        }
+                        
+                        // Check for path bake
+                        if pressed.contains(&keys.path_bake) || actions.contains(&Action::PathBake) {
+                            if playback.is_some() {
+                                println!("\n⚠️ Stop the current playback ({}) before baking", keys.path_play.name());
+                            } else if held {
+                                println!("\n⚠️ The camera is held - release it ({}) before baking", keys.hold_toggle.name());
+                            } else if camera_path.len() < 2 {
+                                println!("\n⚠️ Add at least two keyframes ({}) before baking the path", keys.path_keyframe.name());
+                            } else {
+                                playback = Some(PlaybackClock::new());
+                                stats.path_played();
+                                last_countdown_display = None;
+                                blend_out_start = None;
+                                bake = Some(PathBake::new());
+                                bookmark_transition = None;
+                                let cancelled = timelapse.take();
+                                println!("\n⏺️ Baking {} keyframes over {:.1}s - fly corrections on top of the path", camera_path.len(), camera_path.duration());
+                                if let Some(cancelled) = cancelled {
+                                    println!("   Timelapse cancelled after {} of {} frames", cancelled.frames_done(), cancelled.frame_count());
+                                }
+                                warn_path_bounds(&camera_path, &config.bounds, config.interpolation);
+                                if !patches.is_applied(CAMERA_WRITE) {
+                                    println!("   💡 Enable the camera patch ({}) or the game will overwrite the playback", keys.patch_toggle.name());
+                                }
+                            }
+                        }
+                        
+                        // Check for timelapse
+                        if pressed.contains(&keys.timelapse) || actions.contains(&Action::Timelapse) {
+                            if let Some(cancelled) = timelapse.take() {
+                                controller.resync();
+                                println!("\n⏹️ Timelapse cancelled after {} of {} frames", cancelled.frames_done(), cancelled.frame_count());
+                            } else if held {
+                                println!("\n⚠️ The camera is held - release it ({}) before capturing a timelapse", keys.hold_toggle.name());
+                            } else if camera_path.len() < 2 {
+                                println!("\n⚠️ Add at least two keyframes ({}) before capturing a timelapse", keys.path_keyframe.name());
+                            } else {
+                                let started = process.find_main_window()
+                                    .ok_or_else(|| "game window not found".to_string())
+                                    .and_then(|window| Timelapse::new(window, &camera_path, config.timelapse_interval_ms,
+                                                                      config.timelapse_settle_ms, &config.screenshot_dir));
+                                match started {
+                                    Ok(run) => {
+                                        playback = None;
+                                        bake = None;
+                                        bookmark_transition = None;
+                                        println!("\n🎞️ Capturing {} timelapse frames to {}", run.frame_count(), run.directory());
+                                        if !patches.is_applied(CAMERA_WRITE) {
+                                            println!("   💡 Enable the camera patch ({}) or the game will overwrite the camera", keys.patch_toggle.name());
+                                        }
+                                        stats.path_played();
+                                        timelapse = Some(run);
+                                    }
+                                    Err(e) => println!("\n❌ Failed to start the timelapse: {}", e),
+                                }
+                            }
+                        }
+                        
+                        // Load the next path from path_dir as the current path
+                        if pressed.contains(&keys.path_next) || actions.contains(&Action::PathNext) {
+                            match &mut library {
+                                _ if playback.is_some() || timelapse.is_some() => {
+                                    println!("\n⚠️ Stop the current playback ({}) before switching paths", keys.path_play.name());
+                                }
+                                Some(library) => match library.select_next() {
+                                    Some((name, path)) => {
+                                        println!("\n📚 {}: {} keyframe(s) over {:.1}s - {} plays it", name, path.len(), path.duration(), keys.path_play.name());
+                                        camera_path = path.clone();
+                                        warn_path_bounds(&camera_path, &config.bounds, config.interpolation);
+                                    }
+                                    None => println!("\n⚠️ No paths in {} yet", library.directory()),
+                                },
+                                None => println!("\n⚠️ Set path_dir in the config to switch between paths"),
+                            }
+                        }
+                        
+                        // Check for bookmark save
+                        if pressed.contains(&keys.bookmark_save) || actions.contains(&Action::BookmarkSave) {
+                            match controller.read_pose(&process, base_addr) {
+                                Ok(pose) => {
+                                    let number = bookmarks.save(pose);
+                                    rumble.saved();
+                                    println!("\n🔖 Bookmark {} saved - {} goes through the bookmarks", number, keys.bookmark_next.name());
+                                }
+                                Err(e) => println!("\n❌ Failed to read camera matrix: {}", e),
+                            }
+                        }
+                        
+                        // Go to the next bookmark, flying there over bookmark_transition_ms
+                        if pressed.contains(&keys.bookmark_next) || actions.contains(&Action::BookmarkNext) {
+                            if playback.is_some() || timelapse.is_some() {
+                                println!("\n⚠️ Stop the current playback ({}) before going to a bookmark", keys.path_play.name());
+                            } else if held {
+                                println!("\n⚠️ The camera is held - release it ({}) before going to a bookmark", keys.hold_toggle.name());
+                            } else if let Some((number, pose)) = bookmarks.select_next() {
+                                if config.bookmark_transition_ms == 0 {
+                                    match controller.write_pose(&process, base_addr, pose) {
+                                        Ok(_) => println!("\n🔖 Bookmark {}/{}", number, bookmarks.len()),
+                                        Err(e) => println!("\n❌ Failed to go to bookmark {}: {}", number, e),
+                                    }
+                                    controller.resync();
+                                } else {
+                                    match controller.read_pose(&process, base_addr) {
+                                        Ok(current) => {
+                                            let duration = std::time::Duration::from_millis(config.bookmark_transition_ms);
+                                            bookmark_transition = Some(BookmarkTransition::new(current, pose, duration));
+                                            println!("\n🔖 Flying to bookmark {}/{}", number, bookmarks.len());
                                         }
-                                        patch_toggle_pressed = true;
+                                        Err(e) => println!("\n❌ Failed to read camera matrix: {}", e),
+                                    }
+                                }
+                            } else {
+                                println!("\n⚠️ No bookmarks yet - {} saves the camera as one", keys.bookmark_save.name());
+                            }
+                        }
+                        
+                        // Go through the level's waypoints, flying there over waypoint_transition_ms
+                        let waypoint_forward = pressed.contains(&keys.waypoint_next) || actions.contains(&Action::WaypointNext);
+                        if waypoint_forward || pressed.contains(&keys.waypoint_previous) || actions.contains(&Action::WaypointPrevious) {
+                            let file = waypoint_file(&config.waypoint_dir, &config.level);
+                            if playback.is_some() || timelapse.is_some() {
+                                println!("\n⚠️ Stop the current playback ({}) before going to a waypoint", keys.path_play.name());
+                            } else if held {
+                                println!("\n⚠️ The camera is held - release it ({}) before going to a waypoint", keys.hold_toggle.name());
+                            } else if config.level.is_empty() {
+                                println!("\n⚠️ Set `level` in {} to load that level's waypoints", config_path);
+                            } else if let Err(e) = waypoints.load(&file) {
+                                println!("\n❌ {}", e);
+                            } else if let Some((number, waypoint)) = waypoints.step(waypoint_forward) {
+                                if config.waypoint_transition_ms == 0 {
+                                    bookmark_transition = None;
+                                    match controller.write_pose(&process, base_addr, &waypoint.pose()) {
+                                        Ok(_) => println!("\n🗺️ Waypoint {}/{}: {}", number, waypoints.len(), waypoint.name),
+                                        Err(e) => println!("\n❌ Failed to go to waypoint '{}': {}", waypoint.name, e),
                                     }
+                                    controller.resync();
                                 } else {
-                                    patch_toggle_pressed = false;
+                                    match controller.read_pose(&process, base_addr) {
+                                        Ok(current) => {
+                                            let duration = std::time::Duration::from_millis(config.waypoint_transition_ms);
+                                            bookmark_transition = Some(BookmarkTransition::new(current, &waypoint.pose(), duration));
+                                            println!("\n🗺️ Flying to waypoint {}/{}: {}", number, waypoints.len(), waypoint.name);
+                                        }
+                                        Err(e) => println!("\n❌ Failed to read camera matrix: {}", e),
+                                    }
                                 }
-                                
-                                // Update camera based on input
-                                match controller.update_camera(&process, base_addr) {
-                                    Ok(moved) => {
-                                        if moved {
-                                            // Get and display current position
-                                            if let Ok(current_pos) = process.get_camera_position(base_addr) {
-                                                // Only print if position changed significantly
-                                                let dx = (current_pos.x - last_pos_display.x).abs();
-                                                let dy = (current_pos.y - last_pos_display.y).abs();
-                                                let dz = (current_pos.z - last_pos_display.z).abs();
-                                                
-                                                if dx > 0.1 || dy > 0.1 || dz > 0.1 {
-                                                    let mouse_status = if controller.is_mouse_enabled() { "🖱️ ON" } else { "🖱️ OFF" };
-                                                    print!("\r📍 Camera: X:{:.1}, Y:{:.1}, Z:{:.1} | Mouse: {} | Speed: {:.1}   ", 
-                                                           current_pos.x, current_pos.y, current_pos.z, mouse_status, controller.get_speed());
-                                                    std::io::Write::flush(&mut std::io::stdout()).unwrap();
-                                                    last_pos_display = current_pos;
-                                                }
-                                            }
+                            } else {
+                                println!("\n⚠️ No waypoints in '{}'", file.display());
+                            }
+                        }
+                        
+                        // Step back through the undo history, one snapshot per press
+                        if pressed.contains(&keys.undo) || actions.contains(&Action::Undo) {
+                            if playback.is_some() || timelapse.is_some() {
+                                println!("\n⚠️ Stop the current playback ({}) before undoing a move", keys.path_play.name());
+                            } else if held {
+                                println!("\n⚠️ The camera is held - release it ({}) before undoing a move", keys.hold_toggle.name());
+                            } else {
+                                match controller.read_camera_matrix(&process, base_addr).map(|current| history.undo(&current)) {
+                                    Ok(Some((steps, matrix))) => {
+                                        bookmark_transition = None;
+                                        match controller.write_camera_matrix(&process, base_addr, &matrix) {
+                                            Ok(()) => println!("\n↩️ Camera back {} step(s) to X:{:.1}, Y:{:.1}, Z:{:.1}",
+                                                               steps, matrix.data[12], matrix.data[13], matrix.data[14]),
+                                            Err(e) => println!("\n❌ Failed to undo the camera move: {}", e),
                                         }
+                                        controller.resync();
                                     }
-                                    Err(e) => {
-                                        println!("\n❌ Camera control error: {}", e);
-                                        println!("This might happen if you're not in-game or the game state changed.");
-                                        break;
+                                    Ok(None) => println!("\n⚠️ Nothing to undo - the history starts here"),
+                                    Err(e) => println!("\n❌ Failed to read camera matrix: {}", e),
+                                }
+                            }
+                        }
+                        
+                        // Check for path clear
+                        if pressed.contains(&keys.path_clear) || actions.contains(&Action::PathClear) {
+                            if playback.take().is_some() | timelapse.take().is_some() {
+                                controller.resync();
+                            }
+                            bake = None;
+                            camera_path.clear();
+                            println!("\n🗑️ Path cleared");
+                        }
+                        
+                        // Check for path save
+                        if pressed.contains(&keys.path_save) || actions.contains(&Action::PathSave) {
+                            if camera_path.is_empty() {
+                                println!("\n⚠️ The path has no keyframes to save");
+                            } else {
+                                camera_path.metadata.stamp(offsets_path, author.as_deref());
+                                match camera_path.save(&config.path_file) {
+                                    Ok(_) => {
+                                        rumble.saved();
+                                        println!("\n💾 Saved {} keyframe(s) to {}", camera_path.len(), config.path_file);
                                     }
+                                    Err(e) => println!("\n❌ {}", e),
                                 }
-                                
-                                // Small delay to prevent excessive CPU usage
-                                std::thread::sleep(std::time::Duration::from_millis(16)); // ~60 FPS
                             }
                         }
-                        Err(e) => {
-                            println!("❌ Failed to read camera matrix: {}", e);
-                            // This might mean the matrix pointer chain is incorrect.
-                            
-                            // Fall back to position-only mode
-                            println!("\n📍 Falling back to position-only mode...");
-                            
-                            // Show the actual memory addresses
-                            match process.get_camera_addresses(base_addr) {
-                                Ok((x_addr, y_addr, z_addr)) => {
-                                    println!("\n🔍 Memory addresses:");
-                                    println!("   X: 0x{:X}", x_addr);
-                                    println!("   Y: 0x{:X}", y_addr);
-                                    println!("   Z: 0x{:X}", z_addr);
+                        
+                        // Check for mouse toggle
+                        if pressed.contains(&keys.mouse_toggle) || actions.contains(&Action::MouseToggle) {
+                            if controller.is_mouse_enabled() {
+                                controller.disable_mouse();
+                                println!("\n🖱️ Mouse look disabled");
+                            } else {
+                                controller.enable_mouse();
+                                println!("\n🖱️ Mouse look enabled - move mouse to look around");
+                            }
+                        }
+                        
+                        // Check for smooth movement toggle
+                        if pressed.contains(&keys.smooth_toggle) || actions.contains(&Action::SmoothToggle) {
+                            controller.toggle_movement_mode();
+                            match controller.get_movement_mode() {
+                                MovementMode::Smooth => println!("\n🎥 Smooth movement enabled - camera accelerates and glides"),
+                                MovementMode::Snappy => println!("\n🎥 Snappy movement enabled - fixed step per tick"),
+                            }
+                        }
+                        
+                        // Check for step mode toggle
+                        if pressed.contains(&keys.step_toggle) || actions.contains(&Action::StepToggle) {
+                            if controller.is_stepping() {
+                                controller.disable_steps();
+                                println!("\n🎥 Step mode disabled - keys move the camera while held");
+                            } else {
+                                controller.enable_steps(config.step_distance);
+                                let capture = if config.step_screenshot { ", a screenshot after each" } else { "" };
+                                println!("\n👣 Step mode enabled - each key tap moves the camera {:.1} units{}", config.step_distance, capture);
+                            }
+                        }
+                        
+                        // Check for orbit toggle
+                        if pressed.contains(&keys.orbit_toggle) || actions.contains(&Action::OrbitToggle) {
+                            if controller.is_orbiting() {
+                                controller.disable_orbit();
+                                println!("\n🎥 Orbit mode disabled - free flying");
+                            } else {
+                                // Orbit the skater when its position is known, otherwise the point in front of the camera
+                                let skater = game_offsets.skater.as_ref()
+                                    .and_then(|chain| process.get_skater_position(base_addr, chain).ok());
+                                let target = if skater.is_some() { "skater" } else { "point ahead" };
+                                match controller.read_camera_matrix(&process, base_addr) {
+                                    Ok(matrix) => {
+                                        let pivot = controller.enable_orbit(&matrix, skater, config.orbit_radius);
+                                        println!("\n🎯 Orbiting {} at X:{:.1}, Y:{:.1}, Z:{:.1}", target, pivot.x, pivot.y, pivot.z);
+                                    }
+                                    Err(e) => println!("\n❌ Failed to read camera matrix: {}", e),
+                                }
+                            }
+                        }
+                        
+                        // Check for follow toggle
+                        if pressed.contains(&keys.follow_toggle) || actions.contains(&Action::FollowToggle) {
+                            if controller.is_following() {
+                                controller.disable_follow();
+                                println!("\n🎥 Follow mode disabled - free flying");
+                            } else {
+                                match controller.enable_follow(&process, base_addr, config.follow_smoothing) {
+                                    Ok(_) => println!("\n🛹 Following the skater - movement keys adjust the offset"),
+                                    Err(e) => println!("\n❌ Could not follow the skater: {}", e),
+                                }
+                            }
+                        }
+                        
+                        // Check for two-skater framing toggle
+                        if pressed.contains(&keys.framing_toggle) || actions.contains(&Action::FramingToggle) {
+                            if controller.is_framing() {
+                                controller.disable_framing();
+                                println!("\n🎥 Framing disabled - free flying");
+                            } else {
+                                match controller.enable_framing(&process, base_addr, config.framing_margin, config.framing_elevation,
+                                                                config.framing_min_distance, config.follow_smoothing) {
+                                    Ok(_) => println!("\n🤼 Framing both skaters - the camera keeps them in shot"),
+                                    Err(e) => println!("\n❌ Could not frame both skaters: {}", e),
+                                }
+                            }
+                        }
+                        
+                        // Check for look-at toggle
+                        if pressed.contains(&keys.look_at_toggle) || actions.contains(&Action::LookAtToggle) {
+                            if controller.get_look_at().is_some() {
+                                controller.set_look_at(None);
+                                println!("\n👁️ Look-at target cleared");
+                            } else {
+                                match controller.read_camera_matrix(&process, base_addr).map(|matrix| matrix.get_position()) {
+                                    Ok(target) => {
+                                        println!("\n👁️ Looking at X:{:.1}, Y:{:.1}, Z:{:.1} - fly away and the camera keeps it centred",
+                                                 target.x, target.y, target.z);
+                                        controller.set_look_at(Some(target));
+                                    }
+                                    Err(e) => println!("\n❌ Failed to read camera position: {}", e),
+                                }
+                            }
+                        }
+                        
+                        // Check for jitter toggle
+                        if pressed.contains(&keys.jitter_toggle) || actions.contains(&Action::JitterToggle) {
+                            if controller.is_jittering() {
+                                // Put the clean camera back so the last offset doesn't stick
+                                let clean = controller.read_camera_matrix(&process, base_addr);
+                                controller.set_jitter(None);
+                                if let Ok(matrix) = clean {
+                                    let _ = process.set_camera_matrix(base_addr, &matrix);
+                                }
+                                println!("\n📹 VHS jitter off");
+                            } else {
+                                controller.set_jitter(Some(Jitter::new(config.jitter_position, config.jitter_rotation, config.jitter_frequency)));
+                                println!("\n📹 VHS jitter on - the camera drifts like a hand-held camcorder");
+                            }
+                        }
+                        
+                        // Check for shake toggle
+                        if pressed.contains(&keys.shake_toggle) || actions.contains(&Action::ShakeToggle) {
+                            if controller.is_shaking() {
+                                // Put the clean camera back so the last offset doesn't stick
+                                let clean = controller.read_camera_matrix(&process, base_addr);
+                                controller.set_shake(None);
+                                if let Ok(matrix) = clean {
+                                    let _ = process.set_camera_matrix(base_addr, &matrix);
+                                }
+                                println!("\n🎥 Handheld shake off");
+                            } else {
+                                controller.set_shake(Some(Shake::new(config.shake_position, config.shake_rotation, config.shake_frequency)));
+                                println!("\n🎥 Handheld shake on - the camera sways like it is held on the shoulder");
+                            }
+                        }
+                        
+                        // Check for overlay toggle
+                        if pressed.contains(&keys.overlay_toggle) || actions.contains(&Action::OverlayToggle) {
+                            if overlay.take().is_some() {
+                                println!("\n🎯 Overlay off");
+                            } else {
+                                match open_overlay(&process) {
+                                    Ok(opened) => {
+                                        overlay = Some(opened);
+                                        println!("\n🎯 Overlay on - crosshair and thirds grid over the game");
+                                    }
+                                    Err(e) => println!("\n❌ {}", e),
+                                }
+                            }
+                        }
+                        
+                        // Check for mirror toggle
+                        if pressed.contains(&keys.mirror_toggle) || actions.contains(&Action::MirrorToggle) {
+                            if instances.is_empty() {
+                                println!("\n⚠️ Only one game instance is attached - start with --all-instances to use the others");
+                            } else {
+                                match instances.toggle_mirror() {
+                                    Ok(true) => println!("\n🪞 Mirroring the camera into PID {}", instances.pids().iter()
+                                        .map(u32::to_string).collect::<Vec<_>>().join(", ")),
+                                    Ok(false) => println!("\n🪞 Mirroring off - the other instances have their own cameras back"),
+                                    Err(e) => println!("\n❌ Failed to give a mirrored instance its camera back: {}", e),
+                                }
+                            }
+                        }
+                        
+                        // Check for the next instance, the session ends here and starts again on it
+                        if pressed.contains(&keys.instance_next) || actions.contains(&Action::InstanceNext) {
+                            if instances.is_empty() {
+                                println!("\n⚠️ Only one game instance is attached - start with --all-instances to use the others");
+                            } else {
+                                println!("\n🎮 Handing the controls to the next game instance");
+                                switch_instance = true;
+                                break;
+                            }
+                        }
+                        
+                        // Check for patch toggle
+                        if pressed.contains(&keys.patch_toggle) || actions.contains(&Action::PatchToggle) {
+                            if patch_pending {
+                                println!("\n🔧 Camera patch cancelled - it was still waiting for gameplay");
+                                patch_pending = false;
+                            } else if let Some(reason) = patch_blocker(&process, base_addr, &patches, &guard) {
+                                // Patching during a movie or loading screen crashes some builds
+                                println!("\n⏳ The camera patch waits until gameplay is running: {} ({} again to cancel)", reason, keys.patch_toggle.name());
+                                patch_pending = true;
+                            } else {
+                                toggle_patch(&process, base_addr, &mut patches, &rumble, &mut stats, process.internal().is_some());
+                            }
+                        }
+                        if patch_pending && guard.ready(&process, base_addr) {
+                            println!("\n▶️ Gameplay is running - applying the camera patch");
+                            patch_pending = false;
+                            toggle_patch(&process, base_addr, &mut patches, &rumble, &mut stats, process.internal().is_some());
+                        }
+                        
+                        // Hand the camera back while nobody is flying it. Every device counts through the commands polled
+                        // from it, and playback, timelapses, scripts, plugins, transitions, follow, framing and a held camera
+                        // count as flying.
+                        let has_input = controller.has_input();
+                        let flying = !pressed.is_empty() || !actions.is_empty() || controller.has_motion() || held
+                            || playback.is_some() || timelapse.is_some() || script.is_some() || plugin_wrote
+                            || bookmark_transition.is_some() || controller.is_following() || controller.is_framing();
+                        if let Some(switch) = &mut dead_mans_switch
+                            && let Some(event) = switch.tick(flying, key_events.has_focus(), camera_patch_active(&process, &patches))
+                        {
+                            dead_mans_event(event, &process, base_addr, &mut patches, &guard, &mut patch_pending, process.internal().is_some());
+                        }
+                        
+                        // Check for freeze toggle
+                        if pressed.contains(&keys.freeze_toggle) || actions.contains(&Action::FreezeToggle) {
+                            toggle_freeze(&mut patches);
+                        }
+                        
+                        // Check for HUD toggle
+                        if pressed.contains(&keys.hud_toggle) || actions.contains(&Action::HudToggle) {
+                            toggle_hud(&mut patches);
+                        }
+                        
+                        // Check for skater teleport
+                        if pressed.contains(&keys.skater_teleport) || actions.contains(&Action::SkaterTeleport) {
+                            teleport_skater(&process, base_addr, game_offsets.skater.as_ref(), config.teleport_height);
+                        }
+                        
+                        // Check for camera to skater, a held camera stays put
+                        if pressed.contains(&keys.camera_to_skater) || actions.contains(&Action::CameraToSkater) {
+                            if held {
+                                println!("\n⚠️ The camera is held - release it ({}) before moving it to the skater", keys.hold_toggle.name());
+                            } else if !matrix_mode {
+                                snap_camera_to_skater(&process, base_addr, game_offsets.skater.as_ref(), config.teleport_height);
+                            } else {
+                                match controller.reset_to_skater(&process, base_addr, config.teleport_height) {
+                                    Ok(target) => println!("\n📍 Camera snapped to the skater at X:{:.1}, Y:{:.1}, Z:{:.1}",
+                                                           target.x, target.y, target.z),
+                                    Err(e) => println!("\n❌ Failed to move the camera to the skater: {}", e),
+                                }
+                            }
+                        }
+                        
+                        // The skater stats, for the status line and everything after it that shows them
+                        let skater_stats = (!game_state.is_empty()).then(|| game_state.read(&process, base_addr));
+                        let stats_summary = skater_stats.as_ref().and_then(GameState::summary);
+                        
+                        if held {
+                            // Held: the last pose stays put, the patch keeps the game from moving it
+                        } else if let Some(run) = &mut timelapse {
+                            // The timelapse poses the camera itself, one frame at a time
+                            match run.tick(&process, base_addr, &mut controller, &camera_path, config.interpolation) {
+                                Ok(false) => {
+                                    print!("\r🎞️ Frame {}/{}   ", run.frames_done(), run.frame_count());
+                                    std::io::Write::flush(&mut std::io::stdout()).unwrap();
+                                }
+                                Ok(true) => {
+                                    println!("\n✅ Timelapse finished - {} frames in {}", run.frame_count(), run.directory());
+                                    timelapse = None;
+                                    controller.resync();
+                                }
+                                Err(e) => {
+                                    println!("\n❌ Timelapse stopped at frame {}: {}", run.frames_done() + 1, e);
+                                    log::error!("Timelapse stopped at frame {}: {}", run.frames_done() + 1, e);
+                                    rumble.error();
+                                    stats.error();
+                                    timelapse = None;
+                                    controller.resync();
+                                }
+                            }
+                        } else if let Some(transition) = &bookmark_transition {
+                            // On the way to a bookmark, movement keys and devices take over where the camera is
+                            if controller.has_motion() {
+                                println!("\n✋ Manual input - stopped on the way to the bookmark or waypoint");
+                                bookmark_transition = None;
+                                controller.resync();
+                            } else {
+                                let result = controller.write_pose(&process, base_addr, &transition.pose());
+                                
+                                if let Err(e) = result {
+                                    println!("\n❌ Bookmark transition error: {}", e);
+                                    log::error!("Bookmark transition error: {}", e);
+                                    rumble.error();
+                                    stats.error();
+                                    bookmark_transition = None;
+                                    controller.resync();
+                                } else if transition.is_finished() {
+                                    bookmark_transition = None;
+                                    controller.resync();
                                 }
-                                Err(e) => println!("❌ Failed to get camera addresses: {}", e),
                             }
+                        } else if let Some(clock) = &mut playback {
+                            // Path playback drives the camera, manual controls resume afterwards. A bake plays once,
+                            // straight through, so the take it records stays in order.
+                            let (repeat, scrub) = match bake {
+                                Some(_) => (PlaybackRepeat::Once, 0.0),
+                                None => (playback_repeat, get_scrub_delta(&keys) as f32 * config.scrub_speed),
+                            };
+                            let elapsed = clock.tick(camera_path.duration(), playback_rate, repeat, scrub);
+                            let paused = clock.is_paused();
                             
-                            // Start basic camera control (position-only)
-                            println!("\n🎮 Starting Basic Camera Mode!");
-                            println!("===============================");
-                            println!("Controls:");
-                            println!("   I/K - Move Forward/Backward");
-                            println!("   J/L - Move Left/Right");
-                            println!("   U/O - Move Up/Down");
-                            println!("   P   - Toggle Camera Write Patch");
-                            println!("   Page Up/Down - Increase/Decrease Speed");
-                            println!("");
-                            println!("💡 Switch to Skate3 window and use the controls!");
-                            println!("   Camera will respond to key presses in real-time.");
-                            println!("   Close this terminal window to stop the program.");
-                            println!("");
+                            // Movement keys take over mid-take instead of being ignored
+                            if config.playback_interrupt && bake.is_none() && blend_out_start.is_none()
+                                && controller.has_motion()
+                            {
+                                println!("\n✋ Manual input - handing the camera back over {}ms", config.blend_out_ms);
+                                controller.resync();
+                                blend_out_start = Some(std::time::Instant::now());
+                            }
                             
-                            let mut basic_controller = BasicCameraController::new(10.0); // Move speed: 10 units per press
-                            let mut last_pos_display = cam_pos.clone();
-                            let mut patch_toggle_pressed = false;
-                            let mut camera_patch: Option<CodePatch> = None;
+                            let blend_weight = blend_out_start.map(|blend_start| {
+                                let t = (blend_start.elapsed().as_secs_f32() * 1000.0 / config.blend_out_ms.max(1) as f32).min(1.0);
+                                t * t * (3.0 - 2.0 * t) // Ease in and out
+                            });
                             
-                            loop {
-                                // Check for patch toggle
-                                let p_key_state = unsafe { GetAsyncKeyState(VK_P) };
-                                let p_pressed = (p_key_state & 0x8000u16 as i16) != 0;
-                                let p_just_pressed = (p_key_state & 0x0001u16 as i16) != 0;
-                                
-                                if p_pressed || p_just_pressed {
-                                    if !patch_toggle_pressed {
-                                        match &mut camera_patch {
-                                            Some(patch) => {
-                                                if patch.is_applied {
-                                                    match process.restore_patch(patch) {
-                                                        Ok(_) => println!("\n🔧 Camera patch disabled - game will overwrite camera"),
-                                                        Err(e) => println!("\n❌ Failed to disable patch: {}", e),
-                                                    }
-                                                } else {
-                                                    // Re-apply the patch
-                                                    match process.get_camera_write_patch_address(base_addr) {
-                                                        Ok(patch_addr) => {
-                                                            match process.patch_with_nops(patch_addr, 2) {
-                                                                Ok(new_patch) => {
-                                                                    *patch = new_patch;
-                                                                    println!("\n🔧 Camera patch re-enabled - free camera active!");
-                                                                }
-                                                                Err(e) => println!("\n❌ Failed to re-apply patch: {}", e),
-                                                            }
-                                                        }
-                                                        Err(e) => println!("\n❌ Failed to get patch address: {}", e),
-                                                    }
-                                                }
-                                            }
-                                            None => {
-                                                // First time applying patch
-                                                match process.get_camera_write_patch_address(base_addr) {
-                                                    Ok(patch_addr) => {
-                                                        match process.patch_with_nops(patch_addr, 2) {
-                                                            Ok(patch) => {
-                                                                camera_patch = Some(patch);
-                                                                println!("\n🔧 Camera patch enabled - free camera active!");
-                                                                println!("   You can now move the camera without pausing the game!");
-                                                            }
-                                                            Err(e) => println!("\n❌ Failed to apply patch: {}", e),
-                                                        }
-                                                    }
-                                                    Err(e) => println!("\n❌ Failed to get patch address: {}", e),
-                                                }
+                            let result = if let Some(bake) = &mut bake {
+                                bake_tick(&process, base_addr, &mut controller, &camera_path, bake, elapsed, config.interpolation)
+                            } else {
+                                match blend_weight {
+                                    None => controller.read_camera_matrix(&process, base_addr).and_then(|mut matrix| {
+                                        camera_path.apply(elapsed, config.interpolation, &mut matrix);
+                                        controller.write_camera_matrix(&process, base_addr, &matrix)
+                                    }),
+                                    // Let the controller move the camera, then mix the path pose back in
+                                    Some(weight) => controller.update_camera(&process, base_addr)
+                                        .and_then(|_| controller.read_camera_matrix(&process, base_addr))
+                                        .and_then(|mut matrix| {
+                                            if let Some(path_pose) = camera_path.sample(elapsed, config.interpolation) {
+                                                matrix.set_pose(&blend(&path_pose, &matrix.pose(), weight));
                                             }
-                                        }
-                                        patch_toggle_pressed = true;
-                                    }
-                                } else {
-                                    patch_toggle_pressed = false;
+                                            controller.write_camera_matrix(&process, base_addr, &matrix)
+                                        }),
                                 }
+                            };
+                            
+                            // Countdown so the operator knows when to cue the in-game action, in real seconds at the current rate
+                            let remaining = (camera_path.duration() - elapsed).max(0.0) / playback_rate;
+                            let countdown = (camera_path.keyframe_index_at(elapsed) + 1, (remaining * 10.0).ceil() as u32);
+                            if blend_weight.is_none() && last_countdown_display != Some(countdown) {
+                                let rate = if playback_rate != 1.0 { format!(" | {}x", playback_rate) } else { String::new() };
+                                print!("\r{} Keyframe {}/{} | {:.1}s remaining{}   ", if paused { "⏸️" } else { "▶️" },
+                                       countdown.0, camera_path.len(), remaining, rate);
+                                std::io::Write::flush(&mut std::io::stdout()).unwrap();
+                                last_countdown_display = Some(countdown);
+                            }
+                            
+                            if let Err(e) = result {
+                                println!("\n❌ Path playback error: {}", e);
+                                log::error!("Path playback error: {}", e);
+                                rumble.error();
+                                stats.error();
+                                playback = None;
+                                bake = None;
+                                controller.resync();
+                            } else if blend_weight.is_some_and(|weight| weight >= 1.0) {
+                                println!("\n🎮 Manual control restored");
+                                playback = None;
+                                controller.resync();
+                            } else if blend_weight.is_none() && elapsed >= camera_path.duration() {
+                                println!("\n⏹️ Path playback finished");
+                                playback = None;
+                                controller.resync();
                                 
-                                // Update camera based on input
-                                match basic_controller.update_camera(&process, base_addr) {
-                                    Ok(moved) => {
-                                        if moved {
-                                            // Get and display current position
-                                            if let Ok(current_pos) = process.get_camera_position(base_addr) {
-                                                // Only print if position changed significantly
-                                                let dx = (current_pos.x - last_pos_display.x).abs();
-                                                let dy = (current_pos.y - last_pos_display.y).abs();
-                                                let dz = (current_pos.z - last_pos_display.z).abs();
-                                                
-                                                if dx > 0.1 || dy > 0.1 || dz > 0.1 {
-                                                    print!("\r📍 Camera: X:{:.1}, Y:{:.1}, Z:{:.1} | Speed: {:.1}   ", 
-                                                           current_pos.x, current_pos.y, current_pos.z, basic_controller.get_speed());
-                                                    std::io::Write::flush(&mut std::io::stdout()).unwrap();
-                                                    last_pos_display = current_pos;
+                                // Keep the baked take as a new version and continue refining from it
+                                if let Some(finished) = bake.take() {
+                                    match controller.read_camera_matrix(&process, base_addr) {
+                                        Ok(matrix) => {
+                                            let mut baked = finished.finish(camera_path.duration(), &matrix);
+                                            // Same shot, new take: keep who and where, but not when
+                                            baked.metadata = PathMetadata { created: None, ..camera_path.metadata.clone() };
+                                            baked.metadata.stamp(offsets_path, author.as_deref());
+                                            let baked_file = next_version_path(&config.path_file);
+                                            match baked.save(&baked_file) {
+                                                Ok(_) => {
+                                                    rumble.saved();
+                                                    println!("💾 Baked {} keyframe(s) to {}", baked.len(), baked_file);
                                                 }
+                                                Err(e) => println!("❌ {}", e),
                                             }
+                                            camera_path = baked;
                                         }
+                                        Err(e) => println!("❌ Failed to read camera matrix, bake discarded: {}", e),
                                     }
-                                    Err(e) => {
-                                        println!("\n❌ Camera control error: {}", e);
-                                        println!("This might happen if you're not in-game or the game state changed.");
-                                        break;
+                                }
+                            }
+                        } else {
+                            // Update camera based on input
+                            match controller.update_camera(&process, base_addr) {
+                                Ok(moved) => {
+                                    // Display the current position, also after a stop if the rate held the last move back, and
+                                    // the skater stats as they change
+                                    if (moved || status_line.is_pending() || stats_summary.is_some())
+                                        && let Ok(current_pos) = process.get_camera_position(base_addr)
+                                        && status_line.update(&current_pos, controller.get_fov(), stats_summary.as_deref())
+                                    {
+                                        // Mouse look and zoom need the matrix
+                                        let mouse_status = match (matrix_mode, controller.is_mouse_enabled()) {
+                                            (false, _) => String::new(),
+                                            (true, true) => " | Mouse: 🖱️ ON".to_string(),
+                                            (true, false) => " | Mouse: 🖱️ OFF".to_string(),
+                                        };
+                                        let fov_status = match (controller.get_fov(), controller.get_horizontal_fov()) {
+                                            (Some(fov), Some(hfov)) => format!(" | FOV: {:.2} ({:.1}° H)", fov, hfov),
+                                            _ => String::new(),
+                                        };
+                                        let stats_status = stats_summary.as_ref().map_or(String::new(), |summary| format!(" | {}", summary));
+                                        status_line.show(&format!("📍 Camera: X:{:.1}, Y:{:.1}, Z:{:.1}{} | Speed: {:.1}{}{}",
+                                                                  current_pos.x, current_pos.y, current_pos.z, mouse_status, controller.get_speed(),
+                                                                  fov_status, stats_status));
                                     }
                                 }
-                                
-                                // Small delay to prevent excessive CPU usage
-                                std::thread::sleep(std::time::Duration::from_millis(16)); // ~60 FPS
+                                Err(e) => {
+                                    println!("\n❌ Camera control error: {}", e);
+                                    log::error!("Camera control error: {}", e);
+                                    rumble.error();
+                                    stats.error();
+                                    println!("This might happen if you're not in-game or the game state changed.");
+                                    break;
+                                }
+                            }
+                        }
+                        
+                        update_tunables(&mut tunables, &process, base_addr, &keys);
+                        
+                        // The in-game status line is the overlay's telemetry, drawn by the hook
+                        let osd = process.internal().filter(|_| config.osd);
+                        if let (Some(file), Some(stats)) = (&mut stats_file, &skater_stats)
+                            && let Some(e) = file.write(stats)
+                        {
+                            println!("\n⚠️ {}", e);
+                        }
+                        if (panel.is_some() || overlay.is_some() || osd.is_some())
+                            && let Ok(position) = process.get_camera_position(base_addr)
+                        {
+                            let overlay_state = OverlayState {
+                                position: [position.x, position.y, position.z],
+                                speed: controller.get_speed(),
+                                fov: controller.get_horizontal_fov(),
+                                patch: camera_patch_active(&process, &patches),
+                                stats: skater_stats.clone(),
+                            };
+                            if let Some(panel) = &mut panel {
+                                panel.publish(PanelState {
+                                    position: [position.x, position.y, position.z],
+                                    speed: controller.get_speed(),
+                                    sensitivity: controller.get_mouse_sensitivity(),
+                                    fov: controller.get_horizontal_fov(),
+                                    patch: camera_patch_active(&process, &patches),
+                                });
+                                panel.publish_path(&camera_path);
+                            }
+                            if let Some(internal) = osd {
+                                internal.set_osd(&overlay_state.telemetry());
+                            }
+                            if let Some(overlay) = &overlay {
+                                overlay.publish(overlay_state);
+                            }
+                        }
+                        if osd.is_none()
+                            && let Some(internal) = process.internal()
+                        {
+                            internal.set_osd("");
+                        }
+                        
+                        // Plugins see the camera after everything else moved it and can move it once more
+                        plugin_wrote = false;
+                        if !plugins.is_empty() {
+                            if held {
+                                plugins.pause();
+                            } else if let Ok(matrix) = controller.read_camera_matrix(&process, base_addr) {
+                                let skater = game_offsets.skater.as_ref().and_then(|chain| process.get_skater_position(base_addr, chain).ok());
+                                let mut context = CameraContext {
+                                    matrix: matrix.data,
+                                    fov: controller.get_horizontal_fov().unwrap_or(0.0),
+                                    skater: skater.as_ref().map_or([0.0; 3], |skater| [skater.x, skater.y, skater.z]),
+                                    has_skater: skater.is_some() as u32,
+                                    patch: camera_patch_active(&process, &patches) as u32,
+                                    ..Default::default()
+                                };
+                                for e in plugins.frame(&mut context) {
+                                    println!("\n❌ {} - it was unloaded", e);
+                                }
+                                plugin_wrote = context.write != 0;
+                                if plugin_wrote
+                                    && let Err(e) = controller.write_camera_matrix(&process, base_addr, &context.matrix())
+                                {
+                                    log::warn!("Plugin camera write failed: {}", e);
+                                }
+                            }
+                        }
+                        
+                        // The other instances get the same camera
+                        if instances.is_mirroring()
+                            && let Ok(matrix) = process.get_camera_matrix(base_addr)
+                        {
+                            for e in instances.mirror(&matrix, camera_patch_active(&process, &patches)) {
+                                println!("\n⚠️ Mirroring failed for {}", e);
+                            }
+                        }
+                        
+                        // Latest state for the running script
+                        if let Some(runner) = &script
+                            && let Ok(state) = script_state(&process, base_addr, &controller, &patches, game_offsets.skater.as_ref())
+                        {
+                            runner.publish(state);
+                        }
+                        
+                        // Latest state for remote clients
+                        if let Some(remote) = &remote
+                            && let Ok(state) = camera_state(&process, base_addr, &controller, &patches, skater_stats)
+                        {
+                            remote.publish(state);
+                        }
+                        
+                        // Capture each step once the game has drawn it
+                        if controller.take_step() && config.step_screenshot {
+                            step_shot_at = Some(std::time::Instant::now());
+                        }
+                        if step_shot_at.is_some_and(|at| at.elapsed().as_millis() >= config.timelapse_settle_ms as u128) {
+                            step_shot_at = None;
+                            if screenshot(&process, base_addr, &config.screenshot_dir) {
+                                stats.screenshot_taken();
                             }
                         }
+                        
+                        // New and changed files in path_dir, the selected path follows its file unless it is playing
+                        if let Some(library) = &mut library {
+                            for change in library.poll() {
+                                match change {
+                                    LibraryChange::Added(name) => println!("\n📚 New path in {}: {}", library.directory(), name),
+                                    LibraryChange::Changed(name) => {
+                                        println!("\n📚 {} changed", name);
+                                        if library.selected() == Some(name.as_str())
+                                            && playback.is_none()
+                                            && timelapse.is_none()
+                                            && let Some(path) = library.get(&name)
+                                        {
+                                            camera_path = path.clone();
+                                            println!("   Reloaded as the current path, {} keyframe(s) over {:.1}s", camera_path.len(), camera_path.duration());
+                                        }
+                                    }
+                                    LibraryChange::Removed(name) => println!("\n📚 {} was removed from {}", name, library.directory()),
+                                    LibraryChange::Failed(_, e) => println!("\n❌ {}", e),
+                                }
+                            }
+                        }
+                        
+                        // Record the current pose every tick while telemetry is active, only the position without the matrix
+                        let matrix = process.get_camera_matrix(base_addr).ok().filter(|_| telemetry.is_some());
+                        if let Some(recorder) = &mut telemetry
+                            && let Ok(position) = process.get_camera_position(base_addr)
+                            && let Err(e) = recorder.record(&position, matrix.as_ref(), controller.refresh_fov(&process, base_addr).ok().flatten())
+                        {
+                            println!("\n❌ Telemetry error: {}", e);
+                            toggle_telemetry(&mut telemetry);
+                        }
+                        
+                        if let Ok(position) = process.get_camera_position(base_addr) {
+                            stats.track(&position);
+                        }
+                        if let Ok(matrix) = controller.read_camera_matrix(&process, base_addr) {
+                            if !held {
+                                history.track(&matrix);
+                            }
+                            if let Some(recording) = &mut recording {
+                                recording.record(&matrix);
+                            }
+                        }
+                        title.update(|| attached_title(&process, base_addr, &patches, held));
+                        fov_restore.track(&controller);
+                        
+                        // Small delay to prevent excessive CPU usage, longer while the game has the camera and nothing
+                        // is going on. Key presses queue up in the raw input thread, so none are lost. The polling
+                        // fallback only sees keys held at a tick, so it keeps the short one.
+                        let idle = !flying && !patch_pending && telemetry.is_none() && recording.is_none()
+                            && !camera_patch_active(&process, &patches) && !has_input && !key_events.is_polling();
+                        std::thread::sleep(if idle { idle_poll_interval.unwrap_or(poll_interval) } else { poll_interval });
+                    }
+                    
+                    if telemetry.is_some() {
+                        toggle_telemetry(&mut telemetry);
+                    }
+                    
+                    if let Err(e) = controller.restore_fov(&process, base_addr) {
+                        println!("❌ Failed to restore the FOV: {}", e);
+                    }
+                    fov_restore.track(&controller);
+                    restore_game(&process, base_addr, &mut patches);
+                    if let Err(e) = instances.release() {
+                        println!("❌ Failed to give a mirrored instance its camera back: {}", e);
                     }
                 }
                 Err(e) => {
@@ -346,6 +3090,66 @@ fn main() {
         }
         Err(e) => {
             println!("❌ Failed to get base address: {}", e);
+            log::error!("Failed to get base address: {}", e);
+        }
+    }
+    
+    stats.print();
+    if !config.session_log.is_empty()
+        && let Err(e) = stats.append_to(&config.session_log)
+    {
+        println!("⚠️ {}", e);
+    }
+    title.set(format!("{} | detached", APP_TITLE));
+    log::info!("Detached from PID {}", process.pid());
+    
+    // Worker threads are joined before a pending close event lets the process end
+    drop(key_events);
+    drop(rumble);
+    switch_instance.then_some(process)
+}
+
+// Every other running copy of the game `process` is, for --all-instances
+fn find_other_instances(process: &ProcessHandle) -> Vec<ProcessHandle> {
+    let pids = match find_processes_by_name(process.game().process_name()) {
+        Ok(pids) => pids,
+        Err(e) => {
+            println!("⚠️ {} - attaching to one instance only", e);
+            return Vec::new();
+        }
+    };
+    pids.into_iter()
+        .filter(|&pid| pid != process.pid())
+        .filter_map(|pid| match ProcessHandle::from_pid(pid, process.access()) {
+            Ok(other) => Some(other),
+            Err(e) => {
+                println!("⚠️ Skipping PID {}: {}", pid, e);
+                None
+            }
+        })
+        .collect()
+}
+
+// The controls drive one instance at a time, the instance key ends the session and starts one on the next
+fn run_instances(process: ProcessHandle, all_instances: bool, offsets_path: &str, config_path: &str, config: &Config,
+                 internal: bool, mut animation: Option<Animation>) {
+    let _session = shutdown::session();
+    let mut instances = Instances::new(if all_instances { find_other_instances(&process) } else { Vec::new() });
+    if !instances.is_empty() {
+        println!("🎮 {} game instances attached - {} drives the next one, {} mirrors the camera into all of them",
+                 instances.len() + 1, config.keys.instance_next.name(), config.keys.mirror_toggle.name());
+    }
+    
+    let mut driven = process;
+    while let Some(mut process) = run_free_cam(driven, &mut instances, offsets_path, config_path, config, internal, animation.as_mut()) {
+        if let Err(e) = instances.next(&mut process) {
+            println!("\n❌ {}", e);
+            break;
         }
+        println!("\n🎮 Driving PID {} now", process.pid());
+        driven = process;
     }
+    
+    // Mirrored instances get their camera patch back before the session lets a pending close event end the process
+    drop(instances);
 }
@@ -0,0 +1,44 @@
+// glam at the boundary to the game's camera layout. CameraMatrix::data is column-major like glam's Mat4:
+// columns 0/1/2 are the camera's right, up and forward axes, forward being the way the camera looks and the
+// forward key moves, and column 3 is the position. Math happens in glam types, the conversions below keep
+// the flat arrays the game and the file formats use.
+
+pub use glam::{EulerRot, Mat3, Mat4, Quat, Vec3};
+
+use crate::camera::{CameraMatrix, CameraPosition, Quaternion};
+
+impl From<&CameraPosition> for Vec3 {
+    fn from(position: &CameraPosition) -> Self {
+        Vec3::new(position.x, position.y, position.z)
+    }
+}
+
+impl From<Vec3> for CameraPosition {
+    fn from(vector: Vec3) -> Self {
+        CameraPosition::new(vector.x, vector.y, vector.z)
+    }
+}
+
+impl From<Quaternion> for Quat {
+    fn from(q: Quaternion) -> Self {
+        Quat::from_xyzw(q.x, q.y, q.z, q.w)
+    }
+}
+
+impl From<Quat> for Quaternion {
+    fn from(q: Quat) -> Self {
+        Quaternion::new(q.x, q.y, q.z, q.w)
+    }
+}
+
+impl From<&CameraMatrix> for Mat4 {
+    fn from(matrix: &CameraMatrix) -> Self {
+        Mat4::from_cols_array(&matrix.data)
+    }
+}
+
+impl From<Mat4> for CameraMatrix {
+    fn from(matrix: Mat4) -> Self {
+        CameraMatrix { data: matrix.to_cols_array() }
+    }
+}
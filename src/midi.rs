@@ -0,0 +1,231 @@
+// MIDI controllers: CC knobs and faders from the [midi] table drive the camera, for moves smoother than any
+// key held down. Movement faders are throttles around their centre, so a fader pushed up glides the camera
+// forward until it's brought back. Turn knobs turn the camera as far as they're turned. The FOV and scrub
+// faders set the value straight from where they stand. The controller is polled with the other input sources.
+
+use std::collections::HashMap;
+use std::sync::mpsc::{self, Receiver};
+
+use serde::Deserialize;
+
+use crate::input::{CameraCommand, InputSource};
+
+// CC values run 0-127, a centred fader or knob sends 64
+const CC_MAX: f32 = 127.0;
+const CC_CENTRE: f32 = 64.0;
+
+// MIDI settings, set in the [midi] table. Off unless enabled, and a control without a CC number does nothing.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct MidiConfig {
+    pub enabled: bool,
+    pub port: String,        // Part of the input port's name, empty takes the first port
+    pub channel: u8,         // 1-16, 0 listens on every channel
+    pub forward: Option<u8>, // CC numbers
+    pub right: Option<u8>,
+    pub up: Option<u8>,
+    pub yaw: Option<u8>,
+    pub pitch: Option<u8>,
+    pub roll: Option<u8>,
+    pub fov: Option<u8>,
+    pub scrub: Option<u8>,
+    pub dead_zone: f32,      // Part of a movement fader's travel around the centre that doesn't move, 0-1
+    pub turn_range: f32,     // Degrees a turn knob turns the camera over its whole travel
+    pub fov_range: [f32; 2], // Horizontal degrees at the bottom and the top of the FOV fader
+}
+
+impl Default for MidiConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: String::new(),
+            channel: 0,
+            forward: None,
+            right: None,
+            up: None,
+            yaw: None,
+            pitch: None,
+            roll: None,
+            fov: None,
+            scrub: None,
+            dead_zone: 0.05,
+            turn_range: 180.0,
+            fov_range: [30.0, 120.0],
+        }
+    }
+}
+
+pub struct MidiController {
+    config: MidiConfig,
+    port_name: String,
+    receiver: Receiver<(u8, u8)>, // Controller number and value of every control change on our channel
+    _connection: device::Connection,
+    faders: (f32, f32, f32),      // Where the movement faders stand, -1 to 1 outside the dead zone
+    knobs: HashMap<u8, u8>,       // Last value of each turn knob, a knob's first message only says where it is
+}
+
+impl MidiController {
+    pub fn start(config: &MidiConfig) -> Result<Self, String> {
+        if config.channel > 16 {
+            return Err(format!("MIDI channel {} doesn't exist, use 1-16 or 0 for every channel", config.channel));
+        }
+        let (sender, receiver) = mpsc::channel();
+        let (connection, port_name) = device::connect(&config.port, config.channel, sender)?;
+        Ok(Self {
+            config: config.clone(),
+            port_name,
+            receiver,
+            _connection: connection,
+            faders: (0.0, 0.0, 0.0),
+            knobs: HashMap::new(),
+        })
+    }
+    
+    pub fn port_name(&self) -> &str {
+        &self.port_name
+    }
+    
+    fn throttle(&self, value: u8) -> f32 {
+        throttle(value, self.config.dead_zone)
+    }
+}
+
+// A fader's position as -1 to 1 around its centre, 0 inside the dead zone
+fn throttle(value: u8, dead_zone: f32) -> f32 {
+    let offset = ((value as f32 - CC_CENTRE) / (CC_MAX - CC_CENTRE)).clamp(-1.0, 1.0);
+    let dead_zone = dead_zone.clamp(0.0, 0.99);
+    if offset.abs() <= dead_zone {
+        return 0.0;
+    }
+    offset.signum() * (offset.abs() - dead_zone) / (1.0 - dead_zone)
+}
+
+impl InputSource for MidiController {
+    // Movement faders are sent every tick until they're brought back, the FOV and scrub faders only when they move
+    fn poll(&mut self) -> Vec<CameraCommand> {
+        let mut commands = Vec::new();
+        let mut turn = (0.0, 0.0, 0.0);
+        let turn_step = self.config.turn_range.to_radians() / CC_MAX;
+        for (number, value) in self.receiver.try_iter() {
+            log::debug!("MIDI CC {} = {}", number, value);
+            let control = Some(number);
+            if control == self.config.forward {
+                self.faders.2 = self.throttle(value);
+            } else if control == self.config.right {
+                // Positive X is to the left in this game
+                self.faders.0 = -self.throttle(value);
+            } else if control == self.config.up {
+                self.faders.1 = self.throttle(value);
+            } else if control == self.config.fov {
+                let [bottom, top] = self.config.fov_range;
+                commands.push(CameraCommand::Fov(bottom + (top - bottom) * value as f32 / CC_MAX));
+            } else if control == self.config.scrub {
+                commands.push(CameraCommand::Scrub(value as f32 / CC_MAX));
+            } else if [self.config.yaw, self.config.pitch, self.config.roll].contains(&control) {
+                let turned = match self.knobs.insert(number, value) {
+                    Some(last) => (value as f32 - last as f32) * turn_step,
+                    None => 0.0,
+                };
+                if control == self.config.yaw {
+                    turn.0 += turned;
+                } else if control == self.config.pitch {
+                    // Turned up looks up, mouse look has it the other way round
+                    turn.1 -= turned;
+                } else {
+                    turn.2 += turned;
+                }
+            }
+        }
+        if self.faders != (0.0, 0.0, 0.0) {
+            let (dx, dy, dz) = self.faders;
+            commands.push(CameraCommand::Translate(dx, dy, dz));
+        }
+        if turn != (0.0, 0.0, 0.0) {
+            commands.push(CameraCommand::Rotate(turn.0, turn.1, turn.2));
+        }
+        commands
+    }
+}
+
+#[cfg(feature = "midi")]
+mod device {
+    use std::sync::mpsc::Sender;
+    
+    use midir::{Ignore, MidiInput, MidiInputConnection};
+    
+    // Status byte of a control change, the low four bits are the channel
+    const CONTROL_CHANGE: u8 = 0xB0;
+    
+    pub type Connection = MidiInputConnection<()>;
+    
+    // Opens the first input port with `port` in its name. Messages arrive on midir's thread and are passed on.
+    pub fn connect(port: &str, channel: u8, sender: Sender<(u8, u8)>) -> Result<(Connection, String), String> {
+        let mut input = MidiInput::new("thps3_free_cam").map_err(|e| format!("Failed to open MIDI input: {}", e))?;
+        input.ignore(Ignore::All);
+        let ports = input.ports();
+        let found = ports.iter()
+            .filter_map(|candidate| input.port_name(candidate).ok().map(|name| (candidate, name)))
+            .find(|(_, name)| name.to_lowercase().contains(&port.to_lowercase()));
+        let Some((found, name)) = found else {
+            let names: Vec<String> = ports.iter().filter_map(|candidate| input.port_name(candidate).ok()).collect();
+            return Err(if names.is_empty() {
+                "No MIDI input ports - is the controller plugged in?".to_string()
+            } else {
+                format!("No MIDI input port matches '{}', there are: {}", port, names.join(", "))
+            });
+        };
+        
+        let connection = input.connect(found, "thps3_free_cam", move |_, message, _| {
+            if let Some(change) = control_change(message, channel) {
+                let _ = sender.send(change);
+            }
+        }, ()).map_err(|e| format!("Failed to connect to MIDI port '{}': {}", name, e))?;
+        Ok((connection, name))
+    }
+    
+    // The control change in a MIDI message on `channel` (0 for any) as controller number and value
+    fn control_change(message: &[u8], channel: u8) -> Option<(u8, u8)> {
+        match *message {
+            [status, number, value, ..] if status & 0xF0 == CONTROL_CHANGE && (channel == 0 || status & 0x0F == channel - 1) => {
+                Some((number, value))
+            }
+            _ => None,
+        }
+    }
+}
+
+// Built without the midi feature, connecting says why it can't
+#[cfg(not(feature = "midi"))]
+mod device {
+    use std::sync::mpsc::Sender;
+    
+    pub type Connection = ();
+    
+    pub fn connect(_port: &str, _channel: u8, _sender: Sender<(u8, u8)>) -> Result<(Connection, String), String> {
+        Err("This build has no MIDI support".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn throttle_runs_from_the_centre() {
+        assert_eq!(throttle(64, 0.0), 0.0);
+        assert_eq!(throttle(127, 0.0), 1.0);
+        assert_eq!(throttle(0, 0.0), -1.0);
+        assert!((throttle(96, 0.0) - 0.508).abs() < 0.01);
+    }
+    
+    #[test]
+    fn throttle_dead_zone_is_still_and_the_rest_is_rescaled() {
+        assert_eq!(throttle(66, 0.05), 0.0);
+        assert_eq!(throttle(62, 0.05), 0.0);
+        assert_eq!(throttle(127, 0.05), 1.0);
+        assert_eq!(throttle(0, 0.05), -1.0);
+        assert!(throttle(70, 0.05) > 0.0 && throttle(70, 0.05) < throttle(70, 0.0));
+        // A dead zone of 1 or more still lets the ends through
+        assert_eq!(throttle(127, 5.0), 1.0);
+    }
+}
@@ -0,0 +1,95 @@
+// Numbers typed or pasted by the user, the same on every system. Rust never formats with the system locale,
+// so everything the tool writes uses a decimal point; what it reads may come from a system that uses a
+// decimal comma, so "12,5" is taken as 12.5. Thousands separators are refused rather than guessed at.
+
+// A coordinate as typed: an optional sign, digits, at most one decimal point or comma, and an optional exponent
+// like the `e-5` in 1e-5
+pub fn parse_number(text: &str) -> Result<f32, String> {
+    let text = text.trim();
+    let unsigned = text.strip_prefix(['-', '+']).unwrap_or(text);
+    if unsigned.is_empty() {
+        return Err("Expected a number, got nothing".to_string());
+    }
+    let (digits, exponent) = unsigned.split_once(['e', 'E']).unwrap_or((unsigned, "0"));
+    let exponent_digits = exponent.strip_prefix(['-', '+']).unwrap_or(exponent);
+    if exponent_digits.is_empty() || !exponent_digits.chars().all(|c| c.is_ascii_digit()) {
+        return Err(format!("'{}' is not a number - an exponent needs digits, e.g. 1e-5", text));
+    }
+    if let Some(c) = digits.chars().find(|c| !c.is_ascii_digit() && *c != '.' && *c != ',') {
+        return Err(format!("'{}' is not a number - '{}' isn't allowed, use digits and one decimal point", text, c));
+    }
+    let separators = digits.matches(['.', ',']).count();
+    if separators > 1 {
+        return Err(format!("'{}' has more than one '.' or ',' - leave out thousands separators, e.g. 1234.5", text));
+    }
+    if !digits.chars().any(|c| c.is_ascii_digit()) {
+        return Err(format!("'{}' is not a number", text));
+    }
+    text.replace(',', ".").parse().ok().filter(|value: &f32| value.is_finite()).ok_or_else(|| format!("'{}' is not a number", text))
+}
+
+// Digits after the decimal point or comma, for how precisely a value was typed
+pub fn decimals(text: &str) -> usize {
+    text.trim().split_once(['.', ',']).map_or(0, |(_, decimals)| decimals.len())
+}
+
+// Three coordinates separated by spaces, semicolons or commas: "1.5 -2 30", "1,5; -2; 30" and "1.5,-2,30"
+// all work. Commas only separate values when no value uses them as its decimal separator.
+pub fn parse_coordinates(text: &str) -> Result<[f32; 3], String> {
+    let mut fields: Vec<&str> = text.split(|c: char| c.is_whitespace() || c == ';')
+        .map(|field| field.trim_end_matches(','))
+        .filter(|field| !field.is_empty())
+        .collect();
+    if fields.len() == 1 {
+        fields = fields[0].split(',').collect();
+    }
+    if fields.len() != 3 {
+        return Err(format!("Expected X Y Z, got {} value(s) in '{}'", fields.len(), text.trim()));
+    }
+    Ok([parse_number(fields[0])?, parse_number(fields[1])?, parse_number(fields[2])?])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn numbers_take_a_point_or_a_comma() {
+        assert_eq!(parse_number("12.5"), Ok(12.5));
+        assert_eq!(parse_number(" -12,5 "), Ok(-12.5));
+        assert_eq!(parse_number("+3"), Ok(3.0));
+        assert_eq!(parse_number(".5"), Ok(0.5));
+        assert!(parse_number("1.234,5").is_err());
+        assert!(parse_number("12a").is_err());
+        assert!(parse_number("-").is_err());
+        assert!(parse_number(".").is_err());
+    }
+    
+    #[test]
+    fn numbers_take_an_exponent() {
+        assert_eq!(parse_number("1e-5"), Ok(1e-5));
+        assert_eq!(parse_number("2,5E+2"), Ok(250.0));
+        assert_eq!(parse_number("-3e2"), Ok(-300.0));
+        assert!(parse_number("1e").is_err());
+        assert!(parse_number("1e-").is_err());
+        assert!(parse_number("e5").is_err());
+        assert!(parse_number("1e99").is_err());
+    }
+    
+    #[test]
+    fn decimals_count_either_separator() {
+        assert_eq!(decimals("12"), 0);
+        assert_eq!(decimals("12.50"), 2);
+        assert_eq!(decimals("1,5"), 1);
+    }
+    
+    #[test]
+    fn coordinates_split_on_spaces_semicolons_or_commas() {
+        assert_eq!(parse_coordinates("1.5 -2 30"), Ok([1.5, -2.0, 30.0]));
+        assert_eq!(parse_coordinates("1,5; -2; 30"), Ok([1.5, -2.0, 30.0]));
+        assert_eq!(parse_coordinates("1.5,-2,30"), Ok([1.5, -2.0, 30.0]));
+        assert_eq!(parse_coordinates("1.5, -2, 30"), Ok([1.5, -2.0, 30.0]));
+        assert!(parse_coordinates("1 2").is_err());
+        assert!(parse_coordinates("1 2 3 4").is_err());
+    }
+}
@@ -0,0 +1,159 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+
+use crate::fov::FovAxis;
+
+// Optional file next to the executable for addresses that aren't hardcoded yet
+pub const OFFSETS_FILE: &str = "offsets.toml";
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct PointerChain {
+    pub base: usize,         // Offset from the module base address
+    pub offsets: Vec<usize>, // Pointer offsets, the last one is added without dereferencing
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct FovOffsets {
+    #[serde(flatten)]
+    pub chain: PointerChain,
+    #[serde(default = "default_fov_step")]
+    pub step: f32,
+    #[serde(default = "default_fov_min")]
+    pub min: f32,
+    #[serde(default = "default_fov_max")]
+    pub max: f32,
+    pub default: Option<f32>, // Falls back to the value read when the tool first touches the FOV
+    #[serde(default)]
+    pub axis: FovAxis,
+    #[serde(default)]
+    pub radians: bool,
+    pub game_aspect: Option<f32>,   // Aspect the game's horizontal value refers to, defaults to the screen aspect
+    pub screen_aspect: Option<f32>, // Overrides the aspect detected from the desktop resolution
+    pub target_hfov: Option<f32>,   // Horizontal FOV in degrees, applied as the default when set
+    #[serde(default = "default_target_aspect")]
+    pub target_aspect: f32,         // Aspect ratio `target_hfov` was authored at
+}
+
+fn default_target_aspect() -> f32 {
+    16.0 / 9.0
+}
+
+fn default_fov_step() -> f32 {
+    0.5
+}
+
+fn default_fov_min() -> f32 {
+    5.0
+}
+
+fn default_fov_max() -> f32 {
+    150.0
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ValueType {
+    #[default]
+    F32,
+    I32,
+}
+
+// A named game value (fog density, draw distance, ...) that can be adjusted live
+#[derive(Debug, Clone, Deserialize)]
+pub struct TunableOffsets {
+    pub name: String,
+    #[serde(flatten)]
+    pub chain: PointerChain,
+    #[serde(default, rename = "type")]
+    pub value_type: ValueType,
+    pub min: f32,
+    pub max: f32,
+    #[serde(default = "default_tunable_step")]
+    pub step: f32,
+    // Rewrite the adjusted value every tick, for values the game recalculates each frame (draw distance, LOD bias)
+    #[serde(default)]
+    pub hold: bool,
+}
+
+fn default_tunable_step() -> f32 {
+    1.0
+}
+
+// An instruction that gets replaced with NOPs while a patch is on
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct PatchOffsets {
+    pub offset: usize,           // Instruction offset from the module base address
+    pub original_bytes: Vec<u8>, // Bytes expected at the offset, the patch is refused when they don't match
+}
+
+// A value that tells gameplay apart from movies and loading screens, read as a 32-bit integer
+#[derive(Debug, Clone, Deserialize)]
+pub struct GameStateOffsets {
+    #[serde(flatten)]
+    pub chain: PointerChain,
+    pub gameplay: Vec<i32>, // Values the game holds while a level is being played
+}
+
+// A value that changes when the game starts or stops a replay, read as a 32-bit integer
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReplayOffsets {
+    #[serde(flatten)]
+    pub chain: PointerChain,
+    pub playing: Vec<i32>, // Values the game holds while a replay plays
+}
+
+// Where the game keeps the loaded level: its name as a zero-terminated string, or a 32-bit number when
+// `names` is set, e.g. `names = { "3" = "foundry" }`
+#[derive(Debug, Clone, Deserialize)]
+pub struct LevelOffsets {
+    #[serde(flatten)]
+    pub chain: PointerChain,
+    #[serde(default)]
+    pub names: HashMap<String, String>, // Level number, written as text, to the name used for per-level files
+}
+
+// Skater stats for telemetry overlays, each one optional
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct StatOffsets {
+    pub speed: Option<PointerChain>,          // 32-bit float
+    pub air: Option<PointerChain>,            // 32-bit integer, anything but 0 while the skater is in the air
+    pub grind_balance: Option<PointerChain>,  // 32-bit float
+    pub manual_balance: Option<PointerChain>, // 32-bit float
+    pub score: Option<PointerChain>,          // 32-bit integer
+    pub multiplier: Option<PointerChain>,     // 32-bit integer, the current combo's multiplier
+    pub special: Option<PointerChain>,        // 32-bit float, the special meter
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct GameOffsets {
+    pub camera: Option<PointerChain>, // Replaces the built-in camera chain, for builds where it doesn't resolve
+    pub fov: Option<FovOffsets>,
+    pub freeze: Option<PatchOffsets>, // Instruction that advances the game simulation
+    pub hud: Option<PatchOffsets>, // Instruction that draws the HUD
+    pub skater: Option<PointerChain>, // Skater position, three consecutive floats (X, Y, Z)
+    pub skater2: Option<PointerChain>, // Second player's skater in multiplayer, same layout
+    pub skater_matrix: Option<PointerChain>, // Skater orientation, a 4x4 matrix laid out like the camera's
+    pub game_state: Option<GameStateOffsets>, // The camera patch waits while this isn't a gameplay value
+    pub replay: Option<ReplayOffsets>, // Replay sync starts the path when this turns into a playing value
+    pub level: Option<LevelOffsets>, // Picks the per-level waypoints and settings
+    pub stats: StatOffsets,
+    #[serde(rename = "tunable")]
+    pub tunables: Vec<TunableOffsets>,
+}
+
+pub fn load_offsets(path: &str) -> Result<GameOffsets, String> {
+    let contents = match std::fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(GameOffsets::default()),
+        Err(e) => return Err(format!("Failed to read '{}': {}", path, e)),
+    };
+
+    parse_offsets(&contents, path)
+}
+
+// `path` only names the source in error messages
+pub fn parse_offsets(contents: &str, path: &str) -> Result<GameOffsets, String> {
+    toml::from_str(contents).map_err(|e| format!("Failed to parse '{}': {}", path, e))
+}
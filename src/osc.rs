@@ -0,0 +1,165 @@
+// OSC over UDP, so hardware controllers and tools like TouchOSC or VCV Rack can puppeteer the camera.
+// Messages are turned into the same commands as the HTTP/WebSocket remote control.
+
+use std::net::UdpSocket;
+use std::sync::mpsc::{self, Receiver};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use crate::camera::Quaternion;
+use crate::remote::RemoteCommand;
+use crate::shutdown::{self, Cancel};
+
+// How long a receive waits before checking whether the listener should stop
+const RECEIVE_INTERVAL: Duration = Duration::from_millis(50);
+
+pub struct OscListener {
+    receiver: Receiver<RemoteCommand>,
+    cancel: Option<Cancel>,
+    worker: Option<JoinHandle<()>>,
+}
+
+impl OscListener {
+    pub fn start(bind: &str, port: u16) -> Result<Self, String> {
+        let socket = UdpSocket::bind((bind, port)).map_err(|e| format!("Failed to listen for OSC on {}:{}: {}", bind, port, e))?;
+        socket.set_read_timeout(Some(RECEIVE_INTERVAL)).map_err(|e| format!("Failed to listen for OSC on {}:{}: {}", bind, port, e))?;
+        let (sender, receiver) = mpsc::channel();
+        let (cancel, token) = shutdown::token();
+        
+        let worker = std::thread::spawn(move || {
+            let mut buffer = [0u8; 4096];
+            while !token.is_cancelled() {
+                let length = match socket.recv(&mut buffer) {
+                    Ok(length) => length,
+                    Err(e) if matches!(e.kind(), std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut) => continue,
+                    Err(_) => return,
+                };
+                let mut messages = Vec::new();
+                parse_packet(&buffer[..length], &mut messages);
+                for (address, arguments) in messages {
+                    // Anything unknown, malformed or not finite is dropped, controllers send a lot we don't use
+                    if let Some(command) = to_command(&address, &arguments).and_then(|command| command.check().ok())
+                        && sender.send(command).is_err()
+                    {
+                        return;
+                    }
+                }
+            }
+        });
+        
+        Ok(Self { receiver, cancel: Some(cancel), worker: Some(worker) })
+    }
+    
+    // Commands received since the last call
+    pub fn poll(&self) -> Vec<RemoteCommand> {
+        self.receiver.try_iter().collect()
+    }
+}
+
+impl Drop for OscListener {
+    fn drop(&mut self) {
+        self.cancel = None;
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
+
+// Maps an OSC address and its numeric arguments to a command
+fn to_command(address: &str, arguments: &[f32]) -> Option<RemoteCommand> {
+    match (address, arguments) {
+        ("/camera/position", &[x, y, z]) => Some(RemoteCommand::SetPosition { x, y, z }),
+        // Degrees around X, Y and Z like .chan files, or a quaternion as x, y, z, w
+        ("/camera/rotation", &[x, y, z]) => {
+            let rotation = Quaternion::from_euler_xyz(x.to_radians(), y.to_radians(), z.to_radians());
+            Some(RemoteCommand::SetRotation { rotation: [rotation.x, rotation.y, rotation.z, rotation.w] })
+        }
+        ("/camera/rotation", &[x, y, z, w]) => Some(RemoteCommand::SetRotation { rotation: [x, y, z, w] }),
+        ("/camera/pose", &[x, y, z, qx, qy, qz, qw]) => Some(RemoteCommand::SetPose { position: [x, y, z], rotation: [qx, qy, qz, qw] }),
+        ("/camera/fov", &[fov]) => Some(RemoteCommand::SetFov { fov }),
+        ("/camera/matrix", arguments) if arguments.len() == 16 => {
+            let mut matrix = [0.0; 16];
+            matrix.copy_from_slice(arguments);
+            Some(RemoteCommand::SetMatrix { matrix })
+        }
+        ("/speed", &[speed]) => Some(RemoteCommand::SetSpeed { speed }),
+        ("/camera/move", &[right, up, forward]) => Some(RemoteCommand::Move { right, up, forward }),
+        ("/camera/turn", &[yaw, pitch]) => Some(RemoteCommand::Turn { yaw, pitch, roll: 0.0 }),
+        ("/camera/turn", &[yaw, pitch, roll]) => Some(RemoteCommand::Turn { yaw, pitch, roll }),
+        // Buttons send 1 when pressed and 0 when released, only the press counts
+        (address, arguments) if address.starts_with("/action/") && arguments.first().is_none_or(|&value| value != 0.0) => {
+            let name = &address["/action/".len()..];
+            let action = serde_json::from_value(serde_json::Value::String(name.to_string())).ok()?;
+            Some(RemoteCommand::Action { action })
+        }
+        _ => None,
+    }
+}
+
+// Null-terminated string padded to a multiple of 4 bytes, returns it and the rest of the data
+fn read_string(data: &[u8]) -> Option<(&str, &[u8])> {
+    let end = data.iter().position(|&byte| byte == 0)?;
+    let string = std::str::from_utf8(&data[..end]).ok()?;
+    let padded = (end + 4) & !3;
+    Some((string, data.get(padded..)?))
+}
+
+fn read_u32(data: &[u8]) -> Option<(u32, &[u8])> {
+    let bytes: [u8; 4] = data.get(..4)?.try_into().ok()?;
+    Some((u32::from_be_bytes(bytes), &data[4..]))
+}
+
+// Collects the messages in a packet, unpacking bundles. Numeric arguments are converted to f32.
+fn parse_packet(data: &[u8], messages: &mut Vec<(String, Vec<f32>)>) {
+    if let Some(mut elements) = data.strip_prefix(b"#bundle\0") {
+        // Time tags are ignored, everything is applied as it arrives
+        let Some(rest) = elements.get(8..) else { return };
+        elements = rest;
+        while let Some((size, rest)) = read_u32(elements) {
+            let Some(element) = rest.get(..size as usize) else { return };
+            parse_packet(element, messages);
+            elements = &rest[size as usize..];
+        }
+    } else if let Some(message) = parse_message(data) {
+        messages.push(message);
+    }
+}
+
+fn parse_message(data: &[u8]) -> Option<(String, Vec<f32>)> {
+    let (address, rest) = read_string(data)?;
+    // Very old senders leave out the type tags
+    let Some((tags, mut rest)) = read_string(rest) else {
+        return Some((address.to_string(), Vec::new()));
+    };
+    
+    let mut arguments = Vec::new();
+    for tag in tags.strip_prefix(',')?.chars() {
+        match tag {
+            'f' => {
+                let (bits, next) = read_u32(rest)?;
+                arguments.push(f32::from_bits(bits));
+                rest = next;
+            }
+            'i' => {
+                let (value, next) = read_u32(rest)?;
+                arguments.push(value as i32 as f32);
+                rest = next;
+            }
+            'd' => {
+                let bytes: [u8; 8] = rest.get(..8)?.try_into().ok()?;
+                arguments.push(f64::from_be_bytes(bytes) as f32);
+                rest = &rest[8..];
+            }
+            'T' => arguments.push(1.0),
+            'F' | 'N' | 'I' => arguments.push(0.0),
+            // Strings and blobs aren't used by any address, skip over them
+            's' | 'S' => rest = read_string(rest)?.1,
+            'b' => {
+                let (size, next) = read_u32(rest)?;
+                rest = next.get(((size as usize + 3) & !3)..)?;
+            }
+            _ => return None,
+        }
+    }
+    Some((address.to_string(), arguments))
+}
@@ -0,0 +1,339 @@
+// Optional overlay over the game window for framing shots: a crosshair in the middle, a rule-of-thirds grid
+// and a line of camera telemetry. It is a layered window that clicks and keys go straight through, kept on top
+// of the game's client area as the game window moves, and drawn with plain GDI on its own thread like the
+// control panel. Games in exclusive fullscreen draw over every other window, so it needs windowed or
+// borderless mode. Needs the `gui` feature.
+
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+
+use winapi::shared::minwindef::DWORD;
+use winapi::shared::windef::HWND;
+use winapi::um::winuser::{PostThreadMessageW, WM_QUIT};
+
+use crate::skater::GameState;
+
+#[cfg(feature = "gui")]
+use window::window_thread;
+
+// What the overlay shows, published by the main loop every tick
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OverlayState {
+    pub position: [f32; 3],
+    pub speed: f32,
+    pub fov: Option<f32>, // Horizontal degrees, None when the game has no FOV offsets
+    pub patch: bool,
+    pub stats: Option<GameState>, // With [stats] chains in the offsets file
+}
+
+impl OverlayState {
+    // The telemetry line, also what the hook shows inside the game with `osd = true`
+    pub fn telemetry(&self) -> String {
+        let [x, y, z] = self.position;
+        let line = format!("X {:.1}  Y {:.1}  Z {:.1}   Speed {:.0}   FOV {}   Patch {}", x, y, z, self.speed,
+                           self.fov.map_or("n/a".to_string(), |fov| format!("{:.0}", fov)), if self.patch { "on" } else { "off" });
+        match self.stats.as_ref().and_then(GameState::summary) {
+            Some(stats) => format!("{}   {}", line, stats),
+            None => line,
+        }
+    }
+}
+
+pub struct Overlay {
+    shared: Arc<Mutex<OverlayState>>,
+    window_thread: Option<(DWORD, JoinHandle<()>)>, // Win32 thread ID to post WM_QUIT to
+}
+
+impl Overlay {
+    pub fn start(game_window: HWND) -> Result<Self, String> {
+        let (ready_sender, ready) = mpsc::channel();
+        let shared = Arc::new(Mutex::new(OverlayState::default()));
+        let window_shared = Arc::clone(&shared);
+        // Window handles are only pointers to Rust, the number is what crosses to the other thread
+        let game_window = game_window as usize;
+        let thread = std::thread::spawn(move || window_thread(game_window as HWND, window_shared, ready_sender));
+        
+        match ready.recv() {
+            Ok(Ok(thread_id)) => Ok(Self { shared, window_thread: Some((thread_id, thread)) }),
+            Ok(Err(e)) => Err(e),
+            Err(_) => Err("The overlay thread stopped".to_string()),
+        }
+    }
+    
+    pub fn publish(&self, state: OverlayState) {
+        if let Ok(mut shared) = self.shared.lock() {
+            *shared = state;
+        }
+    }
+}
+
+impl Drop for Overlay {
+    // Same as the control panel: the window thread sleeps in GetMessage until it gets a message
+    fn drop(&mut self) {
+        if let Some((thread_id, thread)) = self.window_thread.take() {
+            unsafe { PostThreadMessageW(thread_id, WM_QUIT, 0, 0) };
+            let _ = thread.join();
+        }
+    }
+}
+
+// Built without the gui feature, the overlay says why it can't open and the free cam carries on without it
+#[cfg(not(feature = "gui"))]
+fn window_thread(_game_window: HWND, _shared: Arc<Mutex<OverlayState>>, ready: mpsc::Sender<Result<DWORD, String>>) {
+    let _ = ready.send(Err("This build has no overlay".to_string()));
+}
+
+#[cfg(feature = "gui")]
+mod window {
+    use std::cell::RefCell;
+    use std::mem;
+    use std::ptr;
+    use std::sync::mpsc::Sender;
+    use std::sync::{Arc, Mutex};
+    
+    use winapi::shared::minwindef::{DWORD, LPARAM, LRESULT, UINT, WPARAM};
+    use winapi::shared::windef::{COLORREF, HDC, HFONT, HWND, POINT, RECT};
+    use winapi::um::errhandlingapi::GetLastError;
+    use winapi::um::libloaderapi::GetModuleHandleW;
+    use winapi::um::processthreadsapi::GetCurrentThreadId;
+    use winapi::um::wingdi::{
+        BitBlt, CreateCompatibleBitmap, CreateCompatibleDC, CreateFontW, CreatePen, CreateSolidBrush, DeleteDC, DeleteObject,
+        LineTo, MoveToEx, SelectObject, SetBkMode, SetTextColor, TextOutW, CLIP_DEFAULT_PRECIS, DEFAULT_CHARSET, FF_MODERN,
+        FIXED_PITCH, FW_NORMAL, NONANTIALIASED_QUALITY, OUT_DEFAULT_PRECIS, PS_SOLID, SRCCOPY, TRANSPARENT,
+    };
+    use winapi::um::winuser::{
+        BeginPaint, ClientToScreen, CreateWindowExW, DefWindowProcW, DestroyWindow, DispatchMessageW, EndPaint, FillRect,
+        GetClientRect, GetMessageW, InvalidateRect, IsIconic, IsWindow, KillTimer, RegisterClassExW, SetLayeredWindowAttributes,
+        SetTimer, SetWindowDisplayAffinity, SetWindowPos, ShowWindow, TranslateMessage, HWND_TOPMOST, LWA_COLORKEY, MSG, PAINTSTRUCT, SWP_NOACTIVATE,
+        SWP_SHOWWINDOW, SW_HIDE, WM_ERASEBKGND, WM_PAINT, WM_TIMER, WNDCLASSEXW, WS_EX_LAYERED, WS_EX_NOACTIVATE,
+        WS_EX_TOOLWINDOW, WS_EX_TOPMOST, WS_EX_TRANSPARENT, WS_POPUP,
+    };
+    
+    use super::OverlayState;
+    
+    const CLASS_NAME: &str = "Thps3FreeCamOverlay";
+    
+    // Often enough to keep up with the game window being dragged, ~30 Hz
+    const REFRESH_MS: UINT = 33;
+    const REFRESH_TIMER: usize = 1;
+    
+    // Pixels in the key colour are see-through, so nothing drawn on the overlay uses it. Colours are 0x00BBGGRR.
+    const KEY_COLOR: COLORREF = 0x00FF00FF;
+    const LINE_COLOR: COLORREF = 0x00FFFFFF;
+    const SHADOW_COLOR: COLORREF = 0x00000000; // Drawn a pixel off every line, so lines show on bright scenes too
+    
+    const CROSSHAIR_SIZE: i32 = 12;
+    
+    // Leaves the window out of screen captures, newer than the winapi headers (Windows 10 2004 and later)
+    const WDA_EXCLUDEFROMCAPTURE: DWORD = 0x11;
+    
+    struct OverlayWindow {
+        game_window: HWND,
+        shared: Arc<Mutex<OverlayState>>,
+        font: HFONT,
+        drawn: Option<OverlayState>,
+        bounds: Option<(i32, i32, i32, i32)>, // Screen position and size of the game's client area, None while hidden
+    }
+    
+    thread_local! {
+        // The window procedure has no other way to reach the overlay
+        static OVERLAY: RefCell<Option<OverlayWindow>> = const { RefCell::new(None) };
+    }
+    
+    fn wide(text: &str) -> Vec<u16> {
+        text.encode_utf16().chain(Some(0)).collect()
+    }
+    
+    pub(super) fn window_thread(game_window: HWND, shared: Arc<Mutex<OverlayState>>, ready: Sender<Result<DWORD, String>>) {
+        let class_name = wide(CLASS_NAME);
+        let class = WNDCLASSEXW {
+            cbSize: mem::size_of::<WNDCLASSEXW>() as UINT,
+            style: 0,
+            lpfnWndProc: Some(window_proc),
+            cbClsExtra: 0,
+            cbWndExtra: 0,
+            hInstance: unsafe { GetModuleHandleW(ptr::null()) },
+            hIcon: ptr::null_mut(),
+            hCursor: ptr::null_mut(),
+            hbrBackground: ptr::null_mut(),
+            lpszMenuName: ptr::null(),
+            lpszClassName: class_name.as_ptr(),
+            hIconSm: ptr::null_mut(),
+        };
+        unsafe { RegisterClassExW(&class) };
+        
+        // Layered and transparent lets every click through, tool window keeps it off the taskbar and Alt+Tab
+        let title = wide("THPS3 Free Cam Overlay");
+        let window = unsafe {
+            CreateWindowExW(WS_EX_LAYERED | WS_EX_TRANSPARENT | WS_EX_TOPMOST | WS_EX_TOOLWINDOW | WS_EX_NOACTIVATE,
+                            class_name.as_ptr(), title.as_ptr(), WS_POPUP, 0, 0, 0, 0, ptr::null_mut(), ptr::null_mut(),
+                            class.hInstance, ptr::null_mut())
+        };
+        if window.is_null() {
+            let error_code = unsafe { GetLastError() };
+            let _ = ready.send(Err(format!("Failed to create the overlay window (Error: {})", error_code)));
+            return;
+        }
+        // Screenshots, timelapses and recording software copy the screen, the guides shouldn't end up in the footage.
+        // Older Windows refuses and the overlay is captured along with the game.
+        unsafe {
+            SetLayeredWindowAttributes(window, KEY_COLOR, 0, LWA_COLORKEY);
+            SetWindowDisplayAffinity(window, WDA_EXCLUDEFROMCAPTURE);
+        }
+        
+        // Smoothed text would leave key-coloured fringes around the letters
+        let face = wide("Consolas");
+        let font = unsafe {
+            CreateFontW(-14, 0, 0, 0, FW_NORMAL, 0, 0, 0, DEFAULT_CHARSET, OUT_DEFAULT_PRECIS, CLIP_DEFAULT_PRECIS,
+                        NONANTIALIASED_QUALITY, FIXED_PITCH | FF_MODERN, face.as_ptr())
+        };
+        OVERLAY.with(|cell| *cell.borrow_mut() = Some(OverlayWindow { game_window, shared, font, drawn: None, bounds: None }));
+        
+        unsafe { SetTimer(window, REFRESH_TIMER, REFRESH_MS, None) };
+        refresh(window);
+        let _ = ready.send(Ok(unsafe { GetCurrentThreadId() }));
+        
+        let mut message: MSG = unsafe { mem::zeroed() };
+        while unsafe { GetMessageW(&mut message, ptr::null_mut(), 0, 0) } > 0 {
+            unsafe {
+                TranslateMessage(&message);
+                DispatchMessageW(&message);
+            }
+        }
+        
+        OVERLAY.with(|cell| cell.borrow_mut().take());
+        unsafe {
+            KillTimer(window, REFRESH_TIMER);
+            DestroyWindow(window);
+            DeleteObject(font as _);
+        }
+    }
+    
+    unsafe extern "system" fn window_proc(window: HWND, message: UINT, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+        match message {
+            WM_TIMER => refresh(window),
+            WM_PAINT => {
+                paint(window);
+                return 0;
+            }
+            // Painting covers the whole window, erasing first would only flicker
+            WM_ERASEBKGND => return 1,
+            _ => {}
+        }
+        unsafe { DefWindowProcW(window, message, wparam, lparam) }
+    }
+    
+    // Messages that arrive while the overlay is being set up or torn down are left to the default handling
+    fn with_overlay(action: impl FnOnce(&mut OverlayWindow)) {
+        OVERLAY.with(|cell| {
+            if let Ok(mut overlay) = cell.try_borrow_mut()
+                && let Some(overlay) = overlay.as_mut()
+            {
+                action(overlay);
+            }
+        });
+    }
+    
+    // Screen position and size of the game's client area, None while it is minimized or gone
+    fn client_bounds(game_window: HWND) -> Option<(i32, i32, i32, i32)> {
+        if unsafe { IsWindow(game_window) == 0 || IsIconic(game_window) != 0 } {
+            return None;
+        }
+        let mut rect: RECT = unsafe { mem::zeroed() };
+        let mut origin = POINT { x: 0, y: 0 };
+        if unsafe { GetClientRect(game_window, &mut rect) == 0 || ClientToScreen(game_window, &mut origin) == 0 } {
+            return None;
+        }
+        let (width, height) = (rect.right - rect.left, rect.bottom - rect.top);
+        (width > 0 && height > 0).then_some((origin.x, origin.y, width, height))
+    }
+    
+    // Follows the game window and redraws when something changed
+    fn refresh(window: HWND) {
+        with_overlay(|overlay| {
+            let bounds = client_bounds(overlay.game_window);
+            if bounds != overlay.bounds {
+                match bounds {
+                    Some((x, y, width, height)) => unsafe {
+                        SetWindowPos(window, HWND_TOPMOST, x, y, width, height, SWP_NOACTIVATE | SWP_SHOWWINDOW);
+                    },
+                    None => unsafe {
+                        ShowWindow(window, SW_HIDE);
+                    },
+                }
+                overlay.bounds = bounds;
+                overlay.drawn = None;
+            }
+            
+            let state = overlay.shared.lock().map(|state| state.clone()).ok();
+            if overlay.bounds.is_some() && state != overlay.drawn {
+                overlay.drawn = state;
+                unsafe { InvalidateRect(window, ptr::null(), 0) };
+            }
+        });
+    }
+    
+    fn line(dc: HDC, (x1, y1): (i32, i32), (x2, y2): (i32, i32)) {
+        unsafe {
+            MoveToEx(dc, x1, y1, ptr::null_mut());
+            LineTo(dc, x2, y2);
+        }
+    }
+    
+    // Thirds grid and crosshair, drawn once per colour so the shadow lies under the lines
+    fn draw_guides(dc: HDC, width: i32, height: i32, offset: i32) {
+        for third in [width / 3, width * 2 / 3] {
+            line(dc, (third + offset, 0), (third + offset, height));
+        }
+        for third in [height / 3, height * 2 / 3] {
+            line(dc, (0, third + offset), (width, third + offset));
+        }
+        let (x, y) = (width / 2 + offset, height / 2 + offset);
+        line(dc, (x - CROSSHAIR_SIZE, y), (x + CROSSHAIR_SIZE + 1, y));
+        line(dc, (x, y - CROSSHAIR_SIZE), (x, y + CROSSHAIR_SIZE + 1));
+    }
+    
+    // Draws into a bitmap first and copies that to the window in one go, so the lines don't flicker
+    fn paint(window: HWND) {
+        let mut paint: PAINTSTRUCT = unsafe { mem::zeroed() };
+        let dc = unsafe { BeginPaint(window, &mut paint) };
+        with_overlay(|overlay| {
+            let (Some((_, _, width, height)), Some(state)) = (overlay.bounds, &overlay.drawn) else {
+                return;
+            };
+            unsafe {
+                let buffer = CreateCompatibleDC(dc);
+                let bitmap = CreateCompatibleBitmap(dc, width, height);
+                let old_bitmap = SelectObject(buffer, bitmap as _);
+                
+                let background = CreateSolidBrush(KEY_COLOR);
+                FillRect(buffer, &RECT { left: 0, top: 0, right: width, bottom: height }, background);
+                DeleteObject(background as _);
+                
+                for (color, offset) in [(SHADOW_COLOR, 1), (LINE_COLOR, 0)] {
+                    let pen = CreatePen(PS_SOLID as i32, 1, color);
+                    let old_pen = SelectObject(buffer, pen as _);
+                    draw_guides(buffer, width, height, offset);
+                    SelectObject(buffer, old_pen);
+                    DeleteObject(pen as _);
+                }
+                
+                let text: Vec<u16> = state.telemetry().encode_utf16().collect();
+                let old_font = SelectObject(buffer, overlay.font as _);
+                SetBkMode(buffer, TRANSPARENT as i32);
+                for (color, offset) in [(SHADOW_COLOR, 1), (LINE_COLOR, 0)] {
+                    SetTextColor(buffer, color);
+                    TextOutW(buffer, 8 + offset, 8 + offset, text.as_ptr(), text.len() as i32);
+                }
+                SelectObject(buffer, old_font);
+                
+                BitBlt(dc, 0, 0, width, height, buffer, 0, 0, SRCCOPY);
+                SelectObject(buffer, old_bitmap);
+                DeleteObject(bitmap as _);
+                DeleteDC(buffer);
+            }
+        });
+        unsafe { EndPaint(window, &paint) };
+    }
+}
@@ -0,0 +1,272 @@
+// A searchable list of everything the hotkeys can do, for when the key for something is forgotten, and of the
+// settings the control panel has sliders for. The palette key asks for a search in the tool's console while the
+// free cam keeps running; the chosen entry runs as an Action, the same way a voice command or hot corner would,
+// or asks for the setting's new value.
+
+use std::io::{BufRead, Write};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::sync::{Mutex, OnceLock};
+
+use winapi::um::wincon::GetConsoleWindow;
+use winapi::um::winuser::SetForegroundWindow;
+
+use crate::action::Action;
+use crate::config::KeyBindings;
+use crate::numbers::parse_number;
+use crate::panel::PanelCommand;
+
+// Matches listed per search, numbered 1-9 so a single digit picks one
+const MAX_MATCHES: usize = 9;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Setting {
+    Speed,
+    Sensitivity,
+    Fov,
+}
+
+impl Setting {
+    // The setting's name, the [keys] name of a setting entry
+    pub fn name(self) -> &'static str {
+        match self {
+            Setting::Speed => "speed",
+            Setting::Sensitivity => "mouse_sensitivity",
+            Setting::Fov => "fov",
+        }
+    }
+    
+    fn description(self) -> &'static str {
+        match self {
+            Setting::Speed => "Set move speed (units per second)",
+            Setting::Sensitivity => "Set mouse sensitivity",
+            Setting::Fov => "Set horizontal FOV (degrees)",
+        }
+    }
+    
+    // The same commands as the control panel's sliders
+    pub fn command(self, value: f32) -> PanelCommand {
+        match self {
+            Setting::Speed => PanelCommand::Speed(value),
+            Setting::Sensitivity => PanelCommand::Sensitivity(value),
+            Setting::Fov => PanelCommand::Fov(value),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PaletteCommand {
+    Action(Action),
+    Setting(Setting),
+}
+
+pub struct PaletteEntry {
+    pub command: PaletteCommand,
+    pub key: String,
+    pub description: &'static str,
+}
+
+impl PaletteEntry {
+    fn name(&self) -> &'static str {
+        match self.command {
+            PaletteCommand::Action(action) => action.name(),
+            PaletteCommand::Setting(setting) => setting.name(),
+        }
+    }
+}
+
+// Every binding that has an Action, with the key it is bound to, and the settings. Position-only mode leaves out
+// what it can't do, the FOV needs FOV offsets.
+pub fn entries(keys: &KeyBindings, matrix_mode: bool, has_fov: bool) -> Vec<PaletteEntry> {
+    let settings = [
+        Some(Setting::Speed),
+        matrix_mode.then_some(Setting::Sensitivity),
+        (matrix_mode && has_fov).then_some(Setting::Fov),
+    ];
+    keys.table()
+        .into_iter()
+        .filter(|binding| matrix_mode || !binding.matrix_only)
+        .filter_map(|binding| {
+            let action = Action::ALL.iter().copied().find(|action| action.name() == binding.name)?;
+            Some(PaletteEntry { command: PaletteCommand::Action(action), key: binding.key.name(), description: binding.description })
+        })
+        .chain(settings.into_iter().flatten().map(|setting| PaletteEntry {
+            command: PaletteCommand::Setting(setting),
+            key: "setting".to_string(),
+            description: setting.description(),
+        }))
+        .collect()
+}
+
+// Scores `text` for a query whose letters appear in it in order, None when they don't.
+// Letters right after each other and at the start of a word count extra, so "tp" prefers "Toggle Camera Write Patch"
+// over letters scattered through a long description, and the query as a whole word or part of one counts most.
+pub fn fuzzy_score(query: &str, text: &str) -> Option<i32> {
+    let query = query.trim().to_lowercase();
+    let lowercase = text.to_lowercase();
+    let text: Vec<char> = lowercase.chars().collect();
+    let mut score = 0;
+    let mut position = 0;
+    let mut previous: Option<usize> = None;
+    
+    for wanted in query.chars().filter(|c| !c.is_whitespace()) {
+        let found = position + text[position..].iter().position(|&c| c == wanted)?;
+        score += 1;
+        if previous.is_some_and(|previous| previous + 1 == found) {
+            score += 5;
+        }
+        if found == 0 || !text[found - 1].is_alphanumeric() {
+            score += 3;
+        }
+        previous = Some(found);
+        position = found + 1;
+    }
+    
+    if lowercase.contains(&query) {
+        score += 20;
+    }
+    
+    // Shorter texts win ties
+    Some(score * 100 - text.len() as i32)
+}
+
+// Best matches first, searching both the description and the [keys] name
+pub fn search<'a>(entries: &'a [PaletteEntry], query: &str) -> Vec<&'a PaletteEntry> {
+    let mut scored: Vec<(i32, &PaletteEntry)> = entries.iter()
+        .filter_map(|entry| {
+            let score = [fuzzy_score(query, entry.description), fuzzy_score(query, entry.name())]
+                .into_iter()
+                .flatten()
+                .max()?;
+            Some((score, entry))
+        })
+        .collect();
+    scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+    scored.into_iter().take(MAX_MATCHES).map(|(_, entry)| entry).collect()
+}
+
+// Lines typed into the console, read on a thread of their own so the free cam doesn't wait for them. Everything
+// that reads the console goes through here, a second reader would get half the lines.
+fn console_lines() -> &'static Mutex<Receiver<String>> {
+    static LINES: OnceLock<Mutex<Receiver<String>>> = OnceLock::new();
+    LINES.get_or_init(|| {
+        let (sender, receiver) = mpsc::channel();
+        std::thread::spawn(move || {
+            for line in std::io::stdin().lock().lines() {
+                let Ok(line) = line else { break };
+                if sender.send(line.trim().to_string()).is_err() {
+                    break;
+                }
+            }
+        });
+        Mutex::new(receiver)
+    })
+}
+
+fn show_prompt() {
+    print!("> ");
+    let _ = std::io::stdout().flush();
+}
+
+// A line typed into the console after a "> " prompt, None once stdin is closed. Waits for it.
+pub fn read_line() -> Option<String> {
+    show_prompt();
+    console_lines().lock().ok()?.recv().ok()
+}
+
+// What the open palette came to this tick
+pub enum PalettePoll {
+    Open,
+    Closed,
+    Picked(PanelCommand),
+}
+
+// The palette while it's open. Polled every tick, it takes whatever was typed since and never waits.
+pub struct Palette {
+    matches: Vec<PaletteCommand>,
+    setting: Option<Setting>, // Picked, waiting for its value
+}
+
+impl Palette {
+    // Lines typed before the palette was opened aren't searches
+    pub fn open() -> Self {
+        if let Ok(lines) = console_lines().lock() {
+            lines.try_iter().for_each(drop);
+        }
+        // Windows may refuse to hand over the focus, the console title says where to type then
+        unsafe { SetForegroundWindow(GetConsoleWindow()) };
+        println!("\n🔎 Command palette - type part of a command or setting in this console, Enter on an empty line closes it");
+        show_prompt();
+        Self { matches: Vec::new(), setting: None }
+    }
+    
+    pub fn poll(&mut self, entries: &[PaletteEntry]) -> PalettePoll {
+        loop {
+            let line = match console_lines().lock().map(|lines| lines.try_recv()) {
+                Ok(Ok(line)) => line,
+                Ok(Err(TryRecvError::Empty)) => return PalettePoll::Open,
+                _ => return PalettePoll::Closed,
+            };
+            if let Some(poll) = self.line(entries, &line) {
+                return poll;
+            }
+            show_prompt();
+        }
+    }
+    
+    // Handles one typed line, None while the palette stays open
+    fn line(&mut self, entries: &[PaletteEntry], line: &str) -> Option<PalettePoll> {
+        if line.is_empty() {
+            println!("   Palette closed");
+            return Some(PalettePoll::Closed);
+        }
+        
+        if let Some(setting) = self.setting {
+            return match parse_number(line) {
+                Ok(value) if value > 0.0 => Some(PalettePoll::Picked(setting.command(value))),
+                Ok(_) => {
+                    println!("   It has to be more than 0");
+                    None
+                }
+                Err(e) => {
+                    println!("   {}", e);
+                    None
+                }
+            };
+        }
+        
+        if let Ok(number) = line.parse::<usize>()
+            && let Some(&command) = number.checked_sub(1).and_then(|index| self.matches.get(index))
+        {
+            return self.pick(entries, command);
+        }
+        
+        let matches = search(entries, line);
+        self.matches = matches.iter().map(|entry| entry.command).collect();
+        match matches.as_slice() {
+            [] => println!("   Nothing matches '{}'", line),
+            [only] => return self.pick(entries, only.command),
+            _ => {
+                for (index, entry) in matches.iter().enumerate() {
+                    println!("   {}. {} ({})", index + 1, entry.description, entry.key);
+                }
+                println!("   Type a number to pick it, or search again");
+            }
+        }
+        None
+    }
+    
+    fn pick(&mut self, entries: &[PaletteEntry], command: PaletteCommand) -> Option<PalettePoll> {
+        let description = entries.iter().find(|entry| entry.command == command).map_or("", |entry| entry.description);
+        match command {
+            PaletteCommand::Action(action) => {
+                println!("   Running: {}", description);
+                Some(PalettePoll::Picked(PanelCommand::Action(action)))
+            }
+            PaletteCommand::Setting(setting) => {
+                println!("   {} - type the new value", description);
+                self.setting = Some(setting);
+                None
+            }
+        }
+    }
+}
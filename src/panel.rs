@@ -0,0 +1,482 @@
+// Optional control panel window for the settings a mouse is handier for than keys: sliders for speed, mouse
+// sensitivity and FOV, buttons for the patch, bookmarks and path, a live position readout and the path's
+// keyframes with their ease handles. It is made of plain Win32 controls on its own thread, like the raw input window in hotkeys.rs,
+// so it adds no dependencies and nothing draws with the GPU the game is using. Clicks become commands the
+// main loop picks up between ticks, the same way remote requests do. Needs the `gui` feature.
+
+use std::sync::mpsc::{self, Receiver};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+
+use winapi::shared::minwindef::DWORD;
+use winapi::um::winuser::{PostThreadMessageW, WM_QUIT};
+
+use crate::action::Action;
+use crate::path::{CameraPath, Keyframe};
+#[cfg(feature = "gui")]
+use window::window_thread;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PanelCommand {
+    Speed(f32),
+    Sensitivity(f32),
+    Fov(f32), // Horizontal degrees
+    Action(Action),
+    GoToKeyframe(usize),
+    DeleteKeyframe(usize),
+    SetEase(usize, f32, f32), // Keyframe, ease in and ease out
+}
+
+// What the panel shows, published by the main loop every tick
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PanelState {
+    pub position: [f32; 3],
+    pub speed: f32,
+    pub sensitivity: f32,
+    pub fov: Option<f32>, // None when the game has no FOV offsets
+    pub patch: bool,
+}
+
+#[derive(Default)]
+struct Shared {
+    state: PanelState,
+    keyframes: Vec<Keyframe>,
+    path_revision: u64, // Bumped whenever the keyframes change, so the list is only refilled then
+}
+
+pub struct ControlPanel {
+    receiver: Receiver<PanelCommand>,
+    shared: Arc<Mutex<Shared>>,
+    window_thread: Option<(DWORD, JoinHandle<()>)>, // Win32 thread ID to post WM_QUIT to
+    path_shown: Option<u64>,                         // Revision of the path in the list
+}
+
+impl ControlPanel {
+    pub fn start() -> Result<Self, String> {
+        let (sender, receiver) = mpsc::channel();
+        let (ready_sender, ready) = mpsc::channel();
+        let shared = Arc::new(Mutex::new(Shared::default()));
+        let window_shared = Arc::clone(&shared);
+        let thread = std::thread::spawn(move || window_thread(sender, window_shared, ready_sender));
+        
+        match ready.recv() {
+            Ok(Ok(thread_id)) => Ok(Self { receiver, shared, window_thread: Some((thread_id, thread)), path_shown: None }),
+            Ok(Err(e)) => Err(e),
+            Err(_) => Err("The control panel thread stopped".to_string()),
+        }
+    }
+    
+    // Commands from clicks and slider moves since the last call, nothing once the window is closed
+    pub fn poll(&self) -> Vec<PanelCommand> {
+        self.receiver.try_iter().collect()
+    }
+    
+    pub fn publish(&self, state: PanelState) {
+        if let Ok(mut shared) = self.shared.lock() {
+            shared.state = state;
+        }
+    }
+    
+    // Copies the keyframes over when the path changed, a long baked path isn't copied every tick
+    pub fn publish_path(&mut self, path: &CameraPath) {
+        if self.path_shown == Some(path.revision()) {
+            return;
+        }
+        self.path_shown = Some(path.revision());
+        if let Ok(mut shared) = self.shared.lock() {
+            shared.keyframes = path.keyframes.clone();
+            shared.path_revision += 1;
+        }
+    }
+}
+
+impl Drop for ControlPanel {
+    // Same as the raw input thread: the window thread sleeps in GetMessage until it gets a message
+    fn drop(&mut self) {
+        if let Some((thread_id, thread)) = self.window_thread.take() {
+            unsafe { PostThreadMessageW(thread_id, WM_QUIT, 0, 0) };
+            let _ = thread.join();
+        }
+    }
+}
+
+// Built without the gui feature, the panel says why it can't open and the free cam carries on without it
+#[cfg(not(feature = "gui"))]
+fn window_thread(_sender: mpsc::Sender<PanelCommand>, _shared: Arc<Mutex<Shared>>, ready: mpsc::Sender<Result<DWORD, String>>) {
+    let _ = ready.send(Err("This build has no control panel".to_string()));
+}
+
+#[cfg(feature = "gui")]
+mod window {
+    use std::cell::RefCell;
+    use std::mem;
+    use std::ptr;
+    use std::sync::mpsc::Sender;
+    use std::sync::{Arc, Mutex};
+    
+    use winapi::shared::minwindef::{DWORD, HIWORD, LOWORD, LPARAM, LRESULT, TRUE, UINT, WPARAM};
+    use winapi::shared::windef::{HBRUSH, HMENU, HWND};
+    use winapi::um::commctrl::{
+        InitCommonControlsEx, ICC_BAR_CLASSES, INITCOMMONCONTROLSEX, TBM_GETPOS, TBM_SETPOS, TBM_SETRANGEMAX, TBM_SETRANGEMIN,
+        TRACKBAR_CLASS,
+    };
+    use winapi::um::errhandlingapi::GetLastError;
+    use winapi::um::libloaderapi::GetModuleHandleW;
+    use winapi::um::processthreadsapi::GetCurrentThreadId;
+    use winapi::um::wingdi::{GetStockObject, DEFAULT_GUI_FONT};
+    use winapi::um::winuser::{
+        CreateWindowExW, DefWindowProcW, DestroyWindow, DispatchMessageW, EnableWindow, GetMessageW, GetWindowTextW, KillTimer,
+        LoadCursorW, PostQuitMessage, RegisterClassExW, SendMessageW, SetTimer, SetWindowTextW, ShowWindow, TranslateMessage,
+        BN_CLICKED, BS_PUSHBUTTON, COLOR_BTNFACE, CW_USEDEFAULT, ES_AUTOHSCROLL, IDC_ARROW, LBN_DBLCLK, LBN_SELCHANGE,
+        LBS_NOINTEGRALHEIGHT, LBS_NOTIFY, LB_ADDSTRING, LB_ERR, LB_GETCURSEL, LB_RESETCONTENT, LB_SETCURSEL, MSG,
+        SW_SHOWNOACTIVATE, WM_COMMAND, WM_DESTROY, WM_HSCROLL, WM_SETFONT, WM_TIMER, WNDCLASSEXW, WS_BORDER, WS_CAPTION,
+        WS_CHILD, WS_MINIMIZEBOX, WS_SYSMENU, WS_TABSTOP, WS_VISIBLE, WS_VSCROLL,
+    };
+    
+    use super::{PanelCommand, PanelState, Shared};
+    use crate::action::Action;
+    use crate::numbers::parse_number;
+    use crate::path::Keyframe;
+    
+    const CLASS_NAME: &str = "Thps3FreeCamPanel";
+    
+    // How often the readout and sliders catch up with the main loop, ~10 Hz
+    const REFRESH_MS: UINT = 100;
+    const REFRESH_TIMER: usize = 1;
+    
+    // Control IDs for WM_COMMAND
+    const ID_PATCH: u16 = 1;
+    const ID_BOOKMARK_SAVE: u16 = 2;
+    const ID_BOOKMARK_NEXT: u16 = 3;
+    const ID_PATH_KEYFRAME: u16 = 4;
+    const ID_PATH_PLAY: u16 = 5;
+    const ID_KEYFRAME_GO: u16 = 6;
+    const ID_KEYFRAME_DELETE: u16 = 7;
+    const ID_KEYFRAMES: u16 = 8;
+    const ID_EASE_SET: u16 = 9;
+    
+    // Slider positions are integers, each slider's value is its position times the scale
+    struct Slider {
+        name: &'static str,
+        handle: HWND,
+        label: HWND,
+        scale: f32,
+        decimals: usize,
+    }
+    
+    impl Slider {
+        fn value(&self) -> f32 {
+            unsafe { SendMessageW(self.handle, TBM_GETPOS, 0, 0) as f32 * self.scale }
+        }
+        
+        fn set_value(&self, value: f32) {
+            unsafe { SendMessageW(self.handle, TBM_SETPOS, TRUE as WPARAM, (value / self.scale).round() as LPARAM) };
+            self.show(value);
+        }
+        
+        fn show(&self, value: f32) {
+            set_text(self.label, &format!("{} {:.*}", self.name, self.decimals, value));
+        }
+    }
+    
+    struct PanelWindow {
+        sender: Sender<PanelCommand>,
+        shared: Arc<Mutex<Shared>>,
+        position: HWND,
+        speed: Slider,
+        sensitivity: Slider,
+        fov: Slider,
+        patch: HWND,
+        keyframes: HWND,
+        ease_in: HWND,
+        ease_out: HWND,
+        listed: Vec<Keyframe>,    // The keyframes in the list, for filling in the ease boxes
+        seen: Option<PanelState>, // State at the last refresh, sliders only move when the main loop changed a value
+        path_revision: u64,
+    }
+    
+    thread_local! {
+        // The window procedure has no other way to reach the controls
+        static PANEL: RefCell<Option<PanelWindow>> = const { RefCell::new(None) };
+    }
+    
+    fn wide(text: &str) -> Vec<u16> {
+        text.encode_utf16().chain(Some(0)).collect()
+    }
+    
+    fn set_text(window: HWND, text: &str) {
+        unsafe { SetWindowTextW(window, wide(text).as_ptr()) };
+    }
+    
+    fn get_text(window: HWND) -> String {
+        let mut buffer = [0u16; 64];
+        let length = unsafe { GetWindowTextW(window, buffer.as_mut_ptr(), buffer.len() as i32) };
+        String::from_utf16_lossy(&buffer[..length.max(0) as usize])
+    }
+    
+    fn create_control(parent: HWND, class: &str, text: &str, style: DWORD, (x, y, width, height): (i32, i32, i32, i32), id: u16) -> HWND {
+        let (class, text) = (wide(class), wide(text));
+        let control = unsafe {
+            CreateWindowExW(0, class.as_ptr(), text.as_ptr(), WS_CHILD | WS_VISIBLE | style, x, y, width, height, parent,
+                            id as HMENU, GetModuleHandleW(ptr::null()), ptr::null_mut())
+        };
+        unsafe { SendMessageW(control, WM_SETFONT, GetStockObject(DEFAULT_GUI_FONT as i32) as WPARAM, TRUE as LPARAM) };
+        control
+    }
+    
+    fn create_slider(parent: HWND, name: &'static str, y: i32, (min, max): (u32, u32), scale: f32, decimals: usize) -> Slider {
+        let label = create_control(parent, "STATIC", name, 0, (10, y + 4, 100, 18), 0);
+        let handle = create_control(parent, TRACKBAR_CLASS, "", WS_TABSTOP, (110, y, 240, 26), 0);
+        unsafe {
+            SendMessageW(handle, TBM_SETRANGEMIN, 0, min as LPARAM);
+            SendMessageW(handle, TBM_SETRANGEMAX, TRUE as WPARAM, max as LPARAM);
+        }
+        Slider { name, handle, label, scale, decimals }
+    }
+    
+    pub(super) fn window_thread(sender: Sender<PanelCommand>, shared: Arc<Mutex<Shared>>, ready: Sender<Result<DWORD, String>>) {
+        let controls = INITCOMMONCONTROLSEX { dwSize: mem::size_of::<INITCOMMONCONTROLSEX>() as DWORD, dwICC: ICC_BAR_CLASSES };
+        unsafe { InitCommonControlsEx(&controls) };
+        
+        let class_name = wide(CLASS_NAME);
+        let class = WNDCLASSEXW {
+            cbSize: mem::size_of::<WNDCLASSEXW>() as UINT,
+            style: 0,
+            lpfnWndProc: Some(window_proc),
+            cbClsExtra: 0,
+            cbWndExtra: 0,
+            hInstance: unsafe { GetModuleHandleW(ptr::null()) },
+            hIcon: ptr::null_mut(),
+            hCursor: unsafe { LoadCursorW(ptr::null_mut(), IDC_ARROW) },
+            hbrBackground: (COLOR_BTNFACE + 1) as HBRUSH,
+            lpszMenuName: ptr::null(),
+            lpszClassName: class_name.as_ptr(),
+            hIconSm: ptr::null_mut(),
+        };
+        unsafe { RegisterClassExW(&class) };
+        
+        let title = wide("THPS3 Free Cam");
+        let window = unsafe {
+            CreateWindowExW(0, class_name.as_ptr(), title.as_ptr(), WS_CAPTION | WS_SYSMENU | WS_MINIMIZEBOX, CW_USEDEFAULT,
+                            CW_USEDEFAULT, 376, 452, ptr::null_mut(), ptr::null_mut(), class.hInstance, ptr::null_mut())
+        };
+        if window.is_null() {
+            let error_code = unsafe { GetLastError() };
+            let _ = ready.send(Err(format!("Failed to create the control panel window (Error: {})", error_code)));
+            return;
+        }
+        
+        let button = |text: &str, x: i32, y: i32, width: i32, id: u16| {
+            create_control(window, "BUTTON", text, BS_PUSHBUTTON | WS_TABSTOP, (x, y, width, 26), id)
+        };
+        let panel = PanelWindow {
+            sender,
+            shared,
+            position: create_control(window, "STATIC", "", 0, (10, 10, 340, 18), 0),
+            speed: create_slider(window, "Speed", 36, (6, 6000), 1.0, 0),
+            sensitivity: create_slider(window, "Sensitivity", 68, (1, 500), 0.01, 2),
+            fov: create_slider(window, "FOV", 100, (20, 150), 1.0, 0),
+            patch: button("Camera patch", 10, 140, 110, ID_PATCH),
+            keyframes: create_control(window, "LISTBOX", "", LBS_NOTIFY | LBS_NOINTEGRALHEIGHT | WS_BORDER | WS_VSCROLL | WS_TABSTOP,
+                                      (10, 196, 340, 140), ID_KEYFRAMES),
+            ease_in: create_control(window, "EDIT", "0", ES_AUTOHSCROLL | WS_BORDER | WS_TABSTOP, (60, 376, 50, 22), 0),
+            ease_out: create_control(window, "EDIT", "0", ES_AUTOHSCROLL | WS_BORDER | WS_TABSTOP, (175, 376, 50, 22), 0),
+            listed: Vec::new(),
+            seen: None,
+            path_revision: 0,
+        };
+        button("Save bookmark", 125, 140, 110, ID_BOOKMARK_SAVE);
+        button("Next bookmark", 240, 140, 110, ID_BOOKMARK_NEXT);
+        create_control(window, "STATIC", "Path keyframes", 0, (10, 176, 340, 18), 0);
+        button("Add keyframe", 10, 342, 80, ID_PATH_KEYFRAME);
+        button("Play path", 96, 342, 80, ID_PATH_PLAY);
+        button("Go to", 182, 342, 80, ID_KEYFRAME_GO);
+        button("Delete", 268, 342, 82, ID_KEYFRAME_DELETE);
+        create_control(window, "STATIC", "Ease in", 0, (10, 380, 50, 18), 0);
+        create_control(window, "STATIC", "Ease out", 0, (120, 380, 55, 18), 0);
+        button("Set ease", 268, 374, 82, ID_EASE_SET);
+        PANEL.with(|cell| *cell.borrow_mut() = Some(panel));
+        
+        // Shown without taking focus from the game
+        unsafe {
+            ShowWindow(window, SW_SHOWNOACTIVATE);
+            SetTimer(window, REFRESH_TIMER, REFRESH_MS, None);
+        }
+        refresh();
+        let _ = ready.send(Ok(unsafe { GetCurrentThreadId() }));
+        
+        let mut message: MSG = unsafe { mem::zeroed() };
+        while unsafe { GetMessageW(&mut message, ptr::null_mut(), 0, 0) } > 0 {
+            unsafe {
+                TranslateMessage(&message);
+                DispatchMessageW(&message);
+            }
+        }
+        
+        // Closed by the free cam rather than the user, the window is still there
+        PANEL.with(|cell| {
+            if cell.borrow_mut().take().is_some() {
+                unsafe {
+                    KillTimer(window, REFRESH_TIMER);
+                    DestroyWindow(window);
+                }
+            }
+        });
+    }
+    
+    unsafe extern "system" fn window_proc(window: HWND, message: UINT, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+        match message {
+            WM_TIMER => refresh(),
+            WM_HSCROLL => with_panel(|panel| panel.slider_moved(lparam as HWND)),
+            WM_COMMAND => with_panel(|panel| panel.command(LOWORD(wparam as DWORD), HIWORD(wparam as DWORD))),
+            WM_DESTROY => {
+                // Closing the panel leaves the free cam running
+                PANEL.with(|cell| cell.borrow_mut().take());
+                unsafe {
+                    KillTimer(window, REFRESH_TIMER);
+                    PostQuitMessage(0);
+                }
+            }
+            _ => {}
+        }
+        unsafe { DefWindowProcW(window, message, wparam, lparam) }
+    }
+    
+    // Messages that arrive while the panel is being set up or torn down are left to the default handling
+    fn with_panel(action: impl FnOnce(&mut PanelWindow)) {
+        PANEL.with(|cell| {
+            if let Ok(mut panel) = cell.try_borrow_mut()
+                && let Some(panel) = panel.as_mut()
+            {
+                action(panel);
+            }
+        });
+    }
+    
+    fn refresh() {
+        with_panel(PanelWindow::refresh);
+    }
+    
+    impl PanelWindow {
+        fn send(&self, command: PanelCommand) {
+            let _ = self.sender.send(command);
+        }
+        
+        fn slider_moved(&self, handle: HWND) {
+            let slider = [&self.speed, &self.sensitivity, &self.fov].into_iter().find(|slider| slider.handle == handle);
+            let Some(slider) = slider else {
+                return;
+            };
+            let value = slider.value();
+            slider.show(value);
+            self.send(if handle == self.speed.handle {
+                PanelCommand::Speed(value)
+            } else if handle == self.sensitivity.handle {
+                PanelCommand::Sensitivity(value)
+            } else {
+                PanelCommand::Fov(value)
+            });
+        }
+        
+        fn selected_keyframe(&self) -> Option<usize> {
+            let selected = unsafe { SendMessageW(self.keyframes, LB_GETCURSEL, 0, 0) };
+            (selected != LB_ERR).then_some(selected as usize)
+        }
+        
+        // Ease handles from the two boxes, each 0 to 1
+        fn typed_ease(&self) -> Result<(f32, f32), String> {
+            let parse = |window: HWND| {
+                let value = parse_number(&get_text(window))?;
+                if (0.0..=1.0).contains(&value) { Ok(value) } else { Err(format!("Ease handles go from 0 to 1, not {}", value)) }
+            };
+            Ok((parse(self.ease_in)?, parse(self.ease_out)?))
+        }
+        
+        fn command(&self, id: u16, notification: u16) {
+            let command = match (id, notification) {
+                (ID_KEYFRAMES, LBN_SELCHANGE) => {
+                    if let Some(keyframe) = self.selected_keyframe().and_then(|index| self.listed.get(index)) {
+                        set_text(self.ease_in, &format!("{:.2}", keyframe.ease_in));
+                        set_text(self.ease_out, &format!("{:.2}", keyframe.ease_out));
+                    }
+                    return;
+                }
+                (ID_PATCH, BN_CLICKED) => PanelCommand::Action(Action::PatchToggle),
+                (ID_BOOKMARK_SAVE, BN_CLICKED) => PanelCommand::Action(Action::BookmarkSave),
+                (ID_BOOKMARK_NEXT, BN_CLICKED) => PanelCommand::Action(Action::BookmarkNext),
+                (ID_PATH_KEYFRAME, BN_CLICKED) => PanelCommand::Action(Action::PathKeyframe),
+                (ID_PATH_PLAY, BN_CLICKED) => PanelCommand::Action(Action::PathPlay),
+                (ID_KEYFRAME_GO, BN_CLICKED) | (ID_KEYFRAMES, LBN_DBLCLK) => match self.selected_keyframe() {
+                    Some(index) => PanelCommand::GoToKeyframe(index),
+                    None => return,
+                },
+                (ID_KEYFRAME_DELETE, BN_CLICKED) => match self.selected_keyframe() {
+                    Some(index) => PanelCommand::DeleteKeyframe(index),
+                    None => return,
+                },
+                (ID_EASE_SET, BN_CLICKED) => match (self.selected_keyframe(), self.typed_ease()) {
+                    (Some(index), Ok((ease_in, ease_out))) => PanelCommand::SetEase(index, ease_in, ease_out),
+                    (None, _) => return,
+                    (_, Err(e)) => {
+                        println!("\n❌ {}", e);
+                        return;
+                    }
+                },
+                _ => return,
+            };
+            self.send(command);
+        }
+        
+        // Catches up with the main loop. A slider only moves when the value changed there, so it doesn't jump
+        // back while a drag is on its way to the main loop.
+        fn refresh(&mut self) {
+            let Ok(shared) = self.shared.lock() else {
+                return;
+            };
+            let state = shared.state.clone();
+            let keyframes = (shared.path_revision != self.path_revision).then(|| (shared.path_revision, shared.keyframes.clone()));
+            drop(shared);
+            
+            let [x, y, z] = state.position;
+            set_text(self.position, &format!("X: {:.1}   Y: {:.1}   Z: {:.1}", x, y, z));
+            
+            let seen = self.seen.as_ref();
+            if seen.is_none_or(|seen| seen.speed != state.speed) {
+                self.speed.set_value(state.speed);
+            }
+            if seen.is_none_or(|seen| seen.sensitivity != state.sensitivity) {
+                self.sensitivity.set_value(state.sensitivity);
+            }
+            if seen.is_none_or(|seen| seen.fov != state.fov) {
+                match state.fov {
+                    Some(fov) => self.fov.set_value(fov),
+                    None => set_text(self.fov.label, "FOV n/a"),
+                }
+                unsafe { EnableWindow(self.fov.handle, state.fov.is_some() as i32) };
+            }
+            if seen.is_none_or(|seen| seen.patch != state.patch) {
+                set_text(self.patch, if state.patch { "Patch: ON" } else { "Patch: OFF" });
+            }
+            self.seen = Some(state);
+            
+            if let Some((revision, keyframes)) = keyframes {
+                self.path_revision = revision;
+                let selected = self.selected_keyframe();
+                unsafe { SendMessageW(self.keyframes, LB_RESETCONTENT, 0, 0) };
+                for (index, keyframe) in keyframes.iter().enumerate() {
+                    let [x, y, z] = keyframe.position;
+                    let mut line = format!("{:>3}   {:>6.1}s   X: {:.1}  Y: {:.1}  Z: {:.1}", index + 1, keyframe.time, x, y, z);
+                    if keyframe.ease_in > 0.0 || keyframe.ease_out > 0.0 {
+                        line.push_str(&format!("   ease {:.2}/{:.2}", keyframe.ease_in, keyframe.ease_out));
+                    }
+                    let line = wide(&line);
+                    unsafe { SendMessageW(self.keyframes, LB_ADDSTRING, 0, line.as_ptr() as LPARAM) };
+                }
+                if let Some(selected) = selected.filter(|&selected| selected < keyframes.len()) {
+                    unsafe { SendMessageW(self.keyframes, LB_SETCURSEL, selected, 0) };
+                }
+                self.listed = keyframes;
+            }
+        }
+    }
+}
@@ -0,0 +1,125 @@
+// Named NOP patches on the game's code: the camera write, and the freeze and HUD instructions from offsets.toml.
+// Every patch checks the bytes it is about to overwrite, and whatever is still applied when the manager
+// goes away is put back, so an early return or a panic doesn't leave the game patched.
+
+use crate::process::{CodePatch, ProcessHandle};
+
+pub const CAMERA_WRITE: &str = "camera write";
+pub const FREEZE: &str = "freeze";
+pub const HUD: &str = "HUD";
+
+struct NamedPatch {
+    name: String,
+    address: usize,
+    original_bytes: Vec<u8>,
+    applied: Option<CodePatch>,
+}
+
+pub struct PatchManager<'a> {
+    process: &'a ProcessHandle,
+    patches: Vec<NamedPatch>, // In the order they were added, restored in reverse
+}
+
+impl<'a> PatchManager<'a> {
+    pub fn new(process: &'a ProcessHandle) -> Self {
+        Self { process, patches: Vec::new() }
+    }
+    
+    // Registers a patch without applying it. Adding a name again replaces it, restoring the old one first.
+    pub fn add(&mut self, name: &str, address: usize, original_bytes: &[u8]) {
+        self.remove(name);
+        self.patches.push(NamedPatch {
+            name: name.to_string(),
+            address,
+            original_bytes: original_bytes.to_vec(),
+            applied: None,
+        });
+    }
+    
+    // Unregisters a patch, restoring it first. Returns whether it was applied.
+    pub fn remove(&mut self, name: &str) -> bool {
+        let applied = self.is_applied(name);
+        if self.contains(name) {
+            let _ = self.restore(name);
+            self.patches.retain(|patch| patch.name != name);
+        }
+        applied
+    }
+    
+    // Every registered patch, applied or not
+    pub fn names(&self) -> Vec<&str> {
+        self.patches.iter().map(|patch| patch.name.as_str()).collect()
+    }
+    
+    pub fn contains(&self, name: &str) -> bool {
+        self.patches.iter().any(|patch| patch.name == name)
+    }
+    
+    pub fn is_applied(&self, name: &str) -> bool {
+        self.patches.iter().any(|patch| patch.name == name && patch.applied.is_some())
+    }
+    
+    fn get_mut(&mut self, name: &str) -> Result<&mut NamedPatch, String> {
+        self.patches.iter_mut()
+            .find(|patch| patch.name == name)
+            .ok_or_else(|| format!("No {} patch for this game", name))
+    }
+    
+    // NOPs the patch's bytes, patch_with_nops refuses when the game has something else there
+    pub fn apply(&mut self, name: &str) -> Result<(), String> {
+        let process = self.process;
+        let patch = self.get_mut(name)?;
+        if patch.applied.is_some() {
+            return Ok(());
+        }
+        
+        let current = process.read_bytes(patch.address, patch.original_bytes.len())?;
+        if current.iter().all(|&b| b == 0x90) {
+            // Left behind by a session that didn't exit cleanly, take it over so it can be restored
+            patch.applied = Some(CodePatch {
+                address: patch.address,
+                original_bytes: patch.original_bytes.clone(),
+                is_applied: true,
+            });
+            log::warn!("{} patch at 0x{:X} was left applied by an earlier session, taking it over", patch.name, patch.address);
+            return Ok(());
+        }
+        
+        patch.applied = Some(process.patch_with_nops(patch.address, &patch.original_bytes)?);
+        log::info!("Applied {} patch at 0x{:X}", patch.name, patch.address);
+        Ok(())
+    }
+    
+    pub fn restore(&mut self, name: &str) -> Result<(), String> {
+        let process = self.process;
+        let patch = self.get_mut(name)?;
+        if let Some(applied) = &mut patch.applied {
+            process.restore_patch(applied)?;
+            patch.applied = None;
+            log::info!("Restored {} patch at 0x{:X}", patch.name, patch.address);
+        }
+        Ok(())
+    }
+    
+    // Returns whether the patch is applied afterwards
+    pub fn toggle(&mut self, name: &str) -> Result<bool, String> {
+        if self.is_applied(name) {
+            self.restore(name).map(|_| false)
+        } else {
+            self.apply(name).map(|_| true)
+        }
+    }
+}
+
+impl Drop for PatchManager<'_> {
+    fn drop(&mut self) {
+        for patch in self.patches.iter_mut().rev() {
+            if let Some(applied) = &mut patch.applied {
+                match self.process.restore_patch(applied) {
+                    Ok(()) => log::info!("Restored {} patch at 0x{:X} on exit", patch.name, patch.address),
+                    Err(e) => log::error!("Failed to restore {} patch on exit: {}", patch.name, e),
+                }
+            }
+        }
+    }
+}
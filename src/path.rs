@@ -0,0 +1,486 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Instant;
+
+use serde::{Deserialize, Serialize};
+
+use crate::camera::{CameraMatrix, CameraPose, CameraPosition, Quaternion};
+use crate::interpolation::{ease, interpolate, InterpolationMode};
+use crate::telemetry::{format_utc_datetime, unix_millis};
+
+// Bumped whenever the path file layout changes in a way older builds can't read
+pub const PATH_FORMAT_VERSION: u32 = 1;
+
+// Hands out path revisions, so two different paths never share one
+static NEXT_REVISION: AtomicU64 = AtomicU64::new(1);
+
+fn next_revision() -> u64 {
+    NEXT_REVISION.fetch_add(1, Ordering::Relaxed)
+}
+
+// Baked paths keep one keyframe per this many seconds of playback
+const BAKE_KEYFRAME_INTERVAL: f32 = 0.1;
+
+// Recorded motion closer than this to the first or last pose is still, and trimmed off
+const STILL_DISTANCE: f32 = 0.5;
+const STILL_ROTATION: f32 = 0.0001; // 1 - |dot| of the two rotations
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Keyframe {
+    pub time: f32,            // Seconds from the start of the path
+    pub position: [f32; 3],
+    pub rotation: [f32; 4],   // Quaternion as x, y, z, w
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub ease_in: f32,         // Handle for slowing down into this keyframe, 0 to 1
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub ease_out: f32,        // Handle for speeding up out of it, 0 to 1
+}
+
+// Keyframes without easing are saved without the ease fields, like before they existed
+fn is_zero(value: &f32) -> bool {
+    *value == 0.0
+}
+
+impl Keyframe {
+    pub fn from_pose(time: f32, pose: &CameraPose) -> Self {
+        let (position, rotation) = (&pose.position, &pose.rotation);
+        Self {
+            time,
+            position: [position.x, position.y, position.z],
+            rotation: [rotation.x, rotation.y, rotation.z, rotation.w],
+            ease_in: 0.0,
+            ease_out: 0.0,
+        }
+    }
+    
+    pub fn from_matrix(time: f32, matrix: &CameraMatrix) -> Self {
+        Self::from_pose(time, &matrix.pose())
+    }
+    
+    pub fn pose(&self) -> CameraPose {
+        CameraPose::new(
+            CameraPosition::new(self.position[0], self.position[1], self.position[2]),
+            Quaternion::new(self.rotation[0], self.rotation[1], self.rotation[2], self.rotation[3]),
+        )
+    }
+}
+
+// Who made a path and for what, so shared files can be told apart. Every field is optional
+// and unknown fields are ignored, so files from older and newer builds load alike.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PathMetadata {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub game_profile: Option<String>, // Offsets file the path was made with
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub level: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created: Option<String>,      // UTC, ISO 8601
+}
+
+impl PathMetadata {
+    // Fills in whatever is still unknown, existing values are kept
+    pub fn stamp(&mut self, game_profile: &str, author: Option<&str>) {
+        if self.game_profile.is_none() {
+            self.game_profile = Some(game_profile.to_string());
+        }
+        if self.author.is_none() {
+            self.author = author.map(|author| author.to_string());
+        }
+        if self.created.is_none() {
+            self.created = Some(format_utc_datetime(unix_millis()));
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CameraPath {
+    #[serde(default)]
+    format_version: u32, // Missing in files from before versioning, read as 0
+    #[serde(default)]
+    pub metadata: PathMetadata,
+    #[serde(default, rename = "keyframe")]
+    pub keyframes: Vec<Keyframe>,
+    #[serde(skip, default = "next_revision")]
+    revision: u64, // New for every edit made through the methods below
+}
+
+impl Default for CameraPath {
+    fn default() -> Self {
+        Self {
+            format_version: PATH_FORMAT_VERSION,
+            metadata: PathMetadata::default(),
+            keyframes: Vec::new(),
+            revision: next_revision(),
+        }
+    }
+}
+
+impl CameraPath {
+    pub fn from_keyframes(keyframes: Vec<Keyframe>) -> Self {
+        Self { keyframes, ..Self::default() }
+    }
+    
+    pub fn load(path: &str) -> Result<Self, String> {
+        let contents = std::fs::read_to_string(path).map_err(|e| format!("Failed to read '{}': {}", path, e))?;
+        Self::parse(&contents, path)
+    }
+    
+    // `path` only names the source in error messages
+    pub fn parse(contents: &str, path: &str) -> Result<Self, String> {
+        // Check the version on its own first, a newer layout may not parse into this one at all
+        #[derive(Deserialize)]
+        struct VersionProbe {
+            #[serde(default)]
+            format_version: u32,
+        }
+        let probe: VersionProbe = toml::from_str(contents).map_err(|e| format!("Failed to parse '{}': {}", path, e))?;
+        if probe.format_version > PATH_FORMAT_VERSION {
+            return Err(format!(
+                "'{}' uses path format {}, but this build only reads up to format {} - update the tool to load it",
+                path, probe.format_version, PATH_FORMAT_VERSION
+            ));
+        }
+        
+        let mut camera_path: CameraPath = toml::from_str(contents).map_err(|e| format!("Failed to parse '{}': {}", path, e))?;
+        camera_path.format_version = PATH_FORMAT_VERSION; // Saved back in the current format
+        camera_path.keyframes.sort_by(|a, b| a.time.total_cmp(&b.time));
+        Ok(camera_path)
+    }
+    
+    pub fn save(&self, path: &str) -> Result<(), String> {
+        let contents = toml::to_string(self).map_err(|e| format!("Failed to serialize camera path: {}", e))?;
+        std::fs::write(path, contents).map_err(|e| format!("Failed to write '{}': {}", path, e))
+    }
+    
+    pub fn is_empty(&self) -> bool {
+        self.keyframes.is_empty()
+    }
+    
+    pub fn len(&self) -> usize {
+        self.keyframes.len()
+    }
+    
+    pub fn duration(&self) -> f32 {
+        self.keyframes.last().map_or(0.0, |keyframe| keyframe.time)
+    }
+    
+    // Differs after every edit and between paths, a copy keeps it until either one is edited
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+    
+    // Drops the keyframes, a new path gets fresh metadata
+    pub fn clear(&mut self) {
+        *self = CameraPath::default();
+    }
+    
+    // Appends the current camera pose `spacing` seconds after the last keyframe
+    pub fn add_keyframe(&mut self, matrix: &CameraMatrix, spacing: f32) {
+        let time = match self.keyframes.last() {
+            Some(last) => last.time + spacing,
+            None => 0.0,
+        };
+        self.keyframes.push(Keyframe::from_matrix(time, matrix));
+        self.revision = next_revision();
+    }
+    
+    // Removes a keyframe, the ones after it move up to close the gap so the rest of the path keeps its timing
+    pub fn remove_keyframe(&mut self, index: usize) -> Option<Keyframe> {
+        if index >= self.keyframes.len() {
+            return None;
+        }
+        let removed = self.keyframes.remove(index);
+        if let Some(next) = self.keyframes.get(index) {
+            let gap = next.time - removed.time;
+            for keyframe in &mut self.keyframes[index..] {
+                keyframe.time -= gap;
+            }
+        }
+        self.revision = next_revision();
+        Some(removed)
+    }
+    
+    // Keeps the stretch from `start` to `end` seconds, which then starts at 0. The cut ends get a keyframe with
+    // the pose the path had there, so the kept part plays exactly as before.
+    pub fn trim(&mut self, start: f32, end: f32, mode: InterpolationMode) -> Result<(), String> {
+        let end = end.min(self.duration());
+        if start < 0.0 || start >= end {
+            return Err(format!("Can't trim to {:.2}s - {:.2}s of a {:.2}s path", start, end, self.duration()));
+        }
+        let (Some(first), Some(last)) = (self.sample(start, mode), self.sample(end, mode)) else {
+            return Err("The path has no keyframes".to_string());
+        };
+        
+        let mut keyframes = vec![Keyframe::from_pose(0.0, &first)];
+        keyframes.extend(self.keyframes.iter()
+            .filter(|keyframe| keyframe.time > start && keyframe.time < end)
+            .map(|keyframe| Keyframe { time: keyframe.time - start, ..keyframe.clone() }));
+        keyframes.push(Keyframe::from_pose(end - start, &last));
+        self.keyframes = keyframes;
+        self.revision = next_revision();
+        Ok(())
+    }
+    
+    // Sets the ease handles of a keyframe, each clamped to 0..1
+    pub fn set_ease(&mut self, index: usize, ease_in: f32, ease_out: f32) -> Result<(), String> {
+        let count = self.keyframes.len();
+        let keyframe = self.keyframes.get_mut(index)
+            .ok_or_else(|| format!("There is no keyframe {} in a path of {} keyframes", index + 1, count))?;
+        keyframe.ease_in = ease_in.clamp(0.0, 1.0);
+        keyframe.ease_out = ease_out.clamp(0.0, 1.0);
+        self.revision = next_revision();
+        Ok(())
+    }
+    
+    // Index of the last keyframe at or before `time`
+    pub fn keyframe_index_at(&self, time: f32) -> usize {
+        self.keyframes.iter().rposition(|keyframe| keyframe.time <= time).unwrap_or(0)
+    }
+    
+    // Pose at `time` seconds, clamped to the ends of the path
+    pub fn sample(&self, time: f32, mode: InterpolationMode) -> Option<CameraPose> {
+        let first = self.keyframes.first()?;
+        if self.keyframes.len() == 1 || time <= first.time {
+            return Some(first.pose());
+        }
+        
+        let last_index = self.keyframes.len() - 1;
+        let segment = match self.keyframes.windows(2).position(|pair| time < pair[1].time) {
+            Some(index) => index,
+            None => return Some(self.keyframes[last_index].pose()),
+        };
+        
+        let from = &self.keyframes[segment];
+        let to = &self.keyframes[segment + 1];
+        let prev = &self.keyframes[segment.saturating_sub(1)];
+        let next = &self.keyframes[(segment + 2).min(last_index)];
+        
+        let length = to.time - from.time;
+        let t = if length > 0.0 { (time - from.time) / length } else { 1.0 };
+        let t = ease(t, from.ease_out, to.ease_in);
+        Some(interpolate(&prev.pose(), &from.pose(), &to.pose(), &next.pose(), t, mode))
+    }
+    
+    // Writes the pose at `time` into `matrix`, leaving the non-rotation elements untouched
+    pub fn apply(&self, time: f32, mode: InterpolationMode, matrix: &mut CameraMatrix) -> bool {
+        match self.sample(time, mode) {
+            Some(pose) => {
+                matrix.set_pose(&pose);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+// Next unused `<name>_v<N>.<ext>` next to `path`, so baking never overwrites an earlier take
+pub fn next_version_path(path: &str) -> String {
+    let (stem, extension) = match path.rsplit_once('.') {
+        Some((stem, extension)) if !stem.is_empty() => (stem, format!(".{}", extension)),
+        _ => (path, String::new()),
+    };
+    
+    // Baking a baked path continues its numbering instead of appending another suffix
+    let stem = match stem.rsplit_once("_v") {
+        Some((base, version)) if !version.is_empty() && version.chars().all(|c| c.is_ascii_digit()) => base,
+        _ => stem,
+    };
+    
+    (2..)
+        .map(|version| format!("{}_v{}{}", stem, version, extension))
+        .find(|candidate| !std::path::Path::new(candidate).exists())
+        .unwrap()
+}
+
+// Records the camera while a path plays with manual corrections layered on top of it
+pub struct PathBake {
+    keyframes: Vec<Keyframe>,
+    position_offset: CameraPosition,
+    rotation_offset: Quaternion, // Relative to the path's own rotation
+}
+
+impl Default for PathBake {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PathBake {
+    pub fn new() -> Self {
+        Self {
+            keyframes: Vec::new(),
+            position_offset: CameraPosition::new(0.0, 0.0, 0.0),
+            rotation_offset: Quaternion::new(0.0, 0.0, 0.0, 1.0),
+        }
+    }
+    
+    // The path pose with all corrections flown so far applied
+    pub fn corrected(&self, pose: &CameraPose) -> CameraPose {
+        CameraPose {
+            position: CameraPosition::new(
+                pose.position.x + self.position_offset.x,
+                pose.position.y + self.position_offset.y,
+                pose.position.z + self.position_offset.z,
+            ),
+            rotation: pose.rotation.multiply(&self.rotation_offset).normalize(),
+        }
+    }
+    
+    // Stores where the operator moved the camera relative to the path, so the correction sticks
+    pub fn update_correction(&mut self, path_pose: &CameraPose, actual: &CameraPose) {
+        self.position_offset = CameraPosition::new(
+            actual.position.x - path_pose.position.x,
+            actual.position.y - path_pose.position.y,
+            actual.position.z - path_pose.position.z,
+        );
+        self.rotation_offset = path_pose.rotation.conjugate().multiply(&actual.rotation).normalize();
+    }
+    
+    pub fn record(&mut self, time: f32, matrix: &CameraMatrix) {
+        if let Some(last) = self.keyframes.last()
+            && time - last.time < BAKE_KEYFRAME_INTERVAL
+        {
+            return;
+        }
+        self.keyframes.push(Keyframe::from_matrix(time, matrix));
+    }
+    
+    // Adds the final pose so the baked path lasts as long as the original
+    pub fn finish(mut self, time: f32, matrix: &CameraMatrix) -> CameraPath {
+        if self.keyframes.last().is_none_or(|last| last.time < time) {
+            self.keyframes.push(Keyframe::from_matrix(time, matrix));
+        }
+        CameraPath::from_keyframes(self.keyframes)
+    }
+}
+
+// Records the camera every tick while it is flown by hand, for improvised moves that keyframes can't capture.
+// Played back, the dense track repeats the move exactly.
+pub struct MotionRecording {
+    started: Instant,
+    keyframes: Vec<Keyframe>,
+}
+
+impl Default for MotionRecording {
+    fn default() -> Self {
+        Self::start()
+    }
+}
+
+impl MotionRecording {
+    pub fn start() -> Self {
+        Self { started: Instant::now(), keyframes: Vec::new() }
+    }
+    
+    pub fn len(&self) -> usize {
+        self.keyframes.len()
+    }
+    
+    pub fn is_empty(&self) -> bool {
+        self.keyframes.is_empty()
+    }
+    
+    pub fn elapsed(&self) -> f32 {
+        self.started.elapsed().as_secs_f32()
+    }
+    
+    pub fn record(&mut self, matrix: &CameraMatrix) {
+        let time = self.elapsed();
+        if self.keyframes.last().is_none_or(|last| time > last.time) {
+            self.keyframes.push(Keyframe::from_matrix(time, matrix));
+        }
+    }
+    
+    // The recorded track with the still stretches before the first move and after the last one cut off.
+    // Empty when the camera never moved.
+    pub fn finish(self) -> CameraPath {
+        let keyframes = self.keyframes;
+        let (Some(first), Some(last)) = (keyframes.first(), keyframes.last()) else {
+            return CameraPath::default();
+        };
+        let (Some(moved), Some(settled)) = (keyframes.iter().position(|keyframe| !is_still(keyframe, first)),
+                                            keyframes.iter().rposition(|keyframe| !is_still(keyframe, last))) else {
+            return CameraPath::default();
+        };
+        
+        // One still keyframe stays on either side, so the move eases out of and into its rest poses. A first
+        // keyframe that isn't still even next to itself (NaN from a bad read) leaves nothing before the move.
+        let start = moved.saturating_sub(1);
+        let kept = &keyframes[start..=(settled + 1).min(keyframes.len() - 1).max(start)];
+        let offset = kept[0].time;
+        CameraPath::from_keyframes(kept.iter().map(|keyframe| Keyframe { time: keyframe.time - offset, ..keyframe.clone() }).collect())
+    }
+}
+
+fn is_still(keyframe: &Keyframe, rest: &Keyframe) -> bool {
+    let distance = (0..3).map(|axis| (keyframe.position[axis] - rest.position[axis]).powi(2)).sum::<f32>().sqrt();
+    let dot: f32 = (0..4).map(|axis| keyframe.rotation[axis] * rest.rotation[axis]).sum();
+    distance < STILL_DISTANCE && 1.0 - dot.abs() < STILL_ROTATION
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn keyframe(time: f32, x: f32) -> Keyframe {
+        Keyframe { time, position: [x, 0.0, 0.0], rotation: [0.0, 0.0, 0.0, 1.0], ease_in: 0.0, ease_out: 0.0 }
+    }
+    
+    fn straight_path() -> CameraPath {
+        CameraPath::from_keyframes(vec![keyframe(0.0, 0.0), keyframe(1.0, 10.0), keyframe(2.0, 20.0), keyframe(3.0, 30.0)])
+    }
+    
+    fn recording(keyframes: Vec<Keyframe>) -> MotionRecording {
+        MotionRecording { started: Instant::now(), keyframes }
+    }
+    
+    #[test]
+    fn every_edit_gets_a_new_revision() {
+        let mut path = straight_path();
+        assert_ne!(path.revision(), CameraPath::default().revision());
+        
+        // Same length, duration and ease totals as before, only the order of the handles changed
+        path.set_ease(0, 0.5, 0.0).unwrap();
+        path.set_ease(1, 0.0, 0.5).unwrap();
+        let revision = path.revision();
+        path.set_ease(0, 0.0, 0.5).unwrap();
+        path.set_ease(1, 0.5, 0.0).unwrap();
+        assert_ne!(path.revision(), revision);
+        assert_eq!(path.clone().revision(), path.revision());
+    }
+    
+    #[test]
+    fn trim_keeps_the_cut_stretch() {
+        let mut path = straight_path();
+        path.trim(0.5, 2.5, InterpolationMode::Linear).unwrap();
+        let keyframes: Vec<(f32, f32)> = path.keyframes.iter().map(|keyframe| (keyframe.time, keyframe.position[0])).collect();
+        assert_eq!(keyframes, [(0.0, 5.0), (0.5, 10.0), (1.5, 20.0), (2.0, 25.0)]);
+    }
+    
+    #[test]
+    fn trim_clamps_the_end_and_refuses_empty_stretches() {
+        let mut path = straight_path();
+        path.trim(2.0, 10.0, InterpolationMode::Linear).unwrap();
+        assert_eq!(path.duration(), 1.0);
+        assert!(straight_path().trim(2.0, 1.0, InterpolationMode::Linear).is_err());
+        assert!(straight_path().trim(-1.0, 1.0, InterpolationMode::Linear).is_err());
+        assert!(straight_path().trim(3.0, 4.0, InterpolationMode::Linear).is_err());
+        assert!(CameraPath::default().trim(0.0, 1.0, InterpolationMode::Linear).is_err());
+    }
+    
+    #[test]
+    fn finish_cuts_the_still_ends() {
+        let path = recording(vec![keyframe(0.0, 0.0), keyframe(1.0, 0.0), keyframe(2.0, 5.0), keyframe(3.0, 10.0),
+                                  keyframe(4.0, 10.0), keyframe(5.0, 10.0)]).finish();
+        let times: Vec<f32> = path.keyframes.iter().map(|keyframe| keyframe.time).collect();
+        assert_eq!(times, [0.0, 1.0, 2.0]);
+    }
+    
+    #[test]
+    fn finish_survives_a_moving_first_keyframe() {
+        assert_eq!(recording(vec![keyframe(0.0, f32::NAN), keyframe(1.0, 5.0)]).finish().keyframes.len(), 2);
+        assert!(recording(vec![keyframe(0.0, 1.0), keyframe(1.0, 1.0)]).finish().keyframes.is_empty());
+    }
+}
@@ -0,0 +1,116 @@
+// Where path playback is on the path. Path time runs at `rate` times real time and can be paused, scrubbed
+// and repeated, so a shot can be rehearsed over and over while the skater practises the line.
+
+use std::time::Instant;
+
+use serde::Deserialize;
+
+// Rates the faster/slower keys step through
+pub const PLAYBACK_RATES: &[f32] = &[0.1, 0.25, 0.5, 0.75, 1.0, 1.5, 2.0, 3.0, 4.0];
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PlaybackRepeat {
+    #[default]
+    Once,
+    Loop,     // Starts over from the beginning
+    PingPong, // Plays backwards to the beginning, then forwards again
+}
+
+impl PlaybackRepeat {
+    pub fn name(self) -> &'static str {
+        match self {
+            PlaybackRepeat::Once => "once",
+            PlaybackRepeat::Loop => "loop",
+            PlaybackRepeat::PingPong => "ping-pong",
+        }
+    }
+    
+    pub fn next(self) -> Self {
+        match self {
+            PlaybackRepeat::Once => PlaybackRepeat::Loop,
+            PlaybackRepeat::Loop => PlaybackRepeat::PingPong,
+            PlaybackRepeat::PingPong => PlaybackRepeat::Once,
+        }
+    }
+}
+
+// The rate `steps` steps faster (or slower, when negative) than `rate`
+pub fn step_rate(rate: f32, steps: i32) -> f32 {
+    let current = PLAYBACK_RATES.iter().position(|&step| step >= rate - 0.001).unwrap_or(PLAYBACK_RATES.len() - 1) as i32;
+    PLAYBACK_RATES[(current + steps).clamp(0, PLAYBACK_RATES.len() as i32 - 1) as usize]
+}
+
+pub struct PlaybackClock {
+    time: f32,
+    backwards: bool, // On the way back in ping-pong
+    paused: bool,
+    last_tick: Instant,
+}
+
+impl Default for PlaybackClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PlaybackClock {
+    pub fn new() -> Self {
+        Self { time: 0.0, backwards: false, paused: false, last_tick: Instant::now() }
+    }
+    
+    pub fn time(&self) -> f32 {
+        self.time
+    }
+    
+    // Jumps to `time` on the path, the next tick carries on from there
+    pub fn seek(&mut self, time: f32) {
+        self.time = time;
+    }
+    
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+    
+    pub fn toggle_pause(&mut self) -> bool {
+        self.paused = !self.paused;
+        self.paused
+    }
+    
+    // Moves the path time on by the real time since the last tick at `rate`, plus `scrub` seconds of path time
+    // per second while a scrub key is held, and wraps it around the ends of a `duration` long path. Returns the
+    // path time for this tick.
+    pub fn tick(&mut self, duration: f32, rate: f32, repeat: PlaybackRepeat, scrub: f32) -> f32 {
+        let seconds = self.last_tick.elapsed().as_secs_f32();
+        self.last_tick = Instant::now();
+        
+        let step = if self.paused { 0.0 } else { seconds * rate };
+        let direction = if self.backwards { -1.0 } else { 1.0 };
+        let delta = step * direction + scrub * seconds;
+        self.time += delta;
+        if duration <= 0.0 {
+            self.time = 0.0;
+            return self.time;
+        }
+        
+        if repeat != PlaybackRepeat::PingPong {
+            self.backwards = false;
+        }
+        match repeat {
+            // Left to run past the end, that is how playback knows it finished
+            PlaybackRepeat::Once => self.time = self.time.max(0.0),
+            PlaybackRepeat::Loop => self.time = self.time.rem_euclid(duration),
+            PlaybackRepeat::PingPong => {
+                // Folded in one step, bouncing one end at a time never finishes once 2 * duration is below
+                // the precision of a large time. In the second half of the fold the path plays the other way.
+                if self.time > duration || self.time < 0.0 {
+                    let folded = self.time.rem_euclid(2.0 * duration);
+                    let returning = folded > duration;
+                    self.time = if returning { 2.0 * duration - folded } else { folded };
+                    self.backwards = (delta > 0.0) == returning;
+                }
+            }
+        }
+        self.time
+    }
+}
@@ -0,0 +1,328 @@
+// Plugins: DLLs in plugin_dir that add camera behaviour without forking the tool, e.g. custom rigs or exporters.
+// They are loaded at startup and called every matrix mode tick with a CameraContext they can change, and with
+// every key press. The interface is plain C, so a plugin can be written in anything that builds a 32-bit DLL:
+// it exports `thps3_free_cam_plugin`, which returns its API version and fills in a PluginVTable. Rust plugins implement the Plugin trait in
+// a cdylib and let export_plugin! write that function.
+
+use std::ffi::{c_char, c_void, CStr};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use winapi::shared::minwindef::HMODULE;
+use winapi::um::errhandlingapi::GetLastError;
+use winapi::um::libloaderapi::{FreeLibrary, GetProcAddress, LoadLibraryW};
+
+use crate::camera::{CameraMatrix, CameraPosition};
+use crate::input::Key;
+
+// Bumped whenever CameraContext or PluginVTable changes. Plugins built for another version aren't loaded.
+pub const PLUGIN_API_VERSION: u32 = 1;
+
+// The function every plugin DLL exports
+pub const PLUGIN_ENTRY: &CStr = c"thps3_free_cam_plugin";
+
+// What a plugin sees every tick. Setting `write` makes the free cam write `matrix` back into the game.
+#[repr(C)]
+#[derive(Debug, Clone, Default)]
+pub struct CameraContext {
+    pub matrix: [f32; 16], // The camera matrix in the game's layout, as left by the plugins before this one
+    pub fov: f32,          // Horizontal degrees, 0 without FOV offsets
+    pub skater: [f32; 3],
+    pub has_skater: u32,   // Non-zero when `skater` was read, needs a [skater] chain
+    pub patch: u32,        // Non-zero while the camera write patch is on
+    pub delta: f32,        // Seconds since the last tick
+    pub write: u32,
+}
+
+impl CameraContext {
+    pub fn matrix(&self) -> CameraMatrix {
+        CameraMatrix { data: self.matrix }
+    }
+    
+    pub fn set_matrix(&mut self, matrix: &CameraMatrix) {
+        self.matrix = matrix.data;
+        self.write = 1;
+    }
+    
+    pub fn skater(&self) -> Option<CameraPosition> {
+        (self.has_skater != 0).then(|| CameraPosition::new(self.skater[0], self.skater[1], self.skater[2]))
+    }
+}
+
+// Filled in by a plugin's entry function. Every callback gets `state` back and may be left out.
+#[repr(C)]
+pub struct PluginVTable {
+    pub api_version: u32,
+    pub name: *const c_char, // NUL-terminated, lives as long as the DLL
+    pub state: *mut c_void,
+    pub init: Option<unsafe extern "C" fn(state: *mut c_void) -> u32>, // Zero when the plugin can't run
+    pub on_frame: Option<unsafe extern "C" fn(state: *mut c_void, context: *mut CameraContext) -> u32>, // Zero on failure
+    pub on_hotkey: Option<unsafe extern "C" fn(state: *mut c_void, vk_code: i32)>,
+    pub destroy: Option<unsafe extern "C" fn(state: *mut c_void)>,
+}
+
+// Returns the plugin's API version. The plugin fills in `table` only when that is PLUGIN_API_VERSION and `size`
+// is at least the size of its PluginVTable, so a table from another version is never read.
+pub type PluginEntry = unsafe extern "C" fn(table: *mut PluginVTable, size: usize) -> u32;
+
+// The Rust side of a plugin, turned into the C interface by export_plugin!
+pub trait Plugin {
+    // Called once after loading. An error keeps the plugin from being used.
+    fn init(&mut self) -> Result<(), String> {
+        Ok(())
+    }
+    
+    // Called every matrix mode tick while the camera isn't held
+    fn on_frame(&mut self, _context: &mut CameraContext) {}
+    
+    // Called for every key pressed while the game or the console has focus
+    fn on_hotkey(&mut self, _key: Key) {}
+}
+
+// Exports `$plugin` (a type implementing Plugin and Default) as `$name`. Panics are caught and unload the plugin.
+#[macro_export]
+macro_rules! export_plugin {
+    ($plugin:ty, $name:literal) => {
+        #[unsafe(no_mangle)]
+        pub unsafe extern "C" fn thps3_free_cam_plugin(table: *mut $crate::plugin::PluginVTable, size: usize) -> u32 {
+            use std::ffi::c_void;
+            use std::panic::{catch_unwind, AssertUnwindSafe};
+            use $crate::plugin::{CameraContext, Plugin, PluginVTable, PLUGIN_API_VERSION};
+            
+            unsafe extern "C" fn init(state: *mut c_void) -> u32 {
+                let plugin = unsafe { &mut *(state as *mut $plugin) };
+                match catch_unwind(AssertUnwindSafe(|| plugin.init())) {
+                    Ok(Ok(())) => 1,
+                    Ok(Err(e)) => {
+                        println!("⚠️ {}: {}", $name, e);
+                        0
+                    }
+                    Err(_) => 0,
+                }
+            }
+            unsafe extern "C" fn on_frame(state: *mut c_void, context: *mut CameraContext) -> u32 {
+                let plugin = unsafe { &mut *(state as *mut $plugin) };
+                let context = unsafe { &mut *context };
+                catch_unwind(AssertUnwindSafe(|| plugin.on_frame(context))).is_ok() as u32
+            }
+            unsafe extern "C" fn on_hotkey(state: *mut c_void, vk_code: i32) {
+                let plugin = unsafe { &mut *(state as *mut $plugin) };
+                let _ = catch_unwind(AssertUnwindSafe(|| plugin.on_hotkey($crate::input::Key(vk_code))));
+            }
+            unsafe extern "C" fn destroy(state: *mut c_void) {
+                drop(unsafe { Box::from_raw(state as *mut $plugin) });
+            }
+            
+            if table.is_null() || size < std::mem::size_of::<PluginVTable>() {
+                return PLUGIN_API_VERSION;
+            }
+            let plugin: Box<$plugin> = Box::default();
+            let vtable = PluginVTable {
+                api_version: PLUGIN_API_VERSION,
+                name: concat!($name, "\0").as_ptr().cast(),
+                state: Box::into_raw(plugin).cast(),
+                init: Some(init),
+                on_frame: Some(on_frame),
+                on_hotkey: Some(on_hotkey),
+                destroy: Some(destroy),
+            };
+            unsafe { table.write(vtable) };
+            PLUGIN_API_VERSION
+        }
+    };
+}
+
+// A plugin DLL and what its entry function returned
+pub struct LoadedPlugin {
+    name: String,
+    module: HMODULE,
+    vtable: PluginVTable,
+}
+
+impl LoadedPlugin {
+    // Loads the DLL at `path`, checks its API version and initialises it
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let wide: Vec<u16> = path.as_os_str().to_string_lossy().encode_utf16().chain(Some(0)).collect();
+        let module = unsafe { LoadLibraryW(wide.as_ptr()) };
+        if module.is_null() {
+            let error_code = unsafe { GetLastError() };
+            return Err(format!("Failed to load plugin '{}' (Error: {}) - plugins must be 32-bit DLLs", path.display(), error_code));
+        }
+        let entry = unsafe { GetProcAddress(module, PLUGIN_ENTRY.as_ptr()) };
+        if entry.is_null() {
+            unsafe { FreeLibrary(module) };
+            return Err(format!("'{}' is not a plugin, it doesn't export {}", path.display(), PLUGIN_ENTRY.to_string_lossy()));
+        }
+        
+        let entry: PluginEntry = unsafe { std::mem::transmute(entry) };
+        let mut table = std::mem::MaybeUninit::<PluginVTable>::zeroed();
+        let version = unsafe { entry(table.as_mut_ptr(), size_of::<PluginVTable>()) };
+        // The version comes back on its own, the table is only read once it's known to have this layout
+        if version != PLUGIN_API_VERSION {
+            unsafe { FreeLibrary(module) };
+            return Err(format!("Plugin '{}' was built for plugin API {}, this build has {}",
+                               path.display(), version, PLUGIN_API_VERSION));
+        }
+        // All zeroes is a valid table (null name and state, no callbacks), so one the plugin left alone is too
+        let vtable = unsafe { table.assume_init() };
+        if vtable.api_version != PLUGIN_API_VERSION {
+            unsafe { FreeLibrary(module) };
+            return Err(format!("Plugin '{}' didn't fill in its plugin table", path.display()));
+        }
+        let name = if vtable.name.is_null() {
+            path.file_stem().map_or(String::new(), |stem| stem.to_string_lossy().to_string())
+        } else {
+            unsafe { CStr::from_ptr(vtable.name) }.to_string_lossy().to_string()
+        };
+        // Dropping it from here on calls destroy and unloads the DLL
+        let plugin = Self { name, module, vtable };
+        if let Some(init) = plugin.vtable.init
+            && unsafe { init(plugin.vtable.state) } == 0
+        {
+            return Err(format!("Plugin '{}' failed to initialise", plugin.name));
+        }
+        Ok(plugin)
+    }
+    
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    
+    fn on_frame(&mut self, context: &mut CameraContext) -> Result<(), String> {
+        match self.vtable.on_frame {
+            Some(on_frame) if unsafe { on_frame(self.vtable.state, context) } == 0 => Err(format!("Plugin '{}' failed", self.name)),
+            _ => Ok(()),
+        }
+    }
+    
+    fn on_hotkey(&mut self, key: Key) {
+        if let Some(on_hotkey) = self.vtable.on_hotkey {
+            unsafe { on_hotkey(self.vtable.state, key.0) };
+        }
+    }
+}
+
+impl Drop for LoadedPlugin {
+    fn drop(&mut self) {
+        if let Some(destroy) = self.vtable.destroy {
+            unsafe { destroy(self.vtable.state) };
+        }
+        unsafe { FreeLibrary(self.module) };
+    }
+}
+
+// Every plugin that loaded, called in file name order
+#[derive(Default)]
+pub struct Plugins {
+    loaded: Vec<LoadedPlugin>,
+    last_frame: Option<Instant>,
+}
+
+impl Plugins {
+    // Where `directory` is: a relative path is next to the executable rather than wherever it was started from
+    pub fn directory(directory: &str) -> PathBuf {
+        let path = Path::new(directory);
+        match std::env::current_exe() {
+            Ok(exe) if path.is_relative() => exe.with_file_name(path),
+            _ => path.to_path_buf(),
+        }
+    }
+    
+    // Loads every .dll in `directory` (see Plugins::directory). A missing directory means no plugins, a DLL that
+    // fails is skipped and reported.
+    pub fn load(directory: &str) -> (Self, Vec<String>) {
+        let mut plugins = Self::default();
+        let mut errors = Vec::new();
+        let Ok(entries) = std::fs::read_dir(Self::directory(directory)) else {
+            return (plugins, errors);
+        };
+        let mut paths: Vec<_> = entries.filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("dll")))
+            .collect();
+        paths.sort();
+        
+        for path in paths {
+            match LoadedPlugin::load(&path) {
+                Ok(plugin) => {
+                    println!("🧩 Loaded plugin '{}' from {}", plugin.name(), path.display());
+                    log::info!("Loaded plugin '{}' from {}", plugin.name(), path.display());
+                    plugins.loaded.push(plugin);
+                }
+                Err(e) => errors.push(e),
+            }
+        }
+        (plugins, errors)
+    }
+    
+    pub fn is_empty(&self) -> bool {
+        self.loaded.is_empty()
+    }
+    
+    pub fn names(&self) -> Vec<&str> {
+        self.loaded.iter().map(LoadedPlugin::name).collect()
+    }
+    
+    // Hands `context` to every plugin in turn, each seeing what the one before changed. A plugin that fails is
+    // unloaded, its error returned.
+    pub fn frame(&mut self, context: &mut CameraContext) -> Vec<String> {
+        let now = Instant::now();
+        context.delta = self.last_frame.map_or(0.0, |then| now.duration_since(then).as_secs_f32());
+        self.last_frame = Some(now);
+        
+        let mut errors = Vec::new();
+        self.loaded.retain_mut(|plugin| match plugin.on_frame(context) {
+            Ok(()) => true,
+            Err(e) => {
+                errors.push(e);
+                false
+            }
+        });
+        errors
+    }
+    
+    // The clock restarts, so the first tick after a pause doesn't see the pause as one long frame
+    pub fn pause(&mut self) {
+        self.last_frame = None;
+    }
+    
+    pub fn hotkey(&mut self, key: Key) {
+        for plugin in &mut self.loaded {
+            plugin.on_hotkey(key);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[derive(Default)]
+    struct Nothing;
+    
+    impl Plugin for Nothing {}
+    
+    crate::export_plugin!(Nothing, "nothing");
+    
+    #[test]
+    fn entry_fills_the_table_only_when_it_fits() {
+        let mut table = std::mem::MaybeUninit::<PluginVTable>::zeroed();
+        let version = unsafe { thps3_free_cam_plugin(table.as_mut_ptr(), size_of::<PluginVTable>() - 1) };
+        assert_eq!(version, PLUGIN_API_VERSION);
+        assert_eq!(unsafe { table.assume_init_ref() }.api_version, 0);
+        
+        let version = unsafe { thps3_free_cam_plugin(table.as_mut_ptr(), size_of::<PluginVTable>()) };
+        let table = unsafe { table.assume_init() };
+        assert_eq!(version, PLUGIN_API_VERSION);
+        assert_eq!(table.api_version, PLUGIN_API_VERSION);
+        assert_eq!(unsafe { CStr::from_ptr(table.name) }, c"nothing");
+        unsafe { table.destroy.unwrap()(table.state) };
+    }
+    
+    #[test]
+    fn relative_directory_is_next_to_the_executable() {
+        let exe = std::env::current_exe().unwrap();
+        assert_eq!(Plugins::directory("plugins"), exe.with_file_name("plugins"));
+        let absolute = exe.parent().unwrap().join("elsewhere");
+        assert_eq!(Plugins::directory(&absolute.to_string_lossy()), absolute);
+    }
+}
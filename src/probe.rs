@@ -0,0 +1,123 @@
+// Experimental: estimate the distance to level geometry along the view ray.
+//
+// There is no known pointer to the game's collision data, so this uses the visible clipping
+// behaviour instead. The camera is pushed forward along its view ray and the pixels in the
+// middle of the game window are sampled. While the camera is still in front of the surface it
+// looks at, the centre keeps roughly the same colour; once the camera passes through it the
+// surface gets clipped and whatever is behind it shows up. A binary search over the distance
+// finds that transition.
+
+use std::thread;
+use std::time::Duration;
+
+use winapi::shared::windef::{HWND, RECT};
+use winapi::um::wingdi::{GetBValue, GetGValue, GetPixel, GetRValue, CLR_INVALID};
+use winapi::um::winuser::{GetClientRect, GetDC, ReleaseDC};
+
+use crate::process::ProcessHandle;
+
+pub struct DepthProbe {
+    pub max_distance: f32,
+    pub iterations: u32,
+    pub settle_ms: u64,       // Time for the game to render a frame at the new position
+    pub color_tolerance: f32, // Average per-channel difference that counts as "different geometry"
+}
+
+impl DepthProbe {
+    pub fn new(max_distance: f32, iterations: u32) -> Self {
+        Self {
+            max_distance,
+            iterations,
+            settle_ms: 60,
+            color_tolerance: 40.0,
+        }
+    }
+    
+    // Average colour of a small square in the middle of the window's client area
+    fn sample_center(window: HWND) -> Result<(f32, f32, f32), String> {
+        let mut rect = RECT { left: 0, top: 0, right: 0, bottom: 0 };
+        if unsafe { GetClientRect(window, &mut rect) } == 0 {
+            return Err("Failed to get game window size".to_string());
+        }
+        
+        let dc = unsafe { GetDC(window) };
+        if dc.is_null() {
+            return Err("Failed to get game window device context".to_string());
+        }
+        
+        let center_x = (rect.right - rect.left) / 2;
+        let center_y = (rect.bottom - rect.top) / 2;
+        let mut total = (0.0, 0.0, 0.0);
+        let mut samples = 0.0;
+        
+        for dy in -2..=2 {
+            for dx in -2..=2 {
+                let color = unsafe { GetPixel(dc, center_x + dx * 2, center_y + dy * 2) };
+                if color == CLR_INVALID {
+                    continue;
+                }
+                total.0 += GetRValue(color) as f32;
+                total.1 += GetGValue(color) as f32;
+                total.2 += GetBValue(color) as f32;
+                samples += 1.0;
+            }
+        }
+        
+        unsafe { ReleaseDC(window, dc) };
+        
+        if samples == 0.0 {
+            return Err("Could not read any pixels from the game window".to_string());
+        }
+        Ok((total.0 / samples, total.1 / samples, total.2 / samples))
+    }
+    
+    fn differs(&self, a: (f32, f32, f32), b: (f32, f32, f32)) -> bool {
+        let diff = ((a.0 - b.0).abs() + (a.1 - b.1).abs() + (a.2 - b.2).abs()) / 3.0;
+        diff > self.color_tolerance
+    }
+    
+    fn sample_at(&self, process: &ProcessHandle, base_addr: usize, window: HWND, distance: f32) -> Result<(f32, f32, f32), String> {
+        let mut matrix = process.get_camera_matrix(base_addr)?;
+        matrix.apply_translation(0.0, 0.0, distance); // Same direction as the forward key
+        process.set_camera_matrix(base_addr, &matrix)?;
+        thread::sleep(Duration::from_millis(self.settle_ms));
+        
+        let color = Self::sample_center(window);
+        
+        // Step back to where we started before evaluating the result
+        matrix.apply_translation(0.0, 0.0, -distance);
+        process.set_camera_matrix(base_addr, &matrix)?;
+        color
+    }
+    
+    // Requires the camera write patch, otherwise the game moves the camera back every frame
+    pub fn measure(&self, process: &ProcessHandle, base_addr: usize) -> Result<f32, String> {
+        let window = process.find_main_window().ok_or("Could not find the game window")?;
+        
+        let original = process.get_camera_matrix(base_addr)?;
+        thread::sleep(Duration::from_millis(self.settle_ms));
+        let baseline = Self::sample_center(window)?;
+        
+        let result = (|| {
+            if !self.differs(baseline, self.sample_at(process, base_addr, window, self.max_distance)?) {
+                return Err(format!("No geometry detected within {:.0} units", self.max_distance));
+            }
+            
+            let mut near = 0.0;
+            let mut far = self.max_distance;
+            for _ in 0..self.iterations {
+                let mid = (near + far) / 2.0;
+                if self.differs(baseline, self.sample_at(process, base_addr, window, mid)?) {
+                    far = mid;
+                } else {
+                    near = mid;
+                }
+            }
+            Ok((near + far) / 2.0)
+        })();
+        
+        // Always put the camera back, even when sampling failed halfway
+        process.set_camera_matrix(base_addr, &original)?;
+        result
+    }
+}
@@ -1,17 +1,58 @@
+use std::cell::RefCell;
 use std::mem;
 use std::ptr;
-use winapi::shared::minwindef::{DWORD, FALSE, HMODULE};
+use winapi::shared::minwindef::{BOOL, DWORD, FALSE, FARPROC, HMODULE, LPARAM, MAX_PATH, TRUE};
+use winapi::shared::windef::HWND;
 use winapi::um::errhandlingapi::GetLastError;
 use winapi::um::handleapi::CloseHandle;
-use winapi::um::memoryapi::{ReadProcessMemory, WriteProcessMemory, VirtualProtectEx};
-use winapi::um::processthreadsapi::OpenProcess;
-use winapi::um::psapi::EnumProcessModules;
+use winapi::um::libloaderapi::{GetModuleHandleA, GetProcAddress};
+use winapi::um::memoryapi::{ReadProcessMemory, WriteProcessMemory, VirtualProtectEx, VirtualAllocEx, VirtualFreeEx, VirtualQueryEx};
+use winapi::um::minwinbase::LPTHREAD_START_ROUTINE;
+use winapi::um::processthreadsapi::{CreateRemoteThread, GetExitCodeThread, OpenProcess};
+use winapi::um::psapi::{EnumProcessModulesEx, GetModuleBaseNameW, LIST_MODULES_ALL};
+use winapi::um::synchapi::WaitForSingleObject;
+use winapi::um::sysinfoapi::{GetNativeSystemInfo, SYSTEM_INFO};
+use winapi::um::winbase::{QueryFullProcessImageNameW, WAIT_OBJECT_0};
 use winapi::um::tlhelp32::{
     CreateToolhelp32Snapshot, Process32First, Process32Next, PROCESSENTRY32, TH32CS_SNAPPROCESS,
 };
-use winapi::um::winnt::{HANDLE, PROCESS_VM_READ, PROCESS_VM_WRITE, PROCESS_VM_OPERATION, PROCESS_QUERY_INFORMATION, PAGE_EXECUTE_READWRITE};
+use winapi::um::winuser::{EnumWindows, GetWindowTextW, GetWindowThreadProcessId, IsWindowVisible};
+use winapi::um::winnt::{
+    HANDLE, PROCESS_VM_READ, PROCESS_VM_WRITE, PROCESS_VM_OPERATION, PROCESS_QUERY_INFORMATION, PROCESS_QUERY_LIMITED_INFORMATION, PROCESS_CREATE_THREAD,
+    PAGE_EXECUTE_READWRITE, PAGE_EXECUTE_WRITECOPY, PAGE_GUARD, PAGE_NOACCESS, PAGE_READWRITE, PAGE_WRITECOPY,
+    MEM_COMMIT, MEM_RESERVE, MEM_RELEASE, MEMORY_BASIC_INFORMATION, PROCESSOR_ARCHITECTURE_INTEL,
+};
+use winapi::um::wow64apiset::IsWow64Process;
+
+use crate::camera::{CameraMatrix, CameraPosition, WriteChannels};
+use crate::game::{find_game, GameCamera, GAMES};
+use crate::image::MemoryImage;
+use crate::internal::InternalCamera;
+use crate::offsets::{LevelOffsets, PatchOffsets, PointerChain};
 
-use crate::camera::{CameraMatrix, CameraPosition};
+// Longest level name read from the [level] chain, the game's internal names are short
+const MAX_LEVEL_NAME: usize = 32;
+
+// One pointer read while following a chain
+pub struct ChainStep {
+    pub address: usize,                  // Where the pointer was read
+    pub offset: usize,                   // Added to the pointer to get the next address
+    pub pointer: Result<usize, String>,  // The pointer, or why it couldn't be used
+}
+
+pub struct ChainTrace {
+    pub steps: Vec<ChainStep>,
+    pub result: Result<usize, String>, // The final address, or the error resolve_pointer_chain would return
+}
+
+// Committed memory in the game, as VirtualQueryEx reports it
+#[derive(Debug, Clone, Copy)]
+pub struct MemoryRegion {
+    pub base: usize,
+    pub size: usize,
+    pub allocation_base: usize, // The module base address for every part of an executable
+    pub writable: bool,
+}
 
 #[derive(Debug, Clone)]
 pub struct CodePatch {
@@ -20,34 +61,158 @@ pub struct CodePatch {
     pub is_applied: bool,
 }
 
+// What the tool needs to do to the game, so the process is opened with no more rights than that
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProcessAccess {
+    Read,   // Reading the camera and the chains, e.g. spectating and diagnosing
+    Write,  // Also writing the camera and patching the game's code
+    Inject, // Also loading the hook DLL for --internal
+}
+
+impl ProcessAccess {
+    fn rights(self) -> DWORD {
+        let read = PROCESS_VM_READ | PROCESS_QUERY_INFORMATION;
+        match self {
+            ProcessAccess::Read => read,
+            ProcessAccess::Write => read | PROCESS_VM_WRITE | PROCESS_VM_OPERATION,
+            ProcessAccess::Inject => read | PROCESS_VM_WRITE | PROCESS_VM_OPERATION | PROCESS_CREATE_THREAD,
+        }
+    }
+}
+
 pub struct ProcessHandle {
     handle: HANDLE,
-    #[allow(dead_code)]
     pid: DWORD,
+    access: ProcessAccess,
+    game: &'static dyn GameCamera,
+    executable: String, // Module the offsets are relative to, the name the process runs under
+    camera_chain: RefCell<PointerChain>, // The game's, unless offsets.toml has a [camera] chain for this build
+    internal: Option<InternalCamera>, // Set once the hook DLL is attached
+    image: Option<MemoryImage>,       // Reads come from a recorded image instead of a running game
+    recorded: RefCell<Option<Vec<(usize, usize)>>>, // Address and length of every read while recording
 }
 
 impl ProcessHandle {
-    pub fn new(process_name: &str) -> Result<Self, String> {
+    pub fn new(process_name: &str, access: ProcessAccess) -> Result<Self, String> {
         let pid = find_process_by_name(process_name)?;
         println!("Found {} with PID: {}", process_name, pid);
         
-        let handle = unsafe { 
-            OpenProcess(
-                PROCESS_VM_READ | PROCESS_VM_WRITE | PROCESS_VM_OPERATION | PROCESS_QUERY_INFORMATION, 
-                FALSE, 
-                pid
-            ) 
-        };
+        Self::from_pid(pid, access)
+    }
+    
+    pub fn from_pid(pid: DWORD, access: ProcessAccess) -> Result<Self, String> {
+        let handle = unsafe { OpenProcess(access.rights(), FALSE, pid) };
         if handle.is_null() {
             let error_code = unsafe { GetLastError() };
-            return Err(format!("Failed to open process with PID: {} (Error code: {})", pid, error_code));
+            return Err(logged(format!("Failed to open process with PID: {} (Error code: {})", pid, error_code)));
+        }
+        
+        // The pointer chains read 32-bit pointers, in a 64-bit process they would read garbage without any error
+        match is_32_bit(handle) {
+            Ok(true) => {}
+            Ok(false) => {
+                unsafe { CloseHandle(handle) };
+                let name = process_name_by_pid(pid).unwrap_or_else(|| format!("PID {}", pid));
+                return Err(logged(format!("{} is a 64-bit process - the free cam only works with the 32-bit game executables ({})",
+                                          name, GAMES.iter().map(|game| game.process_name()).collect::<Vec<_>>().join(", "))));
+            }
+            Err(e) => println!("⚠️ {} - assuming it is a 32-bit game", e),
         }
         
         println!("Successfully opened process handle!");
-        Ok(ProcessHandle { handle, pid })
+        log::info!("Opened PID {}", pid);
+        
+        // A renamed executable can't be recognised, so it gets the first game's offsets
+        let name = process_name_by_pid(pid);
+        let game = match &name {
+            Some(name) => find_game(name).unwrap_or_else(|| {
+                println!("⚠️ '{}' isn't a known game executable - assuming {}", name, GAMES[0].name());
+                GAMES[0]
+            }),
+            None => GAMES[0],
+        };
+        let executable = name.unwrap_or_else(|| game.process_name().to_string());
+        Ok(ProcessHandle {
+            handle,
+            pid,
+            access,
+            game,
+            executable,
+            camera_chain: RefCell::new(game.camera_chain()),
+            internal: None,
+            image: None,
+            recorded: RefCell::new(None),
+        })
+    }
+    
+    // A stand-in for the game that reads from a memory image. Writes and patches fail, there is no game to change.
+    pub fn from_image(image: MemoryImage) -> Self {
+        let game = find_game(&image.executable).unwrap_or(GAMES[0]);
+        ProcessHandle {
+            handle: ptr::null_mut(),
+            pid: 0,
+            access: ProcessAccess::Read,
+            game,
+            executable: image.executable.clone(),
+            camera_chain: RefCell::new(game.camera_chain()),
+            internal: None,
+            image: Some(image),
+            recorded: RefCell::new(None),
+        }
+    }
+    
+    // Keeps the address and length of every successful read from now on, for record-image
+    pub fn start_recording(&self) {
+        *self.recorded.borrow_mut() = Some(Vec::new());
+    }
+    
+    pub fn take_recorded(&self) -> Vec<(usize, usize)> {
+        self.recorded.borrow_mut().take().unwrap_or_default()
+    }
+    
+    fn record(&self, address: usize, length: usize) {
+        if let Some(recorded) = self.recorded.borrow_mut().as_mut() {
+            recorded.push((address, length));
+        }
+        log::trace!("Read {} bytes at 0x{:X}", length, address);
+    }
+    
+    pub fn pid(&self) -> DWORD {
+        self.pid
+    }
+    
+    pub fn access(&self) -> ProcessAccess {
+        self.access
+    }
+    
+    pub fn game(&self) -> &'static dyn GameCamera {
+        self.game
+    }
+    
+    pub fn executable(&self) -> &str {
+        &self.executable
+    }
+    
+    pub fn camera_chain(&self) -> PointerChain {
+        self.camera_chain.borrow().clone()
+    }
+    
+    // Takes a shared reference, an offsets file that changes while attached swaps the chain under the patches
+    pub fn set_camera_chain(&self, chain: PointerChain) {
+        *self.camera_chain.borrow_mut() = chain;
+    }
+    
+    // First visible top-level window owned by the process (the game window)
+    pub fn find_main_window(&self) -> Option<HWND> {
+        main_window(self.pid)
     }
     
     pub fn read_memory<T>(&self, address: usize) -> Result<T, String> {
+        if let Some(image) = &self.image {
+            let bytes = image.read(address, mem::size_of::<T>())?;
+            return Ok(unsafe { ptr::read_unaligned(bytes.as_ptr() as *const T) });
+        }
+        
         let mut buffer: T = unsafe { mem::zeroed() };
         let mut bytes_read = 0;
         
@@ -63,13 +228,20 @@ impl ProcessHandle {
         
         if result == 0 {
             let error_code = unsafe { GetLastError() };
+            // Reads fail all the time while the game loads a level, so they only show up in verbose logs
+            log::debug!("Failed to read {} bytes at 0x{:X} (Error: {})", mem::size_of::<T>(), address, error_code);
             return Err(format!("Failed to read process memory at 0x{:X} (Error: {})", address, error_code));
         }
         
+        self.record(address, mem::size_of::<T>());
         Ok(buffer)
     }
     
     pub fn write_memory<T>(&self, address: usize, value: &T) -> Result<(), String> {
+        if self.image.is_some() {
+            return Err(format!("Can't write to 0x{:X}, a memory image is read-only", address));
+        }
+        
         let mut bytes_written = 0;
         
         let result = unsafe {
@@ -83,35 +255,80 @@ impl ProcessHandle {
         };
         
         if result == 0 {
-            return Err("Failed to write process memory".to_string());
+            let error_code = unsafe { GetLastError() };
+            log::debug!("Failed to write {} bytes at 0x{:X} (Error: {})", mem::size_of::<T>(), address, error_code);
+            return Err(format!("Failed to write process memory at 0x{:X} (Error: {})", address, error_code));
         }
         
+        log::trace!("Wrote {} bytes at 0x{:X}", mem::size_of::<T>(), address);
         Ok(())
     }
     
+    pub fn read_bytes(&self, address: usize, length: usize) -> Result<Vec<u8>, String> {
+        if let Some(image) = &self.image {
+            return image.read(address, length);
+        }
+        
+        let mut buffer = vec![0u8; length];
+        let mut bytes_read = 0;
+        
+        let result = unsafe {
+            ReadProcessMemory(
+                self.handle,
+                address as *const _,
+                buffer.as_mut_ptr() as *mut _,
+                length,
+                &mut bytes_read,
+            )
+        };
+        
+        if result == 0 || bytes_read != length {
+            let error_code = unsafe { GetLastError() };
+            log::debug!("Failed to read {} bytes at 0x{:X} (Error: {})", length, address, error_code);
+            return Err(format!("Failed to read {} bytes at 0x{:X} (Error: {})", length, address, error_code));
+        }
+        
+        self.record(address, length);
+        Ok(buffer)
+    }
+    
+    // Every offset is relative to the game's own executable module
     pub fn get_base_address(&self) -> Result<usize, String> {
+        if let Some(image) = &self.image {
+            return Ok(image.module_base);
+        }
+        self.get_module_base(&self.executable)
+    }
+    
+    // Base address of the module called `name`, e.g. "Skate3.exe", in any case. Looking it up by name keeps DLLs
+    // that overlays like Steam or RTSS inject from ever being taken for the game, whatever order they load in.
+    pub fn get_module_base(&self, name: &str) -> Result<usize, String> {
         let mut modules: [HMODULE; 1024] = [ptr::null_mut(); 1024];
         let mut bytes_needed = 0;
         
         let result = unsafe {
-            EnumProcessModules(
+            EnumProcessModulesEx(
                 self.handle,
                 modules.as_mut_ptr(),
                 mem::size_of_val(&modules) as u32,
                 &mut bytes_needed,
+                LIST_MODULES_ALL,
             )
         };
-        
         if result == 0 {
-            return Err("Failed to enumerate process modules".to_string());
+            let error_code = unsafe { GetLastError() };
+            return Err(format!("Failed to enumerate process modules (Error code: {})", error_code));
         }
         
-        if bytes_needed > 0 {
-            let base_address = modules[0] as usize;
-            Ok(base_address)
-        } else {
-            Err("No modules found".to_string())
+        let count = (bytes_needed as usize / mem::size_of::<HMODULE>()).min(modules.len());
+        for &module in &modules[..count] {
+            let mut module_name = [0u16; MAX_PATH];
+            let length = unsafe { GetModuleBaseNameW(self.handle, module, module_name.as_mut_ptr(), module_name.len() as u32) };
+            if length > 0 && String::from_utf16_lossy(&module_name[..length as usize]).eq_ignore_ascii_case(name) {
+                return Ok(module as usize);
+            }
         }
+        Err(format!("Module '{}' not found in the process", name))
     }
     
     pub fn resolve_pointer_chain(&self, base_address: usize, offsets: &[usize]) -> Result<usize, String> {
@@ -124,32 +341,81 @@ impl ProcessHandle {
                 current_address += offset;
             } else {
                 // First read the pointer value (32-bit), then add the offset
-                match self.read_memory::<u32>(current_address) {
-                    Ok(ptr_value) => {
-                        current_address = ptr_value as usize;
-                        
-                        // Check if the pointer is valid (not null and within reasonable range)
-                        if current_address == 0 {
-                            return Err(format!("Null pointer encountered at step {}", i));
-                        }
-                        if current_address < 0x10000 || current_address > 0x7FFFFFFF {
-                            return Err(format!("Invalid pointer value 0x{:X} at step {}", current_address, i));
-                        }
-                        
-                        // Now add the offset
-                        current_address += offset;
-                    }
-                    Err(e) => {
-                        return Err(format!("Failed to read pointer at step {}: {}", i, e));
-                    }
-                }
+                current_address = self.read_chain_pointer(current_address, i)? + offset;
             }
         }
         
         Ok(current_address)
     }
     
-    pub fn patch_with_nops(&self, address: usize, length: usize) -> Result<CodePatch, String> {
+    // Same walk as resolve_pointer_chain, but keeps every pointer it read so `diagnose` can show where a chain breaks
+    pub fn trace_pointer_chain(&self, base_address: usize, offsets: &[usize]) -> ChainTrace {
+        let mut steps = Vec::new();
+        let mut current_address = base_address;
+        
+        for (i, &offset) in offsets.iter().enumerate() {
+            if i == offsets.len() - 1 {
+                return ChainTrace { steps, result: Ok(current_address + offset) };
+            }
+            let pointer = self.read_chain_pointer(current_address, i);
+            steps.push(ChainStep { address: current_address, offset, pointer: pointer.clone() });
+            match pointer {
+                Ok(pointer) => current_address = pointer + offset,
+                Err(e) => return ChainTrace { steps, result: Err(e) },
+            }
+        }
+        
+        ChainTrace { steps, result: Ok(current_address) }
+    }
+    
+    fn read_chain_pointer(&self, address: usize, step: usize) -> Result<usize, String> {
+        let pointer = self.read_memory::<u32>(address)
+            .map_err(|e| format!("Failed to read pointer at step {}: {}", step, e))? as usize;
+        
+        // Check if the pointer is valid (not null and within reasonable range)
+        if pointer == 0 {
+            return Err(format!("Null pointer encountered at step {}", step));
+        }
+        if !(0x10000..=0x7FFFFFFF).contains(&pointer) {
+            return Err(format!("Invalid pointer value 0x{:X} at step {}", pointer, step));
+        }
+        Ok(pointer)
+    }
+    
+    // Every committed region the game can read, in address order. Only the 32-bit address space is walked,
+    // the game can't have anything above it.
+    pub fn memory_regions(&self) -> Vec<MemoryRegion> {
+        let mut regions = Vec::new();
+        let mut address: usize = 0;
+        while address < 0x8000_0000 {
+            let mut info: MEMORY_BASIC_INFORMATION = unsafe { mem::zeroed() };
+            let size = unsafe {
+                VirtualQueryEx(self.handle, address as *const _, &mut info, mem::size_of::<MEMORY_BASIC_INFORMATION>())
+            };
+            if size == 0 || info.RegionSize == 0 {
+                break;
+            }
+            
+            let base = info.BaseAddress as usize;
+            let readable = info.Protect & (PAGE_NOACCESS | PAGE_GUARD) == 0;
+            if info.State == MEM_COMMIT && readable {
+                regions.push(MemoryRegion {
+                    base,
+                    size: info.RegionSize,
+                    allocation_base: info.AllocationBase as usize,
+                    writable: info.Protect & (PAGE_READWRITE | PAGE_WRITECOPY | PAGE_EXECUTE_READWRITE | PAGE_EXECUTE_WRITECOPY) != 0,
+                });
+            }
+            address = base + info.RegionSize;
+        }
+        regions
+    }
+    
+    // NOPs the instruction at `address`, refusing unless it is `expected_bytes`. On another game build the
+    // address can point into the middle of unrelated code, and NOPing that crashes the game.
+    pub fn patch_with_nops(&self, address: usize, expected_bytes: &[u8]) -> Result<CodePatch, String> {
+        let length = expected_bytes.len();
+        
         // First, read the original bytes
         let mut original_bytes = vec![0u8; length];
         let mut bytes_read = 0;
@@ -166,7 +432,11 @@ impl ProcessHandle {
         
         if read_result == 0 {
             let error_code = unsafe { GetLastError() };
-            return Err(format!("Failed to read original bytes at 0x{:X} (Error: {})", address, error_code));
+            return Err(logged(format!("Failed to read original bytes at 0x{:X} (Error: {})", address, error_code)));
+        }
+        if original_bytes != expected_bytes {
+            return Err(format!("Unexpected bytes {} at 0x{:X} (expected {}) - refusing to patch",
+                               format_bytes(&original_bytes), address, format_bytes(expected_bytes)));
         }
         
         // Change memory protection to allow execution/writing
@@ -183,7 +453,7 @@ impl ProcessHandle {
         
         if protect_result == 0 {
             let error_code = unsafe { GetLastError() };
-            return Err(format!("Failed to change memory protection at 0x{:X} (Error: {})", address, error_code));
+            return Err(logged(format!("Failed to change memory protection at 0x{:X} (Error: {})", address, error_code)));
         }
         
         // Create NOP bytes (0x90)
@@ -202,6 +472,7 @@ impl ProcessHandle {
         };
         
         if write_result == 0 {
+            let error_code = unsafe { GetLastError() };
             // Restore original protection
             unsafe {
                 VirtualProtectEx(
@@ -212,7 +483,7 @@ impl ProcessHandle {
                     &mut old_protect,
                 );
             }
-            return Err("Failed to write NOP bytes".to_string());
+            return Err(logged(format!("Failed to write NOP bytes at 0x{:X} (Error: {})", address, error_code)));
         }
         
         // Restore original protection
@@ -226,6 +497,7 @@ impl ProcessHandle {
             );
         }
         
+        log::debug!("NOPed {} bytes at 0x{:X}", length, address);
         Ok(CodePatch {
             address,
             original_bytes,
@@ -254,7 +526,7 @@ impl ProcessHandle {
         
         if protect_result == 0 {
             let error_code = unsafe { GetLastError() };
-            return Err(format!("Failed to change memory protection at 0x{:X} (Error: {})", patch.address, error_code));
+            return Err(logged(format!("Failed to change memory protection at 0x{:X} (Error: {})", patch.address, error_code)));
         }
         
         // Write original bytes back
@@ -270,6 +542,7 @@ impl ProcessHandle {
         };
         
         if write_result == 0 {
+            let error_code = unsafe { GetLastError() };
             // Restore original protection
             unsafe {
                 VirtualProtectEx(
@@ -280,7 +553,7 @@ impl ProcessHandle {
                     &mut old_protect,
                 );
             }
-            return Err("Failed to restore original bytes".to_string());
+            return Err(logged(format!("Failed to restore original bytes at 0x{:X} (Error: {})", patch.address, error_code)));
         }
         
         // Restore original protection
@@ -295,87 +568,68 @@ impl ProcessHandle {
         }
         
         patch.is_applied = false;
+        log::debug!("Restored {} bytes at 0x{:X}", length, patch.address);
         Ok(())
     }
     
     pub fn get_camera_write_patch_address(&self, base_address: usize) -> Result<usize, String> {
-        // Address of the "repe movsd" instruction that copies camera data
-        // Found via Cheat Engine disassembler: Skate3.exe.text+16B2E4
-        // This instruction overwrites our camera changes, so we NOP it out
-        
-        // The offset 0x16B2E4 is from the .text section, which typically starts at base + 0x1000
-        // But let's try different approaches to find the right address
-        let text_section_offset = 0x1000; // Typical .text section offset
-        let instruction_offset = 0x16B2E4;
-        
-        // Try multiple address calculations
-        let addresses_to_try = vec![
-            base_address + instruction_offset,                    // Direct offset from base
-            base_address + text_section_offset + instruction_offset, // Base + text section + offset
-            base_address + instruction_offset - text_section_offset, // Adjust for text section
+        // The instruction that copies camera data overwrites our camera changes, so we NOP it out.
+        // Its offset is from the .text section, which typically starts at base + 0x1000, so try both.
+        let camera_write = self.game.camera_write();
+        let text_section_offset = 0x1000;
+        let addresses_to_try = [
+            base_address + camera_write.offset,                       // Direct offset from base
+            base_address + text_section_offset + camera_write.offset, // Base + text section + offset
+            base_address + camera_write.offset - text_section_offset, // Adjust for text section
         ];
         
         for &addr in addresses_to_try.iter() {
-            // Try to read 2 bytes from this address to see if it contains the expected instruction
-            let mut test_bytes = vec![0u8; 2];
-            let mut bytes_read = 0;
-            
-            let read_result = unsafe {
-                ReadProcessMemory(
-                    self.handle,
-                    addr as *const _,
-                    test_bytes.as_mut_ptr() as *mut _,
-                    2,
-                    &mut bytes_read,
-                )
-            };
-            
-            if read_result != 0 && bytes_read == 2 {
-                // Check if this matches the expected "repe movsd" instruction (F3 A5)
-                if test_bytes[0] == 0xF3 && test_bytes[1] == 0xA5 {
-                    return Ok(addr);
-                }
+            if let Ok(bytes) = self.read_bytes(addr, camera_write.original_bytes.len())
+                && bytes == camera_write.original_bytes
+            {
+                return Ok(addr);
             }
         }
         
-        // If none of the standard calculations work, return the first one
+        // If none of the standard calculations work, return the first one. patch_with_nops refuses it
+        // unless the game build has the instruction there after all.
         Ok(addresses_to_try[0])
     }
     
+    // `name` is the patch's table in the offsets file, e.g. freeze or hud, and only appears in the error
+    pub fn get_offsets_patch_address(&self, base_address: usize, name: &str, patch: &PatchOffsets) -> Result<usize, String> {
+        // These instructions come from offsets.toml, so try the same base/.text variants as the camera patch.
+        // Unlike the camera patch there is no known-good fallback: NOPing the wrong bytes would crash the game.
+        let text_section_offset = 0x1000;
+        let addresses_to_try = [
+            base_address + patch.offset,
+            base_address + text_section_offset + patch.offset,
+            (base_address + patch.offset).wrapping_sub(text_section_offset),
+        ];
+        
+        for &addr in addresses_to_try.iter() {
+            if let Ok(bytes) = self.read_bytes(addr, patch.original_bytes.len())
+                && bytes == patch.original_bytes
+            {
+                return Ok(addr);
+            }
+        }
+        
+        Err(format!("[{}] instruction not found at offset 0x{:X} - check original_bytes in offsets.toml", name, patch.offset))
+    }
+    
     pub fn get_camera_position(&self, base_address: usize) -> Result<CameraPosition, String> {
-        // Camera pointer chain: "Skate3.exe"+004E1E78+34C+8+4+8C+0+324/328/32C
-        let base_offset = 0x004E1E78;
-        let offsets = vec![0x34C, 0x8, 0x4, 0x8C, 0x0];
-        
-        // Get X position (final offset: 0x324)
-        let mut x_offsets = offsets.clone();
-        x_offsets.push(0x324);
-        let x_addr = self.resolve_pointer_chain(base_address + base_offset, &x_offsets)?;
-        let x: f32 = self.read_memory(x_addr)?;
-        
-        // Get Y position (final offset: 0x328)
-        let mut y_offsets = offsets.clone();
-        y_offsets.push(0x328);
-        let y_addr = self.resolve_pointer_chain(base_address + base_offset, &y_offsets)?;
-        let y: f32 = self.read_memory(y_addr)?;
-        
-        // Get Z position (final offset: 0x32C)
-        let mut z_offsets = offsets.clone();
-        z_offsets.push(0x32C);
-        let z_addr = self.resolve_pointer_chain(base_address + base_offset, &z_offsets)?;
-        let z: f32 = self.read_memory(z_addr)?;
+        // The position is matrix[12]/[13]/[14], 48 bytes into the matrix
+        let matrix_addr = self.get_camera_matrix_address(base_address)?;
+        let x: f32 = self.read_memory(matrix_addr + 12 * 4)?;
+        let y: f32 = self.read_memory(matrix_addr + 13 * 4)?;
+        let z: f32 = self.read_memory(matrix_addr + 14 * 4)?;
         
         Ok(CameraPosition { x, y, z })
     }
     
     pub fn get_camera_matrix(&self, base_address: usize) -> Result<CameraMatrix, String> {
-        // Camera pointer chain: "Skate3.exe"+004E1E78+34C+8+4+8C+0+2F4 (start of 4x4 matrix)
-        // Matrix starts at 0x2F4, positions are at 0x324/0x328/0x32C (which is matrix[12]/[13]/[14])
-        // 0x324 - 0x2F4 = 0x30 = 48 bytes = 12 floats (indices 12/13/14)
-        let base_offset = 0x004E1E78;
-        let offsets = vec![0x34C, 0x8, 0x4, 0x8C, 0x0, 0x2F4];
-        
-        let matrix_addr = self.resolve_pointer_chain(base_address + base_offset, &offsets)?;
+        let matrix_addr = self.get_camera_matrix_address(base_address)?;
         
         // Read the full 4x4 matrix (16 floats)
         let mut data = [0.0f32; 16];
@@ -387,40 +641,36 @@ impl ProcessHandle {
     }
     
     pub fn set_camera_position(&self, base_address: usize, position: &CameraPosition) -> Result<(), String> {
-        // Camera pointer chain: "Skate3.exe"+004E1E78+34C+8+4+8C+0+324/328/32C
-        let base_offset = 0x004E1E78;
-        let offsets = vec![0x34C, 0x8, 0x4, 0x8C, 0x0];
-        
-        // Set X position (final offset: 0x324)
-        let mut x_offsets = offsets.clone();
-        x_offsets.push(0x324);
-        let x_addr = self.resolve_pointer_chain(base_address + base_offset, &x_offsets)?;
-        self.write_memory(x_addr, &position.x)?;
-        
-        // Set Y position (final offset: 0x328)
-        let mut y_offsets = offsets.clone();
-        y_offsets.push(0x328);
-        let y_addr = self.resolve_pointer_chain(base_address + base_offset, &y_offsets)?;
-        self.write_memory(y_addr, &position.y)?;
-        
-        // Set Z position (final offset: 0x32C)
-        let mut z_offsets = offsets.clone();
-        z_offsets.push(0x32C);
-        let z_addr = self.resolve_pointer_chain(base_address + base_offset, &z_offsets)?;
-        self.write_memory(z_addr, &position.z)?;
+        let matrix_addr = self.get_camera_matrix_address(base_address)?;
+        self.write_memory(matrix_addr + 12 * 4, &position.x)?;
+        self.write_memory(matrix_addr + 13 * 4, &position.y)?;
+        self.write_memory(matrix_addr + 14 * 4, &position.z)?;
         
         Ok(())
     }
     
+    fn get_camera_matrix_address(&self, base_address: usize) -> Result<usize, String> {
+        let chain = self.camera_chain.borrow();
+        self.resolve_pointer_chain(base_address + chain.base, &chain.offsets)
+    }
+    
     pub fn set_camera_matrix(&self, base_address: usize, matrix: &CameraMatrix) -> Result<(), String> {
-        // Camera pointer chain: "Skate3.exe"+004E1E78+34C+8+4+8C+0+2F4 (start of 4x4 matrix)
-        let base_offset = 0x004E1E78;
-        let offsets = vec![0x34C, 0x8, 0x4, 0x8C, 0x0, 0x2F4];
+        self.set_camera_matrix_channels(base_address, matrix, WriteChannels::Full)
+    }
+    
+    // Writes only the parts of the matrix in `channels`, the rest stays whatever the game has there
+    pub fn set_camera_matrix_channels(&self, base_address: usize, matrix: &CameraMatrix, channels: WriteChannels) -> Result<(), String> {
+        let matrix_addr = self.get_camera_matrix_address(base_address)?;
         
-        let matrix_addr = self.resolve_pointer_chain(base_address + base_offset, &offsets)?;
+        // The hook applies the matrix from inside the game loop, writing it here as well would race the game again
+        if let Some(internal) = &self.internal
+            && internal.is_active()
+        {
+            internal.write_matrix(matrix_addr, matrix, channels);
+            return Ok(());
+        }
         
-        // Write the full 4x4 matrix (16 floats)
-        for i in 0..16 {
+        for i in channels.floats() {
             self.write_memory(matrix_addr + i * 4, &matrix.data[i])?;
         }
         
@@ -428,38 +678,204 @@ impl ProcessHandle {
     }
     
     pub fn get_camera_addresses(&self, base_address: usize) -> Result<(usize, usize, usize), String> {
-        // Camera pointer chain: "Skate3.exe"+004E1E78+34C+8+4+8C+0+324/328/32C
-        let base_offset = 0x004E1E78;
-        let offsets = vec![0x34C, 0x8, 0x4, 0x8C, 0x0];
+        let matrix_addr = self.get_camera_matrix_address(base_address)?;
+        Ok((matrix_addr + 12 * 4, matrix_addr + 13 * 4, matrix_addr + 14 * 4))
+    }
+    
+    pub fn get_skater_position(&self, base_address: usize, chain: &PointerChain) -> Result<CameraPosition, String> {
+        // Skater pointer chain comes from offsets.toml, pointing at three consecutive floats
+        let addr = self.resolve_pointer_chain(base_address + chain.base, &chain.offsets)?;
+        let x: f32 = self.read_memory(addr)?;
+        let y: f32 = self.read_memory(addr + 4)?;
+        let z: f32 = self.read_memory(addr + 8)?;
+        Ok(CameraPosition { x, y, z })
+    }
+    
+    // Orientation of the skater, only the rotation part is meaningful (the position comes from the [skater] chain)
+    pub fn get_skater_matrix(&self, base_address: usize, chain: &PointerChain) -> Result<CameraMatrix, String> {
+        let addr = self.resolve_pointer_chain(base_address + chain.base, &chain.offsets)?;
+        let mut matrix = CameraMatrix::new();
+        for (i, value) in matrix.data.iter_mut().enumerate() {
+            *value = self.read_memory(addr + i * 4)?;
+        }
+        Ok(matrix)
+    }
+    
+    pub fn set_skater_position(&self, base_address: usize, chain: &PointerChain, position: &CameraPosition) -> Result<(), String> {
+        let addr = self.resolve_pointer_chain(base_address + chain.base, &chain.offsets)?;
+        self.write_memory(addr, &position.x)?;
+        self.write_memory(addr + 4, &position.y)?;
+        self.write_memory(addr + 8, &position.z)?;
+        Ok(())
+    }
+    
+    // A 32-bit state value such as the game state or the replay flag
+    pub fn get_state_value(&self, base_address: usize, chain: &PointerChain) -> Result<i32, String> {
+        let addr = self.resolve_pointer_chain(base_address + chain.base, &chain.offsets)?;
+        self.read_memory(addr)
+    }
+    
+    // A float the game keeps about the skater, such as their speed or a balance meter
+    pub fn get_stat(&self, base_address: usize, chain: &PointerChain) -> Result<f32, String> {
+        let addr = self.resolve_pointer_chain(base_address + chain.base, &chain.offsets)?;
+        self.read_memory(addr)
+    }
+    
+    // Name of the loaded level, lowercase so it matches file names and config tables. None in the menus, where
+    // the name is empty or the number isn't a level.
+    pub fn get_current_level(&self, base_address: usize, level: &LevelOffsets) -> Result<Option<String>, String> {
+        let addr = self.resolve_pointer_chain(base_address + level.chain.base, &level.chain.offsets)?;
+        if !level.names.is_empty() {
+            let id: i32 = self.read_memory(addr)?;
+            return Ok(level.names.get(&id.to_string()).map(|name| name.to_lowercase()));
+        }
+        
+        let bytes = self.read_bytes(addr, MAX_LEVEL_NAME)?;
+        let name: String = bytes.iter().take_while(|&&b| b != 0).map(|&b| b as char).collect();
+        if !name.chars().all(|c| c.is_ascii_graphic() || c == ' ') {
+            return Err(format!("No level name at 0x{:X}", addr));
+        }
+        let name = name.trim().to_lowercase();
+        Ok((!name.is_empty()).then_some(name))
+    }
+    
+    pub fn get_fov(&self, base_address: usize, chain: &PointerChain) -> Result<f32, String> {
+        // FOV pointer chain comes from offsets.toml since it differs per game build
+        let fov_addr = self.resolve_pointer_chain(base_address + chain.base, &chain.offsets)?;
+        self.read_memory(fov_addr)
+    }
+    
+    pub fn set_fov(&self, base_address: usize, chain: &PointerChain, fov: f32) -> Result<(), String> {
+        let fov_addr = self.resolve_pointer_chain(base_address + chain.base, &chain.offsets)?;
+        self.write_memory(fov_addr, &fov)
+    }
+    
+    // Loads a DLL into the game with a remote LoadLibraryW call
+    pub fn inject_library(&self, dll_path: &str) -> Result<(), String> {
+        if self.access != ProcessAccess::Inject {
+            return Err("The game was opened without the rights to inject a DLL".to_string());
+        }
+        // LoadLibraryW is looked up in our own kernel32, which only lines up with the game's when both are 32-bit
+        if cfg!(target_pointer_width = "64") {
+            return Err("Injecting needs a 32-bit build of the tool (--target i686-pc-windows-msvc)".to_string());
+        }
+        
+        let full_path = std::path::absolute(dll_path).map_err(|e| format!("Invalid DLL path '{}': {}", dll_path, e))?;
+        if !full_path.exists() {
+            return Err(format!("Hook DLL not found at {}", full_path.display()));
+        }
+        let wide_path: Vec<u16> = full_path.to_string_lossy().encode_utf16().chain(Some(0)).collect();
+        let path_size = wide_path.len() * mem::size_of::<u16>();
+        
+        let remote_path = unsafe { VirtualAllocEx(self.handle, ptr::null_mut(), path_size, MEM_COMMIT | MEM_RESERVE, PAGE_READWRITE) };
+        if remote_path.is_null() {
+            let error_code = unsafe { GetLastError() };
+            return Err(logged(format!("Failed to allocate memory in the game (Error: {})", error_code)));
+        }
+        
+        let mut bytes_written = 0;
+        let write_result = unsafe {
+            WriteProcessMemory(self.handle, remote_path, wide_path.as_ptr() as *const _, path_size, &mut bytes_written)
+        };
+        if write_result == 0 {
+            let error_code = unsafe { GetLastError() };
+            unsafe { VirtualFreeEx(self.handle, remote_path, 0, MEM_RELEASE) };
+            return Err(logged(format!("Failed to write the DLL path into the game (Error: {})", error_code)));
+        }
         
-        // Get addresses for X, Y, Z
-        let mut x_offsets = offsets.clone();
-        x_offsets.push(0x324);
-        let x_addr = self.resolve_pointer_chain(base_address + base_offset, &x_offsets)?;
+        let thread = unsafe {
+            let kernel32 = GetModuleHandleA(c"kernel32.dll".as_ptr());
+            let load_library = GetProcAddress(kernel32, c"LoadLibraryW".as_ptr());
+            CreateRemoteThread(
+                self.handle,
+                ptr::null_mut(),
+                0,
+                mem::transmute::<FARPROC, LPTHREAD_START_ROUTINE>(load_library),
+                remote_path,
+                0,
+                ptr::null_mut(),
+            )
+        };
+        if thread.is_null() {
+            let error_code = unsafe { GetLastError() };
+            unsafe { VirtualFreeEx(self.handle, remote_path, 0, MEM_RELEASE) };
+            return Err(logged(format!("Failed to start the loader thread in the game (Error: {})", error_code)));
+        }
         
-        let mut y_offsets = offsets.clone();
-        y_offsets.push(0x328);
-        let y_addr = self.resolve_pointer_chain(base_address + base_offset, &y_offsets)?;
+        // The thread still reads the path until it finishes, so only free it afterwards
+        let wait_result = unsafe { WaitForSingleObject(thread, 10_000) };
+        let mut exit_code = 0;
+        unsafe {
+            GetExitCodeThread(thread, &mut exit_code);
+            CloseHandle(thread);
+        }
+        if wait_result != WAIT_OBJECT_0 {
+            return Err("Timed out waiting for the game to load the DLL".to_string());
+        }
+        unsafe { VirtualFreeEx(self.handle, remote_path, 0, MEM_RELEASE) };
         
-        let mut z_offsets = offsets.clone();
-        z_offsets.push(0x32C);
-        let z_addr = self.resolve_pointer_chain(base_address + base_offset, &z_offsets)?;
+        // The exit code is the module handle LoadLibraryW returned
+        if exit_code == 0 {
+            return Err(format!("The game failed to load {} - is it a 32-bit build?", full_path.display()));
+        }
+        Ok(())
+    }
+    
+    // Routes camera matrix writes through the hook DLL, injecting it first unless an earlier session did
+    pub fn attach_hook(&mut self, dll_path: &str) -> Result<&InternalCamera, String> {
+        if !self.game.supports_hook() {
+            return Err(format!("The internal hook doesn't support {} yet", self.game.name()));
+        }
+        let internal = match InternalCamera::connect(self.pid) {
+            Ok(internal) => internal,
+            Err(_) => {
+                self.inject_library(dll_path)?;
+                InternalCamera::connect(self.pid)?
+            }
+        };
+        if internal.hook_address().is_none() {
+            return Err("Hook loaded but the camera copy instruction wasn't found - unsupported game version?".to_string());
+        }
         
-        Ok((x_addr, y_addr, z_addr))
+        Ok(self.internal.insert(internal))
     }
-
+    
+    pub fn internal(&self) -> Option<&InternalCamera> {
+        self.internal.as_ref()
+    }
+    
+    // Hands the camera to the hook or back to the game. The hook starts from the current view so nothing jumps.
+    pub fn set_hook_active(&self, base_address: usize, active: bool) -> Result<(), String> {
+        let internal = self.internal.as_ref().ok_or("Internal hook is not attached")?;
+        if active {
+            let matrix = self.get_camera_matrix(base_address)?;
+            internal.write_matrix(self.get_camera_matrix_address(base_address)?, &matrix, WriteChannels::Full);
+        }
+        internal.set_active(active);
+        Ok(())
+    }
+    
     // ...existing code...
 }
 
 impl Drop for ProcessHandle {
     fn drop(&mut self) {
-        unsafe {
-            CloseHandle(self.handle);
+        if !self.handle.is_null() {
+            unsafe {
+                CloseHandle(self.handle);
+            }
         }
     }
 }
 
-pub fn find_process_by_name(process_name: &str) -> Result<DWORD, String> {
+// Failed Win32 calls outside the per-tick memory access go into the log with their error code
+fn logged(message: String) -> String {
+    log::error!("{}", message);
+    message
+}
+
+// Name and PID of every running process
+fn running_processes() -> Result<Vec<(String, DWORD)>, String> {
     let snapshot = unsafe { CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0) };
     if snapshot.is_null() {
         return Err("Failed to create process snapshot".to_string());
@@ -468,6 +884,7 @@ pub fn find_process_by_name(process_name: &str) -> Result<DWORD, String> {
     let mut process_entry: PROCESSENTRY32 = unsafe { mem::zeroed() };
     process_entry.dwSize = mem::size_of::<PROCESSENTRY32>() as u32;
     
+    let mut processes = Vec::new();
     let mut result = unsafe { Process32First(snapshot, &mut process_entry) };
     
     while result != 0 {
@@ -485,56 +902,134 @@ pub fn find_process_by_name(process_name: &str) -> Result<DWORD, String> {
             }
             String::from_utf8_lossy(&name_bytes).into_owned()
         };
-        
-        if current_name.to_lowercase().contains(&process_name.to_lowercase()) {
-            unsafe { CloseHandle(snapshot) };
-            return Ok(process_entry.th32ProcessID);
-        }
+        processes.push((current_name, process_entry.th32ProcessID));
         
         result = unsafe { Process32Next(snapshot, &mut process_entry) };
     }
     
     unsafe { CloseHandle(snapshot) };
-    Err(format!("Process '{}' not found", process_name))
+    Ok(processes)
 }
 
-pub fn list_all_processes() -> Result<(), String> {
-    let snapshot = unsafe { CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0) };
-    if snapshot.is_null() {
-        return Err("Failed to create process snapshot".to_string());
+pub fn find_process_by_name(process_name: &str) -> Result<DWORD, String> {
+    find_processes_by_name(process_name)?
+        .into_iter()
+        .next()
+        .ok_or_else(|| format!("Process '{}' not found", process_name))
+}
+
+// Every running copy of the game, for attaching to more than one instance
+pub fn find_processes_by_name(process_name: &str) -> Result<Vec<DWORD>, String> {
+    Ok(running_processes()?
+        .into_iter()
+        .filter(|(name, _)| name.to_lowercase().contains(&process_name.to_lowercase()))
+        .map(|(_, pid)| pid)
+        .collect())
+}
+
+// A process the picker offers, with what helps tell copies of the game apart
+#[derive(Debug, Clone)]
+pub struct ProcessInfo {
+    pub pid: DWORD,
+    pub name: String,
+    pub window_title: Option<String>,
+    pub path: Option<String>, // Full path of the executable, None when Windows won't say
+}
+
+// Supported games, and anything that looks like a renamed copy of one
+pub fn candidate_processes() -> Result<Vec<ProcessInfo>, String> {
+    Ok(running_processes()?
+        .into_iter()
+        .filter(|(name, _)| find_game(name).is_some() || name.to_lowercase().contains("skate"))
+        .map(|(name, pid)| ProcessInfo {
+            pid,
+            name,
+            window_title: main_window(pid).and_then(window_title),
+            path: executable_path(pid),
+        })
+        .collect())
+}
+
+// A process is 32-bit when it runs under WOW64, or when Windows itself is 32-bit
+fn is_32_bit(handle: HANDLE) -> Result<bool, String> {
+    let mut wow64 = FALSE;
+    if unsafe { IsWow64Process(handle, &mut wow64) } == 0 {
+        let error_code = unsafe { GetLastError() };
+        return Err(format!("Failed to check whether the process is 32-bit (Error code: {})", error_code));
+    }
+    if wow64 != FALSE {
+        return Ok(true);
     }
     
-    let mut process_entry: PROCESSENTRY32 = unsafe { mem::zeroed() };
-    process_entry.dwSize = mem::size_of::<PROCESSENTRY32>() as u32;
+    let mut info: SYSTEM_INFO = unsafe { mem::zeroed() };
+    unsafe { GetNativeSystemInfo(&mut info) };
+    Ok(unsafe { info.u.s().wProcessorArchitecture } == PROCESSOR_ARCHITECTURE_INTEL)
+}
+
+fn main_window(pid: DWORD) -> Option<HWND> {
+    struct Search {
+        pid: DWORD,
+        window: HWND,
+    }
     
-    let mut result = unsafe { Process32First(snapshot, &mut process_entry) };
+    unsafe extern "system" fn enum_callback(hwnd: HWND, lparam: LPARAM) -> BOOL {
+        let search = unsafe { &mut *(lparam as *mut Search) };
+        let mut window_pid = 0;
+        unsafe { GetWindowThreadProcessId(hwnd, &mut window_pid) };
+        
+        if window_pid == search.pid && unsafe { IsWindowVisible(hwnd) } != 0 {
+            search.window = hwnd;
+            return FALSE; // Stop enumerating
+        }
+        TRUE
+    }
     
-    println!("All running processes:");
-    println!("=====================");
+    let mut search = Search { pid, window: ptr::null_mut() };
+    unsafe {
+        EnumWindows(Some(enum_callback), &mut search as *mut Search as LPARAM);
+    }
     
-    while result != 0 {
-        let current_name = unsafe {
-            let raw_name = process_entry.szExeFile.as_ptr() as *const u8;
-            let mut name_bytes = Vec::new();
-            let mut i = 0;
-            while i < process_entry.szExeFile.len() {
-                let byte = *raw_name.add(i);
-                if byte == 0 {
-                    break;
-                }
-                name_bytes.push(byte);
-                i += 1;
-            }
-            String::from_utf8_lossy(&name_bytes).into_owned()
-        };
-        
-        if current_name.to_lowercase().contains("skate") {
-            println!("🎮 {}: PID {}", current_name, process_entry.th32ProcessID);
+    if search.window.is_null() { None } else { Some(search.window) }
+}
+
+fn window_title(window: HWND) -> Option<String> {
+    let mut title = [0u16; 256];
+    let length = unsafe { GetWindowTextW(window, title.as_mut_ptr(), title.len() as i32) };
+    (length > 0).then(|| String::from_utf16_lossy(&title[..length as usize]))
+}
+
+// Only needs limited query rights, so it also works on processes the free cam couldn't attach to
+fn executable_path(pid: DWORD) -> Option<String> {
+    unsafe {
+        let handle = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, FALSE, pid);
+        if handle.is_null() {
+            return None;
         }
-        
-        result = unsafe { Process32Next(snapshot, &mut process_entry) };
+        let mut path = [0u16; MAX_PATH];
+        let mut length = path.len() as DWORD;
+        let result = QueryFullProcessImageNameW(handle, 0, path.as_mut_ptr(), &mut length);
+        CloseHandle(handle);
+        (result != 0).then(|| String::from_utf16_lossy(&path[..length as usize]))
     }
+}
+
+fn process_name_by_pid(pid: DWORD) -> Option<String> {
+    running_processes().ok()?.into_iter().find(|&(_, other)| other == pid).map(|(name, _)| name)
+}
+
+pub fn list_all_processes() -> Result<(), String> {
+    let processes = candidate_processes()?;
     
-    unsafe { CloseHandle(snapshot) };
+    println!("All running processes:");
+    println!("=====================");
+    
+    for process in processes {
+        println!("🎮 {}: PID {}", process.name, process.pid);
+    }
     Ok(())
 }
+
+// Bytes as hex, the way a disassembler shows them: "F3 A5"
+pub fn format_bytes(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02X}", b)).collect::<Vec<_>>().join(" ")
+}
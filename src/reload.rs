@@ -0,0 +1,91 @@
+// config.toml, the offsets file and path_dir are checked for changes once a second while the free cam runs, so
+// bindings, speeds, chains and paths can be tuned without detaching. Only modification times are read until one
+// changes. FileWatch follows a single file, PathLibrary uses CheckInterval and `modified` for a directory.
+
+use std::path::Path;
+use std::time::{Duration, Instant, SystemTime};
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+// Lets a check through once a second, the first one right away
+#[derive(Default)]
+pub struct CheckInterval {
+    last_check: Option<Instant>,
+}
+
+impl CheckInterval {
+    // Starts with a check already done, the first one comes after a second
+    pub fn started() -> Self {
+        Self { last_check: Some(Instant::now()) }
+    }
+    
+    pub fn due(&mut self) -> bool {
+        if self.last_check.is_some_and(|checked| checked.elapsed() < CHECK_INTERVAL) {
+            return false;
+        }
+        self.last_check = Some(Instant::now());
+        true
+    }
+}
+
+// When the file at `path` was last saved, None when it doesn't exist or isn't a file
+pub fn modified(path: impl AsRef<Path>) -> Option<SystemTime> {
+    std::fs::metadata(path).ok().filter(|metadata| metadata.is_file()).and_then(|metadata| metadata.modified().ok())
+}
+
+pub struct FileWatch {
+    path: String,
+    modified: Option<SystemTime>, // None while the file doesn't exist
+    interval: CheckInterval,
+}
+
+impl FileWatch {
+    // Starts from the file as it is now, only later changes count
+    pub fn new(path: &str) -> Self {
+        Self { path: path.to_string(), modified: modified(path), interval: CheckInterval::started() }
+    }
+    
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+    
+    // Whether the file was saved, created or deleted since the last change, false until the check interval has passed
+    pub fn changed(&mut self) -> bool {
+        if !self.interval.due() {
+            return false;
+        }
+        let modified = modified(&self.path);
+        if modified == self.modified {
+            return false;
+        }
+        self.modified = modified;
+        true
+    }
+    
+    // Loads the file again after it changed. A file that was deleted doesn't load either: it counts as a failed
+    // reload rather than a return to the defaults, like a broken edit.
+    pub fn reload<T>(&self, load: impl FnOnce(&str) -> Result<T, String>) -> Result<T, String> {
+        if self.modified.is_none() {
+            return Err(format!("'{}' was removed", self.path));
+        }
+        load(&self.path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn removed_file_fails_to_reload() {
+        let path = std::env::temp_dir().join(format!("thps3_free_cam_reload_{}.toml", std::process::id()));
+        let path = path.to_string_lossy().into_owned();
+        std::fs::write(&path, "speed = 1").unwrap();
+        let watch = FileWatch::new(&path);
+        assert_eq!(watch.reload(|path| std::fs::read_to_string(path).map_err(|e| e.to_string())).unwrap(), "speed = 1");
+        
+        std::fs::remove_file(&path).unwrap();
+        let watch = FileWatch::new(&path);
+        assert!(watch.reload(|_| Ok(())).is_err());
+    }
+}
@@ -0,0 +1,631 @@
+// Remote control over HTTP and WebSocket, for browser dolly controllers and OBS scripts. Requests
+// become commands the main loop picks up between ticks, the same way key presses do. OSC messages
+// (see osc.rs) arrive as the same commands. A read-only server only streams state, for spectating.
+// Every request carries the token from the config, and browsers may only call in from allowed origins,
+// so a web page open in the same browser can't drive the camera.
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "websocket")]
+use tungstenite::Message;
+
+use crate::action::Action;
+use crate::input::{CameraCommand, InputSource};
+use crate::skater::GameState;
+use crate::shutdown::{self, Cancel, CancelToken};
+
+// How often WebSocket clients get a fresh camera state, ~30 Hz
+#[cfg(feature = "websocket")]
+const STREAM_INTERVAL: Duration = Duration::from_millis(33);
+
+// What a read-only server answers commands with
+const READ_ONLY_REPLY: &str = r#"{"ok":false,"error":"This server is read-only"}"#;
+
+// A client that connects and goes quiet doesn't hold its thread forever
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(2);
+
+// How often the listener checks whether it should stop between connections
+const ACCEPT_INTERVAL: Duration = Duration::from_millis(50);
+
+// Move and turn commands last this long unless they're sent again, so a client that hangs or drops
+// off doesn't leave the camera flying
+pub const MOTION_TIMEOUT: Duration = Duration::from_millis(250);
+
+// Connections served at once, each has its own thread. Further ones are turned away until one closes.
+pub const MAX_CONNECTIONS: usize = 16;
+
+// Who may use the server
+#[derive(Debug, Clone, Default)]
+pub struct RemoteAccess {
+    pub token: String,        // Sent as `Authorization: Bearer <token>` or `?token=<token>`, empty lets anyone in
+    pub origins: Vec<String>, // Web pages allowed to call the server, e.g. "http://localhost:3000"
+}
+
+impl RemoteAccess {
+    // Browsers send an Origin with every cross-site request and WebSocket upgrade, other clients don't
+    fn allows_origin(&self, origin: Option<&str>) -> bool {
+        origin.is_none_or(|origin| self.origins.iter().any(|allowed| allowed.trim_end_matches('/').eq_ignore_ascii_case(origin)))
+    }
+    
+    fn allows_token(&self, authorization: Option<&str>, target: &str) -> bool {
+        if self.token.is_empty() {
+            return true;
+        }
+        let bearer = authorization.and_then(|value| value.trim().strip_prefix("Bearer ")).map(str::trim);
+        let query = target.split_once('?').and_then(|(_, query)| {
+            query.split('&').find_map(|pair| pair.strip_prefix("token="))
+        });
+        [bearer, query].into_iter().flatten().any(|token| same_token(token, &self.token))
+    }
+}
+
+// Compares every byte so the reply time doesn't give away how much of a guess was right
+fn same_token(given: &str, expected: &str) -> bool {
+    given.len() == expected.len() && given.bytes().zip(expected.bytes()).fold(0, |difference, (a, b)| difference | (a ^ b)) == 0
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum RemoteCommand {
+    SetPosition { x: f32, y: f32, z: f32 },
+    SetMatrix { matrix: [f32; 16] },
+    SetRotation { rotation: [f32; 4] }, // Quaternion as x, y, z, w, the position is kept
+    SetPose { position: [f32; 3], rotation: [f32; 4] },
+    SetFov { fov: f32 },                // Horizontal degrees
+    SetSpeed { speed: f32 },
+    Action { action: Action }, // Anything a key binding can do, e.g. "patch_toggle"
+    Move { right: f32, up: f32, forward: f32 }, // Fractions of the move speed, -1 to 1, for MOTION_TIMEOUT
+    Turn { yaw: f32, pitch: f32, #[serde(default)] roll: f32 }, // Degrees per second, positive turns right, up and clockwise
+}
+
+impl RemoteCommand {
+    // A NaN or infinity written to the camera can't be flown out of again, so those are turned away
+    pub fn check(self) -> Result<Self, String> {
+        let values = match &self {
+            RemoteCommand::SetPosition { x, y, z } => vec![*x, *y, *z],
+            RemoteCommand::SetMatrix { matrix } => matrix.to_vec(),
+            RemoteCommand::SetRotation { rotation } => rotation.to_vec(),
+            RemoteCommand::SetPose { position, rotation } => position.iter().chain(rotation).copied().collect(),
+            RemoteCommand::SetFov { fov } => vec![*fov],
+            RemoteCommand::SetSpeed { speed } => vec![*speed],
+            RemoteCommand::Action { .. } => Vec::new(),
+            RemoteCommand::Move { right, up, forward } => vec![*right, *up, *forward],
+            RemoteCommand::Turn { yaw, pitch, roll } => vec![*yaw, *pitch, *roll],
+        };
+        if values.iter().all(|value| value.is_finite()) {
+            Ok(self)
+        } else {
+            Err("Numbers must be finite".to_string())
+        }
+    }
+    
+    // Move and turn keep the camera going after they arrive
+    pub fn is_motion(&self) -> bool {
+        matches!(self, RemoteCommand::Move { .. } | RemoteCommand::Turn { .. })
+    }
+}
+
+// Sent for a connection that moved or turned the camera once it closes, the camera stops right away
+// instead of after MOTION_TIMEOUT
+pub fn stop_commands() -> [RemoteCommand; 2] {
+    [
+        RemoteCommand::Move { right: 0.0, up: 0.0, forward: 0.0 },
+        RemoteCommand::Turn { yaw: 0.0, pitch: 0.0, roll: 0.0 },
+    ]
+}
+
+// Listens on `bind`:`port` without blocking, so accept_connections notices when it's cancelled
+pub fn bind_listener(bind: &str, port: u16) -> Result<TcpListener, String> {
+    let listener = TcpListener::bind((bind, port)).map_err(|e| format!("Failed to listen on {}:{}: {}", bind, port, e))?;
+    listener.set_nonblocking(true).map_err(|e| format!("Failed to listen on {}:{}: {}", bind, port, e))?;
+    Ok(listener)
+}
+
+// Hands every connection to `serve`, which starts its thread, until `token` is cancelled. At most MAX_CONNECTIONS
+// are served at once, further ones are sent `busy` and closed. Returns once every connection has finished.
+pub fn accept_connections(listener: TcpListener, token: &CancelToken, busy: &str, mut serve: impl FnMut(TcpStream) -> JoinHandle<()>) {
+    let mut connections: Vec<JoinHandle<()>> = Vec::new();
+    while !token.is_cancelled() {
+        match listener.accept() {
+            Ok((stream, _)) => {
+                if stream.set_nonblocking(false).is_err() {
+                    continue;
+                }
+                connections.retain(|connection| !connection.is_finished());
+                if connections.len() >= MAX_CONNECTIONS {
+                    let _ = write!(&stream, "{}", busy);
+                    continue;
+                }
+                connections.push(serve(stream));
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                token.wait(ACCEPT_INTERVAL);
+            }
+            Err(_) => break,
+        }
+        connections.retain(|connection| !connection.is_finished());
+    }
+    
+    for connection in connections {
+        let _ = connection.join();
+    }
+}
+
+// Move and turn commands fly the camera like held keys. Clients send them again while the key is held,
+// anything not refreshed within MOTION_TIMEOUT stops. Zeros stop it right away. Clones share one state, so
+// the main loop hands commands to one while the camera controller polls another.
+#[derive(Debug, Clone, Default)]
+pub struct NetworkInput {
+    motion: Arc<Mutex<NetworkMotion>>,
+}
+
+#[derive(Debug, Default)]
+struct NetworkMotion {
+    movement: (f32, f32, f32), // (right, up, forward)
+    turn: (f32, f32, f32),     // Radians per second as (yaw, pitch, roll)
+    moved: Option<Instant>,
+    turned: Option<Instant>,
+}
+
+impl NetworkInput {
+    // Keeps a move or turn command, anything else is handed back
+    pub fn take(&self, command: RemoteCommand) -> Option<RemoteCommand> {
+        let Ok(mut motion) = self.motion.lock() else { return None };
+        match command {
+            RemoteCommand::Move { right, up, forward } => {
+                motion.movement = (right.clamp(-1.0, 1.0), up.clamp(-1.0, 1.0), forward.clamp(-1.0, 1.0));
+                motion.moved = Some(Instant::now());
+                None
+            }
+            RemoteCommand::Turn { yaw, pitch, roll } => {
+                motion.turn = (yaw.to_radians(), pitch.to_radians(), roll.to_radians());
+                motion.turned = Some(Instant::now());
+                None
+            }
+            command => Some(command),
+        }
+    }
+}
+
+impl InputSource for NetworkInput {
+    fn poll(&mut self) -> Vec<CameraCommand> {
+        let Ok(mut motion) = self.motion.lock() else { return Vec::new() };
+        if motion.moved.is_some_and(|since| since.elapsed() >= MOTION_TIMEOUT) {
+            motion.movement = (0.0, 0.0, 0.0);
+            motion.moved = None;
+        }
+        if motion.turned.is_some_and(|since| since.elapsed() >= MOTION_TIMEOUT) {
+            motion.turn = (0.0, 0.0, 0.0);
+            motion.turned = None;
+        }
+        let (right, up, forward) = motion.movement;
+        let (yaw, pitch, roll) = motion.turn;
+        // Positive X is to the left in this game, and mouse look pitches down with a positive value. Nothing is sent
+        // while stopped, so a stopped network input doesn't count as someone flying.
+        let mut commands = Vec::new();
+        if (right, up, forward) != (0.0, 0.0, 0.0) {
+            commands.push(CameraCommand::Translate(-right, up, forward));
+        }
+        if (yaw, pitch, roll) != (0.0, 0.0, 0.0) {
+            commands.push(CameraCommand::Turn(yaw, -pitch, roll));
+        }
+        commands
+    }
+}
+
+// What GET /camera returns and WebSocket clients receive
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct CameraState {
+    pub position: [f32; 3],
+    pub rotation: [f32; 4], // Quaternion as x, y, z, w
+    pub matrix: Vec<f32>,
+    pub speed: f32,
+    pub patch: bool,
+    pub fov: Option<f32>,
+    pub stats: Option<GameState>, // Skater stats, with [stats] chains in the offsets file
+}
+
+// Anything the server can hand out as JSON and send again when it changes
+pub trait StreamState: Serialize + Clone + PartialEq + Default + Send + 'static {}
+
+impl<S: Serialize + Clone + PartialEq + Default + Send + 'static> StreamState for S {}
+
+pub struct RemoteServer<S: StreamState = CameraState> {
+    receiver: Receiver<RemoteCommand>,
+    state: Arc<Mutex<S>>,
+    cancel: Option<Cancel>,
+    listener: Option<JoinHandle<()>>,
+}
+
+impl RemoteServer {
+    // The token can't be left out here, anything that can reach the port could move the camera otherwise
+    pub fn start(bind: &str, port: u16, access: RemoteAccess) -> Result<Self, String> {
+        if access.token.is_empty() {
+            return Err("The remote control needs a remote_token in config.toml".to_string());
+        }
+        Self::listen(bind, port, access, false)
+    }
+}
+
+impl<S: StreamState> RemoteServer<S> {
+    // Serves and streams the published state like `start`, but turns every command away
+    pub fn start_read_only(bind: &str, port: u16, access: RemoteAccess) -> Result<Self, String> {
+        Self::listen(bind, port, access, true)
+    }
+    
+    fn listen(bind: &str, port: u16, access: RemoteAccess, read_only: bool) -> Result<Self, String> {
+        let listener = bind_listener(bind, port)?;
+        let (sender, receiver) = mpsc::channel();
+        let state = Arc::new(Mutex::new(S::default()));
+        let (cancel, token) = shutdown::token();
+        
+        let shared = Arc::clone(&state);
+        let access = Arc::new(access);
+        // Open WebSocket streams see the same token and close themselves
+        let listener = std::thread::spawn(move || {
+            let busy = "HTTP/1.1 503 Service Unavailable\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+            accept_connections(listener, &token, busy, |stream| {
+                let (sender, state, access, token) = (sender.clone(), Arc::clone(&shared), Arc::clone(&access), token.clone());
+                std::thread::spawn(move || handle_connection(stream, sender, state, &access, token, read_only))
+            });
+        });
+        
+        Ok(Self { receiver, state, cancel: Some(cancel), listener: Some(listener) })
+    }
+    
+    // Commands received since the last call
+    pub fn poll(&self) -> Vec<RemoteCommand> {
+        self.receiver.try_iter().collect()
+    }
+    
+    pub fn publish(&self, state: S) {
+        if let Ok(mut shared) = self.state.lock() {
+            *shared = state;
+        }
+    }
+}
+
+impl<S: StreamState> Drop for RemoteServer<S> {
+    // Stops accepting and closes the WebSocket streams before the port is given up
+    fn drop(&mut self) {
+        self.cancel = None;
+        if let Some(listener) = self.listener.take() {
+            let _ = listener.join();
+        }
+    }
+}
+
+fn handle_connection<S: StreamState>(
+    stream: TcpStream,
+    sender: Sender<RemoteCommand>,
+    state: Arc<Mutex<S>>,
+    access: &RemoteAccess,
+    token: CancelToken,
+    read_only: bool,
+) {
+    let _ = stream.set_read_timeout(Some(REQUEST_TIMEOUT));
+    
+    // Peek so the WebSocket handshake still sees the whole request
+    let mut head = [0u8; 1024];
+    let Ok(length) = stream.peek(&mut head) else { return };
+    let request = String::from_utf8_lossy(&head[..length]).to_ascii_lowercase();
+    
+    if request.contains("upgrade: websocket") {
+        handle_websocket(stream, sender, state, access, token, read_only);
+    } else {
+        handle_http(stream, sender, state, access, read_only);
+    }
+}
+
+#[cfg(feature = "websocket")]
+fn handle_websocket<S: StreamState>(
+    stream: TcpStream,
+    sender: Sender<RemoteCommand>,
+    state: Arc<Mutex<S>>,
+    access: &RemoteAccess,
+    token: CancelToken,
+    read_only: bool,
+) {
+    use tungstenite::handshake::server::{ErrorResponse, Request, Response};
+    
+    // Browsers can't add headers to a WebSocket, so pages pass the token in the URL. The rejection type is
+    // tungstenite's.
+    #[allow(clippy::result_large_err)]
+    let check = |request: &Request, response: Response| -> Result<Response, ErrorResponse> {
+        let header = |name: &str| request.headers().get(name).and_then(|value| value.to_str().ok());
+        let target = request.uri().to_string();
+        let error = if !access.allows_origin(header("origin")) {
+            "This origin isn't in remote_origins"
+        } else if !access.allows_token(header("authorization"), &target) {
+            "Missing or wrong token"
+        } else {
+            return Ok(response);
+        };
+        let mut rejection = ErrorResponse::new(Some(error.to_string()));
+        *rejection.status_mut() = tungstenite::http::StatusCode::FORBIDDEN;
+        Err(rejection)
+    };
+    let Ok(mut socket) = tungstenite::accept_hdr(stream, check) else { return };
+    // Short reads so the stream keeps flowing while the client is quiet
+    let _ = socket.get_ref().set_read_timeout(Some(STREAM_INTERVAL));
+    
+    let mut last_sent: Option<S> = None;
+    let mut stop = StopOnClose { sender: &sender, moved: false };
+    loop {
+        if token.is_cancelled() {
+            // Let the client know the tool is going away instead of just dropping the connection
+            let _ = socket.close(None);
+            let _ = socket.flush();
+            return;
+        }
+        
+        match socket.read() {
+            Ok(Message::Text(text)) => {
+                let command = serde_json::from_str::<RemoteCommand>(&text).map_err(|e| e.to_string()).and_then(RemoteCommand::check);
+                let reply = match command {
+                    Ok(_) if read_only => READ_ONLY_REPLY.to_string(),
+                    Ok(command) => {
+                        stop.moved |= command.is_motion();
+                        let _ = sender.send(command);
+                        r#"{"ok":true}"#.to_string()
+                    }
+                    Err(e) => serde_json::json!({ "ok": false, "error": e }).to_string(),
+                };
+                if socket.send(Message::Text(reply)).is_err() {
+                    return;
+                }
+            }
+            Ok(Message::Close(_)) => return,
+            Ok(_) => {}
+            Err(tungstenite::Error::Io(e))
+                if matches!(e.kind(), std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut) => {}
+            Err(_) => return,
+        }
+        
+        let current = state.lock().map(|state| state.clone()).unwrap_or_default();
+        if last_sent.as_ref() != Some(&current) {
+            let Ok(json) = serde_json::to_string(&current) else { return };
+            if socket.send(Message::Text(json)).is_err() {
+                return;
+            }
+            last_sent = Some(current);
+        }
+    }
+}
+
+// Stops whatever a WebSocket client left moving, however its connection ends
+#[cfg(feature = "websocket")]
+struct StopOnClose<'a> {
+    sender: &'a Sender<RemoteCommand>,
+    moved: bool,
+}
+
+#[cfg(feature = "websocket")]
+impl Drop for StopOnClose<'_> {
+    fn drop(&mut self) {
+        if self.moved {
+            for command in stop_commands() {
+                let _ = self.sender.send(command);
+            }
+        }
+    }
+}
+
+// Built without the websocket feature, HTTP still works and WebSocket clients are told why they can't connect
+#[cfg(not(feature = "websocket"))]
+fn handle_websocket<S: StreamState>(
+    stream: TcpStream,
+    _sender: Sender<RemoteCommand>,
+    _state: Arc<Mutex<S>>,
+    _access: &RemoteAccess,
+    _token: CancelToken,
+    _read_only: bool,
+) {
+    let response = r#"{"ok":false,"error":"This build has no WebSocket support, use the HTTP endpoints"}"#;
+    let _ = write!(
+        &stream,
+        "HTTP/1.1 501 Not Implemented\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        response.len(), response
+    );
+}
+
+fn handle_http<S: StreamState>(stream: TcpStream, sender: Sender<RemoteCommand>, state: Arc<Mutex<S>>, access: &RemoteAccess, read_only: bool) {
+    let mut reader = BufReader::new(&stream);
+    
+    let mut request_line = String::new();
+    if reader.read_line(&mut request_line).is_err() {
+        return;
+    }
+    let mut parts = request_line.split_whitespace();
+    let (method, target) = (parts.next().unwrap_or(""), parts.next().unwrap_or(""));
+    let path = target.split('?').next().unwrap_or("");
+    
+    let (mut content_length, mut origin, mut authorization) = (0, None, None);
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header).is_err() || header.trim().is_empty() {
+            break;
+        }
+        let Some((name, value)) = header.split_once(':') else { continue };
+        let (name, value) = (name.trim(), value.trim().to_string());
+        if name.eq_ignore_ascii_case("content-length") {
+            content_length = value.parse().unwrap_or(0);
+        } else if name.eq_ignore_ascii_case("origin") {
+            origin = Some(value);
+        } else if name.eq_ignore_ascii_case("authorization") {
+            authorization = Some(value);
+        }
+    }
+    
+    // Pages from other origins get no CORS headers, so their browser won't let them read the reply either
+    if !access.allows_origin(origin.as_deref()) {
+        reply(&stream, "403 Forbidden", r#"{"ok":false,"error":"This origin isn't in remote_origins"}"#, None);
+        return;
+    }
+    // The browser asks before sending a request with a token, and can't send the token with the question
+    if method == "OPTIONS" {
+        reply(&stream, "204 No Content", "", origin.as_deref());
+        return;
+    }
+    if !access.allows_token(authorization.as_deref(), target) {
+        reply(&stream, "401 Unauthorized", r#"{"ok":false,"error":"Missing or wrong token"}"#, origin.as_deref());
+        return;
+    }
+    
+    let mut body = vec![0u8; content_length.min(64 * 1024)];
+    if reader.read_exact(&mut body).is_err() {
+        return;
+    }
+    
+    let command = route(method, path, &body)
+        .and_then(|command| command.map(RemoteCommand::check).transpose().map_err(|e| ("400 Bad Request", e)));
+    let (status, response) = match command {
+        Ok(Some(_)) if read_only => ("403 Forbidden", READ_ONLY_REPLY.to_string()),
+        Ok(Some(command)) => {
+            let _ = sender.send(command);
+            ("200 OK", r#"{"ok":true}"#.to_string())
+        }
+        Ok(None) => {
+            let current = state.lock().map(|state| state.clone()).unwrap_or_default();
+            ("200 OK", serde_json::to_string(&current).unwrap_or_default())
+        }
+        Err((status, error)) => (status, serde_json::json!({ "ok": false, "error": error }).to_string()),
+    };
+    
+    reply(&stream, status, &response, origin.as_deref());
+}
+
+// Pages from an allowed origin are told they may read the reply and send the token
+fn reply(mut stream: &TcpStream, status: &str, body: &str, origin: Option<&str>) {
+    let cors = origin.map_or(String::new(), |origin| format!(
+        "Access-Control-Allow-Origin: {}\r\nAccess-Control-Allow-Methods: GET, PUT, POST\r\nAccess-Control-Allow-Headers: Authorization, Content-Type\r\nVary: Origin\r\n",
+        origin
+    ));
+    let _ = write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n{}Connection: close\r\n\r\n{}",
+        status, body.len(), cors, body
+    );
+}
+
+// Maps a request to a command, None for reads of the current state
+fn route(method: &str, path: &str, body: &[u8]) -> Result<Option<RemoteCommand>, (&'static str, String)> {
+    let invalid = |e: serde_json::Error| ("400 Bad Request", format!("Invalid request body: {}", e));
+    
+    match (method, path.trim_end_matches('/')) {
+        ("GET", "/camera") => Ok(None),
+        ("PUT" | "POST", "/camera/position") => {
+            #[derive(Deserialize)]
+            struct Position {
+                x: f32,
+                y: f32,
+                z: f32,
+            }
+            let Position { x, y, z } = serde_json::from_slice(body).map_err(invalid)?;
+            Ok(Some(RemoteCommand::SetPosition { x, y, z }))
+        }
+        ("PUT" | "POST", "/camera/matrix") => {
+            #[derive(Deserialize)]
+            struct Matrix {
+                matrix: [f32; 16],
+            }
+            let Matrix { matrix } = serde_json::from_slice(body).map_err(invalid)?;
+            Ok(Some(RemoteCommand::SetMatrix { matrix }))
+        }
+        ("PUT" | "POST", "/camera/rotation") => {
+            #[derive(Deserialize)]
+            struct Rotation {
+                rotation: [f32; 4],
+            }
+            let Rotation { rotation } = serde_json::from_slice(body).map_err(invalid)?;
+            Ok(Some(RemoteCommand::SetRotation { rotation }))
+        }
+        ("PUT" | "POST", "/camera/pose") => {
+            #[derive(Deserialize)]
+            struct Pose {
+                position: [f32; 3],
+                rotation: [f32; 4],
+            }
+            let Pose { position, rotation } = serde_json::from_slice(body).map_err(invalid)?;
+            Ok(Some(RemoteCommand::SetPose { position, rotation }))
+        }
+        ("PUT" | "POST", "/camera/fov") => {
+            #[derive(Deserialize)]
+            struct Fov {
+                fov: f32,
+            }
+            let Fov { fov } = serde_json::from_slice(body).map_err(invalid)?;
+            Ok(Some(RemoteCommand::SetFov { fov }))
+        }
+        ("PUT" | "POST", "/speed") => {
+            #[derive(Deserialize)]
+            struct Speed {
+                speed: f32,
+            }
+            let Speed { speed } = serde_json::from_slice(body).map_err(invalid)?;
+            Ok(Some(RemoteCommand::SetSpeed { speed }))
+        }
+        ("PUT" | "POST", "/camera/move") => {
+            #[derive(Deserialize)]
+            struct Move {
+                right: f32,
+                up: f32,
+                forward: f32,
+            }
+            let Move { right, up, forward } = serde_json::from_slice(body).map_err(invalid)?;
+            Ok(Some(RemoteCommand::Move { right, up, forward }))
+        }
+        ("PUT" | "POST", "/camera/turn") => {
+            #[derive(Deserialize)]
+            struct Turn {
+                yaw: f32,
+                pitch: f32,
+                #[serde(default)]
+                roll: f32,
+            }
+            let Turn { yaw, pitch, roll } = serde_json::from_slice(body).map_err(invalid)?;
+            Ok(Some(RemoteCommand::Turn { yaw, pitch, roll }))
+        }
+        ("POST", path) if path.starts_with("/action/") => {
+            let name = &path["/action/".len()..];
+            let action = serde_json::from_value(serde_json::Value::String(name.to_string()))
+                .map_err(|_| ("404 Not Found", format!("Unknown action '{}'", name)))?;
+            Ok(Some(RemoteCommand::Action { action }))
+        }
+        _ => Err(("404 Not Found", format!("No endpoint for {} {}", method, path))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn check_rejects_non_finite_values() {
+        assert!(RemoteCommand::SetFov { fov: 75.0 }.check().is_ok());
+        assert!(RemoteCommand::SetFov { fov: f32::NAN }.check().is_err());
+        assert!(RemoteCommand::Move { right: 0.0, up: f32::INFINITY, forward: 0.0 }.check().is_err());
+        let mut matrix = [0.0; 16];
+        matrix[15] = f32::NEG_INFINITY;
+        assert!(RemoteCommand::SetMatrix { matrix }.check().is_err());
+        // Too big for an f32, so it arrives as infinity
+        assert!(route("PUT", "/camera/fov", br#"{"fov": 1e39}"#).unwrap().unwrap().check().is_err());
+    }
+    
+    #[test]
+    fn network_motion_expires() {
+        let input = NetworkInput::default();
+        let mut polled = input.clone();
+        assert!(input.take(RemoteCommand::Move { right: 0.0, up: 0.0, forward: 1.0 }).is_none());
+        assert!(matches!(polled.poll()[..], [CameraCommand::Translate(_, _, forward)] if forward == 1.0));
+        
+        std::thread::sleep(MOTION_TIMEOUT);
+        assert!(polled.poll().is_empty());
+    }
+}
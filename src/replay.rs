@@ -0,0 +1,40 @@
+// Replay sync: machinima camera moves have to start with the replay they were made for, which is hard
+// to time by hand. The [replay] value from offsets.toml is watched every tick so the path can start
+// the moment the game starts a replay, and stop when it ends.
+
+use crate::offsets::ReplayOffsets;
+use crate::process::ProcessHandle;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReplayEvent {
+    Started,
+    Ended,
+}
+
+pub struct ReplayWatch {
+    offsets: ReplayOffsets,
+    playing: Option<bool>, // None until the first read, so a replay already running isn't joined halfway
+}
+
+impl ReplayWatch {
+    pub fn new(offsets: ReplayOffsets) -> Self {
+        Self { offsets, playing: None }
+    }
+    
+    pub fn is_playing(&self) -> bool {
+        self.playing == Some(true)
+    }
+    
+    // Whether a replay started or ended since the last call. A value that can't be read, for
+    // example in the menus, counts as no replay.
+    pub fn poll(&mut self, process: &ProcessHandle, base_addr: usize) -> Option<ReplayEvent> {
+        let playing = process.get_state_value(base_addr, &self.offsets.chain)
+            .is_ok_and(|value| self.offsets.playing.contains(&value));
+        let previous = self.playing.replace(playing)?;
+        match (previous, playing) {
+            (false, true) => Some(ReplayEvent::Started),
+            (true, false) => Some(ReplayEvent::Ended),
+            _ => None,
+        }
+    }
+}
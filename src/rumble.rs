@@ -0,0 +1,137 @@
+// Gamepad rumble through XInput, so patch toggles, saves and errors can be felt without looking at
+// the console. Pulses play on their own thread, the main loop never waits for them.
+
+use std::sync::mpsc::{self, Sender};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use serde::Deserialize;
+#[cfg(feature = "gamepad")]
+use winapi::um::xinput::{XInputSetState, XINPUT_VIBRATION};
+
+use crate::shutdown::{self, Cancel};
+
+// Pause between the buzzes of a multi-buzz pulse
+const PULSE_GAP: Duration = Duration::from_millis(80);
+
+// One kind of feedback: `count` buzzes of `duration_ms` each
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(default)]
+pub struct RumblePulse {
+    pub strength: f32, // 0-1
+    pub duration_ms: u64,
+    pub count: u32,
+}
+
+impl Default for RumblePulse {
+    fn default() -> Self {
+        Self { strength: 0.5, duration_ms: 120, count: 1 }
+    }
+}
+
+// Rumble settings, set in the [rumble] table. Off unless enabled.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct RumbleConfig {
+    pub enabled: bool,
+    pub controller: u32, // XInput slot 0-3
+    pub patch_on: RumblePulse,
+    pub patch_off: RumblePulse,
+    pub saved: RumblePulse, // Keyframes and paths
+    pub error: RumblePulse, // Lost the game or a camera write failed
+}
+
+impl Default for RumbleConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            controller: 0,
+            patch_on: RumblePulse { strength: 0.5, duration_ms: 150, count: 1 },
+            patch_off: RumblePulse { strength: 0.5, duration_ms: 80, count: 2 },
+            saved: RumblePulse { strength: 0.3, duration_ms: 60, count: 1 },
+            error: RumblePulse { strength: 1.0, duration_ms: 500, count: 1 },
+        }
+    }
+}
+
+pub struct Rumble {
+    config: RumbleConfig,
+    sender: Option<Sender<RumblePulse>>, // None when rumble is off
+    worker: Option<JoinHandle<()>>,
+    _cancel: Option<Cancel>,
+}
+
+impl Rumble {
+    pub fn new(config: &RumbleConfig) -> Self {
+        if !config.enabled {
+            return Self { config: config.clone(), sender: None, worker: None, _cancel: None };
+        }
+        if !cfg!(feature = "gamepad") {
+            println!("⚠️ Rumble is enabled but this build has no gamepad support");
+            return Self { config: config.clone(), sender: None, worker: None, _cancel: None };
+        }
+        
+        let (sender, receiver) = mpsc::channel::<RumblePulse>();
+        let controller = config.controller;
+        // Shutting down cuts pulses short, the motors are always switched off
+        let (cancel, token) = shutdown::token();
+        let worker = std::thread::spawn(move || {
+            for pulse in receiver {
+                let speed = (pulse.strength.clamp(0.0, 1.0) * u16::MAX as f32) as u16;
+                for buzz in 0..pulse.count {
+                    if buzz > 0 && token.wait(PULSE_GAP) {
+                        break;
+                    }
+                    set_motors(controller, speed);
+                    let cancelled = token.wait(Duration::from_millis(pulse.duration_ms));
+                    set_motors(controller, 0);
+                    if cancelled {
+                        break;
+                    }
+                }
+            }
+        });
+        
+        Self { config: config.clone(), sender: Some(sender), worker: Some(worker), _cancel: Some(cancel) }
+    }
+    
+    fn pulse(&self, pulse: RumblePulse) {
+        if let Some(sender) = &self.sender {
+            let _ = sender.send(pulse);
+        }
+    }
+    
+    pub fn patch_toggled(&self, applied: bool) {
+        self.pulse(if applied { self.config.patch_on } else { self.config.patch_off });
+    }
+    
+    pub fn saved(&self) {
+        self.pulse(self.config.saved);
+    }
+    
+    pub fn error(&self) {
+        self.pulse(self.config.error);
+    }
+}
+
+impl Drop for Rumble {
+    // Lets queued pulses finish, the error pulse when the game is lost comes right before the tool exits
+    fn drop(&mut self) {
+        self.sender = None;
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
+
+// No controller in the slot just means nothing buzzes
+#[cfg(feature = "gamepad")]
+fn set_motors(controller: u32, speed: u16) {
+    let mut vibration = XINPUT_VIBRATION { wLeftMotorSpeed: speed, wRightMotorSpeed: speed };
+    unsafe {
+        XInputSetState(controller, &mut vibration);
+    }
+}
+
+#[cfg(not(feature = "gamepad"))]
+fn set_motors(_controller: u32, _speed: u16) {}
@@ -0,0 +1,244 @@
+// A minimal Cheat Engine for game builds where the built-in camera chain doesn't resolve. The value scan finds
+// the camera position from a coordinate the user knows, or from nothing and a few rounds of "it moved" and
+// "it didn't move". The pointer scan then looks for chains from the executable's static memory to the camera
+// matrix, which can go into offsets.toml as [camera].
+
+use std::ops::Range;
+
+use crate::camera::CameraMatrix;
+use crate::offsets::PointerChain;
+use crate::process::{MemoryRegion, ProcessHandle};
+
+// Pointers followed before giving up, a crowded heap can have millions of chains to the same address
+const SEARCH_BUDGET: usize = 5_000_000;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ScanFilter {
+    Equals(f32, f32), // Value and how far off it may be, a coordinate on screen is rounded
+    Changed,
+    Unchanged,
+    Increased,
+    Decreased,
+}
+
+impl ScanFilter {
+    fn keeps(self, old: f32, new: f32) -> bool {
+        match self {
+            ScanFilter::Equals(value, tolerance) => (new - value).abs() <= tolerance,
+            ScanFilter::Changed => new.to_bits() != old.to_bits(),
+            ScanFilter::Unchanged => new.to_bits() == old.to_bits(),
+            ScanFilter::Increased => new > old,
+            ScanFilter::Decreased => new < old,
+        }
+    }
+}
+
+// The game's writable memory, read region by region. Code and read-only data can't hold the camera.
+fn writable_memory(process: &ProcessHandle, regions: &[MemoryRegion]) -> Vec<(usize, Vec<u8>)> {
+    regions.iter()
+        .filter(|region| region.writable)
+        .filter_map(|region| Some((region.base, process.read_bytes(region.base, region.size).ok()?)))
+        .collect()
+}
+
+fn float_at(bytes: &[u8], offset: usize) -> f32 {
+    f32::from_le_bytes([bytes[offset], bytes[offset + 1], bytes[offset + 2], bytes[offset + 3]])
+}
+
+// Offsets of the aligned 4-byte values in `bytes`
+fn aligned_offsets(bytes: &[u8]) -> impl Iterator<Item = usize> {
+    (0..bytes.len() - bytes.len() % 4).step_by(4)
+}
+
+pub struct ValueScan {
+    snapshot: Vec<(usize, Vec<u8>)>, // Memory at the start of an unknown-value scan, until the first filter
+    candidates: Vec<(usize, f32)>,   // Address and the value the last filter saw, sorted by address
+}
+
+impl ValueScan {
+    // Starts from every float within `tolerance` of `value`
+    pub fn exact(process: &ProcessHandle, value: f32, tolerance: f32) -> Self {
+        let filter = ScanFilter::Equals(value, tolerance);
+        let mut candidates = Vec::new();
+        for (base, bytes) in writable_memory(process, &process.memory_regions()) {
+            candidates.extend(aligned_offsets(&bytes)
+                .map(|offset| (base + offset, float_at(&bytes, offset)))
+                .filter(|&(_, new)| filter.keeps(new, new)));
+        }
+        Self { snapshot: Vec::new(), candidates }
+    }
+    
+    // Starts from every float, the first filter compares against the memory as it is now
+    pub fn unknown(process: &ProcessHandle) -> Self {
+        Self { snapshot: writable_memory(process, &process.memory_regions()), candidates: Vec::new() }
+    }
+    
+    pub fn len(&self) -> usize {
+        if self.snapshot.is_empty() {
+            self.candidates.len()
+        } else {
+            self.snapshot.iter().map(|(_, bytes)| bytes.len() / 4).sum()
+        }
+    }
+    
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    
+    pub fn candidates(&self) -> &[(usize, f32)] {
+        &self.candidates
+    }
+    
+    // Keeps the candidates whose value now passes `filter`, reading each region that has any of them once
+    pub fn filter(&mut self, process: &ProcessHandle, filter: ScanFilter) {
+        let regions = process.memory_regions();
+        
+        if !self.snapshot.is_empty() {
+            let snapshot = std::mem::take(&mut self.snapshot);
+            for (base, current) in writable_memory(process, &regions) {
+                let old = snapshot.iter().find(|(old_base, _)| *old_base == base).map(|(_, bytes)| bytes);
+                for offset in aligned_offsets(&current) {
+                    let new = float_at(&current, offset);
+                    // A region that showed up since the snapshot has no old values, only an exact value can match there
+                    let old_value = match old {
+                        Some(old) if offset + 4 <= old.len() => float_at(old, offset),
+                        _ => f32::NAN,
+                    };
+                    if new.is_finite() && filter.keeps(old_value, new) {
+                        self.candidates.push((base + offset, new));
+                    }
+                }
+            }
+            return;
+        }
+        
+        let mut kept = Vec::new();
+        for region in regions.iter().filter(|region| region.writable) {
+            let start = self.candidates.partition_point(|&(address, _)| address < region.base);
+            let end = self.candidates.partition_point(|&(address, _)| address + 4 <= region.base + region.size);
+            if start >= end {
+                continue;
+            }
+            let Ok(bytes) = process.read_bytes(region.base, region.size) else {
+                continue;
+            };
+            for &(address, old) in &self.candidates[start..end] {
+                let new = float_at(&bytes, address - region.base);
+                if new.is_finite() && filter.keeps(old, new) {
+                    kept.push((address, new));
+                }
+            }
+        }
+        self.candidates = kept;
+    }
+}
+
+// Where the camera matrix starts if `position_address` is one of its position floats (12, 13 or 14), and which
+// one it is. The scan may have found the X, Y or Z coordinate, or another copy of it that isn't in a matrix.
+pub fn camera_matrix_at(process: &ProcessHandle, position_address: usize) -> Option<(usize, usize)> {
+    (12..=14).find_map(|index| {
+        let matrix_address = position_address.checked_sub(index * 4)?;
+        let data: [f32; 16] = process.read_memory(matrix_address).ok()?;
+        CameraMatrix { data }.looks_like_camera().then_some((matrix_address, index))
+    })
+}
+
+// Every aligned 32-bit value in writable memory that points into readable memory, for walking pointers backwards
+pub struct PointerMap {
+    pointers: Vec<(u32, u32)>, // Value and the address holding it, sorted by value
+    module: Range<usize>,      // The executable's own memory, where a chain has to start to survive a restart
+    module_base: usize,
+}
+
+struct ChainSearch {
+    max_offset: usize,
+    limit: usize,
+    budget: usize,
+    offsets: Vec<usize>,   // From the target back towards the executable
+    addresses: Vec<usize>, // Already on the chain, a pointer cycle would only make longer copies of it
+    chains: Vec<PointerChain>,
+}
+
+impl PointerMap {
+    pub fn build(process: &ProcessHandle, module_base: usize) -> Self {
+        let regions = process.memory_regions();
+        let module_regions = regions.iter().filter(|region| region.allocation_base == module_base);
+        let module = module_regions.clone().map(|region| region.base).min().unwrap_or(module_base)
+            ..module_regions.map(|region| region.base + region.size).max().unwrap_or(module_base);
+        
+        let points_into_memory = |value: usize| {
+            let index = regions.partition_point(|region| region.base + region.size <= value);
+            regions.get(index).is_some_and(|region| region.base <= value)
+        };
+        
+        let mut pointers = Vec::new();
+        for (base, bytes) in writable_memory(process, &regions) {
+            for offset in aligned_offsets(&bytes) {
+                let value = u32::from_le_bytes([bytes[offset], bytes[offset + 1], bytes[offset + 2], bytes[offset + 3]]);
+                if value >= 0x10000 && points_into_memory(value as usize) {
+                    pointers.push((value, (base + offset) as u32));
+                }
+            }
+        }
+        pointers.sort_unstable();
+        Self { pointers, module, module_base }
+    }
+    
+    pub fn len(&self) -> usize {
+        self.pointers.len()
+    }
+    
+    pub fn is_empty(&self) -> bool {
+        self.pointers.is_empty()
+    }
+    
+    // Chains from the executable to `target` with up to `max_depth` pointers, each pointer at most `max_offset`
+    // bytes before the next address. Shorter chains come first, they are the likelier to stay valid.
+    pub fn find_chains(&self, target: usize, max_depth: usize, max_offset: usize, limit: usize) -> Vec<PointerChain> {
+        let mut search = ChainSearch {
+            max_offset,
+            limit,
+            budget: SEARCH_BUDGET,
+            offsets: Vec::new(),
+            addresses: vec![target],
+            chains: Vec::new(),
+        };
+        for depth in 1..=max_depth {
+            self.search(target, depth, &mut search);
+            if search.chains.len() >= limit || search.budget == 0 {
+                break;
+            }
+        }
+        search.chains
+    }
+    
+    // Records chains with exactly `remaining` more pointers, so each depth only finds the chains it adds
+    fn search(&self, target: usize, remaining: usize, search: &mut ChainSearch) {
+        let lowest = target.saturating_sub(search.max_offset);
+        let start = self.pointers.partition_point(|&(value, _)| (value as usize) < lowest);
+        for &(value, address) in &self.pointers[start..] {
+            if value as usize > target || search.chains.len() >= search.limit || search.budget == 0 {
+                break;
+            }
+            search.budget -= 1;
+            
+            let address = address as usize;
+            if search.addresses.contains(&address) {
+                continue;
+            }
+            search.offsets.push(target - value as usize);
+            if self.module.contains(&address) {
+                if remaining == 1 {
+                    let mut offsets: Vec<usize> = search.offsets.iter().rev().copied().collect();
+                    offsets.push(0); // The last offset is added without dereferencing
+                    search.chains.push(PointerChain { base: address - self.module_base, offsets });
+                }
+            } else if remaining > 1 {
+                search.addresses.push(address);
+                self.search(address, remaining - 1, search);
+                search.addresses.pop();
+            }
+            search.offsets.pop();
+        }
+    }
+}
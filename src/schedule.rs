@@ -0,0 +1,70 @@
+// Starts path playback at a set time of day, so a camera move made in Blender can be lined up with a run
+// that starts on a clock instead of on a key press. Times are the computer's local time.
+
+use std::time::{Duration, Instant};
+
+use winapi::um::minwinbase::SYSTEMTIME;
+use winapi::um::sysinfoapi::GetLocalTime;
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+// Later than this after the start time, playback no longer counts as on time
+const LATE_LIMIT: Duration = Duration::from_secs(1);
+
+pub enum StartEvent {
+    Start,
+    Missed(Duration), // How long ago the start time was
+}
+
+#[derive(Debug, Clone)]
+pub struct StartTime {
+    label: String, // As typed, for messages
+    at: Instant,
+}
+
+// "HH:MM" or "HH:MM:SS" as seconds since midnight
+fn parse_time_of_day(text: &str) -> Result<u64, String> {
+    let parts: Vec<&str> = text.trim().split(':').collect();
+    let invalid = || format!("'{}' is not a time - use HH:MM or HH:MM:SS, e.g. 21:30:00", text);
+    if !(2..=3).contains(&parts.len()) {
+        return Err(invalid());
+    }
+    let values: Vec<u64> = parts.iter().map(|part| part.parse().map_err(|_| invalid())).collect::<Result<_, _>>()?;
+    let (hours, minutes, seconds) = (values[0], values[1], values.get(2).copied().unwrap_or(0));
+    if hours > 23 || minutes > 59 || seconds > 59 {
+        return Err(invalid());
+    }
+    Ok(hours * 3600 + minutes * 60 + seconds)
+}
+
+// Local time since midnight
+fn now_of_day() -> Duration {
+    let mut time: SYSTEMTIME = unsafe { std::mem::zeroed() };
+    unsafe { GetLocalTime(&mut time) };
+    Duration::from_secs(time.wHour as u64 * 3600 + time.wMinute as u64 * 60 + time.wSecond as u64)
+        + Duration::from_millis(time.wMilliseconds as u64)
+}
+
+impl StartTime {
+    // The next time the clock shows `text`, today or tomorrow
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let target = Duration::from_secs(parse_time_of_day(text)?);
+        let now = now_of_day();
+        let wait = if target > now { target - now } else { target + Duration::from_secs(SECONDS_PER_DAY) - now };
+        Ok(Self { label: text.trim().to_string(), at: Instant::now() + wait })
+    }
+    
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+    
+    pub fn remaining(&self) -> Duration {
+        self.at.saturating_duration_since(Instant::now())
+    }
+    
+    // Once the start time has come, says whether there is still time to start
+    pub fn poll(&self) -> Option<StartEvent> {
+        let late = Instant::now().checked_duration_since(self.at)?;
+        Some(if late <= LATE_LIMIT { StartEvent::Start } else { StartEvent::Missed(late) })
+    }
+}
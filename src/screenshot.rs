@@ -0,0 +1,118 @@
+#[cfg(feature = "screenshots")]
+use std::fs::File;
+#[cfg(feature = "screenshots")]
+use std::io::BufWriter;
+use std::mem;
+use std::ptr;
+
+use winapi::shared::minwindef::UINT;
+use winapi::shared::windef::{HWND, POINT, RECT};
+use winapi::um::wingdi::{
+    BitBlt, CreateCompatibleBitmap, CreateCompatibleDC, DeleteDC, DeleteObject, GetDIBits, SelectObject, BITMAPINFO,
+    BITMAPINFOHEADER, BI_RGB, DIB_RGB_COLORS, SRCCOPY,
+};
+use winapi::um::winuser::{ClientToScreen, GetClientRect, GetDC, ReleaseDC};
+
+use crate::camera::CameraPosition;
+use crate::telemetry::{format_utc_datetime, unix_millis};
+
+// Copies the game window's client area as it is on screen. Reading the screen instead of the
+// window DC picks up what Direct3D rendered; exclusive fullscreen can still come out black.
+fn capture_window(window: HWND) -> Result<(u32, u32, Vec<u8>), String> {
+    let mut rect = RECT { left: 0, top: 0, right: 0, bottom: 0 };
+    let mut origin = POINT { x: 0, y: 0 };
+    if unsafe { GetClientRect(window, &mut rect) } == 0 || unsafe { ClientToScreen(window, &mut origin) } == 0 {
+        return Err("Failed to get the game window area".to_string());
+    }
+    let (width, height) = (rect.right - rect.left, rect.bottom - rect.top);
+    if width <= 0 || height <= 0 {
+        return Err("Game window is minimized".to_string());
+    }
+    
+    unsafe {
+        let screen_dc = GetDC(ptr::null_mut());
+        if screen_dc.is_null() {
+            return Err("Failed to get the screen device context".to_string());
+        }
+        let memory_dc = CreateCompatibleDC(screen_dc);
+        let bitmap = CreateCompatibleBitmap(screen_dc, width, height);
+        let previous = SelectObject(memory_dc, bitmap as *mut _);
+        
+        let copied = BitBlt(memory_dc, 0, 0, width, height, screen_dc, origin.x, origin.y, SRCCOPY) != 0;
+        
+        // Top-down 32 bit rows, so the pixels come out in reading order
+        let mut info: BITMAPINFO = mem::zeroed();
+        info.bmiHeader.biSize = mem::size_of::<BITMAPINFOHEADER>() as u32;
+        info.bmiHeader.biWidth = width;
+        info.bmiHeader.biHeight = -height;
+        info.bmiHeader.biPlanes = 1;
+        info.bmiHeader.biBitCount = 32;
+        info.bmiHeader.biCompression = BI_RGB;
+        let mut pixels = vec![0u8; width as usize * height as usize * 4];
+        SelectObject(memory_dc, previous);
+        let lines = GetDIBits(memory_dc, bitmap, 0, height as UINT, pixels.as_mut_ptr() as *mut _, &mut info, DIB_RGB_COLORS);
+        
+        DeleteObject(bitmap as *mut _);
+        DeleteDC(memory_dc);
+        ReleaseDC(ptr::null_mut(), screen_dc);
+        
+        if !copied || lines != height {
+            return Err("Failed to copy the game window".to_string());
+        }
+        
+        // BGRX to RGBA
+        for pixel in pixels.chunks_exact_mut(4) {
+            pixel.swap(0, 2);
+            pixel[3] = 255;
+        }
+        Ok((width as u32, height as u32, pixels))
+    }
+}
+
+#[cfg(feature = "screenshots")]
+fn write_png(path: &str, width: u32, height: u32, pixels: &[u8]) -> Result<(), String> {
+    let file = File::create(path).map_err(|e| format!("Failed to create '{}': {}", path, e))?;
+    let mut encoder = png::Encoder::new(BufWriter::new(file), width, height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    
+    let mut writer = encoder.write_header().map_err(|e| format!("Failed to write '{}': {}", path, e))?;
+    writer.write_image_data(pixels).map_err(|e| format!("Failed to write '{}': {}", path, e))
+}
+
+#[cfg(not(feature = "screenshots"))]
+fn write_png(_path: &str, _width: u32, _height: u32, _pixels: &[u8]) -> Result<(), String> {
+    Err("This build has no screenshot support".to_string())
+}
+
+// Fails in a build without the `screenshots` feature, checked before any folder is created
+pub fn check_supported() -> Result<(), String> {
+    if cfg!(feature = "screenshots") {
+        Ok(())
+    } else {
+        Err("This build has no screenshot support".to_string())
+    }
+}
+
+// Saves the game window as a PNG at `path`
+pub fn save_screenshot(window: HWND, path: &str) -> Result<(), String> {
+    let (width, height, pixels) = capture_window(window)?;
+    write_png(path, width, height, &pixels)
+}
+
+// UTC date and time for file names: colons aren't allowed on Windows, and the milliseconds keep quick shots apart
+pub fn file_timestamp() -> String {
+    let millis = unix_millis();
+    format_utc_datetime(millis).replace(':', "-").replace('Z', &format!(".{:03}", millis % 1000))
+}
+
+// Saves the game window to `directory` as a timestamped PNG named after the camera position, returns the file
+pub fn take_screenshot(window: HWND, directory: &str, position: &CameraPosition) -> Result<String, String> {
+    check_supported()?;
+    std::fs::create_dir_all(directory).map_err(|e| format!("Failed to create '{}': {}", directory, e))?;
+    let path = format!("{}/thps3_{}_X{:.0}_Y{:.0}_Z{:.0}.png", directory.trim_end_matches(['/', '\\']), file_timestamp(),
+                       position.x, position.y, position.z);
+    
+    save_screenshot(window, &path)?;
+    Ok(path)
+}
@@ -0,0 +1,332 @@
+// Shot scripts: small rhai programs in script_dir that drive the camera, e.g. fly from A to B in 5 seconds and
+// then orbit the skater for 10, without recompiling anything. Each script is bound to a key in the [scripts]
+// table of config.toml and runs on its own thread. What it does to the camera reaches the free cam as the same
+// commands remote control sends, so scripts go through the same checks. Needs the `scripting` feature.
+
+use std::sync::mpsc::{self, Receiver};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+
+use crate::camera::{CameraMatrix, CameraPosition};
+use crate::interpolation::InterpolationMode;
+use crate::remote::RemoteCommand;
+use crate::shutdown::{self, Cancel};
+
+pub const SCRIPT_EXTENSION: &str = "rhai";
+
+// What the free cam publishes for the running script every tick
+#[derive(Debug, Clone, Default)]
+pub struct ScriptState {
+    pub matrix: CameraMatrix,
+    pub patch: bool,
+    pub fov: Option<f32>,                // Horizontal degrees
+    pub skater: Option<CameraPosition>, // With a [skater] chain
+}
+
+#[derive(Debug)]
+pub enum ScriptEvent {
+    Command(RemoteCommand),
+    Finished(Result<(), String>),
+}
+
+pub struct ScriptRunner {
+    name: String,
+    receiver: Receiver<ScriptEvent>,
+    state: Arc<Mutex<ScriptState>>,
+    cancel: Option<Cancel>,
+    worker: Option<JoinHandle<()>>,
+}
+
+impl ScriptRunner {
+    // Runs the script at `path`, starting from `state`. Errors in the script itself arrive as a Finished event.
+    pub fn start(path: &str, interpolation: InterpolationMode, state: ScriptState) -> Result<Self, String> {
+        let source = std::fs::read_to_string(path).map_err(|e| format!("Failed to read script '{}': {}", path, e))?;
+        let name = std::path::Path::new(path).file_stem().map_or(path.to_string(), |stem| stem.to_string_lossy().to_string());
+        let (sender, receiver) = mpsc::channel();
+        let state = Arc::new(Mutex::new(state));
+        let (cancel, token) = shutdown::token();
+        
+        let shared = Arc::clone(&state);
+        let script_name = name.clone();
+        let worker = std::thread::spawn(move || {
+            let result = run(&script_name, &source, interpolation, sender.clone(), shared, token.clone());
+            // A script that was stopped has nobody left to tell
+            if !token.is_cancelled() {
+                let _ = sender.send(ScriptEvent::Finished(result));
+            }
+        });
+        
+        Ok(Self { name, receiver, state, cancel: Some(cancel), worker: Some(worker) })
+    }
+    
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    
+    // Commands sent since the last call, and whether the script has finished
+    pub fn poll(&self) -> Vec<ScriptEvent> {
+        self.receiver.try_iter().collect()
+    }
+    
+    pub fn publish(&self, state: ScriptState) {
+        if let Ok(mut shared) = self.state.lock() {
+            *shared = state;
+        }
+    }
+}
+
+impl Drop for ScriptRunner {
+    // Stops the script at its next step or wait
+    fn drop(&mut self) {
+        self.cancel = None;
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
+
+#[cfg(feature = "scripting")]
+use engine::run;
+
+// Built without the scripting feature, every script says why it can't run
+#[cfg(not(feature = "scripting"))]
+fn run(_name: &str, _source: &str, _interpolation: InterpolationMode, _sender: mpsc::Sender<ScriptEvent>,
+       _state: Arc<Mutex<ScriptState>>, _token: shutdown::CancelToken) -> Result<(), String> {
+    Err("This build has no scripting".to_string())
+}
+
+#[cfg(feature = "scripting")]
+mod engine {
+    use std::rc::Rc;
+    use std::sync::mpsc::Sender;
+    use std::sync::{Arc, Mutex};
+    use std::time::{Duration, Instant};
+    
+    use rhai::{Array, Dynamic, Engine, EvalAltResult};
+    
+    use super::{ScriptEvent, ScriptState};
+    use crate::action::Action;
+    use crate::camera::{CameraMatrix, CameraPosition};
+    use crate::convert::{read_path, PathFormat};
+    use crate::input::{parse_key_name, Key};
+    use crate::interpolation::InterpolationMode;
+    use crate::remote::RemoteCommand;
+    use crate::shutdown::CancelToken;
+    
+    type ScriptResult<T> = Result<T, Box<EvalAltResult>>;
+    
+    // How often moves send a new pose, about once per free cam tick
+    const TICK: Duration = Duration::from_millis(16);
+    
+    // Time the free cam gets to write a move's last pose, so whatever the script reads next already has it
+    const SETTLE: Duration = Duration::from_millis(50);
+    
+    // Frame rate for .chan and per-frame CSV files played with play_path
+    const PATH_FPS: f32 = 60.0;
+    
+    // What the script's functions share
+    struct Host {
+        sender: Sender<ScriptEvent>,
+        state: Arc<Mutex<ScriptState>>,
+        token: CancelToken,
+        interpolation: InterpolationMode,
+    }
+    
+    impl Host {
+        fn state(&self) -> ScriptState {
+            self.state.lock().map(|state| state.clone()).unwrap_or_default()
+        }
+        
+        fn send(&self, command: RemoteCommand) -> ScriptResult<()> {
+            self.sender.send(ScriptEvent::Command(command.check()?)).map_err(|_| "The free cam stopped".into())
+        }
+        
+        fn write(&self, matrix: &CameraMatrix) -> ScriptResult<()> {
+            self.send(RemoteCommand::SetMatrix { matrix: matrix.data })
+        }
+        
+        fn sleep(&self, duration: Duration) -> ScriptResult<()> {
+            if self.token.wait(duration) {
+                return Err("Stopped".into());
+            }
+            Ok(())
+        }
+        
+        // Calls `pose_at` with the fraction of `seconds` gone by every tick and writes what it returns
+        fn animate(&self, seconds: f32, mut pose_at: impl FnMut(f32) -> ScriptResult<CameraMatrix>) -> ScriptResult<()> {
+            let started = Instant::now();
+            loop {
+                let progress = if seconds > 0.0 { (started.elapsed().as_secs_f32() / seconds).min(1.0) } else { 1.0 };
+                self.write(&pose_at(progress)?)?;
+                if progress >= 1.0 {
+                    return self.sleep(SETTLE);
+                }
+                self.sleep(TICK)?;
+            }
+        }
+        
+        fn skater(&self) -> ScriptResult<CameraPosition> {
+            self.state().skater.ok_or_else(|| "The skater position isn't known, it needs a [skater] chain".into())
+        }
+        
+        // Circles `pivot` by `degrees` in `seconds` at the camera's current distance and height, facing it.
+        // `pivot` is asked again every tick, so a moving skater is followed.
+        fn orbit(&self, degrees: f32, seconds: f32, pivot: impl Fn() -> ScriptResult<CameraPosition>) -> ScriptResult<()> {
+            let mut matrix = self.state().matrix;
+            let start = matrix.get_position();
+            let centre = pivot()?;
+            let (dx, dz) = (start.x - centre.x, start.z - centre.z);
+            let (radius, height, angle) = ((dx * dx + dz * dz).sqrt(), start.y - centre.y, dz.atan2(dx));
+            self.animate(seconds, |progress| {
+                let centre = pivot()?;
+                let angle = angle + (degrees * progress).to_radians();
+                let position = CameraPosition::new(centre.x + radius * angle.cos(), centre.y + height, centre.z + radius * angle.sin());
+                matrix.set_position(&position);
+                matrix.look_at(&centre);
+                Ok(matrix.clone())
+            })
+        }
+    }
+    
+    // Script numbers are integers or floats depending on how they're written, both are fine everywhere
+    fn number(value: &Dynamic) -> ScriptResult<f32> {
+        match (value.as_float(), value.as_int()) {
+            (Ok(value), _) => Ok(value as f32),
+            (_, Ok(value)) => Ok(value as f32),
+            _ => Err(format!("Expected a number, got {}", value.type_name()).into()),
+        }
+    }
+    
+    fn array(values: &[f32]) -> Array {
+        values.iter().map(|&value| Dynamic::from_float(value as rhai::FLOAT)).collect()
+    }
+    
+    // Smooth start and stop for fly_to
+    fn ease(t: f32) -> f32 {
+        t * t * (3.0 - 2.0 * t)
+    }
+    
+    pub(super) fn run(name: &str, source: &str, interpolation: InterpolationMode, sender: Sender<ScriptEvent>,
+                      state: Arc<Mutex<ScriptState>>, token: CancelToken) -> Result<(), String> {
+        let host = Rc::new(Host { sender, state, token: token.clone(), interpolation });
+        let mut engine = Engine::new();
+        // Loops that never wait still stop when the script is stopped
+        engine.on_progress(move |_| token.is_cancelled().then_some(Dynamic::UNIT));
+        let prefix = name.to_string();
+        engine.on_print(move |text| println!("\n📜 {}: {}", prefix, text));
+        
+        let h = Rc::clone(&host);
+        engine.register_fn("position", move || {
+            let position = h.state().matrix.get_position();
+            array(&[position.x, position.y, position.z])
+        });
+        let h = Rc::clone(&host);
+        engine.register_fn("rotation", move || {
+            let rotation = h.state().matrix.to_quaternion();
+            array(&[rotation.x, rotation.y, rotation.z, rotation.w])
+        });
+        let h = Rc::clone(&host);
+        engine.register_fn("fov", move || h.state().fov.map_or(Dynamic::UNIT, |fov| Dynamic::from_float(fov as rhai::FLOAT)));
+        let h = Rc::clone(&host);
+        engine.register_fn("skater", move || h.state().skater.map_or(Dynamic::UNIT, |skater| Dynamic::from_array(array(&[skater.x, skater.y, skater.z]))));
+        let h = Rc::clone(&host);
+        engine.register_fn("patch_active", move || h.state().patch);
+        
+        let h = Rc::clone(&host);
+        engine.register_fn("set_position", move |x: Dynamic, y: Dynamic, z: Dynamic| -> ScriptResult<()> {
+            h.send(RemoteCommand::SetPosition { x: number(&x)?, y: number(&y)?, z: number(&z)? })?;
+            h.sleep(SETTLE)
+        });
+        let h = Rc::clone(&host);
+        engine.register_fn("set_rotation", move |x: Dynamic, y: Dynamic, z: Dynamic, w: Dynamic| -> ScriptResult<()> {
+            h.send(RemoteCommand::SetRotation { rotation: [number(&x)?, number(&y)?, number(&z)?, number(&w)?] })?;
+            h.sleep(SETTLE)
+        });
+        let h = Rc::clone(&host);
+        engine.register_fn("look_at", move |x: Dynamic, y: Dynamic, z: Dynamic| -> ScriptResult<()> {
+            let mut matrix = h.state().matrix;
+            matrix.look_at(&CameraPosition::new(number(&x)?, number(&y)?, number(&z)?));
+            h.write(&matrix)?;
+            h.sleep(SETTLE)
+        });
+        let h = Rc::clone(&host);
+        engine.register_fn("set_fov", move |fov: Dynamic| -> ScriptResult<()> {
+            h.send(RemoteCommand::SetFov { fov: number(&fov)? })?;
+            h.sleep(SETTLE)
+        });
+        let h = Rc::clone(&host);
+        engine.register_fn("set_speed", move |speed: Dynamic| -> ScriptResult<()> {
+            h.send(RemoteCommand::SetSpeed { speed: number(&speed)? })
+        });
+        
+        let h = Rc::clone(&host);
+        engine.register_fn("fly_to", move |x: Dynamic, y: Dynamic, z: Dynamic, seconds: Dynamic| -> ScriptResult<()> {
+            let target = CameraPosition::new(number(&x)?, number(&y)?, number(&z)?);
+            let mut matrix = h.state().matrix;
+            let start = matrix.get_position();
+            h.animate(number(&seconds)?, |progress| {
+                let t = ease(progress);
+                matrix.set_position(&CameraPosition::new(
+                    start.x + (target.x - start.x) * t,
+                    start.y + (target.y - start.y) * t,
+                    start.z + (target.z - start.z) * t,
+                ));
+                Ok(matrix.clone())
+            })
+        });
+        let h = Rc::clone(&host);
+        engine.register_fn("orbit", move |x: Dynamic, y: Dynamic, z: Dynamic, degrees: Dynamic, seconds: Dynamic| -> ScriptResult<()> {
+            let pivot = CameraPosition::new(number(&x)?, number(&y)?, number(&z)?);
+            h.orbit(number(&degrees)?, number(&seconds)?, || Ok(pivot.clone()))
+        });
+        let h = Rc::clone(&host);
+        engine.register_fn("orbit_skater", move |degrees: Dynamic, seconds: Dynamic| -> ScriptResult<()> {
+            h.orbit(number(&degrees)?, number(&seconds)?, || h.skater())
+        });
+        let h = Rc::clone(&host);
+        engine.register_fn("play_path", move |file: &str| -> ScriptResult<()> {
+            let format = PathFormat::from_extension(file).ok_or_else(|| format!("Can't tell the format of '{}' from its extension", file))?;
+            let path = read_path(file, format, PATH_FPS)?;
+            let duration = path.duration();
+            let mut matrix = h.state().matrix;
+            h.animate(duration, |progress| {
+                path.apply(progress * duration, h.interpolation, &mut matrix);
+                Ok(matrix.clone())
+            })
+        });
+        
+        let h = Rc::clone(&host);
+        engine.register_fn("action", move |name: &str| -> ScriptResult<()> {
+            let action: Action = serde_json::from_value(serde_json::Value::String(name.to_string()))
+                .map_err(|_| format!("Unknown action '{}'", name))?;
+            h.send(RemoteCommand::Action { action })?;
+            h.sleep(SETTLE)
+        });
+        let h = Rc::clone(&host);
+        engine.register_fn("patch", move |on: bool| -> ScriptResult<()> {
+            if h.state().patch != on {
+                h.send(RemoteCommand::Action { action: Action::PatchToggle })?;
+            }
+            h.sleep(SETTLE)
+        });
+        let h = Rc::clone(&host);
+        engine.register_fn("sleep", move |seconds: Dynamic| -> ScriptResult<()> {
+            let seconds = number(&seconds)?;
+            h.sleep(Duration::try_from_secs_f32(seconds.max(0.0)).map_err(|_| format!("Can't sleep for {} seconds", seconds))?)
+        });
+        let h = Rc::clone(&host);
+        engine.register_fn("wait_key", move |name: &str| -> ScriptResult<()> {
+            let key = Key(parse_key_name(name).ok_or_else(|| format!("Unknown key name '{}'", name))?);
+            // Down and up again, so the next wait_key doesn't see the same press
+            while !key.is_pressed() {
+                h.sleep(TICK)?;
+            }
+            while key.is_pressed() {
+                h.sleep(TICK)?;
+            }
+            Ok(())
+        });
+        
+        engine.run(source).map_err(|e| e.to_string())
+    }
+}
@@ -0,0 +1,79 @@
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use crate::camera::{CameraMatrix, Quaternion};
+
+// Each octave adds detail at twice the frequency and half the amplitude of the one before
+const OCTAVES: u32 = 3;
+
+// Handheld camera shake for documentary-style footage: smooth Perlin noise on every axis, slow sway with a
+// little tremble on top and no frame-to-frame noise, unlike the VHS jitter. Like the jitter, the offset is
+// added when the matrix is written, and the controller hands out the clean matrix when it is read back.
+pub struct Shake {
+    position: f32, // Largest offset along the camera axes, in world units
+    rotation: f32, // Largest pitch/yaw/roll offset, in radians
+    frequency: f32, // Sways per second of the slowest octave
+    seed: u32,
+    started: Instant,
+}
+
+impl Shake {
+    pub fn new(position: f32, rotation_degrees: f32, frequency: f32) -> Self {
+        let seed = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |time| time.subsec_nanos());
+        
+        Self {
+            position: position.max(0.0),
+            rotation: rotation_degrees.max(0.0).to_radians(),
+            frequency: frequency.max(0.01),
+            seed,
+            started: Instant::now(),
+        }
+    }
+    
+    // Offsets as (right, up, forward, pitch, yaw, roll), each in -1..1
+    fn offsets(&self) -> [f32; 6] {
+        let time = self.started.elapsed().as_secs_f64() * self.frequency as f64;
+        std::array::from_fn(|axis| {
+            let seed = self.seed.wrapping_add(axis as u32 * 0x6C8E_9CF5);
+            let (mut sum, mut amplitude, mut total) = (0.0, 1.0, 0.0);
+            for octave in 0..OCTAVES {
+                sum += perlin(seed.wrapping_add(octave), time * (1 << octave) as f64) * amplitude;
+                total += amplitude;
+                amplitude *= 0.5;
+            }
+            sum / total
+        })
+    }
+    
+    // Returns `matrix` with the shake for this moment applied, the controller keeps the clean one to hand back on reads
+    pub fn apply(&self, matrix: &CameraMatrix) -> CameraMatrix {
+        let [right, up, forward, pitch, yaw, roll] = self.offsets();
+        
+        let mut shaken = matrix.clone();
+        let sway = Quaternion::from_euler_xyz(pitch * self.rotation, yaw * self.rotation, roll * self.rotation);
+        shaken.set_rotation(&matrix.to_quaternion().multiply(&sway));
+        shaken.apply_translation(right * self.position, up * self.position, forward * self.position);
+        
+        shaken
+    }
+}
+
+// Gradient in -1..1 for a whole-numbered point, hashed so every seed gives a different curve
+fn gradient(seed: u32, point: i64) -> f32 {
+    let mut hash = seed ^ (point as u32).wrapping_mul(0x9E37_79B9) ^ ((point >> 32) as u32);
+    hash ^= hash >> 16;
+    hash = hash.wrapping_mul(0x85EB_CA6B);
+    hash ^= hash >> 13;
+    hash = hash.wrapping_mul(0xC2B2_AE35);
+    hash ^= hash >> 16;
+    hash as f32 / u32::MAX as f32 * 2.0 - 1.0
+}
+
+// One-dimensional Perlin noise, smooth and in -1..1
+fn perlin(seed: u32, x: f64) -> f32 {
+    let cell = x.floor();
+    let t = (x - cell) as f32;
+    let fade = t * t * t * (t * (t * 6.0 - 15.0) + 10.0);
+    let a = gradient(seed, cell as i64) * t;
+    let b = gradient(seed, cell as i64 + 1) * (t - 1.0);
+    (a + (b - a) * fade) * 2.0
+}
@@ -0,0 +1,127 @@
+// Ctrl+C, Ctrl+Break and closing the console window stop the free cam cleanly instead of killing it,
+// so the camera patch doesn't stay in the game until it restarts. Worker threads hold a CancelToken
+// that fires on shutdown or when their owner is dropped, so each one can finish up and be joined.
+// The handler is installed once at startup and only steps in while a Session is running, so Ctrl+C
+// still ends one-off commands and prompts straight away.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+use crossbeam_channel::{Receiver, Sender, TryRecvError};
+
+use winapi::shared::minwindef::{BOOL, DWORD, FALSE, TRUE};
+use winapi::um::consoleapi::SetConsoleCtrlHandler;
+use winapi::um::wincon::{CTRL_C_EVENT, CTRL_BREAK_EVENT};
+
+static RUNNING: AtomicBool = AtomicBool::new(false);
+static REQUESTED: AtomicBool = AtomicBool::new(false);
+static FINISHED: AtomicBool = AtomicBool::new(false);
+
+// Nothing is ever sent, dropping the sender disconnects every receiver at once
+struct Broadcast {
+    sender: Option<Sender<()>>,
+    receiver: Receiver<()>,
+}
+
+impl Broadcast {
+    fn new() -> Self {
+        let (sender, receiver) = crossbeam_channel::bounded(0);
+        Self { sender: Some(sender), receiver }
+    }
+}
+
+static BROADCAST: LazyLock<Mutex<Broadcast>> = LazyLock::new(|| Mutex::new(Broadcast::new()));
+
+// Windows kills the process about five seconds after a close, logoff or shutdown event
+const CLEANUP_TIMEOUT: Duration = Duration::from_millis(4500);
+
+pub fn install() {
+    unsafe {
+        SetConsoleCtrlHandler(Some(on_console_event), TRUE);
+    }
+}
+
+// Whether the main loop should stop and restore the game
+pub fn requested() -> bool {
+    REQUESTED.load(Ordering::SeqCst)
+}
+
+// Stops the main loop and wakes every worker thread waiting on a CancelToken
+pub fn request() {
+    FINISHED.store(false, Ordering::SeqCst);
+    REQUESTED.store(true, Ordering::SeqCst);
+    if let Ok(mut broadcast) = BROADCAST.lock() {
+        broadcast.sender.take();
+    }
+}
+
+// Held by a mode that restores the game when it stops. Declare it before anything that restores on drop:
+// it goes last, lets a pending close event end the process and clears the request for the next session.
+pub struct Session(());
+
+pub fn session() -> Session {
+    RUNNING.store(true, Ordering::SeqCst);
+    Session(())
+}
+
+impl Drop for Session {
+    fn drop(&mut self) {
+        RUNNING.store(false, Ordering::SeqCst);
+        REQUESTED.store(false, Ordering::SeqCst);
+        if let Ok(mut broadcast) = BROADCAST.lock() {
+            *broadcast = Broadcast::new();
+        }
+        FINISHED.store(true, Ordering::SeqCst);
+    }
+}
+
+// Runs on its own thread. Outside a session the default handler ends the process. Ctrl+C and Ctrl+Break
+// leave the rest to the main loop, for the other events the process ends as soon as this returns, so it
+// waits for the session to finish cleaning up.
+unsafe extern "system" fn on_console_event(event: DWORD) -> BOOL {
+    if !RUNNING.load(Ordering::SeqCst) {
+        return FALSE;
+    }
+    request();
+    if event != CTRL_C_EVENT && event != CTRL_BREAK_EVENT {
+        let started = Instant::now();
+        while !FINISHED.load(Ordering::SeqCst) && started.elapsed() < CLEANUP_TIMEOUT {
+            std::thread::sleep(Duration::from_millis(10));
+        }
+    }
+    TRUE
+}
+
+// Kept by whoever owns a worker thread, cancels the thread's token when dropped
+pub struct Cancel {
+    _sender: Sender<()>,
+}
+
+#[derive(Clone)]
+pub struct CancelToken {
+    owner: Receiver<()>,
+    shutdown: Receiver<()>,
+}
+
+// A token for a new worker thread and the handle that cancels it
+pub fn token() -> (Cancel, CancelToken) {
+    let (sender, receiver) = crossbeam_channel::bounded(0);
+    let shutdown = BROADCAST.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).receiver.clone();
+    (Cancel { _sender: sender }, CancelToken { owner: receiver, shutdown })
+}
+
+impl CancelToken {
+    pub fn is_cancelled(&self) -> bool {
+        self.owner.try_recv() == Err(TryRecvError::Disconnected) || self.shutdown.try_recv() == Err(TryRecvError::Disconnected)
+    }
+    
+    // Sleeps for up to `timeout`, returns early with true once cancelled
+    pub fn wait(&self, timeout: Duration) -> bool {
+        crossbeam_channel::select! {
+            recv(self.owner) -> _ => true,
+            recv(self.shutdown) -> _ => true,
+            default(timeout) => false,
+        }
+    }
+}
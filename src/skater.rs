@@ -0,0 +1,158 @@
+// What the game knows about the skater beyond where they are: how fast they go, whether they're in the air, how
+// their balance meters stand and how the run is scoring. Each value comes from its own chain in the [stats]
+// table of offsets.toml and is left out while it can't be read, e.g. in the menus. Streamers use it for
+// telemetry overlays, with or without the free camera.
+
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+use crate::offsets::StatOffsets;
+use crate::process::ProcessHandle;
+
+// Shortest time between rewrites of the stats file. OBS text sources only read it about once a second.
+const STATS_FILE_INTERVAL: Duration = Duration::from_millis(100);
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct GameState {
+    pub speed: Option<f32>,          // As the game keeps it
+    pub airborne: Option<bool>,
+    pub air_time: Option<f32>,       // Seconds in the air so far, or of the last jump once landed
+    pub grind_balance: Option<f32>,  // Balance meters as the game keeps them, 0 is centred
+    pub manual_balance: Option<f32>,
+    pub score: Option<i32>,
+    pub multiplier: Option<i32>,     // The running combo's multiplier
+    pub special: Option<f32>,        // Special meter as the game keeps it
+}
+
+impl GameState {
+    // The values that could be read, for status lines. None when there are none.
+    pub fn summary(&self) -> Option<String> {
+        let mut parts = Vec::new();
+        if let Some(speed) = self.speed {
+            parts.push(format!("Skater {:.0}", speed));
+        }
+        match (self.airborne, self.air_time) {
+            (Some(true), Some(time)) => parts.push(format!("Air {:.1}s", time)),
+            (_, Some(time)) => parts.push(format!("Last air {:.1}s", time)),
+            (Some(false), None) => parts.push("Grounded".to_string()),
+            _ => {}
+        }
+        if let Some(balance) = self.grind_balance {
+            parts.push(format!("Grind {:+.2}", balance));
+        }
+        if let Some(balance) = self.manual_balance {
+            parts.push(format!("Manual {:+.2}", balance));
+        }
+        if let Some(score) = self.score {
+            parts.push(format!("Score {}", score));
+        }
+        if let Some(multiplier) = self.multiplier {
+            parts.push(format!("x{}", multiplier));
+        }
+        if let Some(special) = self.special {
+            parts.push(format!("Special {:.2}", special));
+        }
+        (!parts.is_empty()).then(|| parts.join("  "))
+    }
+}
+
+pub struct GameStateReader {
+    offsets: StatOffsets,
+    air_since: Option<Instant>, // When the current jump started
+    air_time: Option<f32>,      // Length of the current or last jump
+}
+
+impl GameStateReader {
+    pub fn new(offsets: StatOffsets) -> Self {
+        Self { offsets, air_since: None, air_time: None }
+    }
+    
+    // No [stats] chains declared, nothing to read
+    pub fn is_empty(&self) -> bool {
+        let StatOffsets { speed, air, grind_balance, manual_balance, score, multiplier, special } = &self.offsets;
+        [speed, air, grind_balance, manual_balance, score, multiplier, special].iter().all(|chain| chain.is_none())
+    }
+    
+    // Air time is timed between reads, so it is only as precise as they are frequent
+    pub fn read(&mut self, process: &ProcessHandle, base_addr: usize) -> GameState {
+        let read_f32 = |chain: &Option<_>| chain.as_ref().and_then(|chain| process.get_stat(base_addr, chain).ok());
+        let read_i32 = |chain: &Option<_>| chain.as_ref().and_then(|chain| process.get_state_value(base_addr, chain).ok());
+        let airborne = read_i32(&self.offsets.air).map(|value| value != 0);
+        
+        match airborne {
+            Some(true) => {
+                let since = *self.air_since.get_or_insert_with(Instant::now);
+                self.air_time = Some(since.elapsed().as_secs_f32());
+            }
+            Some(false) => self.air_since = None,
+            None => {
+                self.air_since = None;
+                self.air_time = None;
+            }
+        }
+        
+        GameState {
+            speed: read_f32(&self.offsets.speed),
+            airborne,
+            air_time: self.air_time,
+            grind_balance: read_f32(&self.offsets.grind_balance),
+            manual_balance: read_f32(&self.offsets.manual_balance),
+            score: read_i32(&self.offsets.score),
+            multiplier: read_i32(&self.offsets.multiplier),
+            special: read_f32(&self.offsets.special),
+        }
+    }
+}
+
+// A file with the latest stats for OBS text and browser sources to poll: the status line summary as plain text,
+// or the whole state as JSON when the name ends in .json
+pub struct StatsFile {
+    path: String,
+    json: bool,
+    written: Option<GameState>, // What the file holds
+    last_write: Option<Instant>,
+    failed: bool, // The last write failed, so the next failure isn't reported again
+}
+
+impl StatsFile {
+    pub fn new(path: &str) -> Self {
+        let json = path.to_ascii_lowercase().ends_with(".json");
+        Self { path: path.to_string(), json, written: None, last_write: None, failed: false }
+    }
+    
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+    
+    // Rewrites the file when the stats changed. The new contents are written next to it and renamed over it, so
+    // a source polling it never reads half a file. Returns what went wrong, once until a write works again.
+    pub fn write(&mut self, state: &GameState) -> Option<String> {
+        if self.written.as_ref() == Some(state) || self.last_write.is_some_and(|at| at.elapsed() < STATS_FILE_INTERVAL) {
+            return None;
+        }
+        self.last_write = Some(Instant::now());
+        
+        let contents = if self.json {
+            serde_json::to_string(state).unwrap_or_default()
+        } else {
+            state.summary().unwrap_or_default()
+        };
+        let temporary = format!("{}.tmp", self.path);
+        let result = std::fs::write(&temporary, contents)
+            .and_then(|()| std::fs::rename(&temporary, &self.path))
+            .map_err(|e| format!("Failed to write the stats file '{}': {}", self.path, e));
+        match result {
+            Ok(()) => {
+                self.written = Some(state.clone());
+                self.failed = false;
+                None
+            }
+            Err(e) => {
+                let first = !self.failed;
+                self.failed = true;
+                first.then_some(e)
+            }
+        }
+    }
+}
@@ -0,0 +1,111 @@
+// Read-only telemetry for stats overlays, and for anyone who'd rather the tool didn't change the game at all.
+// The camera and the skater are read at a fixed rate and every reading is handed out as JSON. Nothing in here
+// writes memory or applies a patch.
+
+use std::time::Instant;
+
+use serde::Serialize;
+
+use crate::camera::CameraPosition;
+use crate::fov::{preset_from_game_fov, screen_aspect};
+use crate::offsets::{FovOffsets, GameOffsets, PointerChain};
+use crate::process::ProcessHandle;
+use crate::skater::{GameState, GameStateReader};
+use crate::telemetry::unix_millis;
+
+// Where something is, which way it faces and how fast it moves
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct BodyState {
+    pub position: [f32; 3],
+    pub rotation: Option<[f32; 4]>, // Quaternion as x, y, z, w, for the skater only with a [skater_matrix] chain
+    pub speed: Option<f32>,         // World units per second since the previous reading
+}
+
+// One reading, printed as a JSON line and streamed to WebSocket clients
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct SpectateFrame {
+    pub time_ms: u128, // Unix milliseconds
+    pub camera: BodyState,
+    pub fov: Option<f32>,          // Horizontal degrees, with FOV offsets
+    pub skater: Option<BodyState>, // With a [skater] chain that resolves
+    pub stats: Option<GameState>,  // With [stats] chains
+}
+
+pub struct Spectator {
+    skater_chain: Option<PointerChain>,
+    skater_matrix_chain: Option<PointerChain>,
+    fov_offsets: Option<FovOffsets>,
+    screen_aspect: f32,
+    stats: GameStateReader,
+    previous: Option<(Instant, CameraPosition, Option<CameraPosition>)>, // The last reading, for speeds
+}
+
+// Units per second between two readings `seconds` apart
+fn speed(from: &CameraPosition, to: &CameraPosition, seconds: f32) -> Option<f32> {
+    let distance = ((to.x - from.x).powi(2) + (to.y - from.y).powi(2) + (to.z - from.z).powi(2)).sqrt();
+    (seconds > 0.0).then(|| distance / seconds)
+}
+
+impl Spectator {
+    pub fn new(offsets: &GameOffsets) -> Self {
+        let fov_offsets = offsets.fov.clone();
+        let screen_aspect = fov_offsets.as_ref().and_then(|fov| fov.screen_aspect).unwrap_or_else(screen_aspect);
+        Self {
+            skater_chain: offsets.skater.clone(),
+            skater_matrix_chain: offsets.skater_matrix.clone(),
+            fov_offsets,
+            screen_aspect,
+            stats: GameStateReader::new(offsets.stats.clone()),
+            previous: None,
+        }
+    }
+    
+    // Fails only when the camera can't be read, the FOV and the skater are left out when theirs can't
+    pub fn read(&mut self, process: &ProcessHandle, base_addr: usize) -> Result<SpectateFrame, String> {
+        let now = Instant::now();
+        let matrix = process.get_camera_matrix(base_addr)?;
+        let camera_position = matrix.get_position();
+        let skater_position = self.skater_chain.as_ref().and_then(|chain| process.get_skater_position(base_addr, chain).ok());
+        let skater_rotation = self.skater_matrix_chain.as_ref()
+            .and_then(|chain| process.get_skater_matrix(base_addr, chain).ok())
+            .map(|matrix| matrix.to_quaternion());
+        let fov = self.fov_offsets.as_ref().and_then(|fov| {
+            let value = process.get_fov(base_addr, &fov.chain).ok()?;
+            let game_aspect = fov.game_aspect.unwrap_or(self.screen_aspect);
+            Some(preset_from_game_fov(value, self.screen_aspect, game_aspect, fov.axis, fov.radians))
+        });
+        
+        let stats = (!self.stats.is_empty()).then(|| self.stats.read(process, base_addr));
+        
+        let (camera_speed, skater_speed) = match &self.previous {
+            Some((then, camera, skater)) => {
+                let seconds = now.duration_since(*then).as_secs_f32();
+                let skater_speed = match (skater, &skater_position) {
+                    (Some(from), Some(to)) => speed(from, to, seconds),
+                    _ => None,
+                };
+                (speed(camera, &camera_position, seconds), skater_speed)
+            }
+            None => (None, None),
+        };
+        
+        let rotation = matrix.to_quaternion();
+        let frame = SpectateFrame {
+            time_ms: unix_millis(),
+            camera: BodyState {
+                position: [camera_position.x, camera_position.y, camera_position.z],
+                rotation: Some([rotation.x, rotation.y, rotation.z, rotation.w]),
+                speed: camera_speed,
+            },
+            fov,
+            skater: skater_position.as_ref().map(|position| BodyState {
+                position: [position.x, position.y, position.z],
+                rotation: skater_rotation.map(|rotation| [rotation.x, rotation.y, rotation.z, rotation.w]),
+                speed: skater_speed,
+            }),
+            stats,
+        };
+        self.previous = Some((now, camera_position, skater_position));
+        Ok(frame)
+    }
+}
@@ -0,0 +1,99 @@
+// Session summary printed when the tool lets go of the game and appended to a CSV, handy when
+// troubleshooting a long session and for seeing how far the camera went
+
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::time::Instant;
+
+use crate::camera::CameraPosition;
+use crate::telemetry::{format_timestamp, format_utc_datetime, unix_millis};
+
+// Moves longer than this in one tick are teleports, playback starts or level loads, not flying
+const MAX_TICK_DISTANCE: f32 = 1000.0;
+
+const CSV_HEADER: &str = "ended,duration_s,distance,patch_toggles,screenshots,paths_played,errors";
+
+pub struct SessionStats {
+    started: Instant,
+    last_position: Option<CameraPosition>,
+    distance: f32,
+    patch_toggles: u32,
+    screenshots: u32,
+    paths_played: u32,
+    errors: u32,
+}
+
+impl Default for SessionStats {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SessionStats {
+    pub fn new() -> Self {
+        Self {
+            started: Instant::now(),
+            last_position: None,
+            distance: 0.0,
+            patch_toggles: 0,
+            screenshots: 0,
+            paths_played: 0,
+            errors: 0,
+        }
+    }
+    
+    // Called with the camera position every tick
+    pub fn track(&mut self, position: &CameraPosition) {
+        if let Some(last) = &self.last_position {
+            let (dx, dy, dz) = (position.x - last.x, position.y - last.y, position.z - last.z);
+            let step = (dx * dx + dy * dy + dz * dz).sqrt();
+            if step <= MAX_TICK_DISTANCE {
+                self.distance += step;
+            }
+        }
+        self.last_position = Some(position.clone());
+    }
+    
+    pub fn patch_toggled(&mut self) {
+        self.patch_toggles += 1;
+    }
+    
+    pub fn screenshot_taken(&mut self) {
+        self.screenshots += 1;
+    }
+    
+    pub fn path_played(&mut self) {
+        self.paths_played += 1;
+    }
+    
+    pub fn error(&mut self) {
+        self.errors += 1;
+    }
+    
+    pub fn print(&self) {
+        println!("\n📊 Session summary:");
+        println!("   Duration: {}", format_timestamp(self.started.elapsed().as_millis()));
+        println!("   Distance flown: {:.0} units", self.distance);
+        println!("   Patch toggles: {}", self.patch_toggles);
+        println!("   Screenshots: {}", self.screenshots);
+        println!("   Paths played: {}", self.paths_played);
+        println!("   Errors: {}", self.errors);
+    }
+    
+    // Adds one row per session, the header is written when the file is new
+    pub fn append_to(&self, path: &str) -> Result<(), String> {
+        let is_new = !std::path::Path::new(path).exists();
+        let mut file = OpenOptions::new().create(true).append(true).open(path)
+            .map_err(|e| format!("Failed to open '{}': {}", path, e))?;
+        
+        let mut rows = String::new();
+        if is_new {
+            rows.push_str(CSV_HEADER);
+            rows.push('\n');
+        }
+        rows.push_str(&format!("{},{:.1},{:.1},{},{},{},{}\n", format_utc_datetime(unix_millis()),
+                               self.started.elapsed().as_secs_f32(), self.distance, self.patch_toggles,
+                               self.screenshots, self.paths_played, self.errors));
+        file.write_all(rows.as_bytes()).map_err(|e| format!("Failed to write '{}': {}", path, e))
+    }
+}
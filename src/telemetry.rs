@@ -0,0 +1,192 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use crate::camera::{CameraMatrix, CameraPose, CameraPosition};
+use crate::fov::focal_length_mm;
+
+// Emit a sync marker every 60 ticks (~1 second at the 16ms poll interval)
+const DEFAULT_SYNC_INTERVAL: u64 = 60;
+
+pub fn unix_millis() -> u128 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or(0)
+}
+
+// Formats milliseconds as HH:MM:SS.mmm, the layout video editors and OBS marker lists use
+pub fn format_timestamp(millis: u128) -> String {
+    let hours = millis / 3_600_000;
+    let minutes = (millis / 60_000) % 60;
+    let seconds = (millis / 1000) % 60;
+    let ms = millis % 1000;
+    format!("{:02}:{:02}:{:02}.{:03}", hours, minutes, seconds, ms)
+}
+
+// Formats Unix milliseconds as a UTC ISO 8601 date and time (2024-05-01T18:30:00Z)
+pub fn format_utc_datetime(unix_ms: u128) -> String {
+    let seconds = (unix_ms / 1000) as i64;
+    let days = seconds.div_euclid(86_400);
+    let time_of_day = seconds.rem_euclid(86_400);
+    
+    // Civil date from days since 1970-01-01 (Howard Hinnant's algorithm)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    
+    format!("{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z", year, month, day,
+            time_of_day / 3600, (time_of_day / 60) % 60, time_of_day % 60)
+}
+
+pub fn csv_header() -> String {
+    let mut header = String::from("kind,tick,elapsed_ms,wall_ms,x,y,z");
+    for i in 0..16 {
+        header.push_str(&format!(",m{}", i));
+    }
+    header.push_str(",hfov,vfov,focal_mm");
+    header
+}
+
+pub struct TelemetryRecorder {
+    log: BufWriter<File>,
+    sync_file: BufWriter<File>,
+    chan_file: Option<BufWriter<File>>,
+    log_path: String,
+    sync_path: String,
+    chan_path: String,
+    started: Instant,
+    tick: u64,
+    sync_interval: u64,
+}
+
+impl TelemetryRecorder {
+    pub fn start(prefix: &str) -> Result<Self, String> {
+        let stamp = unix_millis();
+        let log_path = format!("{}_{}.csv", prefix, stamp);
+        let sync_path = format!("{}_{}_sync.txt", prefix, stamp);
+        let chan_path = format!("{}_{}.chan", prefix, stamp);
+        
+        let log_file = File::create(&log_path)
+            .map_err(|e| format!("Failed to create telemetry log '{}': {}", log_path, e))?;
+        let sync_file = File::create(&sync_path)
+            .map_err(|e| format!("Failed to create sync file '{}': {}", sync_path, e))?;
+        
+        let mut recorder = Self {
+            log: BufWriter::new(log_file),
+            sync_file: BufWriter::new(sync_file),
+            chan_file: None,
+            log_path,
+            sync_path,
+            chan_path,
+            started: Instant::now(),
+            tick: 0,
+            sync_interval: DEFAULT_SYNC_INTERVAL,
+        };
+        
+        writeln!(recorder.log, "{}", csv_header()).map_err(|e| format!("Failed to write telemetry header: {}", e))?;
+        
+        // Marker at tick 0 so footage can be aligned from the very first frame
+        recorder.write_sync_marker()?;
+        Ok(recorder)
+    }
+    
+    pub fn log_path(&self) -> &str {
+        &self.log_path
+    }
+    
+    pub fn sync_path(&self) -> &str {
+        &self.sync_path
+    }
+    
+    // Only written once a full matrix has been recorded (not in position-only mode)
+    pub fn chan_path(&self) -> Option<&str> {
+        self.chan_file.as_ref().map(|_| self.chan_path.as_str())
+    }
+    
+    // `fov` is the (horizontal, vertical) FOV in degrees when FOV control is configured
+    pub fn record(&mut self, position: &CameraPosition, matrix: Option<&CameraMatrix>, fov: Option<(f32, f32)>) -> Result<(), String> {
+        self.tick += 1;
+        
+        let elapsed = self.started.elapsed().as_millis();
+        let mut row = format!(
+            "pose,{},{},{},{:.6},{:.6},{:.6}",
+            self.tick, elapsed, unix_millis(), position.x, position.y, position.z
+        );
+        // Position-only mode has no matrix, leave those columns empty
+        for i in 0..16 {
+            match matrix {
+                Some(m) => row.push_str(&format!(",{:.6}", m.data[i])),
+                None => row.push(','),
+            }
+        }
+        match fov {
+            Some((hfov, vfov)) => row.push_str(&format!(",{:.4},{:.4},{:.4}", hfov, vfov, focal_length_mm(hfov))),
+            None => row.push_str(",,,"),
+        }
+        writeln!(self.log, "{}", row).map_err(|e| format!("Failed to write telemetry: {}", e))?;
+        
+        if let Some(matrix) = matrix {
+            self.write_chan_frame(&matrix.pose(), fov.map(|(_, vfov)| vfov))?;
+        }
+        
+        if self.tick.is_multiple_of(self.sync_interval) {
+            self.write_sync_marker()?;
+        }
+        
+        Ok(())
+    }
+    
+    // Nuke .chan line: frame tx ty tz rx ry rz [vfov], rotations in degrees for XYZ rotation order
+    fn write_chan_frame(&mut self, pose: &CameraPose, vfov: Option<f32>) -> Result<(), String> {
+        if self.chan_file.is_none() {
+            let file = File::create(&self.chan_path)
+                .map_err(|e| format!("Failed to create chan file '{}': {}", self.chan_path, e))?;
+            self.chan_file = Some(BufWriter::new(file));
+        }
+        
+        let position = &pose.position;
+        let (rx, ry, rz) = pose.to_euler_xyz();
+        let mut line = format!(
+            "{} {:.6} {:.6} {:.6} {:.6} {:.6} {:.6}",
+            self.tick, position.x, position.y, position.z, rx.to_degrees(), ry.to_degrees(), rz.to_degrees()
+        );
+        if let Some(vfov) = vfov {
+            line.push_str(&format!(" {:.6}", vfov));
+        }
+        
+        if let Some(chan) = &mut self.chan_file {
+            writeln!(chan, "{}", line).map_err(|e| format!("Failed to write chan frame: {}", e))?;
+        }
+        Ok(())
+    }
+    
+    fn write_sync_marker(&mut self) -> Result<(), String> {
+        let elapsed = self.started.elapsed().as_millis();
+        let wall = unix_millis();
+        
+        let mut row = format!("sync,{},{},{},,,", self.tick, elapsed, wall);
+        row.push_str(&",".repeat(19));
+        writeln!(self.log, "{}", row).map_err(|e| format!("Failed to write sync marker: {}", e))?;
+        
+        writeln!(self.sync_file, "{} SYNC tick={} wall_ms={}", format_timestamp(elapsed), self.tick, wall)
+            .map_err(|e| format!("Failed to write sync marker: {}", e))?;
+        Ok(())
+    }
+    
+    pub fn finish(mut self) -> Result<(), String> {
+        self.write_sync_marker()?;
+        self.log.flush().map_err(|e| format!("Failed to flush telemetry log: {}", e))?;
+        self.sync_file.flush().map_err(|e| format!("Failed to flush sync file: {}", e))?;
+        if let Some(chan) = &mut self.chan_file {
+            chan.flush().map_err(|e| format!("Failed to flush chan file: {}", e))?;
+        }
+        Ok(())
+    }
+}
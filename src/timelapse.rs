@@ -0,0 +1,84 @@
+use std::time::{Duration, Instant};
+
+use winapi::shared::windef::HWND;
+
+use crate::controller::CameraController;
+use crate::interpolation::InterpolationMode;
+use crate::path::CameraPath;
+use crate::process::ProcessHandle;
+use crate::screenshot::{check_supported, file_timestamp, save_screenshot};
+
+// Steps the camera along a path and saves a screenshot at every step. Capture time doesn't matter,
+// each frame is posed, given time to render and only then saved.
+pub struct Timelapse {
+    window: HWND,
+    directory: String,
+    times: Vec<f32>, // Path time of every frame
+    next: usize,
+    settle: Duration,
+    posed_at: Option<Instant>, // When the pose for the next frame was written
+}
+
+impl Timelapse {
+    // One frame every `interval_ms` of path time, or one per keyframe when it is 0
+    pub fn new(window: HWND, camera_path: &CameraPath, interval_ms: u64, settle_ms: u64, screenshot_dir: &str) -> Result<Self, String> {
+        check_supported()?;
+        let times: Vec<f32> = if interval_ms == 0 {
+            camera_path.keyframes.iter().map(|keyframe| keyframe.time).collect()
+        } else {
+            let interval = interval_ms as f32 / 1000.0;
+            let count = (camera_path.duration() / interval).floor() as usize + 1;
+            (0..count).map(|frame| frame as f32 * interval).collect()
+        };
+        
+        let directory = format!("{}/timelapse_{}", screenshot_dir.trim_end_matches(['/', '\\']), file_timestamp());
+        std::fs::create_dir_all(&directory).map_err(|e| format!("Failed to create '{}': {}", directory, e))?;
+        
+        Ok(Self {
+            window,
+            directory,
+            times,
+            next: 0,
+            settle: Duration::from_millis(settle_ms),
+            posed_at: None,
+        })
+    }
+    
+    pub fn frame_count(&self) -> usize {
+        self.times.len()
+    }
+    
+    pub fn directory(&self) -> &str {
+        &self.directory
+    }
+    
+    // Poses or captures the next frame, returns whether the sequence is done
+    pub fn tick(&mut self, process: &ProcessHandle, base_addr: usize, controller: &mut CameraController,
+                camera_path: &CameraPath, mode: InterpolationMode) -> Result<bool, String> {
+        let Some(&time) = self.times.get(self.next) else {
+            return Ok(true);
+        };
+        
+        match self.posed_at {
+            None => {
+                let mut matrix = controller.read_camera_matrix(process, base_addr)?;
+                camera_path.apply(time, mode, &mut matrix);
+                controller.write_camera_matrix(process, base_addr, &matrix)?;
+                self.posed_at = Some(Instant::now());
+            }
+            // The game needs a frame or two to render the new pose before it can be captured
+            Some(posed_at) if posed_at.elapsed() >= self.settle => {
+                let file = format!("{}/frame_{:05}.png", self.directory, self.next + 1);
+                save_screenshot(self.window, &file)?;
+                self.next += 1;
+                self.posed_at = None;
+            }
+            Some(_) => {}
+        }
+        Ok(self.next >= self.times.len())
+    }
+    
+    pub fn frames_done(&self) -> usize {
+        self.next
+    }
+}
@@ -0,0 +1,50 @@
+// Live status in the console window title, so it shows in the taskbar and Alt+Tab while the game has focus
+
+use std::time::{Duration, Instant};
+
+use winapi::um::wincon::SetConsoleTitleW;
+
+pub const APP_TITLE: &str = "THPS3 Free Cam";
+
+// Coordinates change every tick while flying, the title doesn't need to keep up with that
+const UPDATE_INTERVAL: Duration = Duration::from_millis(250);
+
+pub fn set_console_title(title: &str) {
+    let wide: Vec<u16> = title.encode_utf16().chain(std::iter::once(0)).collect();
+    unsafe {
+        SetConsoleTitleW(wide.as_ptr());
+    }
+}
+
+pub struct ConsoleTitle {
+    current: String,
+    updated_at: Option<Instant>,
+}
+
+impl Default for ConsoleTitle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ConsoleTitle {
+    pub fn new() -> Self {
+        Self { current: String::new(), updated_at: None }
+    }
+    
+    // Sets the title right away
+    pub fn set(&mut self, title: String) {
+        if title != self.current {
+            set_console_title(&title);
+            self.current = title;
+        }
+        self.updated_at = Some(Instant::now());
+    }
+    
+    // Sets the title from `status` a few times a second at most, skipping the work in between
+    pub fn update(&mut self, status: impl FnOnce() -> String) {
+        if self.updated_at.is_none_or(|at| at.elapsed() >= UPDATE_INTERVAL) {
+            self.set(status());
+        }
+    }
+}
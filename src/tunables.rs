@@ -0,0 +1,108 @@
+use crate::config::KeyBindings;
+use crate::offsets::{TunableOffsets, ValueType};
+use crate::process::ProcessHandle;
+
+pub struct TunableSet {
+    tunables: Vec<TunableOffsets>,
+    selected: usize,
+    cycle_pressed: bool,
+    held_values: Vec<Option<f32>>,
+}
+
+impl TunableSet {
+    pub fn new(tunables: Vec<TunableOffsets>) -> Self {
+        let held_values = vec![None; tunables.len()];
+        Self {
+            tunables,
+            selected: 0,
+            cycle_pressed: false,
+            held_values,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tunables.is_empty()
+    }
+
+    pub fn tunables(&self) -> &[TunableOffsets] {
+        &self.tunables
+    }
+
+    pub fn select_next(&mut self) {
+        if !self.tunables.is_empty() {
+            self.selected = (self.selected + 1) % self.tunables.len();
+        }
+    }
+
+    pub fn read(process: &ProcessHandle, base_addr: usize, tunable: &TunableOffsets) -> Result<f32, String> {
+        let addr = process.resolve_pointer_chain(base_addr + tunable.chain.base, &tunable.chain.offsets)?;
+        match tunable.value_type {
+            ValueType::F32 => process.read_memory::<f32>(addr),
+            ValueType::I32 => process.read_memory::<i32>(addr).map(|v| v as f32),
+        }
+    }
+
+    pub fn write(process: &ProcessHandle, base_addr: usize, tunable: &TunableOffsets, value: f32) -> Result<f32, String> {
+        let value = value.clamp(tunable.min, tunable.max);
+        let addr = process.resolve_pointer_chain(base_addr + tunable.chain.base, &tunable.chain.offsets)?;
+        match tunable.value_type {
+            ValueType::F32 => process.write_memory(addr, &value)?,
+            ValueType::I32 => process.write_memory(addr, &(value.round() as i32))?,
+        }
+        Ok(value)
+    }
+
+    // Tab cycles the selected tunable, numpad +/- adjust it while held, numpad * releases a held value (default bindings).
+    // Returns the name and new value whenever something changed so the caller can report it.
+    pub fn update(&mut self, process: &ProcessHandle, base_addr: usize, bindings: &KeyBindings) -> Result<Option<(String, f32)>, String> {
+        if self.tunables.is_empty() {
+            return Ok(None);
+        }
+
+        // Released before rewriting, so a value that can't be written any more can still be let go
+        if bindings.tunable_release.is_pressed() && self.held_values[self.selected].take().is_some() {
+            let tunable = &self.tunables[self.selected];
+            let value = Self::read(process, base_addr, tunable)?;
+            return Ok(Some((format!("{} (released)", tunable.name), value)));
+        }
+
+        // Keep overriding values the game would otherwise recalculate. One whose chain stopped resolving, e.g. after
+        // a level change, is let go and reported once instead of failing every tick.
+        for (tunable, held) in self.tunables.iter().zip(&mut self.held_values) {
+            if let Some(value) = *held
+                && let Err(e) = Self::write(process, base_addr, tunable, value)
+            {
+                *held = None;
+                return Err(format!("{} released: {}", tunable.name, e));
+            }
+        }
+
+        if bindings.tunable_next.is_pressed() {
+            if !self.cycle_pressed {
+                self.select_next();
+                self.cycle_pressed = true;
+                let tunable = &self.tunables[self.selected];
+                let value = Self::read(process, base_addr, tunable)?;
+                return Ok(Some((tunable.name.clone(), value)));
+            }
+        } else {
+            self.cycle_pressed = false;
+        }
+
+        let direction = if bindings.tunable_increase.is_pressed() {
+            1.0
+        } else if bindings.tunable_decrease.is_pressed() {
+            -1.0
+        } else {
+            return Ok(None);
+        };
+
+        let tunable = &self.tunables[self.selected];
+        let value = Self::read(process, base_addr, tunable)?;
+        let new_value = Self::write(process, base_addr, tunable, value + direction * tunable.step)?;
+        if tunable.hold {
+            self.held_values[self.selected] = Some(new_value);
+        }
+        Ok(Some((tunable.name.clone(), new_value)))
+    }
+}
@@ -0,0 +1,213 @@
+// Voice commands through Windows speech recognition. The recognizer runs in a PowerShell child
+// process (System.Speech) and prints each phrase it heard, which a reader thread forwards here.
+// Settings take a number after their name, like "speed 300", set the way the palette sets them.
+
+use std::io::{BufRead, BufReader};
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc::{self, Receiver};
+use std::thread::JoinHandle;
+
+use crate::action::Action;
+use crate::numbers::parse_number;
+use crate::palette::Setting;
+
+// Spoken phrase, the action it triggers and the state it asks for (None for one-shot actions)
+const PHRASES: &[(&str, Action, Option<bool>)] = &[
+    ("patch on", Action::PatchToggle, Some(true)),
+    ("patch off", Action::PatchToggle, Some(false)),
+    ("mouse on", Action::MouseToggle, Some(true)),
+    ("mouse off", Action::MouseToggle, Some(false)),
+    ("smooth on", Action::SmoothToggle, Some(true)),
+    ("smooth off", Action::SmoothToggle, Some(false)),
+    ("step mode on", Action::StepToggle, Some(true)),
+    ("step mode off", Action::StepToggle, Some(false)),
+    ("freeze", Action::FreezeToggle, Some(true)),
+    ("unfreeze", Action::FreezeToggle, Some(false)),
+    ("hide hud", Action::HudToggle, Some(true)),
+    ("show hud", Action::HudToggle, Some(false)),
+    ("orbit on", Action::OrbitToggle, Some(true)),
+    ("orbit off", Action::OrbitToggle, Some(false)),
+    ("follow on", Action::FollowToggle, Some(true)),
+    ("follow off", Action::FollowToggle, Some(false)),
+    ("frame both", Action::FramingToggle, Some(true)),
+    ("frame off", Action::FramingToggle, Some(false)),
+    ("look here", Action::LookAtToggle, Some(true)),
+    ("look free", Action::LookAtToggle, Some(false)),
+    ("jitter on", Action::JitterToggle, Some(true)),
+    ("jitter off", Action::JitterToggle, Some(false)),
+    ("shake on", Action::ShakeToggle, Some(true)),
+    ("shake off", Action::ShakeToggle, Some(false)),
+    ("show grid", Action::OverlayToggle, Some(true)),
+    ("hide grid", Action::OverlayToggle, Some(false)),
+    ("next game", Action::InstanceNext, None),
+    ("mirror on", Action::MirrorToggle, Some(true)),
+    ("mirror off", Action::MirrorToggle, Some(false)),
+    ("hold camera", Action::HoldToggle, Some(true)),
+    ("release camera", Action::HoldToggle, Some(false)),
+    ("start recording", Action::TelemetryToggle, Some(true)),
+    ("stop recording", Action::TelemetryToggle, Some(false)),
+    ("take screenshot", Action::Screenshot, None),
+    ("copy position", Action::CopyTransform, None),
+    ("teleport skater", Action::SkaterTeleport, None),
+    ("find skater", Action::CameraToSkater, None),
+    ("next path", Action::PathNext, None),
+    ("save bookmark", Action::BookmarkSave, None),
+    ("next bookmark", Action::BookmarkNext, None),
+    ("next waypoint", Action::WaypointNext, None),
+    ("previous waypoint", Action::WaypointPrevious, None),
+    ("undo move", Action::Undo, None),
+    ("show help", Action::Help, None),
+    ("add keyframe", Action::PathKeyframe, None),
+    ("record motion", Action::PathRecord, Some(true)),
+    ("stop motion", Action::PathRecord, Some(false)),
+    ("play path", Action::PathPlay, Some(true)),
+    ("stop path", Action::PathPlay, Some(false)),
+    ("pause path", Action::PlaybackPause, Some(true)),
+    ("resume path", Action::PlaybackPause, Some(false)),
+    ("save path", Action::PathSave, None),
+    ("bake path", Action::PathBake, None),
+    ("start timelapse", Action::Timelapse, Some(true)),
+    ("stop timelapse", Action::Timelapse, Some(false)),
+];
+
+// Spoken setting name, the setting, and the whole numbers that can be said after it as (first, last, step)
+const SETTINGS: &[(&str, Setting, (u32, u32, u32))] = &[
+    ("speed", Setting::Speed, (10, 2000, 10)),
+    ("field of view", Setting::Fov, (20, 140, 1)),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum VoiceCommand {
+    Action(Action, Option<bool>), // With the state it asks for, None for one-shot actions
+    Setting(Setting, f32),
+}
+
+// What a recognised phrase asks for, None for anything that isn't a command
+fn parse_command(heard: &str) -> Option<VoiceCommand> {
+    if let Some(&(_, action, state)) = PHRASES.iter().find(|&&(phrase, _, _)| phrase == heard) {
+        return Some(VoiceCommand::Action(action, state));
+    }
+    SETTINGS.iter().find_map(|&(name, setting, _)| {
+        let value = heard.strip_prefix(name)?.strip_prefix(' ')?;
+        parse_number(value).ok().map(|value| VoiceCommand::Setting(setting, value))
+    })
+}
+
+// The settings for the recognizer, `name=value,value,...` joined with '|'
+fn setting_grammar() -> String {
+    SETTINGS.iter()
+        .map(|&(name, _, (first, last, step))| {
+            let values: Vec<String> = (first..=last).step_by(step as usize).map(|value| value.to_string()).collect();
+            format!("{}={}", name, values.join(","))
+        })
+        .collect::<Vec<_>>()
+        .join("|")
+}
+
+// Only the phrases above can be recognised, which keeps false triggers from game audio down
+const RECOGNIZER_SCRIPT: &str = r#"
+$ErrorActionPreference = 'Stop'
+Add-Type -AssemblyName System.Speech
+$engine = New-Object System.Speech.Recognition.SpeechRecognitionEngine
+$engine.SetInputToDefaultAudioDevice()
+$choices = New-Object System.Speech.Recognition.Choices
+$choices.Add([string[]]($env:THPS3_VOICE_PHRASES -split ';'))
+$engine.LoadGrammar((New-Object System.Speech.Recognition.Grammar((New-Object System.Speech.Recognition.GrammarBuilder($choices)))))
+foreach ($setting in ($env:THPS3_VOICE_SETTINGS -split '\|')) {
+    $name, $values = $setting -split '='
+    $builder = New-Object System.Speech.Recognition.GrammarBuilder($name)
+    $builder.Append((New-Object System.Speech.Recognition.Choices([string[]]($values -split ','))))
+    $engine.LoadGrammar((New-Object System.Speech.Recognition.Grammar($builder)))
+}
+[Console]::Out.WriteLine('ready')
+while ($true) {
+    $result = $engine.Recognize()
+    if ($result -and $result.Confidence -ge [double]$env:THPS3_VOICE_CONFIDENCE) {
+        [Console]::Out.WriteLine($result.Text)
+    }
+}
+"#;
+
+pub struct VoiceCommands {
+    recognizer: Child,
+    receiver: Receiver<String>,
+    reader: Option<JoinHandle<()>>,
+}
+
+impl VoiceCommands {
+    pub fn start(min_confidence: f32) -> Result<Self, String> {
+        let phrases: Vec<&str> = PHRASES.iter().map(|&(phrase, _, _)| phrase).collect();
+        let mut recognizer = Command::new("powershell")
+            .args(["-NoProfile", "-NonInteractive", "-Command", RECOGNIZER_SCRIPT])
+            .env("THPS3_VOICE_PHRASES", phrases.join(";"))
+            .env("THPS3_VOICE_SETTINGS", setting_grammar())
+            .env("THPS3_VOICE_CONFIDENCE", min_confidence.to_string())
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| format!("Failed to start speech recognition: {}", e))?;
+        
+        let mut lines = BufReader::new(recognizer.stdout.take().ok_or("Speech recognition has no output")?).lines();
+        
+        // The script says 'ready' once the microphone and grammar are set up, anything else means it failed
+        if !matches!(lines.next(), Some(Ok(line)) if line.trim() == "ready") {
+            let _ = recognizer.kill();
+            return Err("Speech recognition failed to start - is a microphone connected?".to_string());
+        }
+        
+        let (sender, receiver) = mpsc::channel();
+        let reader = std::thread::spawn(move || {
+            for line in lines.map_while(Result::ok) {
+                if sender.send(line.trim().to_lowercase()).is_err() {
+                    break;
+                }
+            }
+        });
+        
+        Ok(Self { recognizer, receiver, reader: Some(reader) })
+    }
+    
+    pub fn phrases() -> impl Iterator<Item = &'static str> {
+        PHRASES.iter().map(|&(phrase, _, _)| phrase)
+    }
+    
+    // The settings that take a number, with the range that can be said
+    pub fn settings() -> impl Iterator<Item = (&'static str, u32, u32)> {
+        SETTINGS.iter().map(|&(name, _, (first, last, _))| (name, first, last))
+    }
+    
+    // Commands heard since the last call
+    pub fn poll(&mut self) -> Vec<VoiceCommand> {
+        self.receiver.try_iter().filter_map(|heard| {
+            let command = parse_command(&heard)?;
+            println!("\n🎙️ \"{}\"", heard);
+            Some(command)
+        }).collect()
+    }
+}
+
+impl Drop for VoiceCommands {
+    // Killing the recognizer closes its output, which ends the reader thread
+    fn drop(&mut self) {
+        let _ = self.recognizer.kill();
+        let _ = self.recognizer.wait();
+        if let Some(reader) = self.reader.take() {
+            let _ = reader.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn parses_phrases_and_settings() {
+        assert_eq!(parse_command("freeze"), Some(VoiceCommand::Action(Action::FreezeToggle, Some(true))));
+        assert_eq!(parse_command("speed 300"), Some(VoiceCommand::Setting(Setting::Speed, 300.0)));
+        assert_eq!(parse_command("field of view 90"), Some(VoiceCommand::Setting(Setting::Fov, 90.0)));
+        assert_eq!(parse_command("speed"), None);
+        assert_eq!(parse_command("speedy 3"), None);
+    }
+}
@@ -0,0 +1,82 @@
+// Named waypoints for guided tours of a level, read from `<level>.waypoints.json` in waypoint_dir. The file is
+// a list of spots in tour order, e.g. `[{ "name": "Bowl", "position": [x, y, z], "rotation": [x, y, z, w] }]`,
+// with the rotation as the quaternion the copy key puts on the clipboard.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use crate::camera::{CameraPose, CameraPosition, Quaternion};
+use crate::level;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Waypoint {
+    pub name: String,
+    pub position: [f32; 3],
+    pub rotation: [f32; 4], // Quaternion as x, y, z, w
+}
+
+impl Waypoint {
+    pub fn pose(&self) -> CameraPose {
+        CameraPose::new(
+            CameraPosition::new(self.position[0], self.position[1], self.position[2]),
+            Quaternion::new(self.rotation[0], self.rotation[1], self.rotation[2], self.rotation[3]),
+        )
+    }
+}
+
+pub fn waypoint_file(waypoint_dir: &str, level: &str) -> PathBuf {
+    Path::new(waypoint_dir).join(format!("{}.waypoints.json", level::file_name(level)))
+}
+
+#[derive(Default)]
+pub struct WaypointTour {
+    waypoints: Vec<Waypoint>,
+    current: Option<usize>, // The waypoint last gone to
+}
+
+impl WaypointTour {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    pub fn len(&self) -> usize {
+        self.waypoints.len()
+    }
+    
+    pub fn is_empty(&self) -> bool {
+        self.waypoints.is_empty()
+    }
+    
+    // Reads the file again, so edits made during a tour show up on the next press. The tour carries on
+    // from the same number, or from the start when the file got shorter.
+    pub fn load(&mut self, path: &Path) -> Result<(), String> {
+        let contents = fs::read_to_string(path).map_err(|e| format!("Failed to read '{}': {}", path.display(), e))?;
+        let waypoints: Vec<Waypoint> = serde_json::from_str(&contents)
+            .map_err(|e| format!("Failed to parse '{}': {}", path.display(), e))?;
+        if let Some(waypoint) = waypoints.iter().find(|waypoint| waypoint.rotation.iter().all(|&value| value == 0.0)) {
+            return Err(format!("Waypoint '{}' in '{}' has no rotation", waypoint.name, path.display()));
+        }
+        
+        self.current = self.current.filter(|&current| current < waypoints.len());
+        self.waypoints = waypoints;
+        Ok(())
+    }
+    
+    // The waypoint after (or before) the current one, wrapping around, with its number counting from 1
+    pub fn step(&mut self, forward: bool) -> Option<(usize, Waypoint)> {
+        let count = self.waypoints.len();
+        if count == 0 {
+            return None;
+        }
+        let index = match self.current {
+            Some(current) if forward => (current + 1) % count,
+            Some(current) => (current + count - 1) % count,
+            None if forward => 0,
+            None => count - 1,
+        };
+        self.current = Some(index);
+        Some((index + 1, self.waypoints[index].clone()))
+    }
+}